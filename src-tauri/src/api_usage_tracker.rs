@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use chrono::{DateTime, Utc};
+use tracing::{info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +16,10 @@ pub struct ApiUsage {
     pub daily_remaining: Option<u32>,
     pub daily_reset: Option<DateTime<Utc>>,
     pub last_updated: Option<DateTime<Utc>>,
+    /// Hash of the API key these numbers belong to; each Nexus account has
+    /// its own quota
+    #[serde(default)]
+    pub key_id: Option<String>,
 }
 
 impl Default for ApiUsage {
@@ -25,24 +32,104 @@ impl Default for ApiUsage {
             daily_remaining: None,
             daily_reset: None,
             last_updated: None,
+            key_id: None,
         }
     }
 }
 
+/// Short hash of an API key, so the key itself is never written next to
+/// the usage
+fn key_id(api_key: &str) -> String {
+    Sha256::digest(api_key.as_bytes()).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Below this share of the limit left, a low quota warning is sent
+const LOW_QUOTA_PERCENT: u32 = 10;
+
+/// Sent once when the requests left drop below `LOW_QUOTA_PERCENT`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaWarning {
+    pub remaining: u32,
+    pub limit: u32,
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+impl ApiUsage {
+    /// Requests left before Nexus starts refusing them, with that window's
+    /// limit and reset time. The daily allowance is used first; once it's
+    /// gone, Nexus still allows the hourly one.
+    fn available(&self) -> Option<(u32, u32, Option<DateTime<Utc>>)> {
+        match (self.daily_remaining, self.daily_limit) {
+            (Some(remaining), Some(limit)) if remaining > 0 => Some((remaining, limit, self.daily_reset)),
+            _ => Some((self.hourly_remaining?, self.hourly_limit?, self.hourly_reset)),
+        }
+    }
+
+    /// The usage known for `api_key`; nothing is known yet for a key that
+    /// was never used
+    fn for_key(&self, api_key: &str) -> Self {
+        if self.key_id.as_deref() == Some(key_id(api_key).as_str()) {
+            self.clone()
+        } else {
+            Self {
+                key_id: Some(key_id(api_key)),
+                ..Self::default()
+            }
+        }
+    }
+
+    /// When requests are accepted again, while the quota is used up
+    fn exhausted_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let (remaining, _, resets_at) = self.clone().refreshed(now).available()?;
+        resets_at.filter(|reset| remaining == 0 && *reset > now)
+    }
+
+    /// Windows whose reset time has passed start over at their full limit
+    fn refreshed(mut self, now: DateTime<Utc>) -> Self {
+        if self.hourly_reset.is_some_and(|reset| reset <= now) {
+            self.hourly_remaining = self.hourly_limit;
+        }
+        if self.daily_reset.is_some_and(|reset| reset <= now) {
+            self.daily_remaining = self.daily_limit;
+        }
+        self
+    }
+}
+
 pub struct ApiUsageTracker {
     usage: Arc<Mutex<ApiUsage>>,
+    /// Where the last known usage is kept between launches
+    path: Option<PathBuf>,
 }
 
 impl ApiUsageTracker {
-    pub fn new() -> Self {
+    /// Start from the usage saved at `path` by the previous session, if any
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let usage = path
+            .as_deref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str::<ApiUsage>(&json).ok())
+            .map(|usage| usage.refreshed(Utc::now()))
+            .unwrap_or_default();
+
         Self {
-            usage: Arc::new(Mutex::new(ApiUsage::default())),
+            usage: Arc::new(Mutex::new(usage)),
+            path,
         }
     }
 
-    /// Update usage from Nexus API response headers
-    pub async fn update_from_headers(&self, headers: &reqwest::header::HeaderMap) {
+    /// Update usage from the headers of a response to a request made with
+    /// `api_key`. Returns a warning when this response took the quota below
+    /// `LOW_QUOTA_PERCENT`.
+    pub async fn update_from_headers(
+        &self,
+        headers: &reqwest::header::HeaderMap,
+        api_key: &str,
+    ) -> Option<QuotaWarning> {
         let mut usage = self.usage.lock().await;
+        *usage = usage.for_key(api_key);
+        let before = usage.available();
 
         // Parse hourly limits
         if let Some(hourly_limit) = headers.get("x-rl-hourly-limit") {
@@ -88,19 +175,126 @@ impl ApiUsageTracker {
 
         usage.last_updated = Some(Utc::now());
 
-        println!("📊 API Usage Updated:");
-        println!("   Hourly: {}/{}",
+        info!("📊 API Usage Updated:");
+        info!("   Hourly: {}/{}",
             usage.hourly_remaining.unwrap_or(0),
             usage.hourly_limit.unwrap_or(0)
         );
-        println!("   Daily: {}/{}",
+        info!("   Daily: {}/{}",
             usage.daily_remaining.unwrap_or(0),
             usage.daily_limit.unwrap_or(0)
         );
+
+        if let Some(path) = &self.path {
+            if let Err(e) = save_usage(path, &usage).await {
+                warn!("Failed to save API usage: {}", e);
+            }
+        }
+
+        low_quota_warning(before, usage.available())
+    }
+
+    /// When requests made with `api_key` are accepted again, while its
+    /// quota is used up
+    pub async fn exhausted_until(&self, api_key: &str) -> Option<DateTime<Utc>> {
+        self.usage.lock().await.for_key(api_key).exhausted_until(Utc::now())
+    }
+
+    /// Get current usage stats of `api_key`
+    pub async fn get_usage(&self, api_key: &str) -> ApiUsage {
+        self.usage.lock().await.for_key(api_key).refreshed(Utc::now())
     }
+}
+
+/// A warning when the requests left crossed below the threshold
+fn low_quota_warning(
+    before: Option<(u32, u32, Option<DateTime<Utc>>)>,
+    after: Option<(u32, u32, Option<DateTime<Utc>>)>,
+) -> Option<QuotaWarning> {
+    let is_low = |(remaining, limit, _): (u32, u32, Option<DateTime<Utc>>)| remaining < limit * LOW_QUOTA_PERCENT / 100;
+    let (remaining, limit, resets_at) = after?;
+    if !is_low(after?) || before.is_some_and(is_low) {
+        return None;
+    }
+    Some(QuotaWarning {
+        remaining,
+        limit,
+        resets_at,
+    })
+}
 
-    /// Get current usage stats
-    pub async fn get_usage(&self) -> ApiUsage {
-        self.usage.lock().await.clone()
+async fn save_usage(path: &Path, usage: &ApiUsage) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(usage).map_err(|e| e.to_string())?;
+    tokio::fs::write(path, json).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_refreshed_resets_past_windows() {
+        let at = |hour: u32| Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        let usage = ApiUsage {
+            hourly_limit: Some(100),
+            hourly_remaining: Some(3),
+            hourly_reset: Some(at(10)),
+            daily_limit: Some(2500),
+            daily_remaining: Some(40),
+            daily_reset: Some(at(23)),
+            last_updated: Some(at(9)),
+            key_id: Some(key_id("first-key")),
+        };
+
+        let later = usage.clone().refreshed(at(11));
+        assert_eq!(later.hourly_remaining, Some(100));
+        assert_eq!(later.daily_remaining, Some(40));
+
+        assert_eq!(usage.refreshed(at(9)).hourly_remaining, Some(3));
+    }
+
+    #[test]
+    fn test_quota_exhaustion() {
+        let at = |hour: u32| Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        let mut usage = ApiUsage {
+            hourly_limit: Some(500),
+            hourly_remaining: Some(0),
+            hourly_reset: Some(at(10)),
+            daily_limit: Some(20000),
+            daily_remaining: Some(12),
+            daily_reset: Some(at(23)),
+            last_updated: Some(at(9)),
+            key_id: Some(key_id("first-key")),
+        };
+        // The daily allowance is still there
+        assert_eq!(usage.exhausted_until(at(9)), None);
+
+        usage.daily_remaining = Some(0);
+        assert_eq!(usage.exhausted_until(at(9)), Some(at(10)));
+        assert_eq!(usage.exhausted_until(at(11)), None);
+
+        // Another account's quota isn't used up
+        assert_eq!(usage.for_key("first-key").exhausted_until(at(9)), Some(at(10)));
+        assert_eq!(usage.for_key("second-key").exhausted_until(at(9)), None);
+    }
+
+    #[test]
+    fn test_low_quota_warning_once() {
+        let window = |remaining: u32| Some((remaining, 500, None));
+        assert_eq!(low_quota_warning(window(60), window(50)), None);
+        assert_eq!(
+            low_quota_warning(window(50), window(49)),
+            Some(QuotaWarning {
+                remaining: 49,
+                limit: 500,
+                resets_at: None
+            })
+        );
+        assert_eq!(low_quota_warning(window(49), window(48)), None);
+        assert!(low_quota_warning(None, window(3)).is_some());
     }
 }
@@ -0,0 +1,12 @@
+//! The parts of the mod manager that work on plain paths and settings, with no
+//! Tauri runtime behind them. The app wires them to an `AppHandle`; the
+//! integration tests in `tests/` drive them against temp directories.
+
+pub use crate::archive::{extract_file, ArchiveFormat};
+pub use crate::events::{AppEvent, EventSink};
+pub use crate::models::{Mod, ModManifest};
+pub use crate::mod_installer::{scan_mods, InstallError, InstallResult, InstallStrategy, ModBackup, ModInstaller};
+pub use crate::nexus_meta::NexusMeta;
+pub use crate::nxm_protocol::{NxmError, NxmUrl};
+pub use crate::settings::{InstallMode, Settings};
+pub use crate::staging::{deploy_staged, staging_root};
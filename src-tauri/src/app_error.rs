@@ -0,0 +1,153 @@
+//! Errors returned by commands. Each one reaches the frontend as
+//! `{ code, params, message }`: the UI translates `code` with `params` filled
+//! in and falls back to the English `message` for codes it doesn't know.
+//! Errors from the rest of the backend are still plain strings and arrive
+//! as `other`.
+
+use crate::mod_installer::InstallError;
+use crate::nexus_api::NexusApiError;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    GamePathNotConfigured,
+    SmapiPathNotConfigured,
+    NexusApiKeyNotConfigured,
+    ReadOnlyMode,
+    /// The mod is in one of the unmanaged folders
+    UnmanagedFolder,
+    ModsFolderNotFound,
+    ModNotFound { path: String },
+    DownloadNotFound { id: String },
+    /// The downloads folder can't move while files are being written to it
+    DownloadsActive,
+    ManifestNotFound,
+    Other(String),
+}
+
+impl AppError {
+    /// Stable identifier the frontend looks translations up by
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::GamePathNotConfigured => "gamePathNotConfigured",
+            AppError::SmapiPathNotConfigured => "smapiPathNotConfigured",
+            AppError::NexusApiKeyNotConfigured => "nexusApiKeyNotConfigured",
+            AppError::ReadOnlyMode => "readOnlyMode",
+            AppError::UnmanagedFolder => "unmanagedFolder",
+            AppError::ModsFolderNotFound => "modsFolderNotFound",
+            AppError::ModNotFound { .. } => "modNotFound",
+            AppError::DownloadNotFound { .. } => "downloadNotFound",
+            AppError::DownloadsActive => "downloadsActive",
+            AppError::ManifestNotFound => "manifestNotFound",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    /// Values substituted into the translated message
+    pub fn params(&self) -> BTreeMap<&'static str, String> {
+        let mut params = BTreeMap::new();
+        match self {
+            AppError::ModNotFound { path } => {
+                params.insert("path", path.clone());
+            }
+            AppError::DownloadNotFound { id } => {
+                params.insert("id", id.clone());
+            }
+            AppError::Other(message) => {
+                params.insert("message", message.clone());
+            }
+            _ => {}
+        }
+        params
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::GamePathNotConfigured => write!(f, "Game path not configured. Please set it in settings."),
+            AppError::SmapiPathNotConfigured => write!(f, "SMAPI path not configured. Please set it in settings."),
+            AppError::NexusApiKeyNotConfigured => write!(f, "Nexus API key not configured"),
+            AppError::ReadOnlyMode => write!(f, "Read-only mode is on, turn it off in Settings to change mods"),
+            AppError::UnmanagedFolder => write!(f, "This folder is marked as unmanaged, change it by hand"),
+            AppError::ModsFolderNotFound => write!(f, "Mods folder not found"),
+            AppError::ModNotFound { path } => write!(f, "Mod not found: {}", path),
+            AppError::DownloadNotFound { id } => write!(f, "Download not found: {}", id),
+            AppError::DownloadsActive => {
+                write!(f, "Wait for running downloads to finish before changing the downloads folder")
+            }
+            AppError::ManifestNotFound => write!(f, "No manifest.json found in mod archive"),
+            AppError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("params", &self.params())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+/// Lets coded errors pass through the string-typed parts of the backend,
+/// keeping only the English message
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<InstallError> for AppError {
+    fn from(err: InstallError) -> Self {
+        match err {
+            InstallError::ManifestNotFound => AppError::ManifestNotFound,
+            other => AppError::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<NexusApiError> for AppError {
+    fn from(err: NexusApiError) -> Self {
+        AppError::Other(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_params_and_message() {
+        let json = serde_json::to_value(AppError::DownloadNotFound { id: "abc".to_string() }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "code": "downloadNotFound",
+                "params": { "id": "abc" },
+                "message": "Download not found: abc",
+            })
+        );
+
+        let json = serde_json::to_value(AppError::from("Disk full".to_string())).unwrap();
+        assert_eq!(json["code"], "other");
+        assert_eq!(json["message"], "Disk full");
+    }
+}
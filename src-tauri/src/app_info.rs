@@ -0,0 +1,112 @@
+//! Version, build and storage details for the about page and diagnostics
+
+use crate::settings::Settings;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// How the backend was built
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    pub tauri_version: &'static str,
+    pub crate_version: &'static str,
+    /// e.g. "windows-x86_64"
+    pub target: String,
+    pub debug: bool,
+}
+
+/// Where the app keeps its files
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirs {
+    pub app_data: PathBuf,
+    pub settings_file: PathBuf,
+    pub downloads: PathBuf,
+    pub backups: PathBuf,
+    pub temp: PathBuf,
+    pub logs: PathBuf,
+    pub smapi_log: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub name: String,
+    pub version: String,
+    pub build: BuildInfo,
+    pub data_dirs: DataDirs,
+    /// Optional behaviour currently switched on in settings
+    pub features: Vec<&'static str>,
+}
+
+/// Names of the opt-in settings that are on
+pub fn active_features(settings: &Settings) -> Vec<&'static str> {
+    [
+        ("autoInstall", settings.auto_install),
+        ("nxmHandler", settings.nxm_handler_enabled),
+        ("checkUpdatesOnStartup", settings.check_updates_on_startup),
+        ("installDependencies", settings.install_dependencies),
+        ("localApi", settings.local_api_enabled),
+        ("webhook", !settings.webhook_url.trim().is_empty()),
+        ("scriptHooks", settings.script_hooks_enabled),
+        ("readOnlyMode", settings.read_only_mode),
+        ("debugApiPayloads", settings.debug_api_payloads),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect()
+}
+
+pub fn app_info(app_handle: &AppHandle) -> Result<AppInfo, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let settings = Settings::load(app_handle).unwrap_or_default();
+    let package = app_handle.package_info();
+
+    Ok(AppInfo {
+        name: package.name.clone(),
+        version: package.version.to_string(),
+        build: BuildInfo {
+            tauri_version: tauri::VERSION,
+            crate_version: env!("CARGO_PKG_VERSION"),
+            target: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+            debug: cfg!(debug_assertions),
+        },
+        data_dirs: DataDirs {
+            settings_file: app_data_dir.join("settings.json"),
+            downloads: settings.downloads_path(&app_data_dir),
+            backups: app_data_dir.join("backups"),
+            temp: app_data_dir.join("temp"),
+            logs: app_data_dir.join("logs"),
+            smapi_log: crate::settings::smapi_log_path(),
+            app_data: app_data_dir,
+        },
+        features: active_features(&settings),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_features() {
+        let settings = Settings {
+            auto_install: false,
+            install_dependencies: false,
+            ..Settings::default()
+        };
+        assert!(active_features(&settings).is_empty());
+
+        let settings = Settings {
+            local_api_enabled: true,
+            webhook_url: "https://discord.com/api/webhooks/1/abc".to_string(),
+            read_only_mode: true,
+            ..settings
+        };
+        assert_eq!(active_features(&settings), vec!["localApi", "webhook", "readOnlyMode"]);
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use tracing::info;
+use zip::ZipArchive;
+
+/// Archive container, detected from the file's magic bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZip,
+    Rar,
+    /// Gzip-compressed tarball (.tar.gz / .tgz)
+    Gzip,
+}
+
+impl ArchiveFormat {
+    /// Identify the format from the first bytes of the file
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") || header.starts_with(b"PK\x07\x08") {
+            Some(ArchiveFormat::Zip)
+        } else if header.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+            Some(ArchiveFormat::SevenZip)
+        } else if header.starts_with(b"Rar!\x1A\x07") {
+            Some(ArchiveFormat::Rar)
+        } else if header.starts_with(&[0x1F, 0x8B]) {
+            Some(ArchiveFormat::Gzip)
+        } else {
+            None
+        }
+    }
+
+    /// Peek at the start of a reader, leaving it rewound
+    pub fn detect<R: Read + Seek>(reader: &mut R) -> Result<Option<Self>, String> {
+        let mut header = [0u8; 8];
+        let mut read = 0;
+        while read < header.len() {
+            match reader.read(&mut header[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) => return Err(format!("Failed to read archive header: {}", e)),
+            }
+        }
+
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| format!("Failed to rewind archive: {}", e))?;
+
+        Ok(Self::from_magic(&header[..read]))
+    }
+}
+
+/// Extract an archive file into `dest`, whatever its extension says
+pub fn extract_file(archive_path: &Path, dest: &Path) -> Result<ArchiveFormat, String> {
+    let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    extract(file, dest)
+}
+
+/// Detect the archive format of `reader` and extract it into `dest`
+pub fn extract<R: Read + Seek>(mut reader: R, dest: &Path) -> Result<ArchiveFormat, String> {
+    let format = ArchiveFormat::detect(&mut reader)?
+        .ok_or_else(|| "Unrecognized archive format".to_string())?;
+
+    info!("📦 Detected archive format: {:?}", format);
+
+    match format {
+        ArchiveFormat::Zip => extract_zip(reader, dest)?,
+        ArchiveFormat::SevenZip => sevenz_rust::decompress(reader, dest)
+            .map_err(|e| format!("Invalid 7z archive: {}", e))?,
+        ArchiveFormat::Rar => {
+            return Err("RAR archives are not supported, please extract it manually".to_string())
+        }
+        ArchiveFormat::Gzip => extract_tar_gz(reader, dest)?,
+    }
+
+    Ok(format)
+}
+
+fn extract_tar_gz<R: Read>(reader: R, dest: &Path) -> Result<(), String> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(reader));
+    // unpack() refuses entries escaping `dest` (absolute paths, "..")
+    archive
+        .unpack(dest)
+        .map_err(|e| format!("Invalid tar.gz archive: {}", e))
+}
+
+fn extract_zip<R: Read + Seek>(reader: R, dest: &Path) -> Result<(), String> {
+    let mut archive = ZipArchive::new(reader).map_err(|e| format!("Invalid ZIP: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+
+        let outpath = match file.enclosed_name() {
+            Some(path) => dest.join(path),
+            None => continue,
+        };
+
+        if file.name().ends_with('/') {
+            // Directory
+            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+        } else {
+            // File
+            if let Some(parent) = outpath.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+
+            let mut outfile = File::create(&outpath).map_err(|e| e.to_string())?;
+            std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+        }
+
+        // Set permissions on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = file.unix_mode() {
+                // Ensure we always have write permissions for the user
+                // This prevents "Permission denied" errors when extracting files into read-only directories
+                // or when trying to overwrite read-only files (though we clean up first)
+                let safe_mode = if file.name().ends_with('/') {
+                    // For directories, ensure rwx for user (0o700)
+                    mode | 0o700
+                } else {
+                    // For files, ensure rw for user (0o600)
+                    mode | 0o600
+                };
+
+                fs::set_permissions(&outpath, fs::Permissions::from_mode(safe_mode))
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn test_detects_format_by_magic_bytes() {
+        assert_eq!(ArchiveFormat::from_magic(b"PK\x03\x04rest"), Some(ArchiveFormat::Zip));
+        assert_eq!(
+            ArchiveFormat::from_magic(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, 0x00, 0x04]),
+            Some(ArchiveFormat::SevenZip)
+        );
+        assert_eq!(ArchiveFormat::from_magic(b"Rar!\x1A\x07\x01\x00"), Some(ArchiveFormat::Rar));
+        assert_eq!(ArchiveFormat::from_magic(&[0x1F, 0x8B, 0x08]), Some(ArchiveFormat::Gzip));
+        assert_eq!(ArchiveFormat::from_magic(b"{\"Name\""), None);
+    }
+
+    #[test]
+    fn test_extracts_misnamed_zip() {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buffer);
+            writer
+                .start_file("TestMod/manifest.json", zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(b"{}").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let temp = std::env::temp_dir().join("treasure_chest_archive_test");
+        let _ = fs::remove_dir_all(&temp);
+        fs::create_dir_all(&temp).unwrap();
+
+        // Saved with the wrong extension, as Nexus sometimes serves them
+        let archive_path = temp.join("TestMod.7z");
+        fs::write(&archive_path, buffer.into_inner()).unwrap();
+
+        let dest = temp.join("out");
+        let format = extract_file(&archive_path, &dest).unwrap();
+        assert_eq!(format, ArchiveFormat::Zip);
+        assert!(dest.join("TestMod").join("manifest.json").exists());
+
+        let _ = fs::remove_dir_all(&temp);
+    }
+
+    #[test]
+    fn test_extracts_tar_gz() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "TestMod/manifest.json", &b"{}"[..])
+            .unwrap();
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let dest = std::env::temp_dir().join("treasure_chest_archive_tar_test");
+        let _ = fs::remove_dir_all(&dest);
+        fs::create_dir_all(&dest).unwrap();
+
+        let format = extract(Cursor::new(bytes), &dest).unwrap();
+        assert_eq!(format, ArchiveFormat::Gzip);
+        assert!(dest.join("TestMod").join("manifest.json").exists());
+
+        let _ = fs::remove_dir_all(&dest);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::settings::BackupFormat;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use tracing::info;
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Create a timestamped snapshot of `mod_path` inside `backups_dir`.
+///
+/// Folder snapshots hardlink files that are unchanged since the previous
+/// folder snapshot instead of copying them, so frequent updates of large mods
+/// only cost the space of the files that actually changed. Zip and Zstd
+/// snapshots are written as a single compressed archive instead.
+pub fn create_snapshot(
+    mod_path: &Path,
+    backups_dir: &Path,
+    format: &BackupFormat,
+) -> std::io::Result<PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let extension = match format {
+        BackupFormat::Folder => "",
+        BackupFormat::Zip | BackupFormat::Zstd => ".zip",
+    };
+
+    let mut snapshot_path = backups_dir.join(format!("{}{}", timestamp, extension));
+    // Two snapshots in the same second would otherwise collide
+    let mut suffix = 1;
+    while snapshot_path.exists() {
+        snapshot_path = backups_dir.join(format!("{}-{}{}", timestamp, suffix, extension));
+        suffix += 1;
+    }
+
+    fs::create_dir_all(backups_dir)?;
+
+    match format {
+        BackupFormat::Folder => {}
+        BackupFormat::Zip => {
+            write_zip_snapshot(mod_path, &snapshot_path, CompressionMethod::Deflated)?;
+            info!("   Snapshot {}: compressed (zip)", snapshot_path.display());
+            return Ok(snapshot_path);
+        }
+        BackupFormat::Zstd => {
+            write_zip_snapshot(mod_path, &snapshot_path, CompressionMethod::Zstd)?;
+            info!("   Snapshot {}: compressed (zstd)", snapshot_path.display());
+            return Ok(snapshot_path);
+        }
+    }
+
+    let previous = latest_snapshot(backups_dir);
+    fs::create_dir_all(&snapshot_path)?;
+
+    let mut stats = SnapshotStats::default();
+    snapshot_dir(mod_path, &snapshot_path, previous.as_deref(), &mut stats)?;
+
+    info!(
+        "   Snapshot {}: {} file(s) copied, {} hardlinked",
+        snapshot_path.display(),
+        stats.copied,
+        stats.linked
+    );
+
+    Ok(snapshot_path)
+}
+
+/// Restore a snapshot (folder or compressed) into `target`, which must not exist yet.
+/// Files are always copied out, never linked, so later edits to the restored mod
+/// can't alter other snapshots.
+pub fn restore_snapshot(snapshot: &Path, target: &Path) -> std::io::Result<()> {
+    if snapshot.is_dir() {
+        return copy_dir_all(snapshot, target);
+    }
+
+    let file = File::open(snapshot)?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    fs::create_dir_all(target)?;
+    archive
+        .extract(target)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// List snapshots (folders or .zip archives) of a backups folder, oldest first
+pub fn list_snapshots(backups_dir: &Path) -> Vec<PathBuf> {
+    let mut snapshots: Vec<(u64, String, PathBuf)> = fs::read_dir(backups_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    let stem = name.trim_end_matches(".zip");
+                    let timestamp = stem.split('-').next()?.parse::<u64>().ok()?;
+                    Some((timestamp, name, e.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    snapshots.sort();
+    snapshots.into_iter().map(|(_, _, path)| path).collect()
+}
+
+/// Delete all but the newest `keep` snapshots of a backups folder.
+/// Returns how many were removed.
+pub fn prune_snapshots(backups_dir: &Path, keep: usize) -> std::io::Result<usize> {
+    let snapshots = list_snapshots(backups_dir);
+    let excess = snapshots.len().saturating_sub(keep);
+
+    // Folder snapshots only hardlink each other's files, so any can go
+    for snapshot in &snapshots[..excess] {
+        if snapshot.is_dir() {
+            fs::remove_dir_all(snapshot)?;
+        } else {
+            fs::remove_file(snapshot)?;
+        }
+    }
+    Ok(excess)
+}
+
+fn latest_snapshot(backups_dir: &Path) -> Option<PathBuf> {
+    list_snapshots(backups_dir).into_iter().rev().find(|p| p.is_dir())
+}
+
+fn write_zip_snapshot(
+    mod_path: &Path,
+    snapshot_path: &Path,
+    method: CompressionMethod,
+) -> std::io::Result<()> {
+    let to_io = |e: zip::result::ZipError| std::io::Error::other(e);
+
+    let file = File::create(snapshot_path)?;
+    let mut writer = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(method);
+
+    for entry in WalkDir::new(mod_path).min_depth(1) {
+        let entry = entry?;
+        let relative = entry
+            .path()
+            .strip_prefix(mod_path)
+            .map_err(std::io::Error::other)?;
+        // Zip entries always use forward slashes
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        if entry.file_type().is_dir() {
+            writer.add_directory(name, options).map_err(to_io)?;
+        } else {
+            writer.start_file(name, options).map_err(to_io)?;
+            let mut source = File::open(entry.path())?;
+            std::io::copy(&mut source, &mut writer)?;
+        }
+    }
+
+    writer.finish().map_err(to_io)?;
+    Ok(())
+}
+
+fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), dst.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct SnapshotStats {
+    copied: usize,
+    linked: usize,
+}
+
+fn snapshot_dir(
+    src: &Path,
+    dst: &Path,
+    previous: Option<&Path>,
+    stats: &mut SnapshotStats,
+) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let src_path = entry.path();
+        let dst_path = dst.join(&name);
+        let prev_path = previous.map(|p| p.join(&name));
+
+        if entry.file_type()?.is_dir() {
+            snapshot_dir(&src_path, &dst_path, prev_path.as_deref(), stats)?;
+            continue;
+        }
+
+        // Link to the previous snapshot's copy if the content is unchanged
+        if let Some(prev) = prev_path.filter(|p| p.is_file()) {
+            if files_identical(&src_path, &prev)? && fs::hard_link(&prev, &dst_path).is_ok() {
+                stats.linked += 1;
+                continue;
+            }
+        }
+
+        fs::copy(&src_path, &dst_path)?;
+        stats.copied += 1;
+    }
+
+    Ok(())
+}
+
+/// Compare two files by size, then byte for byte
+fn files_identical(a: &Path, b: &Path) -> std::io::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut reader_a = BufReader::new(File::open(a)?);
+    let mut reader_b = BufReader::new(File::open(b)?);
+    let mut buf_a = [0u8; 8192];
+    let mut buf_b = [0u8; 8192];
+
+    loop {
+        let read_a = reader_a.read(&mut buf_a)?;
+        if read_a == 0 {
+            return Ok(true);
+        }
+
+        reader_b.read_exact(&mut buf_b[..read_a])?;
+        if buf_a[..read_a] != buf_b[..read_a] {
+            return Ok(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_snapshot_hardlinks_unchanged_files() {
+        let temp_dir = std::env::temp_dir().join("sdv_mgr_test_backup");
+        let mod_dir = temp_dir.join("TestMod");
+        let backups_dir = temp_dir.join("backups");
+
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(mod_dir.join("assets")).unwrap();
+        fs::write(mod_dir.join("manifest.json"), "{}").unwrap();
+        fs::write(mod_dir.join("assets").join("big.png"), vec![7u8; 20_000]).unwrap();
+
+        let first = create_snapshot(&mod_dir, &backups_dir, &BackupFormat::Folder).unwrap();
+
+        // Change one file, keep the other
+        fs::write(mod_dir.join("manifest.json"), "{\"Version\": \"2.0.0\"}").unwrap();
+        let second = create_snapshot(&mod_dir, &backups_dir, &BackupFormat::Folder).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(list_snapshots(&backups_dir), vec![first.clone(), second.clone()]);
+        assert_eq!(fs::read_to_string(first.join("manifest.json")).unwrap(), "{}");
+        assert_eq!(
+            fs::read_to_string(second.join("manifest.json")).unwrap(),
+            "{\"Version\": \"2.0.0\"}"
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let first_png = fs::metadata(first.join("assets").join("big.png")).unwrap();
+            let second_png = fs::metadata(second.join("assets").join("big.png")).unwrap();
+            assert_eq!(first_png.ino(), second_png.ino());
+        }
+
+        // Pruning the older snapshot leaves the linked files of the newer one
+        assert_eq!(prune_snapshots(&backups_dir, 1).unwrap(), 1);
+        assert_eq!(list_snapshots(&backups_dir), vec![second.clone()]);
+        assert_eq!(fs::read(second.join("assets").join("big.png")).unwrap().len(), 20_000);
+        assert_eq!(prune_snapshots(&backups_dir, 5).unwrap(), 0);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_compressed_snapshot_restores_transparently() {
+        let temp_dir = std::env::temp_dir().join("sdv_mgr_test_backup_zstd");
+        let mod_dir = temp_dir.join("TestMod");
+        let backups_dir = temp_dir.join("backups");
+        let restored = temp_dir.join("Restored");
+
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(mod_dir.join("assets")).unwrap();
+        fs::write(mod_dir.join("manifest.json"), "{}").unwrap();
+        fs::write(mod_dir.join("assets").join("data.json"), "[1, 2, 3]").unwrap();
+
+        let snapshot = create_snapshot(&mod_dir, &backups_dir, &BackupFormat::Zstd).unwrap();
+        assert!(snapshot.is_file());
+        assert_eq!(list_snapshots(&backups_dir), vec![snapshot.clone()]);
+
+        restore_snapshot(&snapshot, &restored).unwrap();
+        assert_eq!(fs::read_to_string(restored.join("manifest.json")).unwrap(), "{}");
+        assert_eq!(
+            fs::read_to_string(restored.join("assets").join("data.json")).unwrap(),
+            "[1, 2, 3]"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
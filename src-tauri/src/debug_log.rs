@@ -0,0 +1,51 @@
+use crate::settings::Settings;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// Payload log is rotated to `.old` once it grows past this size (5 MB)
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Get the path of the API payload log inside the app data directory
+pub fn api_payload_log_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("logs").join("api_payloads.log"))
+}
+
+/// Append a raw API payload to the payload log, if payload logging is enabled in settings.
+/// Payloads can contain signed download URLs, so they never go to stdout.
+pub fn log_api_payload(app_handle: &AppHandle, settings: &Settings, context: &str, payload: &str) {
+    if !settings.debug_api_payloads {
+        return;
+    }
+
+    let Some(log_path) = api_payload_log_path(app_handle) else {
+        return;
+    };
+
+    if let Err(e) = append_entry(&log_path, context, payload) {
+        warn!("Failed to write API payload log: {}", e);
+    }
+}
+
+fn append_entry(log_path: &Path, context: &str, payload: &str) -> std::io::Result<()> {
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Rotate once the log gets too big
+    if fs::metadata(log_path).map(|m| m.len() > MAX_LOG_SIZE).unwrap_or(false) {
+        fs::rename(log_path, log_path.with_extension("log.old"))?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(log_path)?;
+    writeln!(
+        file,
+        "[{}] {}\n{}\n",
+        chrono::Utc::now().to_rfc3339(),
+        context,
+        payload
+    )
+}
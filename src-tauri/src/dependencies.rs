@@ -0,0 +1,670 @@
+use crate::endorsements::mod_page_url;
+use crate::events::{self, AppEvent};
+use crate::mod_installer::{InstallResult, ModInstaller};
+use crate::models::{ContentPackInfo, Mod, ModDependency, ModManifest};
+use crate::nexus_api::{compare_versions, NexusClient, NexusRequirement};
+use crate::settings::Settings;
+use crate::update_all::AvailableUpdate;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Nexus ids of frameworks content packs commonly depend on, so the usual
+/// cases don't need a smapi.io lookup
+const KNOWN_NEXUS_IDS: &[(&str, u32)] = &[
+    ("Pathoschild.ContentPatcher", 1915),
+    ("spacechase0.SpaceCore", 1348),
+    ("spacechase0.JsonAssets", 1720),
+    ("spacechase0.GenericModConfigMenu", 5098),
+    ("spacechase0.DynamicGameAssets", 9365),
+    ("Esca.FarmTypeManager", 3231),
+    ("Cherry.ExpandedPreconditionsUtility", 6529),
+    ("Cherry.ShopTileFramework", 5005),
+    ("DIGUS.MailFrameworkMod", 1536),
+    ("Digus.ProducerFrameworkMod", 4970),
+    ("Platonymous.Toolkit", 1726),
+    ("PeacefulEnd.AlternativeTextures", 9246),
+    ("PeacefulEnd.FashionSense", 9969),
+];
+
+/// A required dependency that couldn't be installed automatically
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingDependency {
+    pub unique_id: String,
+    /// Mods in the archive that need it
+    pub required_by: Vec<String>,
+    pub nexus_mod_id: Option<u32>,
+    pub page_url: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyProblem {
+    Missing,
+    Disabled,
+    /// Installed, but older than the mod's minimum version
+    Outdated,
+}
+
+/// An enabled mod whose required dependency won't be loaded by SMAPI, or is
+/// too old for it
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyIssue {
+    pub unique_id: String,
+    pub name: String,
+    pub dependency_id: String,
+    pub problem: DependencyProblem,
+    pub nexus_mod_id: Option<u32>,
+    /// Minimum version the mod needs, for outdated dependencies
+    pub required_version: Option<String>,
+    pub installed_version: Option<String>,
+}
+
+/// `ContentPackFor` and the non-optional `Dependencies` of a mod
+fn required_ids<'a>(
+    content_pack_for: Option<&'a ContentPackInfo>,
+    dependencies: Option<&'a Vec<ModDependency>>,
+) -> impl Iterator<Item = &'a String> {
+    let dependencies = dependencies
+        .into_iter()
+        .flatten()
+        .filter(|d| d.is_required != Some(false))
+        .map(|d| &d.unique_id);
+    content_pack_for.map(|c| &c.unique_id).into_iter().chain(dependencies)
+}
+
+/// Required dependencies of enabled mods that are missing or disabled
+pub fn dependency_issues(mods: &[Mod]) -> Vec<DependencyIssue> {
+    let mut issues = Vec::new();
+
+    for m in mods.iter().filter(|m| m.is_enabled) {
+        for dependency_id in required_ids(m.content_pack_for.as_ref(), m.dependencies.as_ref()) {
+            if dependency_id.eq_ignore_ascii_case("SMAPI") {
+                continue;
+            }
+
+            let installed: Vec<&Mod> = mods
+                .iter()
+                .filter(|d| d.unique_id.eq_ignore_ascii_case(dependency_id))
+                .collect();
+            let problem = if installed.is_empty() {
+                DependencyProblem::Missing
+            } else if installed.iter().all(|d| !d.is_enabled) {
+                DependencyProblem::Disabled
+            } else {
+                continue;
+            };
+
+            issues.push(DependencyIssue {
+                unique_id: m.unique_id.clone(),
+                name: m.name.clone(),
+                dependency_id: dependency_id.clone(),
+                problem,
+                nexus_mod_id: known_nexus_id(dependency_id),
+                required_version: None,
+                installed_version: None,
+            });
+        }
+
+        for (dependency_id, minimum_version) in version_floors(m) {
+            let Some(installed) = mods
+                .iter()
+                .filter(|d| d.unique_id.eq_ignore_ascii_case(dependency_id))
+                .max_by_key(|d| d.is_enabled)
+            else {
+                continue;
+            };
+            if compare_versions(&installed.version, minimum_version) != Ordering::Less {
+                continue;
+            }
+
+            issues.push(DependencyIssue {
+                unique_id: m.unique_id.clone(),
+                name: m.name.clone(),
+                dependency_id: installed.unique_id.clone(),
+                problem: DependencyProblem::Outdated,
+                nexus_mod_id: installed.nexus_mod_id.or_else(|| known_nexus_id(dependency_id)),
+                required_version: Some(minimum_version.to_string()),
+                installed_version: Some(installed.version.clone()),
+            });
+        }
+    }
+    issues
+}
+
+/// A disabled mod that has to be enabled along with the ones asked for
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredEnable {
+    pub id: String,
+    pub unique_id: String,
+    pub name: String,
+    /// Name of the mod that needs it
+    pub required_by: String,
+}
+
+/// Result of `set_mods_enabled`. When enabling needs more mods than were
+/// asked for, nothing is changed until the caller decides whether to
+/// include them.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnablePlan {
+    pub required: Vec<RequiredEnable>,
+    pub applied: bool,
+    /// Mods that couldn't be changed, as "name: error"
+    pub errors: Vec<String>,
+}
+
+/// Disabled mods that must be enabled for the mods in `ids` (by `Mod::id`)
+/// to load, following dependencies of dependencies. Dependencies that
+/// aren't installed at all are left to `dependency_issues`.
+pub fn required_enables(mods: &[Mod], ids: &[String]) -> Vec<RequiredEnable> {
+    let mut pending: Vec<&Mod> = mods.iter().filter(|m| ids.contains(&m.id)).collect();
+    let mut planned: HashSet<String> = pending.iter().map(|m| m.unique_id.to_lowercase()).collect();
+    let mut required = Vec::new();
+
+    while let Some(m) = pending.pop() {
+        for dependency_id in required_ids(m.content_pack_for.as_ref(), m.dependencies.as_ref()) {
+            let key = dependency_id.to_lowercase();
+            if key == "smapi" || planned.contains(&key) {
+                continue;
+            }
+            let installed: Vec<&Mod> = mods
+                .iter()
+                .filter(|d| d.unique_id.eq_ignore_ascii_case(dependency_id))
+                .collect();
+            if installed.iter().any(|d| d.is_enabled) {
+                continue;
+            }
+            let Some(dependency) = installed.first() else {
+                continue;
+            };
+
+            planned.insert(key);
+            required.push(RequiredEnable {
+                id: dependency.id.clone(),
+                unique_id: dependency.unique_id.clone(),
+                name: dependency.name.clone(),
+                required_by: m.name.clone(),
+            });
+            pending.push(dependency);
+        }
+    }
+    required
+}
+
+/// Highest minimum version a mod needs of each dependency, from the
+/// manifest's `MinimumVersion` fields and the recorded framework floors.
+/// SMAPI itself isn't a mod and is left out.
+fn version_floors(m: &Mod) -> Vec<(&str, &str)> {
+    let manifest = m
+        .content_pack_for
+        .iter()
+        .filter_map(|c| Some((c.unique_id.as_str(), c.minimum_version.as_deref()?)))
+        .chain(
+            m.dependencies
+                .iter()
+                .flatten()
+                .filter_map(|d| Some((d.unique_id.as_str(), d.minimum_version.as_deref()?))),
+        );
+    let recorded = m
+        .framework_floors
+        .iter()
+        .map(|f| (f.unique_id.as_str(), f.minimum_version.as_str()));
+
+    let mut floors: Vec<(&str, &str)> = Vec::new();
+    for (unique_id, minimum_version) in manifest.chain(recorded) {
+        if unique_id.eq_ignore_ascii_case("SMAPI") {
+            continue;
+        }
+        match floors.iter_mut().find(|(id, _)| id.eq_ignore_ascii_case(unique_id)) {
+            Some(floor) if compare_versions(minimum_version, floor.1) == Ordering::Greater => floor.1 = minimum_version,
+            Some(_) => {}
+            None => floors.push((unique_id, minimum_version)),
+        }
+    }
+    floors
+}
+
+/// Updates for the frameworks behind `Outdated` issues, in the shape
+/// `update_all` takes. Frameworks without a known Nexus page are left out.
+pub fn framework_updates(issues: &[DependencyIssue], mods: &[Mod]) -> Vec<AvailableUpdate> {
+    let mut updates: Vec<AvailableUpdate> = Vec::new();
+    for issue in issues.iter().filter(|i| i.problem == DependencyProblem::Outdated) {
+        let Some(nexus_mod_id) = issue.nexus_mod_id else {
+            continue;
+        };
+        if updates.iter().any(|u| u.unique_id.eq_ignore_ascii_case(&issue.dependency_id)) {
+            continue;
+        }
+        let Some(framework) = mods.iter().find(|m| m.unique_id.eq_ignore_ascii_case(&issue.dependency_id)) else {
+            continue;
+        };
+
+        updates.push(AvailableUpdate {
+            unique_id: framework.unique_id.clone(),
+            name: framework.name.clone(),
+            nexus_mod_id,
+            current_version: framework.version.clone(),
+            latest_version: None,
+            latest_file_id: None,
+        });
+    }
+    updates
+}
+
+pub fn known_nexus_id(unique_id: &str) -> Option<u32> {
+    KNOWN_NEXUS_IDS
+        .iter()
+        .find(|(id, _)| id.eq_ignore_ascii_case(unique_id))
+        .map(|(_, nexus_id)| *nexus_id)
+}
+
+/// Requirements from a mod page that the library doesn't have: no installed
+/// mod came from that page, is known to live there, or has that name.
+/// External requirements and the mod itself are left out.
+fn missing_nexus_requirements(requirements: Vec<NexusRequirement>, installed: &[Mod], mod_id: u32) -> Vec<NexusRequirement> {
+    let mut missing: Vec<NexusRequirement> = Vec::new();
+    for requirement in requirements {
+        let Ok(required_id) = requirement.mod_id.parse::<u32>() else {
+            continue;
+        };
+        if requirement.external_requirement || required_id == mod_id {
+            continue;
+        }
+        let installed = installed.iter().any(|m| {
+            m.nexus_mod_id == Some(required_id)
+                || known_nexus_id(&m.unique_id) == Some(required_id)
+                || m.name.eq_ignore_ascii_case(&requirement.mod_name)
+        });
+        if !installed && !missing.iter().any(|r| r.mod_id == requirement.mod_id) {
+            missing.push(requirement);
+        }
+    }
+    missing
+}
+
+/// After installing a mod from Nexus, check the requirements its page lists
+/// and send `nexus-requirements-missing` for those not installed, so the
+/// user can queue them. Catches soft requirements manifests leave out.
+pub async fn offer_nexus_requirements(app_handle: &AppHandle, mod_id: u32) {
+    let Ok(settings) = Settings::load(app_handle) else {
+        return;
+    };
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.has_api_key() || settings.game_path.is_empty() {
+        return;
+    }
+
+    let requirements = match nexus.mod_requirements("stardewvalley", mod_id).await {
+        Ok(requirements) => requirements,
+        Err(e) => {
+            warn!("Failed to fetch requirements of mod {}: {}", mod_id, e);
+            return;
+        }
+    };
+
+    let installed = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    let missing = missing_nexus_requirements(requirements, &installed, mod_id);
+    if !missing.is_empty() {
+        info!("🧩 Mod {} lists {} requirement(s) that aren't installed", mod_id, missing.len());
+        events::emit(
+            app_handle,
+            AppEvent::NexusRequirementsMissing {
+                mod_id,
+                requirements: missing,
+            },
+        );
+    }
+}
+
+/// A required mod `queue_requirements` couldn't queue
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementFailure {
+    pub mod_id: u32,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRequirements {
+    pub download_ids: Vec<String>,
+    pub failed: Vec<RequirementFailure>,
+}
+
+/// Queue the latest main file of each required mod the user accepted. A mod
+/// that can't be queued doesn't stop the others.
+pub async fn queue_requirements(app_handle: &AppHandle, mod_ids: Vec<u32>) -> Result<QueuedRequirements, String> {
+    let settings = Settings::load(app_handle)?;
+    let nexus = NexusClient::new(app_handle, &settings);
+
+    let mut queued = QueuedRequirements::default();
+    for mod_id in mod_ids {
+        let result = async {
+            let files = nexus.mod_files("stardewvalley", mod_id).await?;
+            let file = crate::update_all::latest_main_file(&files)
+                .ok_or_else(|| format!("No main file on the page of mod {}", mod_id))?;
+            crate::mod_files::queue_mod_files(app_handle, mod_id, vec![file.file_id]).await
+        }
+        .await;
+
+        match result {
+            Ok(download_ids) => queued.download_ids.extend(download_ids),
+            Err(error) => {
+                warn!("Failed to queue required mod {}: {}", mod_id, error);
+                queued.failed.push(RequirementFailure { mod_id, error });
+            }
+        }
+    }
+    Ok(queued)
+}
+
+/// Required dependencies (`ContentPackFor` and non-optional `Dependencies`)
+/// of the archive's mods that aren't installed or shipped in the archive
+/// itself, each with the names of the mods that need it
+fn missing_dependencies(manifests: &[ModManifest], installed: &[Mod]) -> Vec<(String, Vec<String>)> {
+    let present: HashSet<String> = installed
+        .iter()
+        .map(|m| m.unique_id.to_lowercase())
+        .chain(manifests.iter().map(|m| m.unique_id.to_lowercase()))
+        .collect();
+
+    let mut missing: Vec<(String, Vec<String>)> = Vec::new();
+    for manifest in manifests {
+        for unique_id in required_ids(manifest.content_pack_for.as_ref(), manifest.dependencies.as_ref()) {
+            // SMAPI itself isn't installed as a mod
+            if present.contains(&unique_id.to_lowercase()) || unique_id.eq_ignore_ascii_case("SMAPI") {
+                continue;
+            }
+
+            match missing.iter_mut().find(|(id, _)| id.eq_ignore_ascii_case(unique_id)) {
+                Some((_, required_by)) => required_by.push(manifest.name.clone()),
+                None => missing.push((unique_id.clone(), vec![manifest.name.clone()])),
+            }
+        }
+    }
+    missing
+}
+
+/// Download and install the missing required dependencies of the mods in
+/// `archive_path`, so they're in place before the archive itself is
+/// installed. Dependencies that can't be installed are reported with
+/// `dependencies-missing` and don't stop the install.
+///
+/// Files are requested without a key from the website, which Nexus only
+/// allows for premium accounts.
+pub async fn install_dependencies(app_handle: &AppHandle, archive_path: &Path) -> Result<Vec<InstallResult>, String> {
+    let settings = Settings::load(app_handle)?;
+    if !settings.install_dependencies {
+        return Ok(Vec::new());
+    }
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let game_path = PathBuf::from(&settings.game_path);
+    let installer = ModInstaller::new(
+        app_handle.clone(),
+        app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("temp"),
+    )
+    .unattended();
+
+    let manifests = installer
+        .archive_manifests(archive_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let missing = missing_dependencies(&manifests, &crate::mod_installer::scan_mods(&game_path));
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    info!("🧩 Missing dependencies: {:?}", missing.iter().map(|(id, _)| id).collect::<Vec<_>>());
+
+    let unknown: Vec<String> = missing
+        .iter()
+        .filter(|(id, _)| known_nexus_id(id).is_none())
+        .map(|(id, _)| id.clone())
+        .collect();
+    let looked_up = if unknown.is_empty() {
+        Default::default()
+    } else {
+        crate::smapi_compat::nexus_ids(app_handle, &unknown).await.unwrap_or_else(|e| {
+            warn!("Failed to look up dependencies on smapi.io: {}", e);
+            Default::default()
+        })
+    };
+
+    let nexus = NexusClient::new(app_handle, &settings);
+    let is_premium = nexus.validate_user().await.map(|u| u.is_premium).unwrap_or(false);
+
+    let mut installed = Vec::new();
+    let mut failed = Vec::new();
+    for (unique_id, required_by) in missing {
+        let nexus_mod_id = known_nexus_id(&unique_id).or_else(|| looked_up.get(&unique_id.to_lowercase()).copied());
+
+        let result = match nexus_mod_id {
+            None => Err("Not found on Nexus Mods".to_string()),
+            Some(_) if !is_premium => Err("Installing dependencies automatically needs a Nexus Mods Premium account".to_string()),
+            Some(mod_id) => install_latest(app_handle, &installer, &game_path, &settings, mod_id).await,
+        };
+
+        match result {
+            Ok(results) => {
+                for result in &results {
+                    info!("   ✓ Installed dependency {} v{}", result.mod_name, result.version);
+                }
+                installed.extend(results);
+            }
+            Err(reason) => {
+                warn!("   ✗ Dependency {} not installed: {}", unique_id, reason);
+                failed.push(MissingDependency {
+                    unique_id,
+                    required_by,
+                    nexus_mod_id,
+                    page_url: nexus_mod_id.map(mod_page_url),
+                    reason,
+                });
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        events::emit(app_handle, AppEvent::DependenciesMissing(failed));
+    }
+    Ok(installed)
+}
+
+/// Install the latest main file of a mod, and any others bundled with it
+async fn install_latest(
+    app_handle: &AppHandle,
+    installer: &ModInstaller,
+    game_path: &Path,
+    settings: &Settings,
+    mod_id: u32,
+) -> Result<Vec<InstallResult>, String> {
+    let files = NexusClient::new(app_handle, settings)
+        .mod_files("stardewvalley", mod_id)
+        .await?;
+    let file = crate::update_all::latest_main_file(&files).ok_or("No main file on the mod page")?;
+    crate::update_all::install_nexus_file(
+        app_handle,
+        installer,
+        game_path,
+        settings,
+        mod_id,
+        file.file_id,
+        crate::mod_registry::ModSource::Nexus,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_mod;
+
+    fn manifest(name: &str, unique_id: &str, content_pack_for: Option<&str>, dependencies: &[(&str, Option<bool>)]) -> ModManifest {
+        ModManifest {
+            name: name.to_string(),
+            author: "Tester".to_string(),
+            version: "1.0.0".to_string(),
+            unique_id: unique_id.to_string(),
+            description: None,
+            dependencies: Some(
+                dependencies
+                    .iter()
+                    .map(|(id, is_required)| ModDependency {
+                        unique_id: id.to_string(),
+                        is_required: *is_required,
+                        minimum_version: None,
+                    })
+                    .collect(),
+            ),
+            content_pack_for: content_pack_for.map(|id| ContentPackInfo {
+                unique_id: id.to_string(),
+                minimum_version: None,
+            }),
+            update_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_missing_dependencies() {
+        let manifests = vec![
+            manifest("[CP] Seasonal Outfits", "Tester.Outfits", Some("Pathoschild.ContentPatcher"), &[
+                ("Tester.Core", None),
+                ("spacechase0.GenericModConfigMenu", Some(false)),
+            ]),
+            manifest("Outfits Core", "Tester.Core", None, &[("spacechase0.SpaceCore", Some(true))]),
+            manifest("[CP] Extra Outfits", "Tester.Extra", Some("pathoschild.contentpatcher"), &[]),
+        ];
+
+        let missing = missing_dependencies(&manifests, &[]);
+        assert_eq!(
+            missing,
+            vec![
+                (
+                    "Pathoschild.ContentPatcher".to_string(),
+                    vec!["[CP] Seasonal Outfits".to_string(), "[CP] Extra Outfits".to_string()]
+                ),
+                ("spacechase0.SpaceCore".to_string(), vec!["Outfits Core".to_string()]),
+            ]
+        );
+        assert_eq!(known_nexus_id("pathoschild.contentpatcher"), Some(1915));
+    }
+
+    #[test]
+    fn test_dependency_issues() {
+        let installed = |unique_id: &str, is_enabled: bool, content_pack_for: Option<&str>| {
+            let manifest = manifest(unique_id, unique_id, content_pack_for, &[("Tester.Core", Some(true))]);
+            Mod {
+                name: manifest.name,
+                dependencies: if unique_id == "Tester.Core" { None } else { manifest.dependencies },
+                content_pack_for: manifest.content_pack_for,
+                is_enabled,
+                ..test_mod(unique_id)
+            }
+        };
+
+        let mods = vec![
+            installed("Tester.Outfits", true, Some("Pathoschild.ContentPatcher")),
+            installed("Tester.Core", false, None),
+            // Disabled mods don't need their dependencies
+            installed("Tester.Unused", false, Some("Esca.FarmTypeManager")),
+        ];
+
+        // Enabling the unused pack would need Core; FTM isn't installed
+        let required = required_enables(&mods, &["Tester.Unused".to_string()]);
+        assert_eq!(required.len(), 1);
+        assert_eq!((required[0].id.as_str(), required[0].required_by.as_str()), ("Tester.Core", "Tester.Unused"));
+        assert!(required_enables(&mods, &["Tester.Unused".to_string(), "Tester.Core".to_string()]).is_empty());
+
+        let issues = dependency_issues(&mods);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].dependency_id, "Pathoschild.ContentPatcher");
+        assert_eq!(issues[0].problem, DependencyProblem::Missing);
+        assert_eq!(issues[0].nexus_mod_id, Some(1915));
+        assert_eq!(issues[1].dependency_id, "Tester.Core");
+        assert_eq!(issues[1].problem, DependencyProblem::Disabled);
+    }
+
+    #[test]
+    fn test_outdated_frameworks() {
+        let mut framework = manifest("Content Patcher", "Pathoschild.ContentPatcher", None, &[]);
+        framework.version = "1.30.0".to_string();
+        let mut pack = manifest("[CP] Outfits", "Tester.Outfits", Some("Pathoschild.ContentPatcher"), &[]);
+        pack.content_pack_for.as_mut().unwrap().minimum_version = Some("1.29.0".to_string());
+
+        let to_mod = |manifest: ModManifest, nexus_mod_id: Option<u32>| Mod {
+            name: manifest.name,
+            version: manifest.version,
+            dependencies: manifest.dependencies,
+            content_pack_for: manifest.content_pack_for,
+            nexus_mod_id,
+            ..test_mod(&manifest.unique_id)
+        };
+        let mut mods = vec![to_mod(framework, Some(1915)), to_mod(pack, None)];
+        assert!(dependency_issues(&mods).is_empty());
+
+        // A recorded floor above the manifest's wins
+        mods[1].framework_floors.push(crate::models::FrameworkFloor {
+            unique_id: "pathoschild.contentpatcher".to_string(),
+            minimum_version: "1.31".to_string(),
+        });
+        let issues = dependency_issues(&mods);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].problem, DependencyProblem::Outdated);
+        assert_eq!(issues[0].required_version.as_deref(), Some("1.31"));
+        assert_eq!(issues[0].installed_version.as_deref(), Some("1.30.0"));
+
+        let updates = framework_updates(&issues, &mods);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].nexus_mod_id, 1915);
+        assert_eq!(updates[0].current_version, "1.30.0");
+    }
+
+    #[test]
+    fn test_missing_nexus_requirements() {
+        let requirement = |mod_id: &str, mod_name: &str, external_requirement: bool| NexusRequirement {
+            mod_id: mod_id.to_string(),
+            mod_name: mod_name.to_string(),
+            url: None,
+            notes: None,
+            external_requirement,
+        };
+        let mut installed = manifest("Generic Mod Config Menu", "spacechase0.GenericModConfigMenu", None, &[]);
+        installed.unique_id = "Tester.Renamed".to_string();
+        let core = manifest("Core", "Pathoschild.ContentPatcher", None, &[]);
+        let mods: Vec<Mod> = [(installed, None), (core, None)]
+            .into_iter()
+            .map(|(manifest, nexus_mod_id)| Mod {
+                name: manifest.name,
+                nexus_mod_id,
+                ..test_mod(&manifest.unique_id)
+            })
+            .collect();
+
+        let requirements = vec![
+            requirement("1915", "Content Patcher", false),
+            requirement("5098", "Generic Mod Config Menu", false),
+            requirement("0", "SMAPI", true),
+            requirement("42", "The mod itself", false),
+            requirement("1348", "SpaceCore", false),
+            requirement("1348", "SpaceCore", false),
+        ];
+        let missing = missing_nexus_requirements(requirements, &mods, 42);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].mod_name, "SpaceCore");
+    }
+}
@@ -0,0 +1,182 @@
+use crate::settings::{smapi_log_path, Settings};
+use regex::Regex;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Only the tail of each log goes into the bundle
+const MAX_LOG_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageStats {
+    mods_bytes: Option<u64>,
+    downloads_bytes: u64,
+    backups_bytes: u64,
+    temp_bytes: u64,
+    logs_bytes: u64,
+}
+
+/// Build a diagnostics zip for support requests and return its path.
+/// API keys, cookies and signed download keys are redacted from everything included.
+pub fn export_diagnostics(app_handle: &AppHandle, destination: Option<PathBuf>) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let output_path = match destination {
+        Some(path) => path,
+        None => {
+            let dir = app_data_dir.join("diagnostics");
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create diagnostics directory: {}", e))?;
+            dir.join(format!("diagnostics-{}.zip", chrono::Utc::now().format("%Y%m%d-%H%M%S")))
+        }
+    };
+
+    let settings = Settings::load(app_handle).unwrap_or_default();
+
+    let file = File::create(&output_path).map_err(|e| format!("Failed to create diagnostics bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    // Versions, build and where files live
+    if let Ok(info) = crate::app_info::app_info(app_handle) {
+        add_entry(&mut zip, "app-info.json", &serde_json::to_string_pretty(&info).unwrap_or_default())?;
+    }
+
+    // Settings, with every secret-looking field blanked out
+    let mut settings_json = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    redact_json(&mut settings_json);
+    add_entry(&mut zip, "settings.json", &serde_json::to_string_pretty(&settings_json).unwrap_or_default())?;
+
+    // Installed mods
+    if !settings.game_path.is_empty() {
+        let mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+        add_entry(&mut zip, "mods.json", &serde_json::to_string_pretty(&mods).unwrap_or_default())?;
+    }
+
+    // App logs
+    let logs_dir = app_data_dir.join("logs");
+    if let Ok(entries) = fs::read_dir(&logs_dir) {
+        for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_file()) {
+            if let Some(content) = read_log_tail(&entry.path()) {
+                let name = format!("logs/{}", entry.file_name().to_string_lossy());
+                add_entry(&mut zip, &name, &redact_text(&content))?;
+            }
+        }
+    }
+
+    // Last SMAPI session log
+    if let Some(content) = smapi_log_path().and_then(|p| read_log_tail(&p)) {
+        add_entry(&mut zip, "SMAPI-latest.txt", &redact_text(&content))?;
+    }
+
+    // How long scans, installs and update checks have been taking
+    if let Ok(report) = crate::perf_metrics::report(app_handle) {
+        add_entry(&mut zip, "performance.json", &serde_json::to_string_pretty(&report).unwrap_or_default())?;
+    }
+
+    let stats = StorageStats {
+        mods_bytes: (!settings.game_path.is_empty()).then(|| dir_size(&Path::new(&settings.game_path).join("Mods"))),
+        downloads_bytes: dir_size(&settings.downloads_path(&app_data_dir)),
+        backups_bytes: dir_size(&app_data_dir.join("backups")),
+        temp_bytes: dir_size(&app_data_dir.join("temp")),
+        logs_bytes: dir_size(&logs_dir),
+    };
+    add_entry(&mut zip, "storage.json", &serde_json::to_string_pretty(&stats).unwrap_or_default())?;
+
+    zip.finish().map_err(|e| format!("Failed to finalize diagnostics bundle: {}", e))?;
+
+    info!("🩺 Diagnostics bundle written to: {}", output_path.display());
+    Ok(output_path)
+}
+
+fn add_entry(zip: &mut ZipWriter<File>, name: &str, content: &str) -> Result<(), String> {
+    zip.start_file(name, FileOptions::default())
+        .map_err(|e| format!("Failed to add {} to diagnostics bundle: {}", name, e))?;
+    zip.write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to write {} to diagnostics bundle: {}", name, e))
+}
+
+fn read_log_tail(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let start = bytes.len().saturating_sub(MAX_LOG_BYTES);
+    Some(String::from_utf8_lossy(&bytes[start..]).to_string())
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    // Webhook URLs carry their token in the path
+    ["key", "cookie", "token", "secret", "password", "webhook"]
+        .iter()
+        .any(|needle| key.contains(needle))
+}
+
+/// Blank out secret-looking string fields anywhere in a JSON document
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) && value.is_string() {
+                    if !value.as_str().unwrap_or_default().is_empty() {
+                        *value = serde_json::Value::String("<redacted>".to_string());
+                    }
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Redact key-like query parameters and headers from free text logs
+fn redact_text(text: &str) -> String {
+    let pattern = Regex::new(r#"(?i)((?:key|apikey|token|md5|expires)["']?\s*[=:,]\s*\(?\s*["']?)[^&\s"')]+"#).unwrap();
+    pattern.replace_all(text, "${1}<redacted>").to_string()
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let mut settings = serde_json::json!({
+            "gamePath": "/games/Stardew Valley",
+            "nexusApiKey": "super-secret",
+            "nexusAuthCookie": "",
+            "webhookUrl": "https://discord.com/api/webhooks/123/hook-token",
+            "accounts": [{ "name": "me", "apiKey": "other-secret" }]
+        });
+        redact_json(&mut settings);
+
+        assert_eq!(settings["gamePath"], "/games/Stardew Valley");
+        assert_eq!(settings["nexusApiKey"], "<redacted>");
+        assert_eq!(settings["nexusAuthCookie"], "");
+        assert_eq!(settings["webhookUrl"], "<redacted>");
+        assert_eq!(settings["accounts"][0]["apiKey"], "<redacted>");
+
+        let log = "GET https://api.nexusmods.com/x?key=abc123&expires=99 [(\"key\", \"abc123\")]";
+        let redacted = redact_text(log);
+        assert!(!redacted.contains("abc123"));
+        assert!(!redacted.contains("99"));
+    }
+}
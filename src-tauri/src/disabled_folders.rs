@@ -0,0 +1,196 @@
+//! Bringing disabled mod folders to one naming style. SMAPI skips folders
+//! ending in ".disabled" and folders starting with "."; a Mods folder used
+//! with other managers often has both. A hidden group folder disables every
+//! mod in it, so with the suffix style it's made visible and the mods in it
+//! get the suffix instead.
+
+use crate::mod_installer::{disabled_name, is_disabled_name, is_install_leftover, is_mod_group};
+use crate::settings::{DisabledFolderStyle, Settings};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderRename {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedRename {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizeReport {
+    pub style: DisabledFolderStyle,
+    pub dry_run: bool,
+    /// In the order they're made; a group is made visible after the mods in
+    /// it are disabled
+    pub renames: Vec<FolderRename>,
+    pub failed: Vec<FailedRename>,
+}
+
+fn subfolders(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    folders.sort();
+    folders
+}
+
+/// Renames that bring the folders in `dir` to `style`. `disable_all` is set
+/// inside a hidden group being made visible; the mods in it are renamed
+/// first, so one that can't be disabled keeps the group hidden.
+fn plan(dir: &Path, style: DisabledFolderStyle, disable_all: bool, settings: &Settings, renames: &mut Vec<FolderRename>) {
+    for folder in subfolders(dir) {
+        let name = folder.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if is_install_leftover(&name) || settings.is_unmanaged(&folder) {
+            continue;
+        }
+
+        if folder.join("manifest.json").is_file() {
+            if disable_all || is_disabled_name(&name) {
+                let to = dir.join(disabled_name(&name, style));
+                if to != folder {
+                    renames.push(FolderRename {
+                        from: folder.to_string_lossy().to_string(),
+                        to: to.to_string_lossy().to_string(),
+                    });
+                }
+            }
+        } else if name.starts_with('.') {
+            if !is_mod_group(&folder) {
+                continue;
+            }
+            if style == DisabledFolderStyle::Suffix {
+                plan(&folder, style, true, settings, renames);
+                renames.push(FolderRename {
+                    from: folder.to_string_lossy().to_string(),
+                    to: dir.join(name.trim_start_matches('.')).to_string_lossy().to_string(),
+                });
+            } else {
+                plan(&folder, style, disable_all, settings, renames);
+            }
+        } else {
+            plan(&folder, style, disable_all, settings, renames);
+        }
+    }
+}
+
+/// Rename every disabled mod folder in the game's Mods folder to `style`.
+/// With `dry_run` the report lists the renames without making them.
+/// Folders in `unmanaged_folders` are left alone.
+pub fn normalize(settings: &Settings, style: DisabledFolderStyle, dry_run: bool) -> NormalizeReport {
+    let mods_dir = Path::new(&settings.game_path).join("Mods");
+    let mut renames = Vec::new();
+    plan(&mods_dir, style, false, settings, &mut renames);
+
+    let mut failed = Vec::new();
+    if !dry_run {
+        for rename in &renames {
+            let (from, to) = (Path::new(&rename.from), Path::new(&rename.to));
+            let renamed = if !from.exists() {
+                Err("Folder not found".to_string())
+            } else if to.exists() {
+                Err(format!("{} already exists", to.display()))
+            } else if failed.iter().any(|f: &FailedRename| Path::new(&f.path).starts_with(from)) {
+                Err("A mod in this group could not be disabled, so it stays hidden".to_string())
+            } else {
+                fs::rename(from, to).map_err(|e| e.to_string())
+            };
+            if let Err(error) = renamed {
+                failed.push(FailedRename {
+                    path: rename.from.clone(),
+                    error,
+                });
+            }
+        }
+        info!("🏷️ Renamed {} disabled folders ({} failed)", renames.len() - failed.len(), failed.len());
+    }
+
+    NormalizeReport {
+        style,
+        dry_run,
+        renames,
+        failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_both_ways() {
+        let game_dir = std::env::temp_dir().join(format!("tc_disabled_folders_{}", uuid::Uuid::new_v4()));
+        let mods_dir = game_dir.join("Mods");
+        let leftover = ".X.staging-0123456789abcdef0123456789abcdef";
+        for dir in [".LookupAnything", "Off.disabled", ".Group/Pack A", ".Group/.Pack B", "Visible", leftover] {
+            fs::create_dir_all(mods_dir.join(dir)).unwrap();
+            fs::write(mods_dir.join(dir).join("manifest.json"), "{}").unwrap();
+        }
+        fs::create_dir_all(mods_dir.join(".git")).unwrap();
+        fs::create_dir_all(mods_dir.join(".backup/2024/Old")).unwrap();
+        fs::write(mods_dir.join(".backup/2024/Old/manifest.json"), "{}").unwrap();
+        let settings = Settings {
+            game_path: game_dir.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+
+        let preview = normalize(&settings, DisabledFolderStyle::Suffix, true);
+        assert_eq!(preview.renames.len(), 4);
+        assert!(mods_dir.join(".LookupAnything").is_dir());
+
+        let report = normalize(&settings, DisabledFolderStyle::Suffix, false);
+        assert!(report.failed.is_empty());
+        for dir in ["LookupAnything.disabled", "Off.disabled", "Group/Pack A.disabled", "Group/Pack B.disabled"] {
+            assert!(mods_dir.join(dir).join("manifest.json").is_file(), "{}", dir);
+        }
+        assert!(mods_dir.join("Visible").is_dir());
+        assert!(mods_dir.join(".git").is_dir());
+        assert!(mods_dir.join(".backup/2024/Old").is_dir());
+        assert!(!crate::mod_installer::scan_mods(&game_dir).iter().any(|m| m.path.contains("backup")));
+        assert!(mods_dir.join(leftover).is_dir());
+
+        let report = normalize(&settings, DisabledFolderStyle::DotPrefix, false);
+        assert_eq!(report.renames.len(), 4);
+        for dir in [".LookupAnything", ".Off", "Group/.Pack A", "Group/.Pack B"] {
+            assert!(mods_dir.join(dir).join("manifest.json").is_file(), "{}", dir);
+        }
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+
+    #[test]
+    fn test_group_stays_hidden_when_a_mod_in_it_cannot_be_disabled() {
+        let game_dir = std::env::temp_dir().join(format!("tc_disabled_folders_{}", uuid::Uuid::new_v4()));
+        let mods_dir = game_dir.join("Mods");
+        for dir in [".Group/A", ".Group/A.disabled"] {
+            fs::create_dir_all(mods_dir.join(dir)).unwrap();
+            fs::write(mods_dir.join(dir).join("manifest.json"), "{}").unwrap();
+        }
+        let settings = Settings {
+            game_path: game_dir.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+
+        let report = normalize(&settings, DisabledFolderStyle::Suffix, false);
+        assert_eq!(report.renames.last().unwrap().to, mods_dir.join("Group").to_string_lossy());
+        assert_eq!(report.failed.len(), 2);
+        assert!(mods_dir.join(".Group/A").is_dir());
+        assert!(!mods_dir.join("Group").exists());
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
@@ -1,14 +1,24 @@
+use crate::app_error::AppError;
+use crate::events::{self, AppEvent};
+use crate::nexus_api::{retry_after_from_headers, NexusApiError, NexusClient, USER_AGENT};
+use crate::nexus_meta::NexusMeta;
 use crate::nxm_protocol::NxmUrl;
+use crate::settings::MeteredDownloads;
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
+use std::io::{Read, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Manager};
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::{Mutex, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,10 +28,27 @@ pub struct DownloadTask {
     pub nxm_url: NxmUrl,
     pub mod_name: Option<String>,
     pub file_name: String,
+    /// Version of the file on Nexus, if known
+    #[serde(default)]
+    pub file_version: Option<String>,
     pub status: DownloadStatus,
     pub file_path: Option<PathBuf>,
     pub bytes_downloaded: u64,
     pub bytes_total: Option<u64>,
+    /// Size of the archive's contents once installed, if Nexus has a
+    /// content preview for the file
+    #[serde(default)]
+    pub install_size: Option<u64>,
+    /// Name of the Nexus account that queued this download
+    #[serde(default)]
+    pub account: Option<String>,
+    /// Installed by whoever queued it (e.g. "Update all") instead of the
+    /// auto-installer
+    #[serde(default)]
+    pub managed_install: bool,
+    /// The user agreed to download this on a metered connection
+    #[serde(default)]
+    pub metered_approved: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -31,7 +58,14 @@ pub enum DownloadStatus {
     Downloading,
     Paused,
     Completed,
-    Failed { error: String },
+    Failed {
+        error: String,
+        #[serde(default)]
+        retryable: bool,
+    },
+    /// Nexus answered 429 Too Many Requests; the download restarts automatically
+    #[serde(rename = "rateLimited", rename_all = "camelCase")]
+    RateLimited { retry_at: DateTime<Utc> },
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -40,23 +74,504 @@ pub struct DownloadProgress {
     pub download_id: String,
     pub bytes_downloaded: u64,
     pub bytes_total: Option<u64>,
+    /// Smoothed current speed, see `SpeedMeter`
     pub speed_bps: u64,
+    /// Average speed since the download started
+    pub average_speed_bps: u64,
     pub eta_seconds: Option<u64>,
     pub progress_percent: f64,
 }
 
+/// Totals over the whole queue, so a batch can be announced as one figure
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueProgress {
+    pub files_total: usize,
+    pub files_remaining: usize,
+    pub bytes_downloaded: u64,
+    /// Sum of the sizes we know; downloads of unknown size only count once
+    /// they finish
+    pub bytes_total: u64,
+    pub speed_bps: u64,
+    pub eta_seconds: Option<u64>,
+    pub progress_percent: f64,
+}
+
+/// Progress over every download still in the queue. Failed downloads are
+/// left out, finished ones count as done until the list is cleared.
+fn queue_progress(tasks: &[DownloadTask], speed_bps: u64) -> QueueProgress {
+    let mut progress = QueueProgress {
+        files_total: 0,
+        files_remaining: 0,
+        bytes_downloaded: 0,
+        bytes_total: 0,
+        speed_bps,
+        eta_seconds: None,
+        progress_percent: 0.0,
+    };
+
+    for task in tasks {
+        match task.status {
+            DownloadStatus::Failed { .. } => continue,
+            DownloadStatus::Completed => {
+                let size = task.bytes_total.unwrap_or(task.bytes_downloaded);
+                progress.bytes_downloaded += size;
+                progress.bytes_total += size;
+            }
+            _ => {
+                progress.files_remaining += 1;
+                if let Some(total) = task.bytes_total {
+                    progress.bytes_downloaded += task.bytes_downloaded.min(total);
+                    progress.bytes_total += total;
+                }
+            }
+        }
+        progress.files_total += 1;
+    }
+
+    let bytes_left = progress.bytes_total - progress.bytes_downloaded;
+    if progress.bytes_total > 0 {
+        progress.progress_percent = progress.bytes_downloaded as f64 / progress.bytes_total as f64 * 100.0;
+    } else if progress.files_total > 0 {
+        let done = progress.files_total - progress.files_remaining;
+        progress.progress_percent = done as f64 / progress.files_total as f64 * 100.0;
+    }
+    if speed_bps > 0 && progress.files_remaining > 0 {
+        progress.eta_seconds = Some(bytes_left / speed_bps);
+    }
+    progress
+}
+
+/// Sidecar record written next to a finished archive so a later download of the
+/// same file can be recognised and skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchiveRecord {
+    mod_id: u32,
+    file_id: u32,
+    size: u64,
+    md5: String,
+    #[serde(default)]
+    account: Option<String>,
+    #[serde(default)]
+    downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl ArchiveRecord {
+    fn path_for(archive_path: &Path) -> PathBuf {
+        let mut name = archive_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".meta");
+        archive_path.with_file_name(name)
+    }
+
+    fn load(archive_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path_for(archive_path)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self, archive_path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(Self::path_for(archive_path), json)
+    }
+}
+
+/// Sidecar record of a `.part` file, enough to resume it with a fresh
+/// download link after a pause, a failed attempt or an app crash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct PartRecord {
+    mod_id: u32,
+    file_id: u32,
+    /// Bytes known to be flushed to the .part file; anything after is dropped
+    bytes: u64,
+    total: Option<u64>,
+    /// Validators sent as If-Range, so a changed file is downloaded afresh
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+}
+
+impl PartRecord {
+    fn load(part_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(ArchiveRecord::path_for(part_path)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    async fn save(&self, part_path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        tokio::fs::write(ArchiveRecord::path_for(part_path), json).await
+    }
+
+    fn remove(part_path: &Path) {
+        let _ = std::fs::remove_file(ArchiveRecord::path_for(part_path));
+    }
+
+    /// The record of a partial download of this file that can be picked up
+    fn resumable(part_path: &Path, nxm_url: &NxmUrl) -> Option<Self> {
+        let record = Self::load(part_path)?;
+        let on_disk = std::fs::metadata(part_path).ok()?.len();
+        (record.mod_id == nxm_url.mod_id
+            && record.file_id == nxm_url.file_id
+            && record.bytes > 0
+            && on_disk >= record.bytes
+            && record.total.is_none_or(|total| record.bytes < total))
+        .then_some(record)
+    }
+}
+
+/// Where a ranged response continues the partial file, if it does.
+/// Content-Range must start at the bytes we have and agree on the total.
+fn resume_offset(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, record: &PartRecord) -> Option<u64> {
+    if status != reqwest::StatusCode::PARTIAL_CONTENT {
+        return None;
+    }
+    let range = headers.get("content-range")?.to_str().ok()?;
+    let (span, total) = range.strip_prefix("bytes ")?.split_once('/')?;
+    let start = span.split_once('-')?.0.trim().parse::<u64>().ok()?;
+    let total_matches = match (total.trim().parse::<u64>().ok(), record.total) {
+        (Some(total), Some(expected)) => total == expected,
+        _ => true,
+    };
+    (start == record.bytes && total_matches).then_some(start)
+}
+
+/// Delete the partial file of a download and its resume record
+async fn remove_partial(download_dir: &Path, file_name: &str) {
+    let part_path = DownloadManagerHandle::part_path(&download_dir.join(file_name));
+    if part_path.exists() {
+        if let Err(e) = tokio::fs::remove_file(&part_path).await {
+            warn!("Failed to remove partial download: {}", e);
+        }
+    }
+    PartRecord::remove(&part_path);
+}
+
+/// The queue is saved here so downloads survive an app restart
+const QUEUE_FILE_NAME: &str = "download_queue.json";
+
+/// Names shown for a download, looked up from the Nexus API
+#[derive(Debug, Default)]
+struct DisplayInfo {
+    mod_name: Option<String>,
+    file_name: Option<String>,
+    file_version: Option<String>,
+    size_bytes: Option<u64>,
+    install_size: Option<u64>,
+}
+
+fn queue_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join(QUEUE_FILE_NAME))
+}
+
+/// Write the queue to disk (atomically); errors are only logged
+async fn save_queue(app_handle: &AppHandle, queue: &Mutex<VecDeque<DownloadTask>>) {
+    let Some(path) = queue_path(app_handle) else {
+        return;
+    };
+
+    let json = {
+        let queue = queue.lock().await;
+        match serde_json::to_string_pretty(&*queue) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize download queue: {}", e);
+                return;
+            }
+        }
+    };
+
+    let tmp_path = path.with_extension("json.tmp");
+    let result = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&tmp_path, json).await?;
+        tokio::fs::rename(&tmp_path, &path).await
+    }
+    .await;
+
+    if let Err(e) = result {
+        warn!("Failed to save download queue: {}", e);
+    }
+}
+
+/// A saved task as it should come back after a restart: anything that was
+/// in flight is queued again (resuming its .part file), paused downloads stay
+/// paused, finished and failed entries stay as history. Unfinished managed
+/// installs are dropped, nothing is left waiting to install them.
+fn restored_task(mut task: DownloadTask) -> Option<DownloadTask> {
+    match task.status {
+        DownloadStatus::Completed | DownloadStatus::Failed { .. } => {}
+        _ if task.managed_install => return None,
+        DownloadStatus::Paused => task.bytes_downloaded = 0,
+        _ => {
+            task.status = DownloadStatus::Queued;
+            task.bytes_downloaded = 0;
+        }
+    }
+    Some(task)
+}
+
+/// Nexus metadata to store with a mod installed from a downloaded archive
+pub fn archive_nexus_meta(archive_path: &Path, mod_id: u32, file_id: u32) -> NexusMeta {
+    let mut meta = NexusMeta::new(mod_id, file_id);
+    if let Some(record) = ArchiveRecord::load(archive_path) {
+        if record.mod_id == mod_id && record.file_id == file_id {
+            meta.md5 = Some(record.md5);
+            meta.downloaded_at = record.downloaded_at;
+        }
+    }
+    meta
+}
+
+/// Compute the MD5 of a file on disk as a lowercase hex string
+pub fn compute_md5(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// How a download failed, which decides what happens to the task afterwards
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DownloadErrorKind {
+    /// Permanent failure, shown to the user as-is
+    Fatal,
+    /// Worth simply trying again (e.g. truncated transfer)
+    Retryable,
+    /// Nexus rate limited us; wait this long and restart automatically
+    RateLimited(Duration),
+    /// Cancelled by the user; the task is dropped from the queue
+    Cancelled,
+    /// Too large for a metered connection; paused until the user agrees
+    Metered {
+        bytes_total: u64,
+        needs_confirmation: bool,
+    },
+}
+
+/// Error produced while executing a download
+#[derive(Debug)]
+struct DownloadError {
+    message: String,
+    kind: DownloadErrorKind,
+}
+
+impl DownloadError {
+    fn retryable(message: String) -> Self {
+        Self {
+            message,
+            kind: DownloadErrorKind::Retryable,
+        }
+    }
+
+    fn rate_limited(retry_after: Duration) -> Self {
+        Self {
+            message: format!("Rate limited by Nexus Mods, retrying in {}s", retry_after.as_secs()),
+            kind: DownloadErrorKind::RateLimited(retry_after),
+        }
+    }
+
+    fn cancelled() -> Self {
+        Self {
+            message: "Cancelled by user".to_string(),
+            kind: DownloadErrorKind::Cancelled,
+        }
+    }
+
+    fn metered(bytes_total: u64, needs_confirmation: bool) -> Self {
+        Self {
+            message: "Waiting to download on a metered connection".to_string(),
+            kind: DownloadErrorKind::Metered {
+                bytes_total,
+                needs_confirmation,
+            },
+        }
+    }
+}
+
+impl From<String> for DownloadError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            kind: DownloadErrorKind::Fatal,
+        }
+    }
+}
+
+/// Downloads at least this large wait for the user on a metered connection
+const METERED_CONFIRM_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Delay before the first automatic retry, doubled on every further attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Upper bound for the delay between automatic retries
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Exponential backoff before retry number `attempt` (starting at 0)
+fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// How quickly the smoothed speed follows changes: after this long at a new
+/// rate it has moved ~63% of the way there
+const SPEED_SMOOTHING: Duration = Duration::from_secs(2);
+
+/// Turns the jumpy per-window rate of a download into a stable speed (an
+/// exponential moving average weighted by window length) and an overall
+/// average
+struct SpeedMeter {
+    started: Instant,
+    smoothed_bps: Option<f64>,
+}
+
+impl SpeedMeter {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            smoothed_bps: None,
+        }
+    }
+
+    /// Feed `bytes` received over `elapsed`, returning the smoothed speed
+    fn sample(&mut self, bytes: u64, elapsed: Duration) -> u64 {
+        let elapsed = elapsed.as_secs_f64();
+        if elapsed <= 0.0 {
+            return self.smoothed_bps.unwrap_or(0.0) as u64;
+        }
+
+        let rate = bytes as f64 / elapsed;
+        let smoothed = match self.smoothed_bps {
+            Some(previous) => {
+                let weight = 1.0 - (-elapsed / SPEED_SMOOTHING.as_secs_f64()).exp();
+                previous + weight * (rate - previous)
+            }
+            None => rate,
+        };
+        self.smoothed_bps = Some(smoothed);
+        smoothed as u64
+    }
+
+    fn average_bps(&self, downloaded: u64, now: Instant) -> u64 {
+        let elapsed = now.duration_since(self.started).as_secs_f64();
+        if elapsed > 0.0 {
+            (downloaded as f64 / elapsed) as u64
+        } else {
+            0
+        }
+    }
+}
+
+/// Progress of one download is reported at most this often
+const PROGRESS_MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// ...and at least this often while data is arriving, however little
+const PROGRESS_MAX_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Progress of all downloads is sent to the frontend in one batch at most
+/// this often
+const PROGRESS_BATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The resume record of a download is rewritten once at least this much
+/// time has passed...
+const PART_RECORD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// ...and at least this many bytes arrived since it was last written
+const PART_RECORD_BYTES: u64 = 1024 * 1024;
+
+/// Bytes that should arrive before progress is worth reporting again:
+/// 0.5% of the file, between 64 KiB and 8 MiB
+fn progress_byte_step(total: Option<u64>) -> u64 {
+    match total {
+        Some(total) => (total / 200).clamp(64 * 1024, 8 * 1024 * 1024),
+        None => 256 * 1024,
+    }
+}
+
+/// Whether a download's progress should be reported, given the time and
+/// bytes since it was last reported
+fn should_report_progress(elapsed: Duration, bytes: u64, total: Option<u64>) -> bool {
+    elapsed >= PROGRESS_MAX_INTERVAL || (elapsed >= PROGRESS_MIN_INTERVAL && bytes >= progress_byte_step(total))
+}
+
+/// Collects the latest progress of every download so simultaneous downloads
+/// share one `download-progress-batch` event instead of one each
+struct ProgressBatch {
+    pending: HashMap<String, DownloadProgress>,
+    /// Latest speed of every running download, for the queue totals
+    speeds: HashMap<String, u64>,
+    /// Average speed of the last finished download, to estimate new ones
+    recent_average_bps: Option<u64>,
+    last_flush: Instant,
+}
+
+impl ProgressBatch {
+    fn new(now: Instant) -> Self {
+        Self {
+            pending: HashMap::new(),
+            speeds: HashMap::new(),
+            recent_average_bps: None,
+            last_flush: now,
+        }
+    }
+
+    /// Record a progress update, returning the batch to send if it's due
+    fn push(&mut self, progress: DownloadProgress, now: Instant) -> Option<Vec<DownloadProgress>> {
+        self.speeds.insert(progress.download_id.clone(), progress.speed_bps);
+        self.pending.insert(progress.download_id.clone(), progress);
+        if now.duration_since(self.last_flush) < PROGRESS_BATCH_INTERVAL {
+            return None;
+        }
+
+        self.last_flush = now;
+        let mut batch: Vec<DownloadProgress> = self.pending.drain().map(|(_, p)| p).collect();
+        batch.sort_by(|a, b| a.download_id.cmp(&b.download_id));
+        Some(batch)
+    }
+
+    /// Drop the pending update and speed of a download that stopped running
+    fn remove(&mut self, download_id: &str) {
+        self.pending.remove(download_id);
+        self.speeds.remove(download_id);
+    }
+
+    fn total_speed(&self) -> u64 {
+        self.speeds.values().sum()
+    }
+}
+
 #[derive(Clone)]
 pub struct DownloadManager {
     queue: Arc<Mutex<VecDeque<DownloadTask>>>,
     active: Arc<Mutex<HashMap<String, DownloadTask>>>,
     semaphore: Arc<Semaphore>,
-    download_dir: PathBuf,
+    /// Number of permits the semaphore is meant to hold
+    max_concurrent: Arc<Mutex<usize>>,
+    /// Cancellation tokens of downloads currently in flight
+    cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// No new requests are started before this instant (set on 429 responses)
+    cooldown_until: Arc<Mutex<Option<Instant>>>,
+    progress: Arc<Mutex<ProgressBatch>>,
+    /// Changed by `relocate`; each download keeps the folder it started in
+    download_dir: Arc<Mutex<PathBuf>>,
     app_handle: AppHandle,
     client: Client,
 }
 
 impl DownloadManager {
     pub fn new(app_handle: AppHandle, download_dir: PathBuf, max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
         let client = Client::builder()
             .timeout(Duration::from_secs(300)) // 5 minutes timeout
             .build()
@@ -66,7 +581,11 @@ impl DownloadManager {
             queue: Arc::new(Mutex::new(VecDeque::new())),
             active: Arc::new(Mutex::new(HashMap::new())),
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
-            download_dir,
+            max_concurrent: Arc::new(Mutex::new(max_concurrent)),
+            cancel_tokens: Arc::new(Mutex::new(HashMap::new())),
+            cooldown_until: Arc::new(Mutex::new(None)),
+            progress: Arc::new(Mutex::new(ProgressBatch::new(Instant::now()))),
+            download_dir: Arc::new(Mutex::new(download_dir)),
             app_handle,
             client,
         }
@@ -74,6 +593,15 @@ impl DownloadManager {
 
     /// Add a download to the queue
     pub async fn add_to_queue(&self, nxm_url: NxmUrl) -> Result<String, String> {
+        self.enqueue(nxm_url, false).await
+    }
+
+    /// Add a download the caller installs itself; the auto-installer skips it
+    pub async fn add_managed_to_queue(&self, nxm_url: NxmUrl) -> Result<String, String> {
+        self.enqueue(nxm_url, true).await
+    }
+
+    async fn enqueue(&self, nxm_url: NxmUrl, managed_install: bool) -> Result<String, String> {
         // Check if mod is already installed
         let settings = crate::settings::Settings::load(&self.app_handle)
             .map_err(|e| format!("Failed to load settings: {}", e))?;
@@ -96,18 +624,27 @@ impl DownloadManager {
 
         let download_id = Uuid::new_v4().to_string();
 
-        // Generate filename from mod_id and file_id
-        let file_name = format!("mod_{}_file_{}.zip", nxm_url.mod_id, nxm_url.file_id);
+        let info = self.fetch_display_info(&settings, &nxm_url).await;
+
+        // Fall back to a filename from mod_id and file_id
+        let file_name = info
+            .file_name
+            .unwrap_or_else(|| format!("mod_{}_file_{}.zip", nxm_url.mod_id, nxm_url.file_id));
 
         let task = DownloadTask {
             id: download_id.clone(),
             nxm_url: nxm_url.clone(),
-            mod_name: None, // Will be fetched later if needed
+            mod_name: info.mod_name,
             file_name: file_name.clone(),
+            file_version: info.file_version,
             status: DownloadStatus::Queued,
             file_path: None,
             bytes_downloaded: 0,
-            bytes_total: None,
+            bytes_total: info.size_bytes,
+            install_size: info.install_size,
+            account: settings.active_account_name(),
+            managed_install,
+            metered_approved: false,
         };
 
         // Add to queue
@@ -115,18 +652,159 @@ impl DownloadManager {
             let mut queue = self.queue.lock().await;
             queue.push_back(task.clone());
         }
+        save_queue(&self.app_handle, &self.queue).await;
 
         // Emit event to frontend
-        let _ = self.app_handle.emit("download-queued", &task);
+        events::emit(&self.app_handle, AppEvent::DownloadQueued(Box::new(task.clone())));
 
-        // Start processing if permits available
         // Start processing if permits available
         self.start_next_download();
 
+        // Links from the website are one file each; offer the rest of the set
+        if !nxm_url.is_direct() {
+            let app_handle = self.app_handle.clone();
+            let id = download_id.clone();
+            tokio::spawn(async move {
+                crate::mod_files::offer_required_files(&app_handle, &nxm_url, id).await;
+            });
+        }
+
         Ok(download_id)
     }
 
-    /// Start the next download from the queue if permits available
+    /// Bring back the queue saved before the last exit. Tasks saved without
+    /// a mod name or file version get them looked up again.
+    pub async fn restore_queue(&self) -> Result<usize, String> {
+        let Some(path) = queue_path(&self.app_handle) else {
+            return Ok(0);
+        };
+
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("Failed to read download queue: {}", e)),
+        };
+        let saved: Vec<DownloadTask> =
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse download queue: {}", e))?;
+
+        let settings = crate::settings::Settings::load(&self.app_handle)?;
+        let download_dir = self.download_dir.lock().await.clone();
+        let mut restored = Vec::with_capacity(saved.len());
+        for task in saved {
+            let file_name = task.file_name.clone();
+            let Some(mut task) = restored_task(task) else {
+                info!("🗑️ Dropping interrupted managed download {}", file_name);
+                remove_partial(&download_dir, &file_name).await;
+                continue;
+            };
+            if task.mod_name.is_none() || task.file_version.is_none() {
+                let info = self.fetch_display_info(&settings, &task.nxm_url).await;
+                task.mod_name = task.mod_name.or(info.mod_name);
+                task.file_version = task.file_version.or(info.file_version);
+            }
+            restored.push(task);
+        }
+
+        let count = restored.len();
+        {
+            let mut queue = self.queue.lock().await;
+            // Links queued while we were restoring go after the saved ones
+            let newer: Vec<DownloadTask> = queue
+                .drain(..)
+                .filter(|t| !restored.iter().any(|r| r.id == t.id))
+                .collect();
+            queue.extend(restored.iter().cloned());
+            queue.extend(newer);
+        }
+        save_queue(&self.app_handle, &self.queue).await;
+
+        for task in restored {
+            events::emit(&self.app_handle, AppEvent::DownloadQueued(Box::new(task)));
+        }
+        self.start_next_download();
+
+        Ok(count)
+    }
+
+    /// Look up the mod name, original archive name, version and sizes of an
+    /// nxm link. Failures only cost the nicer names and the size estimates,
+    /// so they're logged and ignored.
+    async fn fetch_display_info(&self, settings: &crate::settings::Settings, nxm_url: &NxmUrl) -> DisplayInfo {
+        let nexus = NexusClient::with_client(self.client.clone(), &self.app_handle, settings);
+        if !nexus.has_api_key() {
+            return DisplayInfo::default();
+        }
+
+        let (mod_info, file_info) = tokio::join!(
+            nexus.mod_info(&nxm_url.game, nxm_url.mod_id),
+            nexus.file_info(&nxm_url.game, nxm_url.mod_id, nxm_url.file_id),
+        );
+
+        let mod_name = mod_info
+            .inspect_err(|e| warn!("Failed to fetch mod info for {}: {}", nxm_url.mod_id, e))
+            .ok()
+            .and_then(|info| info.name)
+            .filter(|name| !name.trim().is_empty());
+
+        let file_info = file_info
+            .inspect_err(|e| warn!("Failed to fetch file info for {}: {}", nxm_url.file_id, e))
+            .ok();
+
+        // Only keep the last path component, the name ends up joined to the download dir
+        let file_name = file_info
+            .as_ref()
+            .and_then(|info| info.file_name.as_deref())
+            .and_then(|name| Path::new(name).file_name().map(|n| n.to_string_lossy().to_string()))
+            .filter(|name| !name.starts_with('.'));
+
+        let install_size = match file_info.as_ref().and_then(|info| info.content_preview_link.as_deref()) {
+            Some(link) => nexus
+                .unpacked_size(link)
+                .await
+                .inspect_err(|e| warn!("Failed to fetch content preview for {}: {}", nxm_url.file_id, e))
+                .ok(),
+            None => None,
+        };
+
+        DisplayInfo {
+            mod_name,
+            file_name,
+            size_bytes: file_info.as_ref().and_then(|info| info.size_bytes()),
+            install_size,
+            file_version: file_info.and_then(|info| info.version),
+        }
+    }
+
+    /// Change how many downloads may run at once
+    ///
+    /// Growing takes effect immediately. Shrinking never interrupts running
+    /// downloads; the extra permits are retired as those downloads finish.
+    pub async fn set_max_concurrent(&self, max_concurrent: usize) {
+        let max_concurrent = max_concurrent.max(1);
+        let mut current = self.max_concurrent.lock().await;
+
+        if max_concurrent > *current {
+            self.semaphore.add_permits(max_concurrent - *current);
+        } else if max_concurrent < *current {
+            let excess = *current - max_concurrent;
+            let remaining = excess - self.semaphore.forget_permits(excess);
+            if remaining > 0 {
+                let semaphore = self.semaphore.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(remaining as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+
+        info!("⚙️  Max concurrent downloads: {} -> {}", *current, max_concurrent);
+        *current = max_concurrent;
+        drop(current);
+
+        self.start_next_download();
+    }
+
     /// Start the next download from the queue if permits available
     fn start_next_download(&self) {
         let manager = self.clone();
@@ -135,38 +813,62 @@ impl DownloadManager {
         });
     }
 
+    /// Re-queue rate limited downloads once the cool-down has passed
+    fn schedule_resume_after(&self, delay: Duration) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            {
+                let mut queue = manager.queue.lock().await;
+                for task in queue.iter_mut() {
+                    if matches!(task.status, DownloadStatus::RateLimited { .. }) {
+                        task.status = DownloadStatus::Queued;
+                    }
+                }
+            }
+
+            info!("⏱️  Rate limit cool-down over, resuming downloads");
+            manager.start_next_download();
+        });
+    }
+
     /// Internal async function to process the next download
     async fn process_next_download(&self) {
+        // Don't start anything while Nexus has asked us to back off
+        {
+            let cooldown = self.cooldown_until.lock().await;
+            if cooldown.map(|until| Instant::now() < until).unwrap_or(false) {
+                return;
+            }
+        }
+
         // Try to acquire a permit without blocking
         if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
-            // Get next queued download
+            // Claim the next queued download in one lock, so two callers
+            // can't both pick it
             let task = {
                 let mut queue = self.queue.lock().await;
-                queue.iter_mut().find(|t| matches!(t.status, DownloadStatus::Queued)).cloned()
+                queue.iter_mut().find(|t| matches!(t.status, DownloadStatus::Queued)).map(|t| {
+                    t.status = DownloadStatus::Downloading;
+                    t.clone()
+                })
             };
 
-            if let Some(mut task) = task {
-                task.status = DownloadStatus::Downloading;
-
+            if let Some(task) = task {
                 // Move to active
                 {
                     let mut active = self.active.lock().await;
                     active.insert(task.id.clone(), task.clone());
                 }
 
-                // Update queue status
-                {
-                    let mut queue = self.queue.lock().await;
-                    if let Some(t) = queue.iter_mut().find(|t| t.id == task.id) {
-                        t.status = DownloadStatus::Downloading;
-                    }
-                }
-
                 // Spawn download task
                 let manager = DownloadManagerHandle {
                     queue: self.queue.clone(),
                     active: self.active.clone(),
-                    download_dir: self.download_dir.clone(),
+                    cooldown_until: self.cooldown_until.clone(),
+                    progress: self.progress.clone(),
+                    download_dir: self.download_dir.lock().await.clone(),
                     app_handle: self.app_handle.clone(),
                     client: self.client.clone(),
                 };
@@ -174,17 +876,51 @@ impl DownloadManager {
                 // Clone self to trigger next download
                 let next_trigger = self.clone();
 
+                // Fill any other free slots
+                self.start_next_download();
+
+                let cancel_token = CancellationToken::new();
+                {
+                    let mut tokens = self.cancel_tokens.lock().await;
+                    tokens.insert(task.id.clone(), cancel_token.clone());
+                }
+
                 tokio::spawn(async move {
-                    let result = manager.execute_download(task.clone()).await;
+                    // Dropping the download future aborts the request and stream immediately
+                    let result = tokio::select! {
+                        result = manager.execute_download(task.clone()) => result,
+                        _ = cancel_token.cancelled() => Err(DownloadError::cancelled()),
+                    };
 
                     // Release permit when done
                     drop(permit);
 
+                    {
+                        let mut tokens = next_trigger.cancel_tokens.lock().await;
+                        tokens.remove(&task.id);
+                    }
+
                     // Handle completion
                     match result {
                         Ok(file_path) => {
                             manager.complete_download(task.id, file_path).await;
                         }
+                        Err(DownloadError { kind: DownloadErrorKind::RateLimited(delay), .. }) => {
+                            manager.rate_limit_download(task.id, delay).await;
+                            next_trigger.schedule_resume_after(delay);
+                        }
+                        Err(DownloadError { kind: DownloadErrorKind::Cancelled, .. }) => {
+                            manager.discard_cancelled_download(&task).await;
+                        }
+                        Err(DownloadError {
+                            kind: DownloadErrorKind::Metered {
+                                bytes_total,
+                                needs_confirmation,
+                            },
+                            ..
+                        }) => {
+                            manager.hold_metered_download(task.id, bytes_total, needs_confirmation).await;
+                        }
                         Err(e) => {
                             manager.fail_download(task.id, e).await;
                         }
@@ -204,38 +940,309 @@ impl DownloadManager {
     }
 
     /// Cancel a download
-    pub async fn cancel_download(&self, download_id: &str) -> Result<(), String> {
-        // Remove from queue if queued
+    pub async fn cancel_download(&self, download_id: &str) -> Result<(), AppError> {
+        // If in flight, abort it; the download task removes it from the queue
+        // and deletes the partial file once the stream has stopped
         {
-            let mut queue = self.queue.lock().await;
-            if let Some(pos) = queue.iter().position(|t| t.id == download_id) {
-                queue.remove(pos);
-                let _ = self.app_handle.emit("download-cancelled", download_id);
+            let tokens = self.cancel_tokens.lock().await;
+            if let Some(token) = tokens.get(download_id) {
+                token.cancel();
+                events::emit(&self.app_handle, AppEvent::DownloadCancelled(download_id.to_string()));
                 return Ok(());
             }
         }
 
-        // If active, we need to implement cancellation token (TODO for now)
-        // For now, just mark as failed
+        // Otherwise just remove it from the queue, with any partial file
+        // kept from a pause
+        let removed = {
+            let mut queue = self.queue.lock().await;
+            let pos = queue.iter().position(|t| t.id == download_id);
+            pos.and_then(|pos| queue.remove(pos))
+        };
+
+        let Some(removed) = removed else {
+            return Err(AppError::DownloadNotFound { id: download_id.to_string() });
+        };
+        let download_dir = self.download_dir.lock().await.clone();
+        remove_partial(&download_dir, &removed.file_name).await;
+
+        save_queue(&self.app_handle, &self.queue).await;
+        events::emit(&self.app_handle, AppEvent::DownloadCancelled(download_id.to_string()));
+        Ok(())
+    }
+
+    /// Move a download to `new_index` in the queue (clamped to the queue length)
+    ///
+    /// Queued downloads are started in queue order, so this decides what runs next.
+    pub async fn move_download(&self, download_id: &str, new_index: usize) -> Result<Vec<DownloadTask>, AppError> {
+        let queue_state = {
+            let mut queue = self.queue.lock().await;
+            let pos = queue
+                .iter()
+                .position(|t| t.id == download_id)
+                .ok_or_else(|| AppError::DownloadNotFound { id: download_id.to_string() })?;
+
+            let task = queue.remove(pos).expect("position is in bounds");
+            let new_index = new_index.min(queue.len());
+            queue.insert(new_index, task);
+
+            queue.iter().cloned().collect::<Vec<_>>()
+        };
+        save_queue(&self.app_handle, &self.queue).await;
+
+        events::emit(&self.app_handle, AppEvent::DownloadQueueReordered(queue_state.clone()));
+        Ok(queue_state)
+    }
+
+    /// Move a download to the front of the queue so it starts next
+    pub async fn prioritize_download(&self, download_id: &str) -> Result<Vec<DownloadTask>, AppError> {
+        self.move_download(download_id, 0).await
+    }
+
+    /// Re-queue a failed download, reusing its stored NXM link
+    pub async fn retry_download(&self, download_id: &str) -> Result<(), AppError> {
+        let task = {
+            let mut queue = self.queue.lock().await;
+            let task = queue
+                .iter_mut()
+                .find(|t| t.id == download_id)
+                .ok_or_else(|| AppError::DownloadNotFound { id: download_id.to_string() })?;
+
+            if !matches!(task.status, DownloadStatus::Failed { .. }) {
+                return Err("Only failed downloads can be retried".into());
+            }
+
+            task.status = DownloadStatus::Queued;
+            task.file_path = None;
+            task.bytes_downloaded = 0;
+            task.bytes_total = None;
+            task.clone()
+        };
+
+        save_queue(&self.app_handle, &self.queue).await;
+
+        info!("🔁 Manually retrying download: {}", download_id);
+        events::emit(&self.app_handle, AppEvent::DownloadQueued(Box::new(task)));
+
+        self.start_next_download();
+        Ok(())
+    }
+
+    /// Pause every unfinished download. Downloads in flight are stopped and
+    /// continue from their .part file when resumed. Returns the queue.
+    pub async fn pause_all(&self) -> Vec<DownloadTask> {
         {
-            let mut active = self.active.lock().await;
-            if let Some(task) = active.get_mut(download_id) {
-                task.status = DownloadStatus::Failed {
-                    error: "Cancelled by user".to_string(),
-                };
+            let mut queue = self.queue.lock().await;
+            for task in queue.iter_mut() {
+                if matches!(
+                    task.status,
+                    DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::RateLimited { .. }
+                ) {
+                    task.status = DownloadStatus::Paused;
+                }
+            }
+        }
+
+        // The download tasks see the paused status and keep the entries
+        for token in self.cancel_tokens.lock().await.values() {
+            token.cancel();
+        }
+
+        save_queue(&self.app_handle, &self.queue).await;
+        info!("⏸️ Paused all downloads");
+        self.get_queue_state().await
+    }
+
+    /// Queue every paused download again. Returns the queue.
+    pub async fn resume_all(&self) -> Vec<DownloadTask> {
+        {
+            let mut queue = self.queue.lock().await;
+            for task in queue.iter_mut() {
+                if task.status == DownloadStatus::Paused {
+                    task.status = DownloadStatus::Queued;
+                    // Resuming by hand is the go-ahead on a metered connection
+                    task.metered_approved = true;
+                }
+            }
+        }
+
+        save_queue(&self.app_handle, &self.queue).await;
+        info!("▶️ Resumed all downloads");
+        self.start_next_download();
+        self.get_queue_state().await
+    }
+
+    /// Start a download that was held back on a metered connection
+    pub async fn confirm_metered_download(&self, download_id: &str) -> Result<(), String> {
+        {
+            let mut queue = self.queue.lock().await;
+            let task = queue
+                .iter_mut()
+                .find(|t| t.id == download_id)
+                .ok_or_else(|| format!("Download {} not found", download_id))?;
+            task.metered_approved = true;
+            if task.status == DownloadStatus::Paused {
+                task.status = DownloadStatus::Queued;
             }
         }
 
-        let _ = self.app_handle.emit("download-cancelled", download_id);
+        save_queue(&self.app_handle, &self.queue).await;
+        self.start_next_download();
         Ok(())
     }
 
+    /// Cancel every unfinished download. Returns the queue.
+    pub async fn cancel_all(&self) -> Vec<DownloadTask> {
+        let unfinished: Vec<String> = self
+            .get_queue_state()
+            .await
+            .into_iter()
+            .filter(|t| !matches!(t.status, DownloadStatus::Completed | DownloadStatus::Failed { .. }))
+            .map(|t| t.id)
+            .collect();
+
+        for download_id in unfinished {
+            if let Err(e) = self.cancel_download(&download_id).await {
+                warn!("Failed to cancel {}: {}", download_id, e);
+            }
+        }
+        self.get_queue_state().await
+    }
+
+    /// Average speed of the last large download this session, if any
+    pub async fn recent_speed_bps(&self) -> Option<u64> {
+        self.progress.lock().await.recent_average_bps.filter(|bps| *bps > 0)
+    }
+
     /// Remove completed/failed downloads from queue
     pub async fn clear_completed(&self) -> Result<(), String> {
-        let mut queue = self.queue.lock().await;
-        queue.retain(|t| !matches!(t.status, DownloadStatus::Completed | DownloadStatus::Failed { .. }));
+        {
+            let mut queue = self.queue.lock().await;
+            queue.retain(|t| !matches!(t.status, DownloadStatus::Completed | DownloadStatus::Failed { .. }));
+        }
+        save_queue(&self.app_handle, &self.queue).await;
         Ok(())
     }
+
+    /// Where new downloads are saved
+    pub async fn download_dir(&self) -> PathBuf {
+        self.download_dir.lock().await.clone()
+    }
+
+    /// Save future downloads in `new_dir`. With `move_existing`, archives
+    /// we downloaded, partial files and their records move along and
+    /// finished downloads point at the new location. Anything else in the
+    /// old folder stays. Returns how many files were moved.
+    pub async fn relocate(&self, new_dir: PathBuf, move_existing: bool) -> Result<usize, AppError> {
+        if !self.active.lock().await.is_empty() {
+            return Err(AppError::DownloadsActive);
+        }
+
+        // Held throughout so no download starts in the old folder meanwhile
+        let mut download_dir = self.download_dir.lock().await;
+        let old_dir = download_dir.clone();
+        let mut moved = 0;
+        if move_existing && old_dir != new_dir {
+            let tasks = self.get_queue_state().await;
+            let (from, to) = (old_dir.clone(), new_dir.clone());
+            let (moved_files, error) = tokio::task::spawn_blocking(move || {
+                let files = known_files(&from, &tasks);
+                move_files(&files, &to)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            moved = moved_files.len();
+
+            // Point finished downloads at the files that made it over, even
+            // if a later one failed
+            {
+                let mut queue = self.queue.lock().await;
+                for task in queue.iter_mut() {
+                    let Some(path) = &task.file_path else { continue };
+                    if moved_files.contains(path) {
+                        task.file_path = path.file_name().map(|name| new_dir.join(name));
+                    }
+                }
+            }
+            if let Some(error) = error {
+                drop(download_dir);
+                save_queue(&self.app_handle, &self.queue).await;
+                return Err(error.into());
+            }
+        }
+
+        info!("📁 Downloads folder: {} -> {} ({} files moved)", old_dir.display(), new_dir.display(), moved);
+        *download_dir = new_dir;
+        drop(download_dir);
+
+        save_queue(&self.app_handle, &self.queue).await;
+        Ok(moved)
+    }
+}
+
+/// Files in `dir` that belong to our downloads: the archives of `tasks` and
+/// those with an archive record, their partial files and sidecar records
+fn known_files(dir: &Path, tasks: &[DownloadTask]) -> Vec<PathBuf> {
+    let mut archives: Vec<PathBuf> = tasks
+        .iter()
+        .map(|task| match &task.file_path {
+            Some(path) if path.parent() == Some(dir) => path.clone(),
+            _ => dir.join(&task.file_name),
+        })
+        .collect();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        archives.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|path| path.is_file() && ArchiveRecord::load(path).is_some()),
+        );
+    }
+
+    let mut files = Vec::new();
+    for archive in archives {
+        let part = DownloadManagerHandle::part_path(&archive);
+        for file in [
+            ArchiveRecord::path_for(&archive),
+            ArchiveRecord::path_for(&part),
+            archive,
+            part,
+        ] {
+            if file.is_file() && !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    files
+}
+
+/// Move `files` into `to`, copying when the folders are on different
+/// drives. Files already present in `to` are left alone. Returns the files
+/// moved, and the error that stopped the rest, if any.
+fn move_files(files: &[PathBuf], to: &Path) -> (Vec<PathBuf>, Option<String>) {
+    if let Err(e) = std::fs::create_dir_all(to) {
+        return (Vec::new(), Some(format!("Failed to create downloads folder: {}", e)));
+    }
+
+    let mut moved = Vec::new();
+    for source in files {
+        let Some(name) = source.file_name() else { continue };
+        let target = to.join(name);
+        if target.exists() {
+            warn!("Not moving {}: already in the new downloads folder", source.display());
+            continue;
+        }
+        if std::fs::rename(source, &target).is_err() {
+            if let Err(e) = std::fs::copy(source, &target) {
+                return (moved, Some(format!("Failed to move {}: {}", source.display(), e)));
+            }
+            if let Err(e) = std::fs::remove_file(source) {
+                return (moved, Some(format!("Failed to remove {}: {}", source.display(), e)));
+            }
+        }
+        moved.push(source.clone());
+    }
+    (moved, None)
 }
 
 /// Helper struct for executing downloads (can be cloned and sent to tokio tasks)
@@ -243,156 +1250,294 @@ impl DownloadManager {
 struct DownloadManagerHandle {
     queue: Arc<Mutex<VecDeque<DownloadTask>>>,
     active: Arc<Mutex<HashMap<String, DownloadTask>>>,
+    cooldown_until: Arc<Mutex<Option<Instant>>>,
+    progress: Arc<Mutex<ProgressBatch>>,
     download_dir: PathBuf,
     app_handle: AppHandle,
     client: Client,
 }
 
 impl DownloadManagerHandle {
-    async fn execute_download(&self, task: DownloadTask) -> Result<PathBuf, String> {
-        // Load Nexus Mods API key from settings
-        let settings = crate::settings::Settings::load(&self.app_handle)
-            .map_err(|e| format!("Failed to load settings: {}", e))?;
+    /// Pause the scheduler after a 429 and return the error that parks the download
+    async fn enter_cooldown(&self, delay: Duration) -> DownloadError {
+        warn!("⏳ Rate limited by Nexus Mods, cooling down for {}s", delay.as_secs());
+
+        let mut cooldown = self.cooldown_until.lock().await;
+        let until = Instant::now() + delay;
+        if cooldown.map(|current| current < until).unwrap_or(true) {
+            *cooldown = Some(until);
+        }
 
-        if settings.nexus_api_key.is_empty() {
-            return Err("Nexus Mods API key not configured. Please add your API key in Settings.".to_string());
+        DownloadError::rate_limited(delay)
+    }
+
+    /// Turn a Nexus API failure into a download error, cooling down on 429s
+    async fn api_error(&self, error: NexusApiError) -> DownloadError {
+        error!("❌ {}", error);
+        match error {
+            NexusApiError::RateLimited(delay) => self.enter_cooldown(delay).await,
+            NexusApiError::QuotaExhausted(resets_at) => {
+                self.enter_cooldown((resets_at - Utc::now()).to_std().unwrap_or(Duration::from_secs(60))).await
+            }
+            e if e.is_transient() => DownloadError::retryable(e.to_string()),
+            e => e.to_string().into(),
         }
+    }
 
-        // Step 1: Get the actual download link from Nexus Mods API
-        let api_url = format!(
-            "https://api.nexusmods.com/v1/games/{}/mods/{}/files/{}/download_link.json",
-            task.nxm_url.game,
-            task.nxm_url.mod_id,
-            task.nxm_url.file_id
-        );
+    /// Look for an archive of the same file left over from an earlier download.
+    /// It is only reused when its size and MD5 still match the record written
+    /// when it was downloaded, so no network request is made at all.
+    async fn find_existing_archive(&self, task: &DownloadTask) -> Option<PathBuf> {
+        let file_path = self.download_dir.join(&task.file_name);
+        let record = ArchiveRecord::load(&file_path)?;
 
-        println!("🔍 Fetching download link from API: {}", api_url);
-        println!("   Key: {}", task.nxm_url.key);
-        println!("   Expires: {:?}", task.nxm_url.expires);
-        println!("   User ID: {:?}", task.nxm_url.user_id);
-        println!("   Using Nexus API key: {}...", &settings.nexus_api_key.chars().take(8).collect::<String>());
-
-        // Call API to get download link
-        // Build query parameters
-        let mut query_params = vec![
-            ("key", task.nxm_url.key.clone()),
-            ("expires", task.nxm_url.expires.unwrap_or(0).to_string()),
-        ];
+        if record.mod_id != task.nxm_url.mod_id || record.file_id != task.nxm_url.file_id {
+            return None;
+        }
 
-        // Add user_id if present
-        if let Some(user_id) = task.nxm_url.user_id {
-            query_params.push(("user_id", user_id.to_string()));
+        let size = tokio::fs::metadata(&file_path).await.ok()?.len();
+        if size != record.size {
+            info!("   ⚠ Cached archive size mismatch ({} vs {}), downloading again", size, record.size);
+            return None;
         }
 
-        println!("   📋 Query parameters: {:?}", query_params);
+        let hash_path = file_path.clone();
+        let md5 = tokio::task::spawn_blocking(move || compute_md5(&hash_path))
+            .await
+            .ok()?
+            .ok()?;
 
-        let api_response = self
-            .client
-            .get(&api_url)
-            .query(&query_params)
-            .header("User-Agent", "Treasure Chest Mod Manager/0.1.0")
-            .header("apikey", settings.nexus_api_key.clone())
-            .send()
+        if md5 != record.md5 {
+            info!("   ⚠ Cached archive MD5 mismatch, downloading again");
+            return None;
+        }
+
+        Some(file_path)
+    }
+
+    /// Query the Nexus file info endpoint for the expected archive size in bytes
+    async fn fetch_expected_size(&self, nexus: &NexusClient, task: &DownloadTask) -> Option<u64> {
+        match nexus
+            .file_info(&task.nxm_url.game, task.nxm_url.mod_id, task.nxm_url.file_id)
             .await
-            .map_err(|e| {
-                eprintln!("❌ API request error: {:?}", e);
-                format!("API request failed: {}", e)
-            })?;
+        {
+            Ok(file_info) => file_info.size_bytes(),
+            Err(e) => {
+                warn!("   ⚠ Could not fetch file info ({}), skipping size check", e);
+                None
+            }
+        }
+    }
 
-        let api_status = api_response.status();
-        println!("📡 API Response status: {}", api_status);
+    /// Download a task, retrying transient failures with exponential backoff
+    async fn execute_download(&self, task: DownloadTask) -> Result<PathBuf, DownloadError> {
+        let max_retries = crate::settings::Settings::load(&self.app_handle)
+            .map(|s| s.download_retry_attempts)
+            .unwrap_or(0);
+
+        let mut attempt = 0;
+        loop {
+            match self.try_download(&task).await {
+                Err(e) if e.kind == DownloadErrorKind::Retryable && attempt < max_retries => {
+                    let delay = retry_backoff(attempt);
+                    attempt += 1;
+                    warn!(
+                        "🔁 Download {} failed ({}), retry {}/{} in {}s",
+                        task.id,
+                        e.message,
+                        attempt,
+                        max_retries,
+                        delay.as_secs()
+                    );
+
+                    events::emit(
+                        &self.app_handle,
+                        AppEvent::DownloadRetrying {
+                            download_id: task.id.clone(),
+                            attempt,
+                            max_attempts: max_retries,
+                            error: e.message,
+                        },
+                    );
+
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Single download attempt
+    async fn try_download(&self, task: &DownloadTask) -> Result<PathBuf, DownloadError> {
+        // Skip the transfer entirely if this exact file is already on disk
+        if let Some(existing) = self.find_existing_archive(task).await {
+            info!("♻️  Reusing previously downloaded archive: {}", existing.display());
+
+            let size = tokio::fs::metadata(&existing).await.map(|m| m.len()).ok();
+            {
+                let mut queue = self.queue.lock().await;
+                if let Some(t) = queue.iter_mut().find(|t| t.id == task.id) {
+                    t.bytes_total = size;
+                    t.bytes_downloaded = size.unwrap_or(0);
+                }
+            }
 
-        // Track API usage from response headers
-        let headers = api_response.headers().clone();
-        if let Some(tracker) = self.app_handle.try_state::<crate::api_usage_tracker::ApiUsageTracker>() {
-            tracker.update_from_headers(&headers).await;
+            return Ok(existing);
         }
 
-        if !api_status.is_success() {
-            let error_body = api_response.text().await.unwrap_or_default();
-            eprintln!("❌ API error response: {}", error_body);
-            return Err(format!("API error {}: {}", api_status, error_body));
+        // Load Nexus Mods API key from settings
+        let settings = crate::settings::Settings::load(&self.app_handle)
+            .map_err(|e| format!("Failed to load settings: {}", e))?;
+
+        if settings.active_api_key().is_empty() {
+            return Err("Nexus Mods API key not configured. Please add your API key in Settings.".to_string().into());
         }
 
-        // Get response text for debugging
-        let response_text = api_response.text().await
-            .map_err(|e| format!("Failed to read API response: {}", e))?;
+        let nexus = NexusClient::with_client(self.client.clone(), &self.app_handle, &settings);
 
-        println!("📄 API Response body: {}", response_text);
+        // Expected size according to Nexus, used to detect truncated transfers
+        let expected_size = self.fetch_expected_size(&nexus, task).await;
+        info!("📏 Expected size from Nexus: {:?}", expected_size);
 
-        // Parse JSON response
-        let cdn_links: Vec<serde_json::Value> = serde_json::from_str(&response_text)
-            .map_err(|e| format!("Failed to parse API response as JSON: {}. Response was: {}", e, response_text))?;
+        // Hold large files on a metered connection before asking Nexus for a
+        // link: that costs an API request, and the link may expire while held
+        if let Some(size) = expected_size.filter(|size| *size >= METERED_CONFIRM_BYTES) {
+            let needs_confirmation = match settings.metered_downloads {
+                MeteredDownloads::Allow => None,
+                _ if task.metered_approved => None,
+                MeteredDownloads::Confirm => Some(true),
+                MeteredDownloads::Pause => Some(false),
+            };
+            if let Some(needs_confirmation) = needs_confirmation {
+                if tokio::task::spawn_blocking(crate::metered::is_metered).await.unwrap_or(false) {
+                    return Err(DownloadError::metered(size, needs_confirmation));
+                }
+            }
+        }
 
-        println!("📦 Parsed {} CDN link(s)", cdn_links.len());
+        // Step 1: Get the actual download link from Nexus Mods API
+        info!(
+            "🔍 Fetching download link from API: mod {} file {}",
+            task.nxm_url.mod_id, task.nxm_url.file_id
+        );
+        info!("   Expires: {:?}", task.nxm_url.expires);
+
+        let cdn_links = match nexus.download_links(&task.nxm_url).await {
+            Ok(links) => links,
+            Err(e) => return Err(self.api_error(e).await),
+        };
+
+        info!("📦 Parsed {} CDN link(s)", cdn_links.len());
 
         // Get the CDN URI from the first link
         let download_url = cdn_links
             .first()
-            .and_then(|link| {
-                println!("🔗 Link object: {:?}", link);
-                link.get("URI")
-            })
-            .and_then(|uri| uri.as_str())
-            .ok_or_else(|| format!("No download link in API response. Response was: {}", response_text))?
-            .to_string();
+            .map(|link| link.uri.clone())
+            .ok_or_else(|| "No download link in API response".to_string())?;
 
-        println!("✅ Got CDN URL: {}", download_url);
+        info!("✅ Got CDN URL");
+
+        let file_path = self.download_dir.join(&task.file_name);
+        let part_path = Self::part_path(&file_path);
+
+        // Ask for the rest of a partial file left by an earlier attempt
+        let resume = PartRecord::resumable(&part_path, &task.nxm_url);
+        let mut request = self.client.get(&download_url).header("User-Agent", USER_AGENT);
+        if let Some(record) = &resume {
+            info!("⏯️  Resuming partial download at {} bytes", record.bytes);
+            request = request.header("Range", format!("bytes={}-", record.bytes));
+            if let Some(validator) = record.etag.as_ref().or(record.last_modified.as_ref()) {
+                request = request.header("If-Range", validator);
+            }
+        }
 
         // Make request with proper headers
-        let response = self
-            .client
-            .get(&download_url)
-            .header("User-Agent", "Treasure Chest Mod Manager/0.1.0")
+        let response = request
             .send()
             .await
             .map_err(|e| {
-                eprintln!("❌ Request error: {:?}", e);
-                format!("Request failed: {}", e)
+                // Strip the URL, it is a signed CDN link
+                let e = e.without_url();
+                error!("❌ Request error: {:?}", e);
+                DownloadError::retryable(format!("Request failed: {}", e))
             })?;
 
         let status = response.status();
-        println!("📡 Response status: {}", status);
+        info!("📡 Response status: {}", status);
 
         // Check content type
         if let Some(content_type) = response.headers().get("content-type") {
-            println!("📄 Content-Type: {:?}", content_type);
+            info!("📄 Content-Type: {:?}", content_type);
         }
 
         // Check if this is an HTML page (redirect) instead of a file
         if let Some(content_type) = response.headers().get("content-type") {
             let content_type_str = content_type.to_str().unwrap_or("");
             if content_type_str.contains("text/html") {
-                eprintln!("⚠️  Received HTML instead of file! Nexus might be returning a download page.");
+                warn!("⚠️  Received HTML instead of file! Nexus might be returning a download page.");
                 let html_body = response.text().await.unwrap_or_default();
-                eprintln!("📄 HTML preview: {}", &html_body[..html_body.len().min(500)]);
-                return Err("Received HTML page instead of file. The download URL might need Nexus Mods API access.".to_string());
+                crate::debug_log::log_api_payload(&self.app_handle, &settings, "CDN HTML response", &html_body);
+                return Err("Received HTML page instead of file. The download URL might need Nexus Mods API access.".to_string().into());
             }
         }
 
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let delay = retry_after_from_headers(response.headers());
+            return Err(self.enter_cooldown(delay).await);
+        }
+
+        // The partial file is longer than the file on the server now; without
+        // it the next attempt requests the whole file
+        if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && resume.is_some() {
+            warn!("⚠️  Server rejected the resume range, starting over");
+            let _ = tokio::fs::remove_file(&part_path).await;
+            PartRecord::remove(&part_path);
+            return Box::pin(self.try_download(task)).await;
+        }
+
         if !status.is_success() {
             // Try to get the response body for debugging
             let error_body = response.text().await.unwrap_or_else(|_| "Could not read response body".to_string());
-            eprintln!("❌ HTTP error response body: {}", error_body);
-            return Err(format!("HTTP error {}: {}", status,
-                if error_body.len() > 200 { &error_body[..200] } else { &error_body }));
+            crate::debug_log::log_api_payload(&self.app_handle, &settings, "CDN error response", &error_body);
+            let message = format!("HTTP error {}: {}", status,
+                if error_body.len() > 200 { &error_body[..200] } else { &error_body });
+            return Err(if status.is_server_error() {
+                DownloadError::retryable(message)
+            } else {
+                message.into()
+            });
+        }
+
+        // A full response means the server ignored the range or the file
+        // changed; a partial one that doesn't continue our bytes is unusable
+        let resumed_at = resume
+            .as_ref()
+            .and_then(|record| resume_offset(status, response.headers(), record))
+            .unwrap_or(0);
+        if resumed_at == 0 && status == reqwest::StatusCode::PARTIAL_CONTENT {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            PartRecord::remove(&part_path);
+            return Err(DownloadError::retryable(
+                "Partial download no longer matches the file, starting over".to_string(),
+            ));
         }
 
         // Get total size if available
-        let total_size = response.content_length();
-        println!("📊 Content length: {:?}", total_size);
+        let total_size = response.content_length().map(|length| length + resumed_at);
+        info!("📊 Content length: {:?}", total_size);
 
         // Update task with total size
         {
             let mut queue = self.queue.lock().await;
             if let Some(t) = queue.iter_mut().find(|t| t.id == task.id) {
-                t.bytes_total = total_size;
+                t.bytes_total = total_size.or(t.bytes_total);
+                t.bytes_downloaded = resumed_at;
             }
         }
         {
             let mut active = self.active.lock().await;
             if let Some(t) = active.get_mut(&task.id) {
-                t.bytes_total = total_size;
+                t.bytes_total = total_size.or(t.bytes_total);
             }
         }
 
@@ -401,35 +1546,66 @@ impl DownloadManagerHandle {
             .await
             .map_err(|e| format!("Failed to create download directory: {}", e))?;
 
-        // Create file
-        let file_path = self.download_dir.join(&task.file_name);
-        let mut file = File::create(&file_path)
-            .await
-            .map_err(|e| format!("Failed to create file: {}", e))?;
+        // Download into a .part file, only renamed once complete so a cancelled
+        // or broken transfer never replaces a good archive
+        let mut hasher = Md5::new();
+        let mut file = if resumed_at > 0 {
+            Self::open_resumed_part(&part_path, resumed_at, &mut hasher)
+                .await
+                .map_err(|e| format!("Failed to open partial download: {}", e))?
+        } else {
+            File::create(&part_path)
+                .await
+                .map_err(|e| format!("Failed to create file: {}", e))?
+        };
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let mut part_record = PartRecord {
+            mod_id: task.nxm_url.mod_id,
+            file_id: task.nxm_url.file_id,
+            bytes: resumed_at,
+            total: total_size,
+            etag: header("etag").or_else(|| resume.as_ref().and_then(|r| r.etag.clone())),
+            last_modified: header("last-modified").or_else(|| resume.as_ref().and_then(|r| r.last_modified.clone())),
+        };
+        if let Err(e) = part_record.save(&part_path).await {
+            warn!("Failed to write partial download record: {}", e);
+        }
+        let mut last_record_time = Instant::now();
 
         // Download with progress tracking
-        let mut downloaded: u64 = 0;
+        let mut downloaded: u64 = resumed_at;
         let mut last_progress_time = Instant::now();
         let mut last_progress_bytes = 0u64;
+        let mut speed = SpeedMeter::new(last_progress_time);
 
         use futures::StreamExt;
         let mut stream = response.bytes_stream();
 
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| format!("Stream error: {}", e))?;
+            let chunk = chunk.map_err(|e| DownloadError::retryable(format!("Stream error: {}", e.without_url())))?;
 
             file.write_all(&chunk)
                 .await
                 .map_err(|e| format!("Write error: {}", e))?;
 
+            hasher.update(&chunk);
             downloaded += chunk.len() as u64;
 
-            // Update progress every 100ms
             let now = Instant::now();
-            if now.duration_since(last_progress_time) > Duration::from_millis(100) {
-                let elapsed = now.duration_since(last_progress_time).as_secs_f64();
-                let bytes_diff = downloaded - last_progress_bytes;
-                let speed_bps = (bytes_diff as f64 / elapsed) as u64;
+            if should_report_progress(
+                now.duration_since(last_progress_time),
+                downloaded - last_progress_bytes,
+                total_size,
+            ) {
+                let speed_bps = speed.sample(downloaded - last_progress_bytes, now.duration_since(last_progress_time));
+                let average_speed_bps = speed.average_bps(downloaded - resumed_at, now);
 
                 let eta_seconds = if speed_bps > 0 && total_size.is_some() {
                     let remaining = total_size.unwrap() - downloaded;
@@ -449,6 +1625,7 @@ impl DownloadManagerHandle {
                     bytes_downloaded: downloaded,
                     bytes_total: total_size,
                     speed_bps,
+                    average_speed_bps,
                     eta_seconds,
                     progress_percent,
                 };
@@ -461,8 +1638,23 @@ impl DownloadManagerHandle {
                     }
                 }
 
-                // Emit progress event
-                let _ = self.app_handle.emit("download-progress", &progress);
+                // Bytes are only resumable once they're on disk
+                if now.duration_since(last_record_time) >= PART_RECORD_INTERVAL
+                    && downloaded - part_record.bytes >= PART_RECORD_BYTES
+                    && file.flush().await.is_ok()
+                {
+                    part_record.bytes = downloaded;
+                    if let Err(e) = part_record.save(&part_path).await {
+                        warn!("Failed to update partial download record: {}", e);
+                    }
+                    last_record_time = now;
+                }
+
+                let batch = self.progress.lock().await.push(progress, now);
+                if let Some(batch) = batch {
+                    events::emit(&self.app_handle, AppEvent::DownloadProgressBatch(batch));
+                    self.emit_queue_progress().await;
+                }
 
                 last_progress_time = now;
                 last_progress_bytes = downloaded;
@@ -473,10 +1665,121 @@ impl DownloadManagerHandle {
             .await
             .map_err(|e| format!("Flush error: {}", e))?;
 
+        drop(file);
+
+        // Small files are mostly latency, they'd make estimates too slow
+        let transferred = downloaded - resumed_at;
+        if transferred >= 1024 * 1024 {
+            self.progress.lock().await.recent_average_bps = Some(speed.average_bps(transferred, Instant::now()));
+        }
+
+        // Never hand a truncated archive to the installer
+        let expected = total_size.or(expected_size);
+        let is_truncated = match (total_size, expected_size) {
+            (Some(total), _) if downloaded != total => true,
+            // Nexus may only report size_kb, so allow for rounding to the nearest KB
+            (_, Some(size)) => downloaded.abs_diff(size) >= 1024,
+            _ => false,
+        };
+
+        if is_truncated {
+            error!(
+                "❌ Downloaded size {} does not match expected size {:?}",
+                downloaded, expected
+            );
+            let _ = tokio::fs::remove_file(&part_path).await;
+            PartRecord::remove(&part_path);
+            return Err(DownloadError::retryable(format!(
+                "Download incomplete: received {} bytes but expected {} bytes. Please retry the download.",
+                downloaded,
+                expected.unwrap_or(0)
+            )));
+        }
+
+        tokio::fs::rename(&part_path, &file_path)
+            .await
+            .map_err(|e| format!("Failed to finalize download: {}", e))?;
+        PartRecord::remove(&part_path);
+
+        // Remember what was downloaded so a reinstall can skip the transfer
+        let record = ArchiveRecord {
+            mod_id: task.nxm_url.mod_id,
+            file_id: task.nxm_url.file_id,
+            size: downloaded,
+            md5: format!("{:x}", hasher.finalize()),
+            account: task.account.clone(),
+            downloaded_at: Some(Utc::now()),
+        };
+        if let Err(e) = record.save(&file_path) {
+            warn!("Failed to write archive record: {}", e);
+        }
+
         Ok(file_path)
     }
 
+    fn part_path(file_path: &Path) -> PathBuf {
+        let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".part");
+        file_path.with_file_name(name)
+    }
+
+    /// Open a .part file to append after its first `length` bytes, dropping
+    /// anything written past them and feeding the kept bytes to `hasher`
+    async fn open_resumed_part(part_path: &Path, length: u64, hasher: &mut Md5) -> std::io::Result<File> {
+        let mut file = tokio::fs::OpenOptions::new().read(true).write(true).open(part_path).await?;
+        file.set_len(length).await?;
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        file.seek(SeekFrom::End(0)).await?;
+        Ok(file)
+    }
+
+    /// Drop a cancelled download from the queue and delete its partial file.
+    /// Paused downloads keep both, to resume later.
+    async fn discard_cancelled_download(&self, task: &DownloadTask) {
+        self.progress.lock().await.remove(&task.id);
+
+        // Paused downloads (possibly already resumed) stay in the queue
+        let paused = {
+            let mut queue = self.queue.lock().await;
+            let paused = queue
+                .iter()
+                .any(|t| t.id == task.id && matches!(t.status, DownloadStatus::Paused | DownloadStatus::Queued));
+            if !paused {
+                queue.retain(|t| t.id != task.id);
+            }
+            paused
+        };
+        save_queue(&self.app_handle, &self.queue).await;
+
+        {
+            let mut active = self.active.lock().await;
+            active.remove(&task.id);
+        }
+
+        // A paused download resumes from its partial file later
+        if !paused {
+            remove_partial(&self.download_dir, &task.file_name).await;
+        }
+
+        if paused {
+            info!("⏸️ Download paused: {}", task.id);
+        } else {
+            info!("🛑 Download cancelled: {}", task.id);
+        }
+    }
+
     async fn complete_download(&self, download_id: String, file_path: PathBuf) {
+        self.progress.lock().await.remove(&download_id);
+
         // Update in queue
         {
             let mut queue = self.queue.lock().await;
@@ -485,6 +1788,7 @@ impl DownloadManagerHandle {
                 task.file_path = Some(file_path.clone());
             }
         }
+        save_queue(&self.app_handle, &self.queue).await;
 
         // Remove from active
         {
@@ -493,17 +1797,91 @@ impl DownloadManagerHandle {
         }
 
         // Emit completion event
-        let _ = self.app_handle.emit("download-completed", download_id);
+        events::emit(&self.app_handle, AppEvent::DownloadCompleted(download_id));
+        self.emit_queue_progress().await;
+    }
+
+    /// Send the totals of the whole queue as `download-queue-progress`
+    async fn emit_queue_progress(&self) {
+        let speed_bps = self.progress.lock().await.total_speed();
+        let tasks: Vec<DownloadTask> = self.queue.lock().await.iter().cloned().collect();
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadQueueProgress(queue_progress(&tasks, speed_bps)),
+        );
+    }
+
+    /// Pause a download that is too large for the metered connection
+    async fn hold_metered_download(&self, download_id: String, bytes_total: u64, needs_confirmation: bool) {
+        self.progress.lock().await.remove(&download_id);
+        info!("📶 Metered connection, holding download {} ({} bytes)", download_id, bytes_total);
+
+        {
+            let mut queue = self.queue.lock().await;
+            if let Some(task) = queue.iter_mut().find(|t| t.id == download_id) {
+                task.status = DownloadStatus::Paused;
+                task.bytes_total = Some(bytes_total);
+            }
+        }
+        save_queue(&self.app_handle, &self.queue).await;
+
+        {
+            let mut active = self.active.lock().await;
+            active.remove(&download_id);
+        }
+
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadMeteredHold {
+                download_id,
+                bytes_total,
+                needs_confirmation,
+            },
+        );
+    }
+
+    /// Park a download until the rate limit cool-down is over
+    async fn rate_limit_download(&self, download_id: String, delay: Duration) {
+        self.progress.lock().await.remove(&download_id);
+        let retry_at = Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+
+        {
+            let mut queue = self.queue.lock().await;
+            if let Some(task) = queue.iter_mut().find(|t| t.id == download_id) {
+                task.status = DownloadStatus::RateLimited { retry_at };
+            }
+        }
+
+        {
+            let mut active = self.active.lock().await;
+            active.remove(&download_id);
+        }
+
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadRateLimited {
+                download_id,
+                retry_at,
+            },
+        );
     }
 
-    async fn fail_download(&self, download_id: String, error: String) {
+    async fn fail_download(&self, download_id: String, error: DownloadError) {
+        self.progress.lock().await.remove(&download_id);
+        let retryable = error.kind != DownloadErrorKind::Fatal;
+        let error = error.message;
+
         // Update in queue
         {
             let mut queue = self.queue.lock().await;
             if let Some(task) = queue.iter_mut().find(|t| t.id == download_id) {
-                task.status = DownloadStatus::Failed { error: error.clone() };
+                task.status = DownloadStatus::Failed {
+                    error: error.clone(),
+                    retryable,
+                };
             }
         }
+        save_queue(&self.app_handle, &self.queue).await;
 
         // Remove from active
         {
@@ -512,18 +1890,229 @@ impl DownloadManagerHandle {
         }
 
         // Emit failure event
-        #[derive(Serialize, Clone)]
-        struct FailurePayload {
-            download_id: String,
-            error: String,
-        }
-
-        let _ = self.app_handle.emit(
-            "download-failed",
-            FailurePayload {
+        events::emit(
+            &self.app_handle,
+            AppEvent::DownloadFailed {
                 download_id,
                 error,
+                retryable,
             },
         );
+        self.emit_queue_progress().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(status: DownloadStatus) -> DownloadTask {
+        DownloadTask {
+            id: "abc".to_string(),
+            nxm_url: NxmUrl::parse("nxm://stardewvalley/mods/541/files/9001?key=k&expires=1").unwrap(),
+            mod_name: Some("Lookup Anything".to_string()),
+            file_name: "LookupAnything-541-1-37.zip".to_string(),
+            file_version: Some("1.37.0".to_string()),
+            status,
+            file_path: None,
+            bytes_downloaded: 1024,
+            bytes_total: Some(4096),
+            install_size: None,
+            account: None,
+            managed_install: false,
+            metered_approved: false,
+        }
+    }
+
+    #[test]
+    fn test_queue_progress_totals() {
+        let mut unknown_size = task(DownloadStatus::Queued);
+        unknown_size.bytes_total = None;
+        let failed = DownloadStatus::Failed {
+            error: "boom".to_string(),
+            retryable: true,
+        };
+        let tasks = vec![
+            task(DownloadStatus::Completed),
+            task(DownloadStatus::Downloading),
+            unknown_size,
+            task(failed),
+        ];
+
+        let progress = queue_progress(&tasks, 1024);
+        assert_eq!(progress.files_total, 3);
+        assert_eq!(progress.files_remaining, 2);
+        assert_eq!(progress.bytes_downloaded, 4096 + 1024);
+        assert_eq!(progress.bytes_total, 8192);
+        assert_eq!(progress.eta_seconds, Some(3));
+        assert_eq!(progress.progress_percent, 62.5);
+
+        // Nothing left to do
+        let done = queue_progress(&[task(DownloadStatus::Completed)], 0);
+        assert_eq!((done.files_remaining, done.eta_seconds, done.progress_percent), (0, None, 100.0));
+    }
+
+    #[test]
+    fn test_restored_task_requeues_interrupted_downloads() {
+        let restored = restored_task(task(DownloadStatus::Downloading)).unwrap();
+        assert_eq!(restored.status, DownloadStatus::Queued);
+        assert_eq!(restored.bytes_downloaded, 0);
+        assert_eq!(restored.mod_name.as_deref(), Some("Lookup Anything"));
+
+        let failed = DownloadStatus::Failed {
+            error: "boom".to_string(),
+            retryable: false,
+        };
+        assert_eq!(restored_task(task(failed.clone())).unwrap().status, failed);
+        assert_eq!(restored_task(task(DownloadStatus::Paused)).unwrap().status, DownloadStatus::Paused);
+
+        // Whoever waited to install a managed download is gone after a restart
+        let managed = |status| DownloadTask {
+            managed_install: true,
+            ..task(status)
+        };
+        assert!(restored_task(managed(DownloadStatus::Downloading)).is_none());
+        assert!(restored_task(managed(DownloadStatus::Paused)).is_none());
+        assert!(restored_task(managed(DownloadStatus::Completed)).is_some());
+
+        // Queues saved before file_version existed still load
+        let mut json = serde_json::to_value(task(DownloadStatus::Completed)).unwrap();
+        json.as_object_mut().unwrap().remove("fileVersion");
+        let old: DownloadTask = serde_json::from_value(json).unwrap();
+        assert_eq!(old.file_version, None);
+    }
+
+    #[tokio::test]
+    async fn test_resume_offset() {
+        let record = PartRecord {
+            mod_id: 518,
+            file_id: 9001,
+            bytes: 1000,
+            total: Some(5000),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        let headers = |range: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert("content-range", range.parse().unwrap());
+            headers
+        };
+        let partial = reqwest::StatusCode::PARTIAL_CONTENT;
+
+        assert_eq!(resume_offset(partial, &headers("bytes 1000-4999/5000"), &record), Some(1000));
+        assert_eq!(resume_offset(partial, &headers("bytes 1000-4999/*"), &record), Some(1000));
+        assert_eq!(resume_offset(partial, &headers("bytes 0-4999/5000"), &record), None);
+        assert_eq!(resume_offset(partial, &headers("bytes 1000-5999/6000"), &record), None);
+        assert_eq!(resume_offset(reqwest::StatusCode::OK, &headers("bytes 1000-4999/5000"), &record), None);
+
+        let dir = std::env::temp_dir().join(format!("tc_part_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let part_path = dir.join("Lookup Anything.zip.part");
+        std::fs::write(&part_path, vec![0u8; 1200]).unwrap();
+        record.save(&part_path).await.unwrap();
+
+        let nxm_url = |file_id| NxmUrl {
+            game: "stardewvalley".to_string(),
+            mod_id: 518,
+            file_id,
+            key: String::new(),
+            expires: None,
+            user_id: None,
+        };
+        assert_eq!(PartRecord::resumable(&part_path, &nxm_url(9001)), Some(record));
+        assert_eq!(PartRecord::resumable(&part_path, &nxm_url(9002)), None);
+        std::fs::write(&part_path, vec![0u8; 10]).unwrap();
+        assert_eq!(PartRecord::resumable(&part_path, &nxm_url(9001)), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relocate_moves_only_our_files() {
+        let dir = std::env::temp_dir().join(format!("tc_relocate_{}", Uuid::new_v4()));
+        let (from, to) = (dir.join("Downloads"), dir.join("Mods archive"));
+        std::fs::create_dir_all(&from).unwrap();
+        for name in [
+            "LookupAnything-541-1-37.zip.part",
+            "LookupAnything-541-1-37.zip.part.meta",
+            "ChestsAnywhere.zip",
+            "ChestsAnywhere.zip.meta",
+            "holiday photos.zip",
+            "notes.txt",
+        ] {
+            std::fs::write(from.join(name), "data").unwrap();
+        }
+        std::fs::write(
+            from.join("ChestsAnywhere.zip.meta"),
+            r#"{"mod_id": 518, "file_id": 1, "size": 4, "md5": "abc"}"#,
+        )
+        .unwrap();
+
+        let files = known_files(&from, &[task(DownloadStatus::Paused)]);
+        assert_eq!(files.len(), 4);
+        let (moved, error) = move_files(&files, &to);
+        assert!(error.is_none());
+        assert_eq!(moved.len(), 4);
+        assert!(to.join("LookupAnything-541-1-37.zip.part").exists());
+        assert!(to.join("ChestsAnywhere.zip.meta").exists());
+        assert!(from.join("holiday photos.zip").exists());
+        assert!(from.join("notes.txt").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_speed_meter_smoothing() {
+        let started = Instant::now();
+        let mut meter = SpeedMeter::new(started);
+        let window = Duration::from_millis(100);
+
+        assert!(meter.sample(100_000, window).abs_diff(1_000_000) <= 1);
+
+        // A single stalled window only pulls the speed down a little
+        let after_stall = meter.sample(0, window);
+        assert!(after_stall > 900_000 && after_stall < 1_000_000, "{}", after_stall);
+
+        // A sustained new rate wins after a while
+        let mut speed = after_stall;
+        for _ in 0..200 {
+            speed = meter.sample(20_000, window);
+        }
+        assert!(speed.abs_diff(200_000) < 5_000, "{}", speed);
+
+        assert_eq!(meter.average_bps(1_000_000, started + Duration::from_secs(4)), 250_000);
+    }
+
+    #[test]
+    fn test_progress_throttle_and_batching() {
+        let large = Some(4 * 1024 * 1024 * 1024);
+        // Fast link: plenty of bytes, but never more often than the minimum interval
+        assert!(!should_report_progress(Duration::from_millis(50), 100 * 1024 * 1024, large));
+        assert!(should_report_progress(Duration::from_millis(100), 8 * 1024 * 1024, large));
+        // Small step for a large file isn't worth an update yet
+        assert!(!should_report_progress(Duration::from_millis(500), 1024 * 1024, large));
+        // Slow link: report at least once a second
+        assert!(should_report_progress(Duration::from_secs(1), 10, None));
+
+        let progress = |id: &str, bytes: u64| DownloadProgress {
+            download_id: id.to_string(),
+            bytes_downloaded: bytes,
+            bytes_total: None,
+            speed_bps: 0,
+            average_speed_bps: 0,
+            eta_seconds: None,
+            progress_percent: 0.0,
+        };
+
+        let start = Instant::now();
+        let mut batch = ProgressBatch::new(start);
+        assert!(batch.push(progress("b", 1), start + Duration::from_millis(50)).is_none());
+        assert!(batch.push(progress("a", 1), start + Duration::from_millis(100)).is_none());
+        assert!(batch.push(progress("b", 2), start + Duration::from_millis(150)).is_none());
+
+        let sent = batch.push(progress("a", 2), start + PROGRESS_BATCH_INTERVAL).unwrap();
+        let sent: Vec<(&str, u64)> = sent.iter().map(|p| (p.download_id.as_str(), p.bytes_downloaded)).collect();
+        assert_eq!(sent, [("a", 2), ("b", 2)]);
+        assert!(batch.pending.is_empty());
     }
 }
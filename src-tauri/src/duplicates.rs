@@ -0,0 +1,190 @@
+//! Mods installed in more than one folder, usually after copying a new
+//! version into Mods by hand next to the old one. SMAPI refuses to start
+//! when two enabled folders share a UniqueID, so a scan flags every copy and
+//! the user picks the one to keep.
+
+use crate::app_error::AppError;
+use crate::models::Mod;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedDuplicate {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveReport {
+    /// The copy kept, enabled; its path changes if it was disabled
+    pub kept: String,
+    pub disabled: Vec<String>,
+    pub deleted: Vec<String>,
+    pub failed: Vec<FailedDuplicate>,
+}
+
+/// Point every enabled mod at the other enabled folders with its UniqueID.
+/// SMAPI skips disabled copies, so they don't clash with anything.
+pub fn flag(mods: &mut [Mod]) {
+    let mut paths: HashMap<String, Vec<String>> = HashMap::new();
+    for m in mods.iter().filter(|m| m.is_enabled) {
+        paths.entry(m.unique_id.to_lowercase()).or_default().push(m.path.clone());
+    }
+    for m in mods.iter_mut() {
+        if !m.is_enabled {
+            m.duplicate_paths.clear();
+            continue;
+        }
+        m.duplicate_paths = paths[&m.unique_id.to_lowercase()]
+            .iter()
+            .filter(|path| **path != m.path)
+            .cloned()
+            .collect();
+    }
+}
+
+/// Remove or disable one other copy. Its staged copy goes too, unless the
+/// kept folder is staged in the same place.
+fn remove_copy(settings: &Settings, path: &Path, keep: &Path, delete: bool) -> Result<Option<String>, String> {
+    settings.ensure_managed(path)?;
+    if let Some(tool) = crate::foreign_managers::folder_manager(path) {
+        return Err(format!("Managed by {}, change it there", tool));
+    }
+
+    if !delete {
+        let disabled = crate::mod_installer::set_mod_enabled(path, false, settings.disabled_folder_style)?;
+        return Ok(Some(disabled.to_string_lossy().to_string()));
+    }
+
+    fs::remove_dir_all(path).map_err(|e| format!("Failed to delete: {}", e))?;
+    let game_path = Path::new(&settings.game_path);
+    let staged = crate::staging::staged_path(game_path, path);
+    if staged.is_some() && staged != crate::staging::staged_path(game_path, keep) {
+        if let Err(e) = crate::staging::remove_staged(game_path, path) {
+            warn!("Failed to remove the staged copy of {}: {}", path.display(), e);
+        }
+    }
+    Ok(None)
+}
+
+/// Whether the disabled mod at `path` can be enabled, checked before the
+/// other copies are touched so a failure can't leave none enabled
+fn check_enable(settings: &Settings, path: &Path) -> Result<(), AppError> {
+    settings.ensure_managed(path)?;
+    if let Some(tool) = crate::foreign_managers::folder_manager(path) {
+        return Err(format!("Managed by {}, change it there", tool).into());
+    }
+    if let Some(hidden) = crate::mod_installer::hidden_group(path) {
+        return Err(format!("{} is hidden, which disables every mod in it", hidden.display()).into());
+    }
+    Ok(())
+}
+
+/// Keep the mod at `keep_path`, enabled, and disable or (with `delete`)
+/// delete the other enabled folders with its UniqueID. Copies already
+/// disabled are left alone.
+pub fn resolve(settings: &Settings, keep_path: &str, delete: bool) -> Result<ResolveReport, AppError> {
+    let mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    let keep = mods
+        .iter()
+        .find(|m| m.path == keep_path)
+        .ok_or_else(|| AppError::ModNotFound { path: keep_path.to_string() })?;
+    if !keep.is_enabled {
+        check_enable(settings, Path::new(keep_path))?;
+    }
+
+    let copies = mods
+        .iter()
+        .filter(|m| m.is_enabled && m.path != keep_path && m.unique_id.eq_ignore_ascii_case(&keep.unique_id));
+    let mut report = ResolveReport::default();
+    for path in copies.map(|m| &m.path) {
+        match remove_copy(settings, Path::new(path), Path::new(keep_path), delete) {
+            Ok(Some(disabled)) => report.disabled.push(disabled),
+            Ok(None) => report.deleted.push(path.clone()),
+            Err(error) => report.failed.push(FailedDuplicate {
+                path: path.clone(),
+                error,
+            }),
+        }
+    }
+
+    // Enabled last, so its name can't clash with a copy still enabled
+    report.kept = if keep.is_enabled {
+        keep_path.to_string()
+    } else {
+        crate::mod_installer::set_mod_enabled(Path::new(keep_path), true, settings.disabled_folder_style)?
+            .to_string_lossy()
+            .to_string()
+    };
+
+    info!(
+        "🧹 Kept {} of {} ({} disabled, {} deleted, {} failed)",
+        report.kept,
+        keep.unique_id,
+        report.disabled.len(),
+        report.deleted.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_keeps_one_copy() {
+        let game_dir = std::env::temp_dir().join(format!("tc_duplicates_{}", uuid::Uuid::new_v4()));
+        let mods_dir = game_dir.join("Mods");
+        let write_mod = |dir: &str, version: &str| {
+            fs::create_dir_all(mods_dir.join(dir)).unwrap();
+            let id = if dir == "Other" { "Tester.Other" } else { "Pathoschild.LookupAnything" };
+            fs::write(
+                mods_dir.join(dir).join("manifest.json"),
+                format!(r#"{{"Name": "{}", "Author": "Tester", "Version": "{}", "UniqueID": "{}"}}"#, dir, version, id),
+            )
+            .unwrap();
+        };
+        for (dir, version) in [
+            ("LookupAnything", "1.0.0"),
+            ("Lookup Anything 1.1", "1.1.0"),
+            ("LookupAnything 0.9.disabled", "0.9.0"),
+            ("Other", "1.0.0"),
+        ] {
+            write_mod(dir, version);
+        }
+        let settings = Settings {
+            game_path: game_dir.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+
+        let mods = crate::mod_installer::scan_mods(&game_dir);
+        let other = mods.iter().find(|m| m.unique_id == "Tester.Other").unwrap();
+        assert!(other.duplicate_paths.is_empty());
+        let old = mods.iter().find(|m| m.version == "1.0.0" && m.unique_id != "Tester.Other").unwrap();
+        assert_eq!(old.duplicate_paths, vec![mods_dir.join("Lookup Anything 1.1").to_string_lossy().to_string()]);
+        let disabled = mods.iter().find(|m| m.version == "0.9.0").unwrap();
+        assert!(disabled.duplicate_paths.is_empty());
+
+        let keep = mods_dir.join("Lookup Anything 1.1").to_string_lossy().to_string();
+        let report = resolve(&settings, &keep, false).unwrap();
+        assert_eq!(report.kept, keep);
+        assert_eq!(report.disabled, vec![mods_dir.join("LookupAnything.disabled").to_string_lossy().to_string()]);
+        assert!(crate::mod_installer::scan_mods(&game_dir).iter().all(|m| m.duplicate_paths.is_empty()));
+
+        write_mod("LookupAnything copy", "1.0.0");
+        let report = resolve(&settings, &keep, true).unwrap();
+        assert_eq!(report.deleted, vec![mods_dir.join("LookupAnything copy").to_string_lossy().to_string()]);
+        assert!(mods_dir.join("LookupAnything.disabled").is_dir());
+        assert!(mods_dir.join("LookupAnything 0.9.disabled").is_dir());
+        assert!(crate::mod_installer::scan_mods(&game_dir).iter().all(|m| m.duplicate_paths.is_empty()));
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+use crate::events::{self, AppEvent};
+use crate::models::Mod;
+use crate::nexus_api::NexusClient;
+use crate::settings::{EndorsementReminders, Settings};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tracing::{info, warn};
+
+/// How often the "mods you haven't endorsed" report is produced
+const REPORT_INTERVAL_DAYS: i64 = 7;
+
+/// A mod page remembered after installing, until the mod gets endorsed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedPage {
+    pub mod_id: u32,
+    pub mod_name: String,
+    pub page_url: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+/// Persisted in endorsements.json in the app data directory
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct EndorsementState {
+    #[serde(default)]
+    recorded: Vec<RecordedPage>,
+    #[serde(default)]
+    last_report_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnendorsedMod {
+    pub mod_id: u32,
+    pub name: String,
+    pub page_url: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EndorsementReport {
+    pub generated_at: DateTime<Utc>,
+    pub mods: Vec<UnendorsedMod>,
+}
+
+pub fn mod_page_url(mod_id: u32) -> String {
+    format!("https://www.nexusmods.com/stardewvalley/mods/{}", mod_id)
+}
+
+fn state_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("endorsements.json"))
+}
+
+fn load_state(path: &Path) -> EndorsementState {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(path: &Path, state: &EndorsementState) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize endorsements: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write endorsements: {}", e))
+}
+
+/// Record (and optionally open) a freshly installed mod's Nexus page
+pub fn after_install(app_handle: &AppHandle, settings: &Settings, mod_id: u32, mod_name: &str) {
+    if settings.endorsement_reminders == EndorsementReminders::Off {
+        return;
+    }
+
+    let page_url = mod_page_url(mod_id);
+
+    if let Some(path) = state_path(app_handle) {
+        let mut state = load_state(&path);
+        if !state.recorded.iter().any(|p| p.mod_id == mod_id) {
+            state.recorded.push(RecordedPage {
+                mod_id,
+                mod_name: mod_name.to_string(),
+                page_url: page_url.clone(),
+                installed_at: Utc::now(),
+            });
+            if let Err(e) = save_state(&path, &state) {
+                warn!("Failed to record mod page: {}", e);
+            }
+        }
+    }
+
+    if settings.endorsement_reminders == EndorsementReminders::Open {
+        info!("🌐 Opening mod page: {}", page_url);
+        if let Err(e) = app_handle.opener().open_url(page_url, None::<&str>) {
+            warn!("Failed to open mod page: {}", e);
+        }
+    }
+}
+
+/// Fetch the ids of Stardew mods the user has endorsed or abstained from
+async fn fetch_decided_mod_ids(nexus: &NexusClient) -> Result<HashSet<u32>, String> {
+    let endorsements = nexus.endorsements().await?;
+
+    Ok(endorsements
+        .into_iter()
+        .filter(|e| e.domain_name == "stardewvalley" && e.status != "Undecided")
+        .map(|e| e.mod_id)
+        .collect())
+}
+
+/// Installed Nexus mods and recorded pages the user hasn't endorsed yet
+fn unendorsed_mods(installed: &[Mod], recorded: &[RecordedPage], decided: &HashSet<u32>) -> Vec<UnendorsedMod> {
+    let mut seen = HashSet::new();
+    let mut mods = Vec::new();
+
+    let candidates = installed
+        .iter()
+        .filter_map(|m| m.nexus_mod_id.map(|id| (id, m.name.clone())))
+        .chain(recorded.iter().map(|p| (p.mod_id, p.mod_name.clone())));
+
+    for (mod_id, name) in candidates {
+        if decided.contains(&mod_id) || !seen.insert(mod_id) {
+            continue;
+        }
+        mods.push(UnendorsedMod {
+            mod_id,
+            name,
+            page_url: mod_page_url(mod_id),
+        });
+    }
+
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    mods
+}
+
+/// Build the "mods you haven't endorsed" report
+pub async fn build_report(app_handle: &AppHandle) -> Result<EndorsementReport, String> {
+    let settings = Settings::load(app_handle)?;
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err("Nexus API key not configured".to_string());
+    }
+
+    let decided = fetch_decided_mod_ids(&nexus).await?;
+
+    let installed = if settings.game_path.is_empty() {
+        Vec::new()
+    } else {
+        crate::mod_installer::scan_mods(Path::new(&settings.game_path))
+    };
+
+    let path = state_path(app_handle).ok_or("Failed to get app data directory")?;
+    let mut state = load_state(&path);
+
+    // Endorsed mods no longer need reminding
+    state.recorded.retain(|p| !decided.contains(&p.mod_id));
+
+    let generated_at = Utc::now();
+    let mods = unendorsed_mods(&installed, &state.recorded, &decided);
+
+    state.last_report_at = Some(generated_at);
+    save_state(&path, &state)?;
+
+    Ok(EndorsementReport { generated_at, mods })
+}
+
+/// Emit `endorsement-report` if reminders are on and the last report is a week old
+pub async fn run_weekly_report(app_handle: &AppHandle) {
+    let Ok(settings) = Settings::load(app_handle) else {
+        return;
+    };
+    if settings.endorsement_reminders == EndorsementReminders::Off {
+        return;
+    }
+
+    let Some(path) = state_path(app_handle) else {
+        return;
+    };
+    let last_report_at = load_state(&path).last_report_at;
+    if last_report_at.is_some_and(|at| Utc::now() - at < Duration::days(REPORT_INTERVAL_DAYS)) {
+        return;
+    }
+
+    match build_report(app_handle).await {
+        Ok(report) => {
+            info!("🙏 {} installed mods not endorsed yet", report.mods.len());
+            if !report.mods.is_empty() {
+                events::emit(app_handle, AppEvent::EndorsementReport(report));
+            }
+        }
+        Err(e) => warn!("Failed to build endorsement report: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_mod;
+
+    fn nexus_mod(name: &str, nexus_id: Option<u32>) -> Mod {
+        Mod {
+            unique_id: format!("Someone.{}", name),
+            nexus_mod_id: nexus_id,
+            ..test_mod(name)
+        }
+    }
+
+    #[test]
+    fn test_unendorsed_mods_skips_decided_and_duplicates() {
+        let installed = vec![
+            nexus_mod("Zeta", Some(1)),
+            nexus_mod("Alpha", Some(2)),
+            nexus_mod("Local", None),
+        ];
+        let recorded = vec![
+            RecordedPage {
+                mod_id: 2,
+                mod_name: "Alpha".to_string(),
+                page_url: mod_page_url(2),
+                installed_at: Utc::now(),
+            },
+            RecordedPage {
+                mod_id: 3,
+                mod_name: "Removed Since".to_string(),
+                page_url: mod_page_url(3),
+                installed_at: Utc::now(),
+            },
+        ];
+        let decided: HashSet<u32> = [1].into_iter().collect();
+
+        let mods = unendorsed_mods(&installed, &recorded, &decided);
+        let ids: Vec<u32> = mods.iter().map(|m| m.mod_id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(mods[0].page_url, "https://www.nexusmods.com/stardewvalley/mods/2");
+    }
+}
@@ -0,0 +1,181 @@
+//! Installed SMAPI and game versions, read from the files next to SMAPI's
+//! executable (the version resource of `StardewModdingAPI.dll`, the game's
+//! `.deps.json`) and falling back to the header of the last SMAPI log.
+
+use crate::nexus_api::compare_versions;
+use crate::settings::{detect_smapi_path, Settings};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Oldest SMAPI that runs each game version, newest game first
+const MINIMUM_SMAPI: &[(&str, &str)] = &[
+    ("1.6.9", "4.1.0"),
+    ("1.6.0", "4.0.0"),
+    ("1.5.5", "3.13.0"),
+    ("1.5.0", "3.8.0"),
+];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionSource {
+    /// The version resource of the assembly
+    Assembly,
+    /// The `.deps.json` file written alongside the executable
+    DepsJson,
+    /// The header of the last SMAPI log, as of the last launch
+    SmapiLog,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedVersion {
+    pub version: String,
+    pub source: VersionSource,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentInfo {
+    pub smapi: Option<DetectedVersion>,
+    pub game: Option<DetectedVersion>,
+    /// Oldest SMAPI that supports the installed game version
+    pub minimum_smapi: Option<String>,
+    /// The installed SMAPI is older than `minimum_smapi`
+    pub smapi_outdated: bool,
+}
+
+/// Version of `library` in a .deps.json, from its "Name/1.2.3" key
+fn deps_version(deps_json: &str, library: &str) -> Option<String> {
+    let deps: serde_json::Value = serde_json::from_str(deps_json).ok()?;
+    let prefix = format!("{}/", library);
+    deps.get("libraries")?
+        .as_object()?
+        .keys()
+        .find_map(|key| key.strip_prefix(&prefix))
+        .map(|version| version.to_string())
+}
+
+/// Product version from the version resource of a .NET assembly, without
+/// the "+commit" suffix the SDK appends. The resource stores it as the
+/// UTF-16 key "ProductVersion", padded to 32 bits, then the value.
+fn assembly_version(dll: &[u8]) -> Option<String> {
+    let key: Vec<u8> = "ProductVersion\0".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let start = dll.windows(key.len()).position(|window| window == key.as_slice())? + key.len();
+    let units: Vec<u16> = dll[start..]
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .skip_while(|unit| *unit == 0)
+        .take_while(|unit| *unit != 0)
+        .collect();
+    let version = String::from_utf16(&units).ok()?;
+    let version = version.split('+').next()?.trim();
+    (!version.is_empty() && version.starts_with(|c: char| c.is_ascii_digit())).then(|| version.to_string())
+}
+
+fn read_assembly(dir: &Path, file: &str) -> Option<DetectedVersion> {
+    let dll = fs::read(dir.join(file)).ok()?;
+    Some(DetectedVersion {
+        version: assembly_version(&dll)?,
+        source: VersionSource::Assembly,
+    })
+}
+
+fn minimum_smapi(game_version: &str) -> Option<&'static str> {
+    MINIMUM_SMAPI
+        .iter()
+        .find(|(game, _)| compare_versions(game_version, game) != Ordering::Less)
+        .map(|(_, smapi)| *smapi)
+}
+
+/// Folder holding SMAPI's executable, or where it would be installed
+fn smapi_dir(settings: &Settings) -> Option<PathBuf> {
+    let smapi_path = if settings.smapi_path.is_empty() {
+        detect_smapi_path(Path::new(&settings.game_path))?
+    } else {
+        PathBuf::from(&settings.smapi_path)
+    };
+    smapi_path.parent().map(Path::to_path_buf)
+}
+
+fn read_deps(dir: &Path, file: &str, library: &str) -> Option<DetectedVersion> {
+    let contents = fs::read_to_string(dir.join(file)).ok()?;
+    Some(DetectedVersion {
+        version: deps_version(&contents, library)?,
+        source: VersionSource::DepsJson,
+    })
+}
+
+pub fn environment_info(settings: &Settings) -> EnvironmentInfo {
+    let dir = smapi_dir(settings);
+    let logged = crate::smapi_compat::detect_versions();
+    let from_log = |version: &str| DetectedVersion {
+        version: version.to_string(),
+        source: VersionSource::SmapiLog,
+    };
+
+    let smapi = dir
+        .as_deref()
+        .and_then(|dir| read_assembly(dir, "StardewModdingAPI.dll"))
+        .or_else(|| logged.as_ref().map(|v| from_log(&v.smapi)));
+    let game = dir
+        .as_deref()
+        .and_then(|dir| read_deps(dir, "Stardew Valley.deps.json", "Stardew Valley"))
+        .or_else(|| logged.as_ref().map(|v| from_log(&v.game)));
+
+    let minimum_smapi = game.as_ref().and_then(|g| minimum_smapi(&g.version));
+    let smapi_outdated = match (&smapi, minimum_smapi) {
+        (Some(smapi), Some(minimum)) => compare_versions(&smapi.version, minimum) == Ordering::Less,
+        _ => false,
+    };
+
+    EnvironmentInfo {
+        smapi,
+        game,
+        minimum_smapi: minimum_smapi.map(str::to_string),
+        smapi_outdated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deps_version() {
+        let deps = r#"{
+            "runtimeTarget": { "name": ".NETCoreApp,Version=v6.0" },
+            "libraries": {
+                "Stardew Valley/1.6.15": { "type": "project" },
+                "MonoGame.Framework/3.8.0": { "type": "project" }
+            }
+        }"#;
+        assert_eq!(deps_version(deps, "Stardew Valley").as_deref(), Some("1.6.15"));
+        assert_eq!(deps_version(deps, "StardewModdingAPI"), None);
+        assert_eq!(deps_version("not json", "Stardew Valley"), None);
+    }
+
+    #[test]
+    fn test_assembly_version() {
+        // A String entry of a VS_VERSIONINFO block: key, padding, value
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+        let mut dll = b"MZ\x90\0 some code".to_vec();
+        dll.extend(utf16("FileVersion\0"));
+        dll.extend(utf16("4.1.10.0\0"));
+        dll.extend(utf16("ProductVersion\0"));
+        dll.extend([0, 0]);
+        dll.extend(utf16("4.1.10+3b3e5a5ec1c2d6e7f0a1b2c3d4e5f6a7b8c9d0e1\0"));
+
+        assert_eq!(assembly_version(&dll).as_deref(), Some("4.1.10"));
+        assert_eq!(assembly_version(b"MZ no version resource"), None);
+    }
+
+    #[test]
+    fn test_minimum_smapi() {
+        assert_eq!(minimum_smapi("1.6.15"), Some("4.1.0"));
+        assert_eq!(minimum_smapi("1.6.8"), Some("4.0.0"));
+        assert_eq!(minimum_smapi("1.5.6"), Some("3.13.0"));
+        assert_eq!(minimum_smapi("1.4.5"), None);
+    }
+}
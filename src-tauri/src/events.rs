@@ -0,0 +1,205 @@
+//! Every event sent to the frontend goes through `emit` with an `AppEvent`, so
+//! channel names and payload shapes are defined in one place. The frontend
+//! mirrors them in src/types/events.ts.
+
+use crate::api_usage_tracker::QuotaWarning;
+use crate::dependencies::MissingDependency;
+use crate::download_manager::{DownloadProgress, DownloadTask, QueueProgress};
+use crate::endorsements::EndorsementReport;
+use crate::game_session::GameSessionSummary;
+use crate::maintenance::MaintenanceStatus;
+use crate::mod_installer::{InstallOptions, InstallResult};
+use crate::nexus_api::{ModFile, NexusRequirement};
+use crate::nxm_protocol::NxmUrl;
+use crate::smapi_console::SmapiLine;
+use crate::startup::StartupReport;
+use crate::update_all::{AvailableUpdate, ModUpdateProgress};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+/// Channel the auto-installer listens on
+pub const DOWNLOAD_COMPLETED: &str = "download-completed";
+
+/// Where events are sent; the app handle in production, a recorder in tests
+pub trait EventSink: Send + Sync {
+    fn emit_json(&self, event: &str, payload: serde_json::Value);
+
+    /// Forward the event outside the app (webhooks, script hooks); nothing
+    /// by default
+    fn notify(&self, _event: &AppEvent) {}
+}
+
+impl EventSink for AppHandle {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        let _ = Emitter::emit(self, event, payload);
+    }
+
+    fn notify(&self, event: &AppEvent) {
+        crate::webhook::notify(self, event);
+        crate::script_hooks::run(self, event);
+    }
+}
+
+/// Payloads serialize as the variant's content; struct variants become
+/// camelCase objects and unit variants `null`
+#[derive(Serialize, Clone)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum AppEvent {
+    NxmUrlReceived(NxmUrl),
+    NxmError(String),
+    /// Raw deep link / launch argument, for debugging protocol registration
+    DebugDeepLink(String),
+    DownloadQueued(Box<DownloadTask>),
+    DownloadQueueReordered(Vec<DownloadTask>),
+    /// Latest progress of every download that advanced since the last batch
+    DownloadProgressBatch(Vec<DownloadProgress>),
+    /// Totals over the whole queue, sent along with each batch
+    DownloadQueueProgress(QueueProgress),
+    DownloadCompleted(String),
+    DownloadCancelled(String),
+    DownloadRetrying {
+        download_id: String,
+        attempt: u32,
+        max_attempts: u32,
+        error: String,
+    },
+    DownloadRateLimited {
+        download_id: String,
+        retry_at: DateTime<Utc>,
+    },
+    DownloadFailed {
+        download_id: String,
+        error: String,
+        retryable: bool,
+    },
+    /// A large download was paused because the connection is metered
+    DownloadMeteredHold {
+        download_id: String,
+        bytes_total: u64,
+        needs_confirmation: bool,
+    },
+    /// Other files of a just-queued mod that it probably needs
+    RequiredFilesAvailable {
+        download_id: String,
+        mod_id: u32,
+        files: Vec<ModFile>,
+    },
+    InstallConfirmationNeeded(String),
+    /// The archive holds alternative folders; answer with `install_archive_options`
+    InstallOptionsNeeded(InstallOptions),
+    /// Required dependencies that couldn't be installed automatically
+    DependenciesMissing(Vec<MissingDependency>),
+    /// Requirements listed on an installed mod's Nexus page that aren't installed
+    NexusRequirementsMissing {
+        mod_id: u32,
+        requirements: Vec<NexusRequirement>,
+    },
+    ModInstalled(InstallResult),
+    ModInstallFailed(String),
+    ModUpdatesAvailable(Vec<AvailableUpdate>),
+    ModUpdateProgress(ModUpdateProgress),
+    EndorsementReport(EndorsementReport),
+    StartupComplete(StartupReport),
+    /// A maintenance run finished, on schedule or on request
+    MaintenanceFinished(MaintenanceStatus),
+    NexusSsoWaiting(String),
+    NexusSsoConnected,
+    /// Few Nexus API requests are left before the quota runs out
+    ApiQuotaLow(QuotaWarning),
+    /// A console line of a game launched with captured output
+    SmapiOutput(SmapiLine),
+    SmapiExited {
+        exit_code: Option<i32>,
+        crashed: bool,
+    },
+    /// SMAPI exited; sent in both launch modes
+    GameSessionEnded(GameSessionSummary),
+}
+
+impl AppEvent {
+    pub fn channel(&self) -> &'static str {
+        match self {
+            AppEvent::NxmUrlReceived(_) => "nxm-url-received",
+            AppEvent::NxmError(_) => "nxm-error",
+            AppEvent::DebugDeepLink(_) => "debug-deep-link",
+            AppEvent::DownloadQueued(_) => "download-queued",
+            AppEvent::DownloadQueueReordered(_) => "download-queue-reordered",
+            AppEvent::DownloadProgressBatch(_) => "download-progress-batch",
+            AppEvent::DownloadQueueProgress(_) => "download-queue-progress",
+            AppEvent::DownloadCompleted(_) => DOWNLOAD_COMPLETED,
+            AppEvent::DownloadCancelled(_) => "download-cancelled",
+            AppEvent::DownloadRetrying { .. } => "download-retrying",
+            AppEvent::DownloadRateLimited { .. } => "download-rate-limited",
+            AppEvent::DownloadFailed { .. } => "download-failed",
+            AppEvent::DownloadMeteredHold { .. } => "download-metered-hold",
+            AppEvent::RequiredFilesAvailable { .. } => "required-files-available",
+            AppEvent::InstallConfirmationNeeded(_) => "install-confirmation-needed",
+            AppEvent::InstallOptionsNeeded(_) => "install-options-needed",
+            AppEvent::DependenciesMissing(_) => "dependencies-missing",
+            AppEvent::NexusRequirementsMissing { .. } => "nexus-requirements-missing",
+            AppEvent::ModInstalled(_) => "mod-installed",
+            AppEvent::ModInstallFailed(_) => "mod-install-failed",
+            AppEvent::ModUpdatesAvailable(_) => "mod-updates-available",
+            AppEvent::ModUpdateProgress(_) => "mod-update-progress",
+            AppEvent::EndorsementReport(_) => "endorsement-report",
+            AppEvent::StartupComplete(_) => "startup-complete",
+            AppEvent::MaintenanceFinished(_) => "maintenance-finished",
+            AppEvent::NexusSsoWaiting(_) => "nexus-sso-waiting",
+            AppEvent::NexusSsoConnected => "nexus-sso-connected",
+            AppEvent::ApiQuotaLow(_) => "api-quota-low",
+            AppEvent::SmapiOutput(_) => "smapi-output",
+            AppEvent::SmapiExited { .. } => "smapi-exited",
+            AppEvent::GameSessionEnded(_) => "game-session-ended",
+        }
+    }
+}
+
+/// Send an event to the frontend and any configured webhook
+pub fn emit(sink: &dyn EventSink, event: AppEvent) {
+    match serde_json::to_value(&event) {
+        Ok(payload) => sink.emit_json(event.channel(), payload),
+        Err(e) => warn!("Failed to serialize {} event: {}", event.channel(), e),
+    }
+    sink.notify(&event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, serde_json::Value)>>);
+
+    impl EventSink for Recorder {
+        fn emit_json(&self, event: &str, payload: serde_json::Value) {
+            self.0.lock().unwrap().push((event.to_string(), payload));
+        }
+    }
+
+    #[test]
+    fn test_payload_shapes() {
+        let recorder = Recorder::default();
+        emit(
+            &recorder,
+            AppEvent::DownloadFailed {
+                download_id: "abc".to_string(),
+                error: "boom".to_string(),
+                retryable: true,
+            },
+        );
+        emit(&recorder, AppEvent::DownloadCompleted("abc".to_string()));
+        emit(&recorder, AppEvent::NexusSsoConnected);
+
+        let events = recorder.0.into_inner().unwrap();
+        assert_eq!(events[0].0, "download-failed");
+        assert_eq!(
+            events[0].1,
+            serde_json::json!({"downloadId": "abc", "error": "boom", "retryable": true})
+        );
+        assert_eq!(events[1], (DOWNLOAD_COMPLETED.to_string(), serde_json::json!("abc")));
+        assert_eq!(events[2].1, serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,77 @@
+//! Taking over a Mods folder from Stardrop or ModDrop. Both leave mods where
+//! SMAPI loads them, so nothing is copied: the folders are brought in line
+//! with this app's conventions and then recorded by the library migration.
+//! Stardrop disables a mod by prefixing its folder with ".", which is
+//! renamed to the style set in Settings. A hidden group is only made
+//! visible once every mod in it is disabled; otherwise it stays hidden and
+//! the folders that failed are listed. Mods from ModDrop are recognized by
+//! their "ModDrop:<id>" update keys.
+
+use crate::disabled_folders::{FailedRename, FolderRename};
+use crate::library_migration::MigrationReport;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tracing::info;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ForeignTool {
+    Stardrop,
+    ModDrop,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignImportReport {
+    pub tool: ForeignTool,
+    pub dry_run: bool,
+    /// Disabled folders renamed to the app's style
+    pub renames: Vec<FolderRename>,
+    /// Renames that failed; those folders are left as Stardrop had them
+    pub failed: Vec<FailedRename>,
+    /// Mods with a ModDrop update key
+    pub moddrop_matched: usize,
+    pub migration: MigrationReport,
+}
+
+/// Convert what `tool` left in the Mods folder and record every mod in the
+/// registry. With `dry_run` nothing is written and the report shows what
+/// would change.
+pub async fn import(app_handle: &AppHandle, tool: ForeignTool, dry_run: bool) -> Result<ForeignImportReport, String> {
+    let settings = Settings::load(app_handle)?;
+    if !dry_run {
+        settings.ensure_writable()?;
+    }
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let (renames, failed) = match tool {
+        ForeignTool::Stardrop => {
+            let folders = crate::disabled_folders::normalize(&settings, settings.disabled_folder_style, dry_run);
+            (folders.renames, folders.failed)
+        }
+        ForeignTool::ModDrop => (Vec::new(), Vec::new()),
+    };
+
+    let migration = crate::library_migration::migrate(app_handle, dry_run).await?;
+    let report = ForeignImportReport {
+        tool,
+        dry_run,
+        renames,
+        failed,
+        moddrop_matched: migration.mods.iter().filter(|m| m.moddrop_id.is_some()).count(),
+        migration,
+    };
+    if !dry_run {
+        info!(
+            "📥 Imported {} mods from {:?} ({} folders renamed, {} ModDrop mods)",
+            report.migration.mods.len(),
+            tool,
+            report.renames.len() - report.failed.len(),
+            report.moddrop_matched
+        );
+    }
+    Ok(report)
+}
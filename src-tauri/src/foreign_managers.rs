@@ -0,0 +1,64 @@
+use std::path::Path;
+
+pub const VORTEX: &str = "Vortex";
+
+/// Files other mod managers leave in the folders they deploy, and the tool
+/// that owns them. Vortex drops its marker into every folder it manages and
+/// keeps its deployment manifest in the Mods folder itself.
+const MARKERS: &[(&str, &str)] = &[
+    ("__folder_managed_by_vortex", VORTEX),
+    ("vortex.deployment.json", VORTEX),
+];
+
+/// The tool managing `dir`, if it left a marker there
+pub fn folder_manager(dir: &Path) -> Option<&'static str> {
+    MARKERS
+        .iter()
+        .find(|(marker, _)| dir.join(marker).exists())
+        .map(|(_, tool)| *tool)
+}
+
+/// Tools deploying into the Mods folder of `game_path`, whether they marked
+/// the folder itself or any mod in it
+pub fn mods_folder_managers(game_path: &Path) -> Vec<&'static str> {
+    let mods_dir = game_path.join("Mods");
+    let mut tools: Vec<&'static str> = folder_manager(&mods_dir).into_iter().collect();
+
+    if let Ok(entries) = std::fs::read_dir(&mods_dir) {
+        for entry in entries.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()) {
+            if let Some(tool) = folder_manager(&entry.path()) {
+                if !tools.contains(&tool) {
+                    tools.push(tool);
+                }
+            }
+        }
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_detects_vortex_folders() {
+        let game_dir = std::env::temp_dir().join(format!("tc_foreign_{}", uuid::Uuid::new_v4()));
+        let mods_dir = game_dir.join("Mods");
+        fs::create_dir_all(mods_dir.join("Ours")).unwrap();
+        fs::create_dir_all(mods_dir.join("Theirs")).unwrap();
+        assert!(mods_folder_managers(&game_dir).is_empty());
+
+        fs::write(mods_dir.join("Theirs").join("__folder_managed_by_vortex"), "").unwrap();
+        assert_eq!(folder_manager(&mods_dir.join("Theirs")), Some(VORTEX));
+        assert_eq!(folder_manager(&mods_dir.join("Ours")), None);
+        assert_eq!(mods_folder_managers(&game_dir), vec![VORTEX]);
+
+        // The deployment manifest marks the whole Mods folder
+        fs::remove_file(mods_dir.join("Theirs").join("__folder_managed_by_vortex")).unwrap();
+        fs::write(mods_dir.join("vortex.deployment.json"), "{}").unwrap();
+        assert_eq!(mods_folder_managers(&game_dir), vec![VORTEX]);
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
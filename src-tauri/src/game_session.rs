@@ -0,0 +1,177 @@
+//! Report on a game session launched from the app: once SMAPI exits, its exit
+//! code and the log it just wrote are summarized into `game-session-ended`.
+
+use crate::events::{self, AppEvent};
+use crate::smapi_console::{parse_line, ConsoleMarker};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Child;
+use tauri::AppHandle;
+use tracing::info;
+
+/// Log sources that aren't mods
+const NON_MOD_SOURCES: &[&str] = &["SMAPI", "game"];
+
+/// Warnings and errors one mod logged
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLogIssues {
+    /// Mod name as SMAPI logs it
+    pub source: String,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct LogSummary {
+    errors: usize,
+    warnings: usize,
+    mods: Vec<ModLogIssues>,
+    crashed: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSessionSummary {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+    pub exit_code: Option<i32>,
+    pub crashed: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Mods that logged warnings or errors, worst first
+    pub mods: Vec<ModLogIssues>,
+    /// None when SMAPI didn't write a log this session
+    pub log_path: Option<PathBuf>,
+}
+
+/// Count warnings and errors in a SMAPI log, per mod
+fn summarize_log(log: &str) -> LogSummary {
+    let mut summary = LogSummary::default();
+    let mut by_mod: HashMap<String, ModLogIssues> = HashMap::new();
+
+    for line in log.lines().map(|line| parse_line(line, false)) {
+        summary.crashed |= line.marker == Some(ConsoleMarker::Crashed);
+        let (Some(level), Some(source)) = (line.level.as_deref(), line.source) else {
+            continue;
+        };
+        let is_error = matches!(level, "ERROR" | "ALERT");
+        if !is_error && level != "WARN" {
+            continue;
+        }
+
+        if is_error {
+            summary.errors += 1;
+        } else {
+            summary.warnings += 1;
+        }
+        if NON_MOD_SOURCES.contains(&source.as_str()) {
+            continue;
+        }
+
+        let issues = by_mod.entry(source.clone()).or_insert(ModLogIssues {
+            source,
+            warnings: 0,
+            errors: 0,
+        });
+        if is_error {
+            issues.errors += 1;
+        } else {
+            issues.warnings += 1;
+        }
+    }
+
+    summary.mods = by_mod.into_values().collect();
+    summary
+        .mods
+        .sort_by(|a, b| (b.errors, b.warnings, &a.source).cmp(&(a.errors, a.warnings, &b.source)));
+    summary
+}
+
+/// Wait for SMAPI in the background and report the session when it exits
+pub fn watch(app_handle: &AppHandle, mut child: Child, started_at: DateTime<Utc>) {
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let exit_code = child.wait().ok().and_then(|status| status.code());
+        session_ended(&handle, started_at, exit_code, false);
+    });
+}
+
+/// Summarize the session that started at `started_at`. `console_crashed` is
+/// set when captured output already showed a crash.
+pub fn session_ended(app_handle: &AppHandle, started_at: DateTime<Utc>, exit_code: Option<i32>, console_crashed: bool) {
+    let ended_at = Utc::now();
+
+    // Only a log written since the launch belongs to this session
+    let log_path = crate::settings::smapi_log_path().filter(|path| {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| DateTime::<Utc>::from(modified) >= started_at)
+    });
+    let log = log_path
+        .as_ref()
+        .and_then(|path| fs::read(path).ok())
+        .map(|bytes| summarize_log(&String::from_utf8_lossy(&bytes)))
+        .unwrap_or_default();
+
+    let summary = GameSessionSummary {
+        started_at,
+        ended_at,
+        duration_seconds: (ended_at - started_at).num_seconds(),
+        exit_code,
+        crashed: console_crashed || log.crashed || exit_code.is_some_and(|code| code != 0),
+        errors: log.errors,
+        warnings: log.warnings,
+        mods: log.mods,
+        log_path,
+    };
+    info!(
+        "🎮 Game session ended after {}s: {} errors, {} warnings{}",
+        summary.duration_seconds,
+        summary.errors,
+        summary.warnings,
+        if summary.crashed { ", crashed" } else { "" }
+    );
+    events::emit(app_handle, AppEvent::GameSessionEnded(summary));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_log() {
+        let log = "[12:00:00 INFO  SMAPI] SMAPI 4.0.8 with Stardew Valley 1.6.8\n\
+                   [12:00:01 WARN  SMAPI] Skipped 1 mod\n\
+                   [12:00:02 WARN  Lookup Anything] Couldn't read a config value\n\
+                   [12:00:03 ERROR Fancy Fish] Failed to patch fish\n\
+                   [12:00:03 ERROR Fancy Fish] Failed to patch more fish\n\
+                   [12:00:04 WARN  Fancy Fish] Falling back\n\
+                   [12:00:05 TRACE Fancy Fish] Details nobody reads";
+
+        let summary = summarize_log(log);
+        assert_eq!((summary.errors, summary.warnings), (2, 3));
+        assert!(!summary.crashed);
+        assert_eq!(
+            summary.mods,
+            vec![
+                ModLogIssues {
+                    source: "Fancy Fish".to_string(),
+                    warnings: 1,
+                    errors: 2,
+                },
+                ModLogIssues {
+                    source: "Lookup Anything".to_string(),
+                    warnings: 1,
+                    errors: 0,
+                },
+            ]
+        );
+
+        assert!(summarize_log("[12:10:00 ERROR SMAPI] A fatal error occurred").crashed);
+    }
+}
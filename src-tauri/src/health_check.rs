@@ -0,0 +1,240 @@
+use crate::nexus_api::{NexusApiError, NexusClient};
+use crate::settings::{validate_game_path, validate_smapi_path, Settings};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// Free space below which the disk check reports a warning (1 GiB)
+const LOW_DISK_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl HealthCheck {
+    fn new(id: &str, label: &str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Run every startup check and return the resulting checklist
+pub async fn run_startup_checks(app_handle: &AppHandle) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+
+    let settings = match Settings::load(app_handle) {
+        Ok(settings) => {
+            checks.push(HealthCheck::new("settings", "Settings", CheckStatus::Ok, "Settings loaded"));
+            settings
+        }
+        Err(e) => {
+            checks.push(HealthCheck::new("settings", "Settings", CheckStatus::Error, e));
+            Settings::default()
+        }
+    };
+
+    checks.push(check_game_path(&settings));
+    checks.push(check_smapi(&settings));
+    checks.push(check_api_key(app_handle, &settings).await);
+    checks.push(check_nxm_handler(app_handle, &settings));
+    checks.push(check_foreign_managers(&settings));
+
+    let app_data_dir = app_handle.path().app_data_dir().unwrap_or_default();
+    checks.push(check_disk_space(&settings, &app_data_dir));
+    checks.push(check_writable("temp_writable", "Temp folder", &app_data_dir.join("temp")));
+    checks.push(check_writable(
+        "downloads_writable",
+        "Downloads folder",
+        &settings.downloads_path(&app_data_dir),
+    ));
+
+    checks
+}
+
+fn check_game_path(settings: &Settings) -> HealthCheck {
+    let (id, label) = ("game_path", "Game path");
+
+    if settings.game_path.is_empty() {
+        return HealthCheck::new(id, label, CheckStatus::Error, "Game path not configured");
+    }
+
+    if validate_game_path(Path::new(&settings.game_path)) {
+        HealthCheck::new(id, label, CheckStatus::Ok, settings.game_path.clone())
+    } else {
+        HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Error,
+            format!("No Stardew Valley installation found at {}", settings.game_path),
+        )
+    }
+}
+
+fn check_smapi(settings: &Settings) -> HealthCheck {
+    let (id, label) = ("smapi", "SMAPI");
+
+    if settings.smapi_path.is_empty() {
+        return HealthCheck::new(id, label, CheckStatus::Warning, "SMAPI path not configured");
+    }
+
+    if validate_smapi_path(Path::new(&settings.smapi_path)) {
+        HealthCheck::new(id, label, CheckStatus::Ok, settings.smapi_path.clone())
+    } else {
+        HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Error,
+            format!("SMAPI not found at {}", settings.smapi_path),
+        )
+    }
+}
+
+async fn check_api_key(app_handle: &AppHandle, settings: &Settings) -> HealthCheck {
+    let (id, label) = ("api_key", "Nexus API key");
+
+    if settings.active_api_key().is_empty() {
+        return HealthCheck::new(id, label, CheckStatus::Warning, "Nexus API key not configured");
+    }
+
+    match NexusClient::new(app_handle, settings).validate_user().await {
+        Ok(user) => HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Ok,
+            format!("API key is valid ({})", user.name),
+        ),
+        Err(NexusApiError::Network(e)) => HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Warning,
+            format!("Could not reach Nexus Mods: {}", e),
+        ),
+        Err(NexusApiError::Http { status: reqwest::StatusCode::UNAUTHORIZED, .. }) => {
+            HealthCheck::new(id, label, CheckStatus::Error, "API key was rejected by Nexus Mods")
+        }
+        Err(e) => HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Warning,
+            format!("Unexpected response from Nexus Mods: {}", e),
+        ),
+    }
+}
+
+fn check_nxm_handler(app_handle: &AppHandle, settings: &Settings) -> HealthCheck {
+    let (id, label) = ("nxm_handler", "nxm:// links");
+
+    if !settings.nxm_handler_enabled {
+        return HealthCheck::new(id, label, CheckStatus::Warning, "nxm:// handler is not enabled");
+    }
+
+    #[cfg(desktop)]
+    let registered = {
+        use tauri_plugin_deep_link::DeepLinkExt;
+        app_handle.deep_link().is_registered("nxm").unwrap_or(false)
+    };
+
+    #[cfg(not(desktop))]
+    let registered = {
+        let _ = app_handle;
+        false
+    };
+
+    if registered {
+        HealthCheck::new(id, label, CheckStatus::Ok, "nxm:// links open in Treasure Chest")
+    } else {
+        HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Warning,
+            "nxm:// links are handled by another application",
+        )
+    }
+}
+
+fn check_foreign_managers(settings: &Settings) -> HealthCheck {
+    let (id, label) = ("foreign_managers", "Other mod managers");
+
+    let tools = crate::foreign_managers::mods_folder_managers(Path::new(&settings.game_path));
+    if tools.is_empty() {
+        HealthCheck::new(id, label, CheckStatus::Ok, "No other mod manager deploys to the Mods folder")
+    } else {
+        HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Warning,
+            format!(
+                "{} also deploys to the Mods folder; manage each mod with one tool only, their mods are left untouched here",
+                tools.join(", ")
+            ),
+        )
+    }
+}
+
+fn check_disk_space(settings: &Settings, app_data_dir: &Path) -> HealthCheck {
+    let (id, label) = ("disk_space", "Disk space");
+
+    let mut locations: Vec<PathBuf> = vec![app_data_dir.to_path_buf()];
+    if !settings.game_path.is_empty() {
+        locations.push(PathBuf::from(&settings.game_path));
+    }
+
+    let mut low = Vec::new();
+    for location in locations.iter().filter(|p| p.exists()) {
+        match fs2::available_space(location) {
+            Ok(free) if free < LOW_DISK_SPACE_BYTES => {
+                low.push(format!("{} ({} MB free)", location.display(), free / (1024 * 1024)));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to query free space for {}: {}", location.display(), e),
+        }
+    }
+
+    if low.is_empty() {
+        HealthCheck::new(id, label, CheckStatus::Ok, "Enough free disk space")
+    } else {
+        HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Warning,
+            format!("Low disk space: {}", low.join(", ")),
+        )
+    }
+}
+
+fn check_writable(id: &str, label: &str, dir: &Path) -> HealthCheck {
+    let probe = dir.join(".write_test");
+
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+
+    match result {
+        Ok(_) => HealthCheck::new(id, label, CheckStatus::Ok, dir.display().to_string()),
+        Err(e) => HealthCheck::new(
+            id,
+            label,
+            CheckStatus::Error,
+            format!("{} is not writable: {}", dir.display(), e),
+        ),
+    }
+}
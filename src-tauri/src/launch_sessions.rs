@@ -0,0 +1,131 @@
+use crate::models::Mod;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// One game launch, with the mods that were enabled at the time
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchSession {
+    pub launched_at: DateTime<Utc>,
+    /// UniqueIDs of the enabled mods
+    pub enabled_mods: Vec<String>,
+}
+
+/// Sessions are appended one JSON object per line
+pub fn sessions_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("launch_sessions.jsonl"))
+}
+
+/// Append a session for a launch with the currently installed mods
+pub fn record_launch(app_handle: &AppHandle, mods: &[Mod]) -> Result<(), String> {
+    let path = sessions_path(app_handle).ok_or("Failed to get app data directory")?;
+
+    let session = LaunchSession {
+        launched_at: Utc::now(),
+        enabled_mods: mods
+            .iter()
+            .filter(|m| m.is_enabled)
+            .map(|m| m.unique_id.clone())
+            .collect(),
+    };
+
+    append_session(&path, &session)
+}
+
+fn append_session(path: &Path, session: &LaunchSession) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let line = serde_json::to_string(session).map_err(|e| format!("Failed to serialize session: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open launch sessions: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write launch session: {}", e))
+}
+
+/// Read every recorded session, skipping lines that don't parse
+fn load_sessions(path: &Path) -> Vec<LaunchSession> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Latest launch per mod UniqueID (case-insensitive, as SMAPI treats them)
+fn last_played_by_mod(sessions: &[LaunchSession]) -> HashMap<String, DateTime<Utc>> {
+    let mut last_played: HashMap<String, DateTime<Utc>> = HashMap::new();
+    for session in sessions {
+        for unique_id in &session.enabled_mods {
+            let entry = last_played
+                .entry(unique_id.to_lowercase())
+                .or_insert(session.launched_at);
+            if session.launched_at > *entry {
+                *entry = session.launched_at;
+            }
+        }
+    }
+    last_played
+}
+
+/// Fill in `last_played_at` on each mod from the session log
+pub fn annotate_last_played(app_handle: &AppHandle, mods: &mut [Mod]) {
+    let Some(path) = sessions_path(app_handle) else {
+        return;
+    };
+
+    let last_played = last_played_by_mod(&load_sessions(&path));
+    for m in mods.iter_mut() {
+        m.last_played_at = last_played.get(&m.unique_id.to_lowercase()).copied();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_last_played_keeps_latest_session() {
+        let path = std::env::temp_dir().join("treasure_chest_launch_sessions_test.jsonl");
+        let _ = fs::remove_file(&path);
+
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let second = Utc.with_ymd_and_hms(2024, 2, 1, 12, 0, 0).unwrap();
+
+        append_session(
+            &path,
+            &LaunchSession {
+                launched_at: first,
+                enabled_mods: vec!["Pathoschild.ContentPatcher".to_string(), "Old.Mod".to_string()],
+            },
+        )
+        .unwrap();
+        append_session(
+            &path,
+            &LaunchSession {
+                launched_at: second,
+                enabled_mods: vec!["pathoschild.contentpatcher".to_string()],
+            },
+        )
+        .unwrap();
+
+        let last_played = last_played_by_mod(&load_sessions(&path));
+        assert_eq!(last_played.get("pathoschild.contentpatcher"), Some(&second));
+        assert_eq!(last_played.get("old.mod"), Some(&first));
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -4,17 +4,71 @@ mod nxm_protocol;
 mod download_manager;
 mod mod_installer;
 mod api_usage_tracker;
+mod health_check;
+mod debug_log;
+mod backup;
+mod diagnostics;
+mod mod_query;
+mod saves;
+mod endorsements;
+mod archive;
+mod startup;
+mod launch_sessions;
+mod nexus_api;
+mod nexus_history;
+mod nexus_meta;
+mod nexus_auth;
+mod events;
+mod mod_files;
+mod smapi_compat;
+mod update_all;
+mod local_api;
+mod dependencies;
+mod webhook;
+mod multiplayer_sync;
+mod mod_list;
+mod metered;
+mod mod_registry;
+mod foreign_managers;
+mod script_hooks;
+mod app_info;
+mod smapi_console;
+mod game_session;
+mod maintenance;
+mod smapi_installer;
+mod environment;
+mod perf_metrics;
+mod library_migration;
+mod staging;
+mod logging;
+mod app_error;
+mod mod_config;
+mod vortex_import;
+mod foreign_import;
+mod disabled_folders;
+mod duplicates;
+mod profiles;
+pub mod app_core;
 
 use models::Mod;
-use settings::{Settings, auto_detect_game_path, detect_smapi_path, validate_game_path, validate_smapi_path};
+use settings::{Settings, SaveBackupsOnLaunch, auto_detect_game_path, detect_smapi_path, validate_game_path, validate_smapi_path};
 use nxm_protocol::NxmUrl;
 use download_manager::{DownloadManager, DownloadTask};
-use mod_installer::{ModInstaller, InstallResult};
+use mod_installer::{ModInstaller, InstallError, InstallResult};
+use nexus_meta::NexusMeta;
 use api_usage_tracker::{ApiUsageTracker, ApiUsage};
+use nexus_api::NexusClient;
+use health_check::HealthCheck;
+use mod_query::ModSortKey;
+use saves::SaveModRequirement;
+use endorsements::EndorsementReport;
+use events::AppEvent;
+use app_error::AppError;
 use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
-use tauri::{Emitter, Listener, Manager};
+use tauri::{Listener, Manager};
+use tracing::{error, info, warn};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -22,9 +76,15 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Guard for commands that change the Mods folder, see `Settings::read_only_mode`
+fn ensure_writable(app_handle: &tauri::AppHandle) -> Result<(), AppError> {
+    Settings::load(app_handle)?.ensure_writable()
+}
+
 #[tauri::command]
-async fn install_mod(url: String, game_path: String) -> Result<String, String> {
-    println!("Installing mod from: {}", url);
+async fn install_mod(app_handle: tauri::AppHandle, url: String, game_path: String) -> Result<String, AppError> {
+    ensure_writable(&app_handle)?;
+    info!("Installing mod from: {}", nxm_protocol::loggable(&url));
     
     let bytes = if url.starts_with("http") {
         // 1. Download the file
@@ -48,40 +108,276 @@ async fn install_mod(url: String, game_path: String) -> Result<String, String> {
         fs::create_dir_all(&mods_path).map_err(|e| format!("Failed to create Mods directory: {}", e))?;
     }
 
-    // 3. Extract (assuming zip for now)
-    let reader = Cursor::new(bytes);
-    let mut archive = zip::ZipArchive::new(reader)
-        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
-
-    archive.extract(&mods_path)
-        .map_err(|e| format!("Failed to extract zip: {}", e))?;
+    // 3. Extract (format detected from the archive contents)
+    archive::extract(Cursor::new(bytes), &mods_path)
+        .map_err(|e| format!("Failed to extract archive: {}", e))?;
 
     Ok("Mod installed successfully".to_string())
 }
 
 #[tauri::command]
-fn scan_mods(game_path: String) -> Result<Vec<Mod>, String> {
+fn scan_mods(app_handle: tauri::AppHandle, game_path: String) -> Result<Vec<Mod>, AppError> {
     let mods_path = Path::new(&game_path).join("Mods");
     if !mods_path.exists() {
-        return Err("Mods folder not found".to_string());
+        return Err(AppError::ModsFolderNotFound);
     }
 
-    Ok(mod_installer::scan_mods(Path::new(&game_path)))
+    let timer = perf_metrics::CommandTimer::start("scan_mods");
+    let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+    mod_registry::apply(&app_handle, &mut mods);
+    launch_sessions::annotate_last_played(&app_handle, &mut mods);
+    smapi_compat::annotate(&app_handle, &mut mods);
+    timer.finish(&app_handle, true, Some(mods.len()));
+    Ok(mods)
+}
+
+/// Install date, source, notes and enable history of a mod, by its stable id
+#[tauri::command]
+fn get_mod_record(app_handle: tauri::AppHandle, mod_id: String) -> Result<mod_registry::RegistryEntry, AppError> {
+    mod_registry::entry(&app_handle, &mod_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_mod_notes(app_handle: tauri::AppHandle, mod_id: String, notes: String) -> Result<(), AppError> {
+    mod_registry::set_notes(&app_handle, &mod_id, notes).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_mod_favorite(app_handle: tauri::AppHandle, mod_id: String, favorite: bool) -> Result<(), AppError> {
+    mod_registry::set_favorite(&app_handle, &mod_id, favorite).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn query_mods(
+    app_handle: tauri::AppHandle,
+    game_path: String,
+    sort_by: ModSortKey,
+    descending: bool,
+    category: Option<String>,
+) -> Result<Vec<Mod>, AppError> {
+    let mods_path = Path::new(&game_path).join("Mods");
+    if !mods_path.exists() {
+        return Err(AppError::ModsFolderNotFound);
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let timer = perf_metrics::CommandTimer::start("query_mods");
+        let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+        mod_registry::apply(&app_handle, &mut mods);
+        launch_sessions::annotate_last_played(&app_handle, &mut mods);
+        smapi_compat::annotate(&app_handle, &mut mods);
+        if let Some(category) = category {
+            mods.retain(|m| mod_query::in_category(m, &category));
+        }
+        mod_query::sort_mods(&mut mods, sort_by, descending);
+        timer.finish(&app_handle, true, Some(mods.len()));
+        mods
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("Failed to query mods: {}", e)))
+}
+
+#[tauri::command]
+fn list_saves() -> Vec<String> {
+    saves::list_saves()
+}
+
+#[tauri::command]
+async fn get_save_mod_requirements(game_path: String, save_id: String) -> Result<Vec<SaveModRequirement>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let save_path = saves::save_file_path(&save_id)?;
+        let content = fs::read_to_string(&save_path)
+            .map_err(|e| format!("Failed to read save file: {}", e))?;
+
+        let installed = mod_installer::scan_mods(Path::new(&game_path));
+        Ok(saves::find_save_requirements(&content, &installed))
+    })
+    .await
+    .map_err(|e| format!("Failed to analyze save: {}", e))?
+}
+
+/// Every farm with its farmer, play time and when it was last played
+#[tauri::command]
+async fn list_farms(app_handle: tauri::AppHandle) -> Result<Vec<saves::SaveSummary>, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || saves::list_farms(&app_data_dir))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+#[tauri::command]
+async fn list_save_backups(app_handle: tauri::AppHandle, save_id: String) -> Result<Vec<saves::SaveBackup>, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(saves::list_save_backups(&app_data_dir, &save_id)?)
+}
+
+#[tauri::command]
+async fn backup_save(app_handle: tauri::AppHandle, save_id: String) -> Result<saves::SaveBackup, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let saves_dir = saves::saves_dir().ok_or("Could not locate the Stardew Valley Saves folder")?;
+    tokio::task::spawn_blocking(move || saves::backup_save(&saves_dir, &app_data_dir, &save_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(AppError::from)
+}
+
+/// Replace a save with one of its backups; returns the backup made of the
+/// save it replaced
+#[tauri::command]
+async fn restore_save_backup(
+    app_handle: tauri::AppHandle,
+    save_id: String,
+    backup: String,
+) -> Result<Option<saves::SaveBackup>, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let saves_dir = saves::saves_dir().ok_or("Could not locate the Stardew Valley Saves folder")?;
+    tokio::task::spawn_blocking(move || saves::restore_save_backup(&saves_dir, &app_data_dir, &save_id, &backup))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn list_profiles(app_handle: tauri::AppHandle) -> Result<profiles::ProfileStore, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(profiles::load(&app_data_dir))
+}
+
+/// Save the mods enabled now as a profile and make it the active one
+#[tauri::command]
+async fn save_mod_profile(app_handle: tauri::AppHandle, name: String) -> Result<profiles::ModProfile, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let mods = mod_installer::scan_mods(Path::new(&settings.game_path));
+        profiles::save_profile(&app_data_dir, &name, &mods)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn delete_mod_profile(app_handle: tauri::AppHandle, name: String) -> Result<(), AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(profiles::delete_profile(&app_data_dir, &name)?)
+}
+
+/// Enable the mods of a profile and disable the rest
+#[tauri::command]
+async fn apply_mod_profile(app_handle: tauri::AppHandle, name: String) -> Result<profiles::ApplyReport, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    settings.ensure_writable()?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || profiles::apply_profile(&settings, &app_data_dir, &name))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(AppError::from)
+}
+
+/// Link a save to a profile, or unlink it with no profile
+#[tauri::command]
+fn link_save_profile(app_handle: tauri::AppHandle, save_id: String, profile: Option<String>) -> Result<(), AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(profiles::link_save(&app_data_dir, &save_id, profile.as_deref())?)
+}
+
+/// Whether launching `save_id` (the last played farm if None) would use
+/// another profile than the save's own
+#[tauri::command]
+async fn check_launch_profile(
+    app_handle: tauri::AppHandle,
+    save_id: Option<String>,
+) -> Result<profiles::LaunchCheck, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let save_id = launch_save_id(&app_data_dir, save_id);
+        profiles::check_launch(&profiles::load(&app_data_dir), save_id.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// The farm a launch is for: the one asked for, or the last one played
+fn launch_save_id(app_data_dir: &Path, save_id: Option<String>) -> Option<String> {
+    save_id.or_else(|| saves::list_farms(app_data_dir).into_iter().next().map(|farm| farm.id))
 }
 
 // Settings commands
 #[tauri::command]
-fn load_settings(app_handle: tauri::AppHandle) -> Result<Settings, String> {
-    Settings::load(&app_handle)
+fn load_settings(app_handle: tauri::AppHandle) -> Result<Settings, AppError> {
+    Settings::load(&app_handle).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn save_settings(app_handle: tauri::AppHandle, settings: Settings) -> Result<(), AppError> {
+    settings.save(&app_handle)?;
+    logging::set_level(settings.log_level);
+    Ok(())
+}
+
+#[tauri::command]
+fn add_nexus_account(app_handle: tauri::AppHandle, name: String, api_key: String) -> Result<Settings, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || api_key.trim().is_empty() {
+        return Err("Account name and API key are required".into());
+    }
+
+    let mut settings = Settings::load(&app_handle)?;
+    match settings.nexus_accounts.iter_mut().find(|a| a.name == name) {
+        Some(account) => account.api_key = api_key.trim().to_string(),
+        None => settings.nexus_accounts.push(settings::NexusAccount {
+            name: name.clone(),
+            api_key: api_key.trim().to_string(),
+        }),
+    }
+
+    if settings.active_account.is_none() {
+        settings.active_account = Some(name);
+    }
+
+    settings.save(&app_handle)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+fn remove_nexus_account(app_handle: tauri::AppHandle, name: String) -> Result<Settings, AppError> {
+    let mut settings = Settings::load(&app_handle)?;
+    settings.nexus_accounts.retain(|a| a.name != name);
+
+    if settings.active_account.as_deref() == Some(name.as_str()) {
+        settings.active_account = settings.nexus_accounts.first().map(|a| a.name.clone());
+    }
+
+    settings.save(&app_handle)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn connect_nexus_account(app_handle: tauri::AppHandle) -> Result<Settings, AppError> {
+    nexus_auth::connect(&app_handle).await.map_err(AppError::from)
 }
 
 #[tauri::command]
-fn save_settings(app_handle: tauri::AppHandle, settings: Settings) -> Result<(), String> {
-    settings.save(&app_handle)
+fn set_active_account(app_handle: tauri::AppHandle, name: String) -> Result<Settings, AppError> {
+    let mut settings = Settings::load(&app_handle)?;
+    if !settings.nexus_accounts.iter().any(|a| a.name == name) {
+        return Err(format!("Unknown Nexus account: {}", name).into());
+    }
+
+    settings.active_account = Some(name);
+    settings.save(&app_handle)?;
+    Ok(settings)
 }
 
 #[tauri::command]
-fn auto_detect_paths() -> Result<(Option<String>, Option<String>), String> {
+fn auto_detect_paths() -> Result<(Option<String>, Option<String>), AppError> {
     let game_path = auto_detect_game_path();
 
     let (game_path_str, smapi_path_str) = match game_path {
@@ -110,28 +406,361 @@ fn validate_smapi_path_cmd(path: String) -> bool {
 
 // Download manager commands
 #[tauri::command]
-async fn get_downloads(app_handle: tauri::AppHandle) -> Result<Vec<DownloadTask>, String> {
+async fn get_downloads(app_handle: tauri::AppHandle) -> Result<Vec<DownloadTask>, AppError> {
     let manager = app_handle.state::<DownloadManager>();
     Ok(manager.get_queue_state().await)
 }
 
 // API usage tracking command
 #[tauri::command]
-async fn get_api_usage(app_handle: tauri::AppHandle) -> Result<ApiUsage, String> {
+async fn get_api_usage(app_handle: tauri::AppHandle) -> Result<ApiUsage, AppError> {
+    let settings = Settings::load(&app_handle)?;
     let tracker = app_handle.state::<ApiUsageTracker>();
-    Ok(tracker.get_usage().await)
+    Ok(tracker.get_usage(settings.active_api_key()).await)
+}
+
+#[tauri::command]
+async fn run_startup_checks(app_handle: tauri::AppHandle) -> Result<Vec<HealthCheck>, AppError> {
+    Ok(health_check::run_startup_checks(&app_handle).await)
+}
+
+#[tauri::command]
+async fn get_endorsement_report(app_handle: tauri::AppHandle) -> Result<EndorsementReport, AppError> {
+    endorsements::build_report(&app_handle).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn suggest_missing_mods(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<nexus_history::MissingModSuggestion>, AppError> {
+    nexus_history::suggest_missing_mods(&app_handle).await.map_err(AppError::from)
+}
+
+/// Files on a mod's Nexus page, newest first, so users can pick one to download
+#[tauri::command]
+async fn get_mod_files(app_handle: tauri::AppHandle, mod_id: u32) -> Result<Vec<nexus_api::ModFile>, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    let nexus = NexusClient::new(&app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err(AppError::NexusApiKeyNotConfigured);
+    }
+
+    let mut files = nexus.mod_files("stardewvalley", mod_id).await?;
+    files.sort_by_key(|f| std::cmp::Reverse(f.uploaded_at));
+    Ok(files)
+}
+
+/// What changed since the installed version, newest first, for the update dialog
+#[tauri::command]
+async fn get_mod_changelogs(
+    app_handle: tauri::AppHandle,
+    mod_id: u32,
+    installed_version: Option<String>,
+) -> Result<Vec<nexus_api::ChangelogEntry>, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    let nexus = NexusClient::new(&app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err(AppError::NexusApiKeyNotConfigured);
+    }
+
+    let changelogs = nexus.mod_changelogs("stardewvalley", mod_id).await?;
+    Ok(nexus_api::changes_since(changelogs, installed_version.as_deref()))
+}
+
+#[tauri::command]
+async fn get_required_files(
+    app_handle: tauri::AppHandle,
+    mod_id: u32,
+    file_id: u32,
+) -> Result<Vec<nexus_api::ModFile>, AppError> {
+    mod_files::get_required_files(&app_handle, mod_id, file_id).await.map_err(AppError::from)
+}
+
+/// Queue the requirements picked after `nexus-requirements-missing`
+#[tauri::command]
+async fn queue_nexus_requirements(
+    app_handle: tauri::AppHandle,
+    mod_ids: Vec<u32>,
+) -> Result<dependencies::QueuedRequirements, AppError> {
+    dependencies::queue_requirements(&app_handle, mod_ids).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn queue_mod_files(app_handle: tauri::AppHandle, mod_id: u32, file_ids: Vec<u32>) -> Result<Vec<String>, AppError> {
+    mod_files::queue_mod_files(&app_handle, mod_id, file_ids).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn check_mod_compatibility(app_handle: tauri::AppHandle) -> Result<smapi_compat::CompatibilityReport, AppError> {
+    smapi_compat::check_compatibility(&app_handle).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn disable_broken_mods(app_handle: tauri::AppHandle) -> Result<Vec<smapi_compat::CompatDisabledMod>, AppError> {
+    ensure_writable(&app_handle)?;
+    smapi_compat::disable_broken_mods(&app_handle).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_compat_disabled_mods(app_handle: tauri::AppHandle) -> Result<Vec<smapi_compat::CompatDisabledMod>, AppError> {
+    smapi_compat::list_disabled(&app_handle).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn update_all_mods(
+    app_handle: tauri::AppHandle,
+    updates: Vec<update_all::AvailableUpdate>,
+) -> Result<Vec<update_all::ModUpdateProgress>, AppError> {
+    ensure_writable(&app_handle)?;
+    update_all::update_all(&app_handle, updates).await.map_err(AppError::from)
+}
+
+/// Dry run of `update_all_mods`: what would be downloaded, backed up and how
+/// long it might take
+#[tauri::command]
+async fn plan_updates(
+    app_handle: tauri::AppHandle,
+    updates: Vec<update_all::AvailableUpdate>,
+) -> Result<update_all::UpdatePlan, AppError> {
+    update_all::plan_updates(&app_handle, updates).await.map_err(AppError::from)
+}
+
+/// Queue every mod of an exported or plain-text list that isn't installed
+#[tauri::command]
+async fn import_mod_list(app_handle: tauri::AppHandle, path: String) -> Result<mod_list::ImportReport, AppError> {
+    ensure_writable(&app_handle)?;
+    mod_list::import_mod_list(&app_handle, Path::new(&path)).await.map_err(AppError::from)
+}
+
+/// Host side of multiplayer sync: save the signed modlist to share
+#[tauri::command]
+fn export_modlist(app_handle: tauri::AppHandle, path: String) -> Result<String, AppError> {
+    multiplayer_sync::export_modlist(&app_handle, Path::new(&path)).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn preview_modlist(app_handle: tauri::AppHandle, path: String) -> Result<multiplayer_sync::SyncPreview, AppError> {
+    multiplayer_sync::preview_modlist(&app_handle, Path::new(&path)).map_err(AppError::from)
+}
+
+/// Stop trusting the multiplayer host synced with so far
+#[tauri::command]
+fn forget_sync_host(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    multiplayer_sync::forget_host(&app_handle).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn apply_modlist(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<multiplayer_sync::SyncResult>, AppError> {
+    ensure_writable(&app_handle)?;
+    multiplayer_sync::apply_modlist(&app_handle, Path::new(&path)).await.map_err(AppError::from)
+}
+
+/// Required dependencies of enabled mods that are missing or disabled, to
+/// warn about before launching the game
+#[tauri::command]
+fn analyze_mods(app_handle: tauri::AppHandle, game_path: String) -> Result<Vec<dependencies::DependencyIssue>, AppError> {
+    if !Path::new(&game_path).join("Mods").exists() {
+        return Err(AppError::ModsFolderNotFound);
+    }
+    let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+    mod_registry::apply(&app_handle, &mut mods);
+    Ok(dependencies::dependency_issues(&mods))
+}
+
+/// Record a minimum framework version for a content pack that only states
+/// it in its description; `None` removes it
+#[tauri::command]
+fn set_framework_floor(
+    app_handle: tauri::AppHandle,
+    mod_id: String,
+    framework_id: String,
+    minimum_version: Option<String>,
+) -> Result<(), AppError> {
+    mod_registry::set_framework_floor(&app_handle, &mod_id, &framework_id, minimum_version).map_err(AppError::from)
 }
 
+/// Updates for frameworks older than an installed pack needs, to pass to
+/// `update_all_mods`
 #[tauri::command]
-async fn cancel_download(app_handle: tauri::AppHandle, download_id: String) -> Result<(), String> {
+fn plan_framework_updates(app_handle: tauri::AppHandle, game_path: String) -> Result<Vec<update_all::AvailableUpdate>, AppError> {
+    if !Path::new(&game_path).join("Mods").exists() {
+        return Err(AppError::ModsFolderNotFound);
+    }
+    let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+    mod_registry::apply(&app_handle, &mut mods);
+    Ok(dependencies::framework_updates(&dependencies::dependency_issues(&mods), &mods))
+}
+
+/// Look up the Nexus category of every mod with a Nexus id that doesn't
+/// have one yet. Returns how many mods got a category.
+#[tauri::command]
+async fn refresh_mod_categories(app_handle: tauri::AppHandle, game_path: String) -> Result<usize, AppError> {
+    if !Path::new(&game_path).join("Mods").exists() {
+        return Err(AppError::ModsFolderNotFound);
+    }
+    let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+    mod_registry::apply(&app_handle, &mut mods);
+
+    let settings = Settings::load(&app_handle)?;
+    let nexus = NexusClient::new(&app_handle, &settings);
+    let categories = nexus.game_info("stardewvalley").await?.categories;
+
+    let mut found = Vec::new();
+    for m in mods.iter().filter(|m| m.category.is_none()) {
+        let Some(nexus_mod_id) = m.nexus_mod_id else {
+            continue;
+        };
+        let category_id = match nexus.mod_info("stardewvalley", nexus_mod_id).await {
+            Ok(info) => info.category_id,
+            Err(e) => {
+                warn!("Category lookup failed for {}: {}", m.name, e);
+                continue;
+            }
+        };
+        if let Some(category) = categories.iter().find(|c| Some(c.category_id) == category_id) {
+            found.push((m.path.clone(), category.name.clone()));
+        }
+    }
+
+    mod_registry::record_categories(&app_handle, &found)?;
+    info!("🏷️ Found categories for {} mods", found.len());
+    Ok(found.len())
+}
+
+#[tauri::command]
+async fn send_test_webhook(url: String) -> Result<(), AppError> {
+    webhook::send_test(url.trim()).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_local_api_info(app_handle: tauri::AppHandle) -> Result<local_api::LocalApiInfo, AppError> {
+    local_api::info(&app_handle).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn get_startup_report(app_handle: tauri::AppHandle) -> Result<Option<startup::StartupReport>, AppError> {
+    let state = app_handle.state::<startup::StartupState>();
+    Ok(state.report().await)
+}
+
+#[tauri::command]
+async fn get_maintenance_status(app_handle: tauri::AppHandle) -> Result<maintenance::MaintenanceStatus, AppError> {
+    let state = app_handle.state::<maintenance::MaintenanceState>();
+    Ok(state.status().await)
+}
+
+/// Run maintenance now instead of waiting for the next idle moment
+#[tauri::command]
+async fn run_maintenance(app_handle: tauri::AppHandle) -> Result<maintenance::MaintenanceStatus, AppError> {
+    if let Some(reason) = maintenance::busy_reason(&app_handle).await {
+        return Err(format!("Maintenance can't run now: {}", reason).into());
+    }
+    maintenance::run(&app_handle).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_app_info(app_handle: tauri::AppHandle) -> Result<app_info::AppInfo, AppError> {
+    app_info::app_info(&app_handle).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn export_diagnostics(app_handle: tauri::AppHandle, destination: Option<String>) -> Result<String, AppError> {
+    let path = diagnostics::export_diagnostics(&app_handle, destination.map(PathBuf::from))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn cancel_download(app_handle: tauri::AppHandle, download_id: String) -> Result<(), AppError> {
     let manager = app_handle.state::<DownloadManager>();
     manager.cancel_download(&download_id).await
 }
 
 #[tauri::command]
-async fn clear_completed_downloads(app_handle: tauri::AppHandle) -> Result<(), String> {
+async fn set_download_concurrency(app_handle: tauri::AppHandle, max_concurrent: usize) -> Result<Settings, AppError> {
+    if max_concurrent == 0 {
+        return Err("At least one concurrent download is required".into());
+    }
+
+    let mut settings = Settings::load(&app_handle)?;
+    settings.max_concurrent_downloads = max_concurrent;
+    settings.save(&app_handle)?;
+
+    let manager = app_handle.state::<DownloadManager>();
+    manager.set_max_concurrent(max_concurrent).await;
+    Ok(settings)
+}
+
+/// Put downloads in another folder, or back in the app data folder when
+/// `path` is empty. With `move_existing`, archives already downloaded move too.
+#[tauri::command]
+async fn set_downloads_dir(app_handle: tauri::AppHandle, path: String, move_existing: bool) -> Result<Settings, AppError> {
+    let mut settings = Settings::load(&app_handle)?;
+    settings.downloads_dir = path.trim().to_string();
+
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let download_dir = settings.downloads_path(&app_data_dir);
+    settings::validate_downloads_dir(&download_dir)?;
+
+    let manager = app_handle.state::<DownloadManager>();
+    manager.relocate(download_dir, move_existing).await?;
+    settings.save(&app_handle)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn move_download(
+    app_handle: tauri::AppHandle,
+    download_id: String,
+    new_index: usize,
+) -> Result<Vec<DownloadTask>, AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    manager.move_download(&download_id, new_index).await
+}
+
+#[tauri::command]
+async fn prioritize_download(app_handle: tauri::AppHandle, download_id: String) -> Result<Vec<DownloadTask>, AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    manager.prioritize_download(&download_id).await
+}
+
+#[tauri::command]
+async fn retry_download(app_handle: tauri::AppHandle, download_id: String) -> Result<(), AppError> {
     let manager = app_handle.state::<DownloadManager>();
-    manager.clear_completed().await
+    manager.retry_download(&download_id).await
+}
+
+#[tauri::command]
+async fn pause_all_downloads(app_handle: tauri::AppHandle) -> Result<Vec<DownloadTask>, AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    Ok(manager.pause_all().await)
+}
+
+#[tauri::command]
+async fn resume_all_downloads(app_handle: tauri::AppHandle) -> Result<Vec<DownloadTask>, AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    Ok(manager.resume_all().await)
+}
+
+/// Start a large download that was held because the connection is metered
+#[tauri::command]
+async fn confirm_metered_download(app_handle: tauri::AppHandle, download_id: String) -> Result<(), AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    manager.confirm_metered_download(&download_id).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn cancel_all_downloads(app_handle: tauri::AppHandle) -> Result<Vec<DownloadTask>, AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    Ok(manager.cancel_all().await)
+}
+
+#[tauri::command]
+async fn clear_completed_downloads(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let manager = app_handle.state::<DownloadManager>();
+    manager.clear_completed().await.map_err(AppError::from)
 }
 
 // Mod installer commands
@@ -139,12 +768,13 @@ async fn clear_completed_downloads(app_handle: tauri::AppHandle) -> Result<(), S
 async fn install_mod_from_file(
     app_handle: tauri::AppHandle,
     file_path: String,
-) -> Result<InstallResult, String> {
+) -> Result<Vec<InstallResult>, AppError> {
     // Load settings to get game path
     let settings = Settings::load(&app_handle).map_err(|e| format!("Failed to load settings: {}", e))?;
+    settings.ensure_writable()?;
 
     if settings.game_path.is_empty() {
-        return Err("Game path not configured. Please set it in settings.".to_string());
+        return Err(AppError::GamePathNotConfigured);
     }
 
     let game_path = PathBuf::from(&settings.game_path);
@@ -152,17 +782,63 @@ async fn install_mod_from_file(
     let temp_dir = app_data_dir.join("temp");
 
     let installer = ModInstaller::new(app_handle.clone(), temp_dir);
+    let archive_path = PathBuf::from(file_path);
+
+    if let Err(e) = dependencies::install_dependencies(&app_handle, &archive_path).await {
+        warn!("Failed to install dependencies: {}", e);
+    }
 
-    installer
-        .install_from_archive(&PathBuf::from(file_path), &game_path, &settings, None, None)
+    let timer = perf_metrics::CommandTimer::start("install_mod_from_file");
+    let results = installer
+        .install_from_archive(&archive_path, &game_path, &settings, None, None)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string());
+    timer.finish(&app_handle, results.is_ok(), results.as_ref().ok().map(Vec::len));
+    let results = results?;
+    for result in &results {
+        mod_registry::record_install(&app_handle, result, mod_registry::ModSource::Archive, &archive_path);
+    }
+    Ok(results)
 }
 
+/// Finish an install that sent `install-options-needed` with the folders the user picked
 #[tauri::command]
-async fn test_nxm_url(app_handle: tauri::AppHandle, url: String) -> Result<(), String> {
-    println!("🧪 Manual NXM test triggered from frontend");
-    println!("   URL: {}", url);
+async fn install_archive_options(
+    app_handle: tauri::AppHandle,
+    archive_path: String,
+    folders: Vec<String>,
+    nexus_meta: Option<NexusMeta>,
+) -> Result<Vec<InstallResult>, AppError> {
+    ensure_writable(&app_handle)?;
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    let temp_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("temp");
+    let installer = ModInstaller::new(app_handle.clone(), temp_dir);
+    let archive_path = PathBuf::from(archive_path);
+    let source = if nexus_meta.is_some() {
+        mod_registry::ModSource::Nexus
+    } else {
+        mod_registry::ModSource::Archive
+    };
+
+    let timer = perf_metrics::CommandTimer::start("install_archive_options");
+    let results = installer
+        .install_selected_folders(&archive_path, Path::new(&settings.game_path), &settings, nexus_meta, &folders)
+        .await
+        .map_err(|e| e.to_string());
+    timer.finish(&app_handle, results.is_ok(), results.as_ref().ok().map(Vec::len));
+    let results = results?;
+    for result in &results {
+        mod_registry::record_install(&app_handle, result, source, &archive_path);
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+async fn test_nxm_url(app_handle: tauri::AppHandle, url: String) -> Result<(), AppError> {
+    info!("🧪 Manual NXM test triggered from frontend");
 
     // Parse the NXM URL
     let nxm_url = NxmUrl::parse(&url).map_err(|e| format!("Failed to parse NXM URL: {}", e))?;
@@ -170,28 +846,27 @@ async fn test_nxm_url(app_handle: tauri::AppHandle, url: String) -> Result<(), S
     // Validate
     nxm_url.validate().map_err(|e| format!("NXM URL validation failed: {}", e))?;
 
-    println!("✅ NXM URL parsed and validated successfully");
-    println!("   Game: {}", nxm_url.game);
-    println!("   Mod ID: {}", nxm_url.mod_id);
-    println!("   File ID: {}", nxm_url.file_id);
+    info!("✅ NXM URL parsed and validated successfully");
+    info!("   Game: {}", nxm_url.game);
+    info!("   Mod ID: {}", nxm_url.mod_id);
+    info!("   File ID: {}", nxm_url.file_id);
 
     // Emit event
-    let _ = app_handle.emit("nxm-url-received", &nxm_url);
+    events::emit(&app_handle, AppEvent::NxmUrlReceived(nxm_url.clone()));
 
     // Add to download queue
     let manager = app_handle.state::<DownloadManager>();
     let download_id = manager.add_to_queue(nxm_url.clone()).await
         .map_err(|e| format!("Failed to queue download: {}", e))?;
 
-    println!("📥 Download queued: {}", download_id);
+    info!("📥 Download queued: {}", download_id);
 
     Ok(())
 }
 
 #[tauri::command]
-async fn open_downloads_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
-    let app_data_dir = app_handle.path().app_data_dir().unwrap();
-    let download_dir = app_data_dir.join("downloads").join("nexus");
+async fn open_downloads_folder(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let download_dir = app_handle.state::<DownloadManager>().download_dir().await;
     
     if !download_dir.exists() {
         fs::create_dir_all(&download_dir).map_err(|e| e.to_string())?;
@@ -201,16 +876,16 @@ async fn open_downloads_folder(app_handle: tauri::AppHandle) -> Result<(), Strin
 }
 
 #[tauri::command]
-async fn open_mod_folder(path: String) -> Result<(), String> {
+async fn open_mod_folder(path: String) -> Result<(), AppError> {
     let path = PathBuf::from(path);
     if !path.exists() {
-        return Err("Mod folder does not exist".to_string());
+        return Err(AppError::ModNotFound { path: path.display().to_string() });
     }
     open_folder(&path)
 }
 
 #[tauri::command]
-async fn open_game_mods_folder(game_path: String) -> Result<(), String> {
+async fn open_game_mods_folder(game_path: String) -> Result<(), AppError> {
     let mods_path = Path::new(&game_path).join("Mods");
     if !mods_path.exists() {
         fs::create_dir_all(&mods_path).map_err(|e| e.to_string())?;
@@ -218,43 +893,125 @@ async fn open_game_mods_folder(game_path: String) -> Result<(), String> {
     open_folder(&mods_path)
 }
 
+/// Snapshots taken whenever an install replaced the mod
 #[tauri::command]
-async fn toggle_mod_enabled(mod_path: String, enabled: bool) -> Result<String, String> {
-    let path = PathBuf::from(&mod_path);
-    if !path.exists() {
-        return Err("Mod path does not exist".to_string());
+fn list_mod_backups(app_handle: tauri::AppHandle, unique_id: String) -> Result<Vec<mod_installer::ModBackup>, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    let temp_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("temp");
+    ModInstaller::new(app_handle.clone(), temp_dir)
+        .list_backups(Path::new(&settings.game_path), &unique_id)
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// Put a backed up version back into the Mods folder; the installed version
+/// is backed up first. Returns the restored folder.
+#[tauri::command]
+async fn restore_mod_backup(app_handle: tauri::AppHandle, unique_id: String, timestamp: String) -> Result<String, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    settings.ensure_writable()?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
     }
+    let temp_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("temp");
+    let installer = ModInstaller::new(app_handle.clone(), temp_dir);
 
-    let parent = path.parent().ok_or("Invalid mod path")?;
-    let file_name = path.file_name().ok_or("Invalid mod path")?.to_string_lossy().to_string();
+    let restored = tauri::async_runtime::spawn_blocking(move || {
+        installer.restore_backup(Path::new(&settings.game_path), &unique_id, &timestamp, &settings)
+    })
+    .await
+    .map_err(|e| format!("Failed to restore backup: {}", e))?
+    .map_err(|e| e.to_string())?;
+    Ok(restored.to_string_lossy().to_string())
+}
 
-    let new_name = if enabled {
-        // Enable: Remove .disabled suffix if present
-        if file_name.ends_with(".disabled") {
-            file_name.trim_end_matches(".disabled").to_string()
-        } else {
-            return Ok(mod_path); // Already enabled
-        }
+/// Enable or disable several mods. Enabling also needs the disabled mods
+/// they depend on: without `include_required` the plan comes back
+/// unapplied so the user can be asked, `Some(true)` enables them as well and
+/// `Some(false)` only the mods asked for.
+#[tauri::command]
+async fn set_mods_enabled(
+    app_handle: tauri::AppHandle,
+    game_path: String,
+    mod_ids: Vec<String>,
+    enabled: bool,
+    include_required: Option<bool>,
+) -> Result<dependencies::EnablePlan, AppError> {
+    ensure_writable(&app_handle)?;
+    let settings = Settings::load(&app_handle)?;
+    let mut mods = mod_installer::scan_mods(Path::new(&game_path));
+    mod_registry::apply(&app_handle, &mut mods);
+
+    let required = if enabled {
+        dependencies::required_enables(&mods, &mod_ids)
     } else {
-        // Disable: Add .disabled suffix if not present
-        if !file_name.ends_with(".disabled") {
-            format!("{}.disabled", file_name)
-        } else {
-            return Ok(mod_path); // Already disabled
-        }
+        Vec::new()
     };
+    if !required.is_empty() && include_required.is_none() {
+        return Ok(dependencies::EnablePlan {
+            required,
+            applied: false,
+            errors: Vec::new(),
+        });
+    }
 
-    let new_path = parent.join(&new_name);
-    fs::rename(&path, &new_path).map_err(|e| e.to_string())?;
+    let mut targets = mod_ids;
+    if include_required == Some(true) {
+        targets.extend(required.iter().map(|r| r.id.clone()));
+    }
+
+    let mut errors = Vec::new();
+    for m in mods.iter().filter(|m| targets.contains(&m.id) && !m.is_unmanaged) {
+        if let Err(e) = mod_installer::set_mod_enabled(Path::new(&m.path), enabled, settings.disabled_folder_style) {
+            errors.push(format!("{}: {}", m.name, e));
+        }
+    }
+
+    Ok(dependencies::EnablePlan {
+        required,
+        applied: true,
+        errors,
+    })
+}
 
+#[tauri::command]
+async fn toggle_mod_enabled(app_handle: tauri::AppHandle, mod_path: String, enabled: bool) -> Result<String, AppError> {
+    ensure_writable(&app_handle)?;
+    let settings = Settings::load(&app_handle)?;
+    settings.ensure_managed(Path::new(&mod_path))?;
+    let new_path = mod_installer::set_mod_enabled(Path::new(&mod_path), enabled, settings.disabled_folder_style)?;
     Ok(new_path.to_string_lossy().to_string())
 }
 
+/// A mod's config.json for the config editor; None until the mod has
+/// created it in game
+#[tauri::command]
+async fn get_mod_config(mod_path: String) -> Result<Option<serde_json::Value>, AppError> {
+    Ok(mod_config::read_config(Path::new(&mod_path))?)
+}
+
+#[tauri::command]
+async fn save_mod_config(app_handle: tauri::AppHandle, mod_path: String, json: serde_json::Value) -> Result<(), AppError> {
+    ensure_writable(&app_handle)?;
+    Settings::load(&app_handle)?.ensure_managed(Path::new(&mod_path))?;
+    if !Path::new(&mod_path).is_dir() {
+        return Err(AppError::ModNotFound { path: mod_path });
+    }
+    mod_config::write_config(Path::new(&mod_path), &json)?;
+    info!("⚙️ Saved config of {}", mod_path);
+    Ok(())
+}
+
 #[tauri::command]
-async fn delete_mod(_app_handle: tauri::AppHandle, mod_path: String) -> Result<(), String> {
+async fn delete_mod(app_handle: tauri::AppHandle, mod_path: String) -> Result<(), AppError> {
+    ensure_writable(&app_handle)?;
+    let settings = Settings::load(&app_handle)?;
+    settings.ensure_managed(Path::new(&mod_path))?;
     let path = PathBuf::from(&mod_path);
     if !path.exists() {
-        return Err("Mod path does not exist".to_string());
+        return Err(AppError::ModNotFound { path: mod_path });
+    }
+    if let Some(tool) = foreign_managers::folder_manager(&path) {
+        return Err(format!("This mod is managed by {}, remove it there", tool).into());
     }
 
     // Use the force_remove_dir_all method through a helper
@@ -268,7 +1025,7 @@ async fn delete_mod(_app_handle: tauri::AppHandle, mod_path: String) -> Result<(
             return Ok(());
         }
 
-        println!("   ⚠ Normal remove failed, attempting to force permissions on: {}", path.display());
+        info!("   ⚠ Normal remove failed, attempting to force permissions on: {}", path.display());
 
         // Make everything writable
         use walkdir::WalkDir;
@@ -290,8 +1047,11 @@ async fn delete_mod(_app_handle: tauri::AppHandle, mod_path: String) -> Result<(
     }
 
     force_remove(&path).map_err(|e| format!("Failed to delete mod: {}", e))?;
-    
-    println!("Successfully deleted mod at: {}", path.display());
+    if let Err(e) = staging::remove_staged(Path::new(&settings.game_path), &path) {
+        warn!("Failed to remove the staged copy of {}: {}", path.display(), e);
+    }
+
+    info!("Successfully deleted mod at: {}", path.display());
     Ok(())
 }
 
@@ -306,52 +1066,36 @@ struct UpdateInfo {
 #[tauri::command]
 async fn check_mod_updates(
     app_handle: tauri::AppHandle,
-    _mod_path: String,
+    mod_path: String,
     current_version: String,
     nexus_mod_id: u32,
-) -> Result<UpdateInfo, String> {
-    println!("Checking updates for mod {} (version {})", nexus_mod_id, current_version);
-
-    // Query Nexus API for mod information
-    let api_tracker = app_handle.state::<ApiUsageTracker>();
-    let settings = Settings::load(&app_handle).map_err(|e| e.to_string())?;
-    
-    let api_key = settings.nexus_api_key;
-    if api_key.is_empty() {
-        return Err("Nexus API key not configured".to_string());
-    }
-
-    let url = format!("https://api.nexusmods.com/v1/games/stardewvalley/mods/{}.json", nexus_mod_id);
-    
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("apikey", &api_key)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch mod info: {}", e))?;
+) -> Result<UpdateInfo, AppError> {
+    fetch_update_info(&app_handle, &mod_path, current_version, nexus_mod_id).await
+}
 
-    // Update API usage
-    api_tracker.inner().update_from_headers(response.headers()).await;
+/// Compare an installed version against the latest one on Nexus, and
+/// remember the result in the mod registry so the mod list can show it
+/// without checking again
+async fn fetch_update_info(
+    app_handle: &tauri::AppHandle,
+    mod_path: &str,
+    current_version: String,
+    nexus_mod_id: u32,
+) -> Result<UpdateInfo, AppError> {
+    info!("Checking updates for mod {} (version {})", nexus_mod_id, current_version);
 
-    if !response.status().is_success() {
-        return Err(format!("API request failed with status: {}", response.status()));
+    // Query Nexus API for mod information
+    let settings = Settings::load(app_handle).map_err(|e| e.to_string())?;
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err(AppError::NexusApiKeyNotConfigured);
     }
 
-    let mod_info: serde_json::Value = response.json().await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let mod_info = nexus.mod_info("stardewvalley", nexus_mod_id).await?;
 
     // Get the latest file version
-    // The API returns mod info, we need to find the latest main file
-    let latest_version = mod_info
-        .get("version")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-
-    let latest_file_id = mod_info
-        .get("latest_file_id")
-        .and_then(|v| v.as_u64())
-        .map(|v| v as u32);
+    let latest_version = mod_info.version;
+    let latest_file_id = mod_info.latest_file_id;
 
     // Compare versions using semver if possible
     let has_update = if let Some(ref latest) = latest_version {
@@ -366,7 +1110,34 @@ async fn check_mod_updates(
         false
     };
 
-    println!("Update check result: has_update={}, latest_version={:?}", has_update, latest_version);
+    info!("Update check result: has_update={}, latest_version={:?}", has_update, latest_version);
+
+    let update = if has_update {
+        // Only a teaser for the badge; the full changelog is fetched on demand
+        let changelog = match nexus.mod_changelogs("stardewvalley", nexus_mod_id).await {
+            Ok(changelogs) => nexus_api::changes_since(changelogs, Some(&current_version))
+                .into_iter()
+                .next()
+                .map(|entry| entry.changes.into_iter().take(3).collect())
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to fetch changelog for {}: {}", nexus_mod_id, e);
+                Vec::new()
+            }
+        };
+        Some(mod_registry::ModUpdate {
+            installed_version: current_version.clone(),
+            latest_version: latest_version.clone(),
+            latest_file_id,
+            changelog,
+            checked_at: chrono::Utc::now(),
+        })
+    } else {
+        None
+    };
+    if let Err(e) = mod_registry::record_update(app_handle, mod_path, update) {
+        warn!("{}", e);
+    }
 
     Ok(UpdateInfo {
         has_update,
@@ -376,7 +1147,73 @@ async fn check_mod_updates(
     })
 }
 
-fn open_folder(path: &Path) -> Result<(), String> {
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NxmHandlerStatus {
+    /// Whether the user opted in to handling nxm:// links
+    enabled: bool,
+    /// Whether the OS currently routes nxm:// links to this app
+    registered: bool,
+}
+
+#[tauri::command]
+fn get_nxm_handler_status(app_handle: tauri::AppHandle) -> Result<NxmHandlerStatus, AppError> {
+    let settings = Settings::load(&app_handle)?;
+
+    #[cfg(desktop)]
+    let registered = {
+        use tauri_plugin_deep_link::DeepLinkExt;
+        app_handle.deep_link().is_registered("nxm").unwrap_or(false)
+    };
+
+    #[cfg(not(desktop))]
+    let registered = false;
+
+    Ok(NxmHandlerStatus {
+        enabled: settings.nxm_handler_enabled,
+        registered,
+    })
+}
+
+#[tauri::command]
+fn register_nxm_handler(app_handle: tauri::AppHandle) -> Result<NxmHandlerStatus, AppError> {
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_deep_link::DeepLinkExt;
+        app_handle
+            .deep_link()
+            .register("nxm")
+            .map_err(|e| format!("Failed to register nxm:// protocol: {}", e))?;
+        info!("✅ nxm:// protocol registered by user request");
+    }
+
+    let mut settings = Settings::load(&app_handle)?;
+    settings.nxm_handler_enabled = true;
+    settings.save(&app_handle)?;
+
+    get_nxm_handler_status(app_handle)
+}
+
+#[tauri::command]
+fn unregister_nxm_handler(app_handle: tauri::AppHandle) -> Result<NxmHandlerStatus, AppError> {
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_deep_link::DeepLinkExt;
+        app_handle
+            .deep_link()
+            .unregister("nxm")
+            .map_err(|e| format!("Failed to unregister nxm:// protocol: {}", e))?;
+        info!("🔌 nxm:// protocol unregistered by user request");
+    }
+
+    let mut settings = Settings::load(&app_handle)?;
+    settings.nxm_handler_enabled = false;
+    settings.save(&app_handle)?;
+
+    get_nxm_handler_status(app_handle)
+}
+
+fn open_folder(path: &Path) -> Result<(), AppError> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
@@ -408,31 +1245,28 @@ fn open_folder(path: &Path) -> Result<(), String> {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
-            println!("\n╔══════════════════════════════════════════════════╗");
-            println!("║  🔄 SECOND INSTANCE DETECTED!                   ║");
-            println!("╚══════════════════════════════════════════════════╝");
-            println!("📦 Received {} arguments from second instance:", args.len());
+            info!("🔄 Second instance started with {} arguments", args.len());
 
             for (i, arg) in args.iter().enumerate() {
-                println!("   [{}]: {}", i, arg);
+                info!("   [{}]: {}", i, nxm_protocol::loggable(arg));
 
                 // Check if it's an NXM URL
                 if arg.starts_with("nxm://") {
-                    println!("   ⚡ NXM URL detected in second instance!");
+                    info!("   ⚡ NXM URL detected in second instance!");
 
                     // Parse the URL
                     if let Ok(nxm_url) = crate::nxm_protocol::NxmUrl::parse(arg) {
                         if let Err(e) = nxm_url.validate() {
-                            eprintln!("   ❌ NXM URL validation failed: {}", e);
-                            let _ = app.emit("nxm-error", e.to_string());
+                            error!("   ❌ NXM URL validation failed: {}", e);
+                            events::emit(app, AppEvent::NxmError(e.to_string()));
                             continue;
                         }
 
-                        println!("   ✅ NXM URL parsed: mod_id={}, file_id={}", nxm_url.mod_id, nxm_url.file_id);
+                        info!("   ✅ NXM URL parsed: mod_id={}, file_id={}", nxm_url.mod_id, nxm_url.file_id);
 
                         // Emit event to frontend
-                        let _ = app.emit("nxm-url-received", &nxm_url);
-                        println!("   📡 Emitted nxm-url-received event");
+                        events::emit(app, AppEvent::NxmUrlReceived(nxm_url.clone()));
+                        info!("   📡 Emitted nxm-url-received event");
 
                         // Queue the download
                         let handle = app.clone();
@@ -441,17 +1275,17 @@ pub fn run() {
                             let manager = handle.state::<crate::download_manager::DownloadManager>();
                             match manager.add_to_queue(url.clone()).await {
                                 Ok(download_id) => {
-                                    println!("   📥 Download queued: {} (mod_id={}, file_id={})",
+                                    info!("   📥 Download queued: {} (mod_id={}, file_id={})",
                                         download_id, url.mod_id, url.file_id);
                                 }
                                 Err(e) => {
-                                    eprintln!("   ❌ Failed to queue download: {}", e);
-                                    let _ = handle.emit("nxm-error", format!("Failed to queue download: {}", e));
+                                    error!("   ❌ Failed to queue download: {}", e);
+                                    events::emit(&handle, AppEvent::NxmError(format!("Failed to queue download: {}", e)));
                                 }
                             }
                         });
                     } else {
-                        eprintln!("   ❌ Failed to parse NXM URL");
+                        error!("   ❌ Failed to parse NXM URL");
                     }
                 }
             }
@@ -459,48 +1293,62 @@ pub fn run() {
             // Focus the existing window
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_focus();
-                println!("   🪟 Focused existing window");
+                info!("   🪟 Focused existing window");
             }
         }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_deep_link::init())
         .setup(|app| {
-            // Initialize API usage tracker
-            let api_tracker = ApiUsageTracker::new();
+            let app_data_dir = app.path().app_data_dir().unwrap();
+            let startup_settings = Settings::load(app.handle()).unwrap_or_default();
+
+            // Start logging first so the rest of setup is captured; the guard
+            // flushes the log file when the app state is dropped
+            if let Some(log_guard) = logging::init(&app_data_dir, startup_settings.log_level) {
+                app.manage(log_guard);
+            }
+
+            // Initialize API usage tracker with what the last session saw
+            let api_tracker = ApiUsageTracker::load(Some(app_data_dir.join("api_usage.json")));
             app.manage(api_tracker);
 
+            // Serializes changes to the mod registry file
+            app.manage(mod_registry::RegistryLock::default());
+
             // Initialize download manager
-            let app_data_dir = app.path().app_data_dir().unwrap();
-            let download_dir = app_data_dir.join("downloads").join("nexus");
-            let download_manager = DownloadManager::new(app.handle().clone(), download_dir.clone(), 1);
+            let download_dir = startup_settings.downloads_path(&app_data_dir);
+            let download_manager =
+                DownloadManager::new(app.handle().clone(), download_dir, startup_settings.max_concurrent_downloads);
             app.manage(download_manager);
 
             // Listen for download completion and trigger auto-installation
             let app_handle = app.handle().clone();
-            let download_dir_clone = download_dir.clone();
-            app.listen("download-completed", move |event| {
+            app.listen(events::DOWNLOAD_COMPLETED, move |event| {
                 let download_id = match event.payload().parse::<String>() {
                     Ok(id) => id.trim_matches('"').to_string(),
                     Err(_) => return,
                 };
 
-                println!("Download completed, triggering installation: {}", download_id);
+                info!("Download completed, triggering installation: {}", download_id);
 
                 let handle = app_handle.clone();
-                let dl_dir = download_dir_clone.clone();
                 tauri::async_runtime::spawn(async move {
                     // Load settings
                     let settings = match Settings::load(&handle) {
                         Ok(s) => s,
                         Err(e) => {
-                            eprintln!("Failed to load settings for auto-install: {}", e);
+                            warn!("Failed to load settings for auto-install: {}", e);
                             return;
                         }
                     };
 
                     if settings.game_path.is_empty() {
-                        eprintln!("Game path not configured, skipping auto-install");
+                        warn!("Game path not configured, skipping auto-install");
+                        return;
+                    }
+                    if settings.read_only_mode {
+                        info!("Read-only mode, leaving {} in the downloads folder", download_id);
                         return;
                     }
 
@@ -511,23 +1359,27 @@ pub fn run() {
                     let download = match downloads.iter().find(|d| d.id == download_id) {
                         Some(d) => d,
                         None => {
-                            eprintln!("Download not found: {}", download_id);
+                            warn!("Download not found: {}", download_id);
                             return;
                         }
                     };
 
+                    if download.managed_install {
+                        return;
+                    }
+
                     // Get file path
                     let file_path = match &download.file_path {
                         Some(p) => p.clone(),
-                        None => dl_dir.join(&download.file_name),
+                        None => manager.download_dir().await.join(&download.file_name),
                     };
 
-                    println!("Auto-installing mod from: {}", file_path.display());
+                    info!("Auto-installing mod from: {}", file_path.display());
 
                     // Check if confirmation is required
                     if settings.confirm_before_install {
-                        println!("Confirmation required for installation");
-                        let _ = handle.emit("install-confirmation-needed", download_id);
+                        info!("Confirmation required for installation");
+                        events::emit(&handle, AppEvent::InstallConfirmationNeeded(download_id));
                         return;
                     }
 
@@ -536,16 +1388,38 @@ pub fn run() {
                     let installer = ModInstaller::new(handle.clone(), temp_dir);
                     let game_path = PathBuf::from(&settings.game_path);
 
-                    let nexus_info = Some((download.nxm_url.mod_id, download.nxm_url.file_id));
+                    let mut nexus_meta = download_manager::archive_nexus_meta(
+                        &file_path,
+                        download.nxm_url.mod_id,
+                        download.nxm_url.file_id,
+                    );
+                    nexus_meta.file_version = download.file_version.clone();
                     let mod_name = download.mod_name.clone();
 
-                    match installer.install_from_archive(&file_path, &game_path, &settings, nexus_info, mod_name).await {
-                        Ok(result) => {
-                            println!("Mod installed successfully: {} v{}", result.mod_name, result.version);
+                    if let Err(e) = dependencies::install_dependencies(&handle, &file_path).await {
+                        warn!("Failed to install dependencies: {}", e);
+                    }
+
+                    let timer = perf_metrics::CommandTimer::start("install_download");
+                    let installed = installer.install_from_archive(&file_path, &game_path, &settings, Some(nexus_meta), mod_name).await;
+                    timer.finish(&handle, installed.is_ok(), installed.as_ref().ok().map(Vec::len));
+                    match installed {
+                        Ok(results) => {
+                            for result in &results {
+                                info!("Mod installed successfully: {} v{}", result.mod_name, result.version);
+                                mod_registry::record_install(&handle, result, mod_registry::ModSource::Nexus, &file_path);
+                            }
+                            if let Some(result) = results.first() {
+                                endorsements::after_install(&handle, &settings, download.nxm_url.mod_id, &result.mod_name);
+                            }
+                            dependencies::offer_nexus_requirements(&handle, download.nxm_url.mod_id).await;
+                        }
+                        Err(InstallError::OptionsNeeded) => {
+                            info!("Waiting for the user to choose which folders to install");
                         }
                         Err(e) => {
-                            eprintln!("Auto-installation failed: {}", e);
-                            let _ = handle.emit("mod-install-failed", e.to_string());
+                            warn!("Auto-installation failed: {}", e);
+                            events::emit(&handle, AppEvent::ModInstallFailed(e.to_string()));
                         }
                     }
                 });
@@ -556,54 +1430,44 @@ pub fn run() {
             {
                 use tauri_plugin_deep_link::DeepLinkExt;
 
-                // Register the nxm scheme
-                println!("=== Registering nxm:// protocol handler ===");
-                if let Err(e) = app.deep_link().register("nxm") {
-                    eprintln!("❌ Failed to register nxm:// protocol: {}", e);
+                // Register the nxm scheme only if the user opted in, so we don't
+                // take the handler away from other mod managers (e.g. Vortex)
+                let nxm_handler_enabled = Settings::load(app.handle())
+                    .map(|s| s.nxm_handler_enabled)
+                    .unwrap_or(false);
+
+                if nxm_handler_enabled {
+                    info!("=== Registering nxm:// protocol handler ===");
+                    if let Err(e) = app.deep_link().register("nxm") {
+                        error!("❌ Failed to register nxm:// protocol: {}", e);
+                    } else {
+                        info!("✅ nxm:// protocol registered successfully");
+                    }
                 } else {
-                    println!("✅ nxm:// protocol registered successfully");
+                    info!("ℹ️  nxm:// handler not enabled in settings, skipping registration");
                 }
 
                 // Listen for deep link events
-                println!("📡 Setting up deep link event listener...");
+                info!("📡 Setting up deep link event listener...");
                 let app_handle = app.handle().clone();
 
-                // Handle app launch with deep link arguments
-                let handle_clone = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
-                    use std::env;
-                    let args: Vec<String> = env::args().collect();
-                    println!("🚀 App launched with {} arguments:", args.len());
-                    for (i, arg) in args.iter().enumerate() {
-                        println!("   [{}]: {}", i, arg);
-                        if arg.starts_with("nxm://") {
-                            println!("   ⚠️  NXM URL found in launch arguments!");
-                            let _ = handle_clone.emit("debug-deep-link", arg);
-                        }
-                    }
-                });
-
                 app.listen("deep-link://new-url", move |event| {
-                    println!("\n╔══════════════════════════════════════╗");
-                    println!("║  🔗 DEEP LINK EVENT RECEIVED!       ║");
-                    println!("╚══════════════════════════════════════╝");
-                    println!("Raw payload: {}", event.payload());
+                    info!("🔗 Deep link event received");
 
                     // Parse payload as Vec<String>
                     let urls: Vec<String> = match serde_json::from_str(event.payload()) {
                         Ok(u) => u,
                         Err(e) => {
-                            eprintln!("❌ Failed to parse deep link payload: {}", e);
-                            eprintln!("   Payload was: {}", event.payload());
+                            error!("❌ Failed to parse deep link payload: {}", e);
                             return;
                         }
                     };
 
-                    println!("📦 Parsed {} URL(s)", urls.len());
+                    info!("📦 Parsed {} URL(s)", urls.len());
 
                     for url_str in urls {
-                        println!("\n🔍 Processing URL: {}", url_str);
-                        let _ = app_handle.emit("debug-deep-link", &url_str);
+                        info!("🔍 Processing URL: {}", nxm_protocol::loggable(&url_str));
+                        events::emit(&app_handle, AppEvent::DebugDeepLink(url_str.clone()));
 
                         // Check if it's an NXM URL
                         if !url_str.starts_with("nxm://") {
@@ -615,18 +1479,18 @@ pub fn run() {
                             Ok(nxm_url) => {
                                 // Validate (check expiration)
                                 if let Err(e) = nxm_url.validate() {
-                                    eprintln!("NXM URL validation failed: {}", e);
-                                    let _ = app_handle.emit("nxm-error", e.to_string());
+                                    warn!("NXM URL validation failed: {}", e);
+                                    events::emit(&app_handle, AppEvent::NxmError(e.to_string()));
                                     continue;
                                 }
 
-                                println!(
+                                info!(
                                     "Parsed NXM URL: game={}, mod_id={}, file_id={}",
                                     nxm_url.game, nxm_url.mod_id, nxm_url.file_id
                                 );
 
                                 // Emit success event to frontend
-                                let _ = app_handle.emit("nxm-url-received", &nxm_url);
+                                events::emit(&app_handle, AppEvent::NxmUrlReceived(nxm_url.clone()));
 
                                 // Add to download queue
                                 let handle = app_handle.clone();
@@ -635,97 +1499,405 @@ pub fn run() {
                                     let manager = handle.state::<DownloadManager>();
                                     match manager.add_to_queue(url.clone()).await {
                                         Ok(download_id) => {
-                                            println!("Download queued: {} (mod_id={}, file_id={})",
+                                            info!("Download queued: {} (mod_id={}, file_id={})",
                                                 download_id, url.mod_id, url.file_id);
                                         }
                                         Err(e) => {
-                                            eprintln!("Failed to queue download: {}", e);
-                                            let _ = handle.emit("nxm-error", format!("Failed to queue download: {}", e));
+                                            warn!("Failed to queue download: {}", e);
+                                            events::emit(&handle, AppEvent::NxmError(format!("Failed to queue download: {}", e)));
                                         }
                                     }
                                 });
                             }
                             Err(e) => {
-                                eprintln!("Failed to parse NXM URL: {}", e);
-                                let _ = app_handle.emit("nxm-error", e.to_string());
+                                warn!("Failed to parse NXM URL: {}", e);
+                                events::emit(&app_handle, AppEvent::NxmError(e.to_string()));
                             }
                         }
                     }
                 });
             }
 
+            // Sequenced startup work (launch arguments, reports, update check)
+            app.manage(startup::StartupState::default());
+            tauri::async_runtime::spawn(startup::run(app.handle().clone()));
+
+            // Housekeeping while nothing else is going on
+            app.manage(maintenance::MaintenanceState::load(&app_data_dir));
+            maintenance::start(app.handle());
+
+            // Local API for external tools, when enabled in settings
+            local_api::start(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
             scan_mods,
+            get_mod_record,
+            set_mod_notes,
+            set_mod_favorite,
             install_mod,
             load_settings,
             save_settings,
             auto_detect_paths,
             validate_game_path_cmd,
             validate_smapi_path_cmd,
+            install_smapi,
+            get_environment_info,
             get_downloads,
             get_api_usage,
             cancel_download,
             clear_completed_downloads,
+            pause_all_downloads,
+            resume_all_downloads,
+            cancel_all_downloads,
+            confirm_metered_download,
             install_mod_from_file,
+            install_archive_options,
+            queue_nexus_requirements,
             test_nxm_url,
             open_downloads_folder,
             open_downloads_folder,
             open_mod_folder,
             open_game_mods_folder,
+            list_mod_backups,
+            restore_mod_backup,
+            set_mods_enabled,
             toggle_mod_enabled,
             delete_mod,
             delete_mod,
             check_mod_updates,
-            launch_game
+            launch_game,
+            launch_game_vanilla,
+            get_performance_report,
+            migrate_library,
+            deploy_staged_mods,
+            get_recent_logs,
+            get_mod_config,
+            save_mod_config,
+            list_farms,
+            list_save_backups,
+            backup_save,
+            restore_save_backup,
+            import_vortex,
+            import_foreign_mods,
+            normalize_disabled_folders,
+            resolve_duplicate,
+            list_profiles,
+            save_mod_profile,
+            delete_mod_profile,
+            apply_mod_profile,
+            link_save_profile,
+            check_launch_profile,
+            get_nxm_handler_status,
+            register_nxm_handler,
+            unregister_nxm_handler,
+            run_startup_checks,
+            export_diagnostics,
+            get_app_info,
+            query_mods,
+            add_nexus_account,
+            remove_nexus_account,
+            set_active_account,
+            list_saves,
+            get_save_mod_requirements,
+            set_download_concurrency,
+            set_downloads_dir,
+            get_endorsement_report,
+            retry_download,
+            get_startup_report,
+            get_maintenance_status,
+            run_maintenance,
+            move_download,
+            prioritize_download,
+            suggest_missing_mods,
+            get_mod_files,
+            get_mod_changelogs,
+            get_required_files,
+            queue_mod_files,
+            check_mod_compatibility,
+            disable_broken_mods,
+            get_compat_disabled_mods,
+            update_all_mods,
+            plan_updates,
+            get_local_api_info,
+            send_test_webhook,
+            analyze_mods,
+            set_framework_floor,
+            plan_framework_updates,
+            refresh_mod_categories,
+            import_mod_list,
+            export_modlist,
+            preview_modlist,
+            apply_modlist,
+            forget_sync_host,
+            connect_nexus_account
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+/// Installed SMAPI and game versions, and whether SMAPI is too old for the game
+#[tauri::command]
+fn get_environment_info(app_handle: tauri::AppHandle) -> Result<environment::EnvironmentInfo, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    Ok(environment::environment_info(&settings))
+}
+
+/// Download the latest SMAPI and install (or update) it for the configured game
 #[tauri::command]
-async fn launch_game(app_handle: tauri::AppHandle) -> Result<(), String> {
+async fn install_smapi(app_handle: tauri::AppHandle) -> Result<smapi_installer::SmapiInstallResult, AppError> {
+    smapi_installer::install_latest(&app_handle).await.map_err(AppError::from)
+}
+
+/// Start the game through SMAPI. With `capture_output` SMAPI's console is
+/// streamed as `smapi-output` events instead of opening its own window.
+/// `save_id` is the farm to be played (the last played one if None); with
+/// `autoSwitchProfile` its profile is applied first.
+#[tauri::command]
+async fn launch_game(
+    app_handle: tauri::AppHandle,
+    capture_output: Option<bool>,
+    save_id: Option<String>,
+) -> Result<(), AppError> {
     let settings = Settings::load(&app_handle).map_err(|e| e.to_string())?;
     
     if settings.smapi_path.is_empty() {
-        return Err("SMAPI path not configured. Please set it in settings.".to_string());
+        return Err(AppError::SmapiPathNotConfigured);
     }
 
     let smapi_path = PathBuf::from(&settings.smapi_path);
     if !smapi_path.exists() {
-        return Err("SMAPI executable not found at configured path".to_string());
+        return Err("SMAPI executable not found at configured path".into());
     }
 
     // Determine working directory (usually parent of executable)
     let working_dir = smapi_path.parent().unwrap_or(&smapi_path);
 
-    println!("🚀 Launching game from: {}", smapi_path.display());
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let profile_dir = app_data_dir.clone();
+    let profile_settings = settings.clone();
+    tokio::task::spawn_blocking(move || -> Result<(), String> {
+        let Some(save_id) = launch_save_id(&profile_dir, save_id) else {
+            return Ok(());
+        };
+        let check = profiles::check_launch(&profiles::load(&profile_dir), Some(&save_id));
+        if let (true, true, Some(profile)) = (check.mismatch, profile_settings.auto_switch_profile, &check.save_profile) {
+            profile_settings.ensure_writable().map_err(|e| e.to_string())?;
+            let report = profiles::apply_profile(&profile_settings, &profile_dir, profile)?;
+            if !report.failed.is_empty() {
+                return Err(format!(
+                    "Failed to switch to profile {}: {} mods could not be changed",
+                    profile,
+                    report.failed.len()
+                ));
+            }
+        }
+        if let Err(e) = profiles::record_launch(&profile_dir, &save_id) {
+            warn!("Failed to record the profile of {}: {}", save_id, e);
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // A session with a broken mod can always be rolled back; a failed
+    // backup is logged but doesn't keep the game from starting
+    if settings.save_backups_on_launch != SaveBackupsOnLaunch::Off {
+        let (mode, keep) = (settings.save_backups_on_launch, settings.save_backups_to_keep);
+        match tokio::task::spawn_blocking(move || saves::backup_before_launch(&app_data_dir, mode, keep)).await {
+            Ok(Ok((count, failed))) => {
+                info!("💾 Backed up {} saves before launch", count);
+                for failure in failed {
+                    warn!("Failed to back up {} before launch: {}", failure.save_id, failure.error);
+                }
+            }
+            Ok(Err(e)) => warn!("Failed to back up saves before launch: {}", e),
+            Err(e) => warn!("Failed to back up saves before launch: {}", e),
+        }
+    }
+
+    info!("🚀 Launching game from: {}", smapi_path.display());
 
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new(&smapi_path)
-            .current_dir(working_dir)
+    if capture_output.unwrap_or(false) {
+        smapi_console::launch(&app_handle, &smapi_path, working_dir)?;
+    } else {
+        let started_at = chrono::Utc::now();
+        if let Some(child) = spawn_smapi(&smapi_path, working_dir)? {
+            game_session::watch(&app_handle, child, started_at);
+        }
+    }
+
+    // Remember which mods this session ran with
+    if !settings.game_path.is_empty() {
+        let mods = mod_installer::scan_mods(Path::new(&settings.game_path));
+        if let Err(e) = launch_sessions::record_launch(&app_handle, &mods) {
+            warn!("Failed to record launch session: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Link every staged mod back into Mods, e.g. after the game was reinstalled
+#[tauri::command]
+async fn deploy_staged_mods(app_handle: tauri::AppHandle) -> Result<staging::DeployReport, AppError> {
+    ensure_writable(&app_handle)?;
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    let game_path = PathBuf::from(&settings.game_path);
+    tokio::task::spawn_blocking(move || staging::deploy_staged(&game_path))
+        .await
+        .map_err(|e| e.to_string())?.map_err(AppError::from)
+}
+
+/// The newest lines of the app log for the debug panel, oldest first
+#[tauri::command]
+async fn get_recent_logs(app_handle: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<logging::LogLine>, AppError> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(500);
+    tokio::task::spawn_blocking(move || logging::recent_logs(&app_data_dir, limit))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// Timings of scans, installs and update checks, slowest overall first
+#[tauri::command]
+fn get_performance_report(app_handle: tauri::AppHandle) -> Result<perf_metrics::PerformanceReport, AppError> {
+    perf_metrics::report(&app_handle).map_err(AppError::from)
+}
+
+/// Bring a Mods folder set up outside the app into the registry. With
+/// `dry_run` nothing is written and the report shows what would be recorded.
+#[tauri::command]
+async fn migrate_library(app_handle: tauri::AppHandle, dry_run: bool) -> Result<library_migration::MigrationReport, AppError> {
+    library_migration::migrate(&app_handle, dry_run).await.map_err(AppError::from)
+}
+
+/// Move the mods Vortex deployed into the app's own layout and turn off
+/// the Vortex deployment. With `dry_run` nothing is written.
+#[tauri::command]
+async fn import_vortex(
+    app_handle: tauri::AppHandle,
+    dry_run: bool,
+    staging_dir: Option<String>,
+) -> Result<vortex_import::VortexImportReport, AppError> {
+    vortex_import::import(&app_handle, dry_run, staging_dir).await.map_err(AppError::from)
+}
+
+/// Take over the Mods folder from Stardrop or ModDrop: convert disabled
+/// folders and record every mod in the registry. With `dry_run` nothing is written.
+#[tauri::command]
+async fn import_foreign_mods(
+    app_handle: tauri::AppHandle,
+    tool: foreign_import::ForeignTool,
+    dry_run: bool,
+) -> Result<foreign_import::ForeignImportReport, AppError> {
+    foreign_import::import(&app_handle, tool, dry_run).await.map_err(AppError::from)
+}
+
+/// Rename every disabled mod folder to `style` and make it the style used
+/// from now on. With `dry_run` the report only lists the renames.
+#[tauri::command]
+fn normalize_disabled_folders(
+    app_handle: tauri::AppHandle,
+    style: settings::DisabledFolderStyle,
+    dry_run: bool,
+) -> Result<disabled_folders::NormalizeReport, AppError> {
+    let mut settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    if dry_run {
+        return Ok(disabled_folders::normalize(&settings, style, true));
+    }
+
+    settings.ensure_writable()?;
+    settings.disabled_folder_style = style;
+    settings.save(&app_handle)?;
+    Ok(disabled_folders::normalize(&settings, style, false))
+}
+
+/// Keep the copy of a duplicated mod at `keep_path` and disable the other
+/// folders with its UniqueID, or delete them with `delete`
+#[tauri::command]
+async fn resolve_duplicate(
+    app_handle: tauri::AppHandle,
+    keep_path: String,
+    delete: Option<bool>,
+) -> Result<duplicates::ResolveReport, AppError> {
+    let settings = Settings::load(&app_handle)?;
+    settings.ensure_writable()?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+    tauri::async_runtime::spawn_blocking(move || duplicates::resolve(&settings, &keep_path, delete.unwrap_or(false)))
+        .await
+        .map_err(|e| AppError::Other(e.to_string()))?
+}
+
+/// Start the game without SMAPI, to check whether a problem comes from mods
+#[tauri::command]
+fn launch_game_vanilla(app_handle: tauri::AppHandle) -> Result<(), AppError> {
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err(AppError::GamePathNotConfigured);
+    }
+
+    let executable = settings::detect_game_executable(Path::new(&settings.game_path))
+        .ok_or("Stardew Valley executable not found in the game folder")?;
+    let working_dir = executable.parent().unwrap_or(&executable);
+
+    info!("🚀 Launching game without mods from: {}", executable.display());
+    std::process::Command::new(&executable)
+        .current_dir(working_dir)
+        .spawn()
+        .map_err(|e| format!("Failed to launch game: {}", e))?;
+    Ok(())
+}
+
+/// Start SMAPI in its own console window. Returns the process to watch, or
+/// None on macOS where `open` hands the game to Launch Services, and on
+/// Linux when Steam starts the Windows build under Proton.
+#[cfg_attr(target_os = "macos", allow(unused_variables))]
+fn spawn_smapi(smapi_path: &Path, working_dir: &Path) -> Result<Option<std::process::Child>, String> {
+    // A Windows SMAPI can't be run directly; Steam runs it through Proton
+    // with the launch options the SMAPI installer set for the game
+    #[cfg(target_os = "linux")]
+    if smapi_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe")) {
+        if !smapi_path.components().any(|c| c.as_os_str() == "steamapps") {
+            return Err(format!(
+                "{} is the Windows version of SMAPI, which can only be started through Steam with Proton. \
+                 Install the Linux version of SMAPI to launch it from here.",
+                smapi_path.display()
+            ));
+        }
+        info!("🚀 Launching the Windows build through Steam");
+        std::process::Command::new("steam")
+            .args(["-applaunch", settings::STEAM_APP_ID])
             .spawn()
-            .map_err(|e| format!("Failed to launch game: {}", e))?;
+            .map_err(|e| format!("Failed to start the game through Steam: {}", e))?;
+        return Ok(None);
     }
 
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
-            .arg(&smapi_path)
+            .arg(smapi_path)
             .spawn()
             .map_err(|e| format!("Failed to launch game: {}", e))?;
+        Ok(None)
     }
 
-    #[cfg(target_os = "linux")]
+    #[cfg(not(target_os = "macos"))]
     {
-        std::process::Command::new(&smapi_path)
+        std::process::Command::new(smapi_path)
             .current_dir(working_dir)
             .spawn()
-            .map_err(|e| format!("Failed to launch game: {}", e))?;
+            .map(Some)
+            .map_err(|e| format!("Failed to launch game: {}", e))
     }
-
-    Ok(())
 }
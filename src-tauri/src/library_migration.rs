@@ -0,0 +1,231 @@
+//! One-time import of a Mods folder set up by hand or by another manager into
+//! the mod registry. Every mod gets an entry with its enable state, a hash of
+//! its files and, where one can be found, its Nexus mod id. A dry run reports
+//! all of that without writing anything.
+
+use crate::mod_registry::MigratedMod;
+use crate::settings::Settings;
+use md5::{Digest, Md5};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// Files that change without the mod changing: app metadata and the
+/// settings SMAPI writes on first run
+const UNHASHED_FILES: &[&str] = &[".nexus_meta", crate::mod_installer::CONFIG_FILE_NAME];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NexusIdSource {
+    /// Installed by this app, or given `.nexus_meta` earlier
+    NexusMeta,
+    /// A "Nexus:<id>" update key in the manifest
+    UpdateKeys,
+    /// A framework the app knows the page of
+    KnownMod,
+    /// smapi.io's mod list
+    SmapiIo,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationEntry {
+    pub unique_id: String,
+    pub name: String,
+    pub path: String,
+    pub is_enabled: bool,
+    /// MD5 over the mod's files; None when they couldn't be read
+    pub content_hash: Option<String>,
+    pub nexus_mod_id: Option<u32>,
+    pub nexus_id_source: Option<NexusIdSource>,
+    /// From a "ModDrop:<id>" update key
+    pub moddrop_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    /// A previous migration was already committed
+    pub already_migrated: bool,
+    pub mods: Vec<MigrationEntry>,
+    pub nexus_matched: usize,
+}
+
+/// Mod id on `site` from update keys like "Nexus:541" or "nexus:541@subkey"
+pub(crate) fn update_key_id(update_keys: &[String], site: &str) -> Option<u32> {
+    update_keys.iter().find_map(|key| {
+        let (key_site, id) = key.split_once(':')?;
+        if !key_site.trim().eq_ignore_ascii_case(site) {
+            return None;
+        }
+        id.split('@').next()?.trim().parse().ok()
+    })
+}
+
+fn update_key_nexus_id(update_keys: &[String]) -> Option<u32> {
+    update_key_id(update_keys, "nexus")
+}
+
+/// MD5 over every file of a mod folder: relative paths and contents, in a
+/// fixed order so the same files always give the same hash
+pub(crate) fn folder_hash(dir: &Path) -> std::io::Result<String> {
+    let mut files: Vec<_> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !(e.depth() == 1 && UNHASHED_FILES.iter().any(|name| e.file_name() == *name)))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+
+    let mut hasher = Md5::new();
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        std::io::copy(&mut fs::File::open(&file)?, &mut hasher)?;
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Look at every mod in the Mods folder and, unless `dry_run`, record the
+/// result in the registry and mark the library as migrated
+pub async fn migrate(app_handle: &AppHandle, dry_run: bool) -> Result<MigrationReport, String> {
+    let settings = Settings::load(app_handle)?;
+    if !dry_run {
+        settings.ensure_writable()?;
+    }
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let game_path = settings.game_path.clone();
+    let (mods, mut entries) = tokio::task::spawn_blocking(move || {
+        let mods = crate::mod_installer::scan_mods(Path::new(&game_path));
+        let entries: Vec<MigrationEntry> = mods
+            .iter()
+            .map(|m| {
+                let path = Path::new(&m.path);
+                let update_keys = crate::mod_installer::read_manifest(path)
+                    .map(|manifest| manifest.update_keys)
+                    .unwrap_or_default();
+                let nexus = m
+                    .nexus_mod_id
+                    .map(|id| (id, NexusIdSource::NexusMeta))
+                    .or_else(|| update_key_nexus_id(&update_keys).map(|id| (id, NexusIdSource::UpdateKeys)))
+                    .or_else(|| {
+                        crate::dependencies::known_nexus_id(&m.unique_id).map(|id| (id, NexusIdSource::KnownMod))
+                    });
+                MigrationEntry {
+                    unique_id: m.unique_id.clone(),
+                    name: m.name.clone(),
+                    path: m.path.clone(),
+                    is_enabled: m.is_enabled,
+                    content_hash: folder_hash(path)
+                        .inspect_err(|e| warn!("Failed to hash {}: {}", m.path, e))
+                        .ok(),
+                    nexus_mod_id: nexus.map(|(id, _)| id),
+                    nexus_id_source: nexus.map(|(_, source)| source),
+                    moddrop_id: update_key_id(&update_keys, "moddrop"),
+                }
+            })
+            .collect();
+        (mods, entries)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Whatever is left, smapi.io may know from the mods' update keys
+    let unknown: Vec<String> = entries
+        .iter()
+        .filter(|e| e.nexus_mod_id.is_none())
+        .map(|e| e.unique_id.clone())
+        .collect();
+    if !unknown.is_empty() {
+        match crate::smapi_compat::nexus_ids(app_handle, &unknown).await {
+            Ok(ids) => {
+                for entry in entries.iter_mut().filter(|e| e.nexus_mod_id.is_none()) {
+                    if let Some(id) = ids.get(&entry.unique_id.to_lowercase()) {
+                        entry.nexus_mod_id = Some(*id);
+                        entry.nexus_id_source = Some(NexusIdSource::SmapiIo);
+                    }
+                }
+            }
+            Err(e) => warn!("Couldn't look up Nexus ids on smapi.io: {}", e),
+        }
+    }
+
+    let report = MigrationReport {
+        dry_run,
+        already_migrated: settings.library_migrated,
+        nexus_matched: entries.iter().filter(|e| e.nexus_mod_id.is_some()).count(),
+        mods: entries,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    // Creates the missing entries and records each mod's enable state
+    let mut mods = mods;
+    crate::mod_registry::apply(app_handle, &mut mods);
+    let migrated: Vec<MigratedMod> = report
+        .mods
+        .iter()
+        .map(|e| MigratedMod {
+            path: e.path.clone(),
+            unique_id: e.unique_id.clone(),
+            content_hash: e.content_hash.clone(),
+            nexus_mod_id: e.nexus_mod_id,
+            moddrop_id: e.moddrop_id,
+        })
+        .collect();
+    crate::mod_registry::record_migration(app_handle, &migrated)?;
+
+    // Settings may have changed during the smapi.io lookup
+    let mut settings = Settings::load(app_handle)?;
+    settings.library_migrated = true;
+    settings.save(app_handle)?;
+    info!(
+        "📚 Migrated {} mods into the registry ({} matched to Nexus)",
+        report.mods.len(),
+        report.nexus_matched
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_key_nexus_id() {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        assert_eq!(update_key_nexus_id(&keys(&["GitHub:Pathoschild/SMAPI", "Nexus:541"])), Some(541));
+        assert_eq!(update_key_nexus_id(&keys(&["nexus: 1915@optional"])), Some(1915));
+        assert_eq!(update_key_nexus_id(&keys(&["Nexus:???", "ModDrop:123"])), None);
+        assert_eq!(update_key_nexus_id(&[]), None);
+        assert_eq!(update_key_id(&keys(&["Nexus:541", "ModDrop:123"]), "moddrop"), Some(123));
+    }
+
+    #[test]
+    fn test_folder_hash_ignores_metadata() {
+        let dir = std::env::temp_dir().join(format!("tc_migration_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("assets")).unwrap();
+        fs::write(dir.join("manifest.json"), "{}").unwrap();
+        fs::write(dir.join("assets").join("sprite.png"), "png").unwrap();
+        let hash = folder_hash(&dir).unwrap();
+
+        fs::write(dir.join("config.json"), r#"{"Enabled": true}"#).unwrap();
+        fs::write(dir.join(".nexus_meta"), "{}").unwrap();
+        assert_eq!(folder_hash(&dir).unwrap(), hash);
+
+        fs::write(dir.join("assets").join("sprite.png"), "changed").unwrap();
+        assert_ne!(folder_hash(&dir).unwrap(), hash);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,255 @@
+//! Local HTTP API for external tools (launchers, scripts, Stream Decks).
+//!
+//! Listens on 127.0.0.1 only and requires `Authorization: Bearer <token>`,
+//! where the token is kept in `local_api_token` in the app data directory.
+//!
+//! - `GET /mods` lists installed mods
+//! - `POST /install` with `{"path": "..."}` installs an archive and lists the
+//!   mods it held, or with `{"nxm": "nxm://..."}` queues a download
+//! - `POST /update` checks for updates and installs all of them
+
+use crate::download_manager::DownloadManager;
+use crate::mod_installer::ModInstaller;
+use crate::nxm_protocol::NxmUrl;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{info, warn};
+
+const TOKEN_FILE_NAME: &str = "local_api_token";
+
+/// Largest request body accepted; requests only carry a path or a link
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// What the settings screen shows so users can configure their tools
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiInfo {
+    pub enabled: bool,
+    pub url: String,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallRequest {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    nxm: Option<String>,
+}
+
+/// Read the API token, creating one on first use
+pub fn token(app_handle: &AppHandle) -> Result<String, String> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let path = app_data_dir.join(TOKEN_FILE_NAME);
+
+    if let Ok(token) = fs::read_to_string(&path) {
+        if !token.trim().is_empty() {
+            // Tokens written by earlier versions were readable by everyone
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                if fs::metadata(&path).is_ok_and(|m| m.permissions().mode() & 0o077 != 0) {
+                    let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+                }
+            }
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    crate::settings::write_private(&path, token.as_bytes()).map_err(|e| format!("Failed to write API token: {}", e))?;
+    Ok(token)
+}
+
+pub fn info(app_handle: &AppHandle) -> Result<LocalApiInfo, String> {
+    let settings = Settings::load(app_handle)?;
+    Ok(LocalApiInfo {
+        enabled: settings.local_api_enabled,
+        url: format!("http://127.0.0.1:{}", settings.local_api_port),
+        token: token(app_handle)?,
+    })
+}
+
+/// Compare without bailing out at the first differing byte
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn is_authorized(request: &Request, token: &str) -> bool {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|provided| token_matches(token, provided.trim()))
+}
+
+/// Start the server on its own thread if enabled in settings
+pub fn start(app_handle: &AppHandle) {
+    let Ok(settings) = Settings::load(app_handle) else {
+        return;
+    };
+    if !settings.local_api_enabled {
+        return;
+    }
+
+    let token = match token(app_handle) {
+        Ok(token) => token,
+        Err(e) => {
+            warn!("Local API disabled: {}", e);
+            return;
+        }
+    };
+
+    let address = format!("127.0.0.1:{}", settings.local_api_port);
+    let server = match Server::http(&address) {
+        Ok(server) => server,
+        Err(e) => {
+            warn!("Failed to start local API on {}: {}", address, e);
+            return;
+        }
+    };
+    info!("🔌 Local API listening on http://{}", address);
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let app_handle = app_handle.clone();
+            let token = token.clone();
+            tauri::async_runtime::spawn(async move {
+                handle_request(&app_handle, &token, request).await;
+            });
+        }
+    });
+}
+
+async fn handle_request(app_handle: &AppHandle, token: &str, mut request: Request) {
+    let (status, body) = if !is_authorized(&request, token) {
+        (401, serde_json::json!({ "error": "Missing or invalid token" }))
+    } else {
+        let mut content = String::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY_BYTES)
+            .read_to_string(&mut content);
+
+        match read {
+            Err(e) => (400, serde_json::json!({ "error": e.to_string() })),
+            Ok(_) => match route(app_handle, request.method(), request.url(), &content).await {
+                Ok(body) => (200, body),
+                Err((status, message)) => (status, serde_json::json!({ "error": message })),
+            },
+        }
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        warn!("Local API failed to respond: {}", e);
+    }
+}
+
+async fn route(
+    app_handle: &AppHandle,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> Result<serde_json::Value, (u16, String)> {
+    let path = url.split('?').next().unwrap_or_default();
+    match (method, path) {
+        (Method::Get, "/mods") => {
+            let settings = Settings::load(app_handle).map_err(|e| (500, e))?;
+            if settings.game_path.is_empty() {
+                return Err((409, "Game path not configured".to_string()));
+            }
+            let mut mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+            crate::mod_registry::apply(app_handle, &mut mods);
+            serde_json::to_value(mods).map_err(|e| (500, e.to_string()))
+        }
+        (Method::Post, "/install") => {
+            let request: InstallRequest = serde_json::from_str(body).map_err(|e| (400, e.to_string()))?;
+            match (request.path, request.nxm) {
+                (Some(path), None) => install_archive(app_handle, PathBuf::from(path)).await,
+                (None, Some(nxm)) => queue_nxm(app_handle, &nxm).await,
+                _ => Err((400, "Send exactly one of \"path\" or \"nxm\"".to_string())),
+            }
+        }
+        (Method::Post, "/update") => {
+            let settings = Settings::load(app_handle).map_err(|e| (500, e))?;
+            settings.ensure_writable().map_err(|e| (403, e.to_string()))?;
+            let (updates, _) = crate::update_all::find_updates(app_handle, &settings).await;
+            let results = crate::update_all::update_all(app_handle, updates)
+                .await
+                .map_err(|e| (500, e))?;
+            serde_json::to_value(results).map_err(|e| (500, e.to_string()))
+        }
+        _ => Err((404, format!("No route for {} {}", method, path))),
+    }
+}
+
+async fn install_archive(app_handle: &AppHandle, path: PathBuf) -> Result<serde_json::Value, (u16, String)> {
+    let settings = Settings::load(app_handle).map_err(|e| (500, e))?;
+    if settings.game_path.is_empty() {
+        return Err((409, "Game path not configured".to_string()));
+    }
+    settings.ensure_writable().map_err(|e| (403, e.to_string()))?;
+    if !path.is_file() {
+        return Err((400, format!("Archive not found: {}", path.display())));
+    }
+
+    if let Err(e) = crate::dependencies::install_dependencies(app_handle, &path).await {
+        warn!("Failed to install dependencies: {}", e);
+    }
+
+    let temp_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| (500, e.to_string()))?
+        .join("temp");
+    let results = ModInstaller::new(app_handle.clone(), temp_dir)
+        .unattended()
+        .install_from_archive(&path, Path::new(&settings.game_path), &settings, None, None)
+        .await
+        .map_err(|e| (500, e.to_string()))?;
+    for result in &results {
+        crate::mod_registry::record_install(app_handle, result, crate::mod_registry::ModSource::Archive, &path);
+    }
+
+    serde_json::to_value(results).map_err(|e| (500, e.to_string()))
+}
+
+async fn queue_nxm(app_handle: &AppHandle, nxm: &str) -> Result<serde_json::Value, (u16, String)> {
+    let nxm_url = NxmUrl::parse(nxm).map_err(|e| (400, e.to_string()))?;
+    nxm_url.validate().map_err(|e| (400, e.to_string()))?;
+
+    let manager = app_handle.state::<DownloadManager>();
+    let download_id = manager.add_to_queue(nxm_url).await.map_err(|e| (409, e))?;
+    Ok(serde_json::json!({ "downloadId": download_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc123", "abc124"));
+        assert!(!token_matches("abc123", "abc12"));
+        assert!(!token_matches("abc123", ""));
+    }
+}
@@ -0,0 +1,184 @@
+//! The app log: `tracing` events go to stdout and to a daily file under
+//! `logs/` in the app data folder, so release builds (which have no console)
+//! still leave a trail. The level comes from Settings and can change while
+//! the app runs and applies to this crate; other crates only log warnings
+//! and errors.
+
+use crate::settings::LogLevel;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, Registry};
+
+const LOG_FILE_PREFIX: &str = "treasure-chest";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily files kept before the oldest is deleted
+const KEEP_LOG_FILES: usize = 7;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub timestamp: String,
+    pub level: String,
+    /// Module the message came from
+    pub target: String,
+    pub message: String,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Log this crate at `level`, everything else (HTTP clients, the webview
+/// runtime) at warnings only
+fn filter(level: LogLevel) -> Targets {
+    Targets::new()
+        .with_target(env!("CARGO_CRATE_NAME"), LevelFilter::from(level))
+        .with_default(LevelFilter::WARN)
+}
+
+pub fn logs_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("logs")
+}
+
+/// Install the global subscriber. The returned guard flushes the file
+/// writer when dropped, so keep it for the whole run.
+pub fn init(app_data_dir: &Path, level: LogLevel) -> Option<WorkerGuard> {
+    let (filter, handle) = reload::Layer::new(filter(level));
+
+    let appender = Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(KEEP_LOG_FILES)
+        .build(logs_dir(app_data_dir));
+    let (file_layer, guard) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(fmt::layer().with_ansi(false).with_writer(writer)), Some(guard))
+        }
+        Err(e) => {
+            eprintln!("Failed to open the log file, logging to stdout only: {}", e);
+            (None, None)
+        }
+    };
+
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .try_init();
+    if installed.is_ok() {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+    guard
+}
+
+/// Apply a level changed in Settings
+pub fn set_level(level: LogLevel) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        if let Err(e) = handle.modify(|current| *current = filter(level)) {
+            tracing::warn!("Failed to change the log level: {}", e);
+        }
+    }
+}
+
+/// Split a line written by the fmt layer:
+/// `2024-01-01T12:00:00.000000Z  INFO module::path: message`
+fn parse_line(line: &str) -> Option<LogLine> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ')?;
+    if !["ERROR", "WARN", "INFO", "DEBUG", "TRACE"].contains(&level) {
+        return None;
+    }
+    let (target, message) = rest.split_once(": ")?;
+    Some(LogLine {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+/// Read lines from log file contents; lines that don't start a new entry
+/// belong to the message before them
+fn parse_lines(contents: &str) -> Vec<LogLine> {
+    let mut lines: Vec<LogLine> = Vec::new();
+    for line in contents.lines() {
+        match parse_line(line) {
+            Some(parsed) => lines.push(parsed),
+            None => {
+                if let Some(last) = lines.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// The newest `limit` log lines, oldest first
+pub fn recent_logs(app_data_dir: &Path, limit: usize) -> Vec<LogLine> {
+    let Ok(entries) = fs::read_dir(logs_dir(app_data_dir)) else {
+        return Vec::new();
+    };
+    // Dated file names sort by day
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut recent: Vec<LogLine> = Vec::new();
+    for file in files.iter().rev() {
+        let Ok(contents) = fs::read_to_string(file) else { continue };
+        let mut lines = parse_lines(&contents);
+        lines.append(&mut recent);
+        recent = lines;
+        if recent.len() >= limit {
+            break;
+        }
+    }
+    let skip = recent.len().saturating_sub(limit);
+    recent.split_off(skip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let contents = "2024-01-01T12:00:00.000000Z  INFO sdv_mods_manager_lib::mod_installer: Installing LookupAnything\n\
+                        2024-01-01T12:00:01.000000Z  WARN sdv_mods_manager_lib::nexus_api: Only 49 of 500 requests left\n\
+                        second line\n";
+        let lines = parse_lines(contents);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, "INFO");
+        assert_eq!(lines[0].target, "sdv_mods_manager_lib::mod_installer");
+        assert_eq!(lines[0].message, "Installing LookupAnything");
+        assert_eq!(lines[1].message, "Only 49 of 500 requests left\nsecond line");
+    }
+}
@@ -0,0 +1,281 @@
+//! Low-priority housekeeping: pruning old backups, evicting stale cache
+//! entries, reconciling the mod registry and clearing leftover extraction
+//! folders. Runs every few hours once no download or install is active, or
+//! on request through `run_maintenance`.
+
+use crate::download_manager::{DownloadManager, DownloadStatus};
+use crate::events::{self, AppEvent};
+use crate::settings::Settings;
+use crate::startup::{run_step, StepOutcome, StepReport, StepResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// How often the scheduler looks for an idle moment
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Time between two scheduled runs
+const MAINTENANCE_INTERVAL_HOURS: i64 = 6;
+/// Temp entries younger than this may belong to an install that just started
+const TEMP_MIN_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MaintenanceStatus {
+    pub running: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// None until the first run; the scheduler runs as soon as the app is idle
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Steps of the last run
+    pub steps: Vec<StepReport>,
+}
+
+/// Status of the scheduler, kept for `get_maintenance_status`
+#[derive(Default)]
+pub struct MaintenanceState {
+    status: Mutex<MaintenanceStatus>,
+}
+
+fn status_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("maintenance.json")
+}
+
+impl MaintenanceState {
+    /// The status saved by the last run, so a restart doesn't run
+    /// maintenance again before it's due
+    pub fn load(app_data_dir: &Path) -> Self {
+        let status: MaintenanceStatus = fs::read_to_string(status_path(app_data_dir))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            status: Mutex::new(MaintenanceStatus {
+                running: false,
+                ..status
+            }),
+        }
+    }
+
+    pub async fn status(&self) -> MaintenanceStatus {
+        self.status.lock().await.clone()
+    }
+}
+
+/// Why maintenance shouldn't run right now, if anything is busy
+pub async fn busy_reason(app_handle: &AppHandle) -> Option<String> {
+    if crate::mod_installer::installs_in_progress() {
+        return Some("A mod is being installed".to_string());
+    }
+
+    let manager = app_handle.try_state::<DownloadManager>()?;
+    let active = manager
+        .get_queue_state()
+        .await
+        .iter()
+        .filter(|task| {
+            matches!(
+                task.status,
+                DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::RateLimited { .. }
+            )
+        })
+        .count();
+    (active > 0).then(|| format!("{} download(s) in progress", active))
+}
+
+/// Check for an idle moment every few minutes and run maintenance when due
+pub fn start(app_handle: &AppHandle) {
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+
+            let Some(state) = handle.try_state::<MaintenanceState>() else {
+                return;
+            };
+            let due = state.status().await.next_run_at.is_none_or(|at| at <= Utc::now());
+            if !due || busy_reason(&handle).await.is_some() {
+                continue;
+            }
+            if let Err(e) = run(&handle).await {
+                warn!("Maintenance failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Run every maintenance step now and publish the result
+pub async fn run(app_handle: &AppHandle) -> Result<MaintenanceStatus, String> {
+    let state = app_handle
+        .try_state::<MaintenanceState>()
+        .ok_or("Maintenance not initialized")?;
+    {
+        let mut status = state.status.lock().await;
+        if status.running {
+            return Err("Maintenance is already running".to_string());
+        }
+        status.running = true;
+    }
+
+    info!("=== Running maintenance ===");
+    let steps = vec![
+        run_step(app_handle, "prune_backups", prune_backups).await,
+        run_step(app_handle, "evict_caches", evict_caches).await,
+        run_step(app_handle, "reconcile_registry", reconcile_registry).await,
+        run_step(app_handle, "clean_temp", clean_temp).await,
+    ];
+
+    let finished_at = Utc::now();
+    let status = MaintenanceStatus {
+        running: false,
+        last_run_at: Some(finished_at),
+        next_run_at: Some(finished_at + chrono::Duration::hours(MAINTENANCE_INTERVAL_HOURS)),
+        steps,
+    };
+    *state.status.lock().await = status.clone();
+    info!("=== Maintenance finished ===");
+    if let Err(e) = save_status(app_handle, &status) {
+        warn!("Failed to save maintenance status: {}", e);
+    }
+
+    events::emit(app_handle, AppEvent::MaintenanceFinished(status.clone()));
+    Ok(status)
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+fn save_status(app_handle: &AppHandle, status: &MaintenanceStatus) -> Result<(), String> {
+    let path = status_path(&app_data_dir(app_handle)?);
+    let json = serde_json::to_string_pretty(status).map_err(|e| e.to_string())?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| e.to_string())
+}
+
+/// Keep the newest `backups_per_mod` snapshots of each mod
+async fn prune_backups(app_handle: AppHandle) -> StepResult {
+    let settings = Settings::load(&app_handle)?;
+    if settings.backups_per_mod == 0 {
+        return Ok(StepOutcome::Skipped("Keeping all backups".to_string()));
+    }
+
+    let backups_dir = app_data_dir(&app_handle)?.join("backups");
+    let keep = settings.backups_per_mod;
+    let removed = tokio::task::spawn_blocking(move || {
+        let mut removed = 0;
+        for entry in fs::read_dir(&backups_dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            match crate::backup::prune_snapshots(&path, keep) {
+                Ok(count) => removed += count,
+                Err(e) => warn!("Failed to prune backups in {}: {}", path.display(), e),
+            }
+        }
+        removed
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(StepOutcome::Done(Some(format!("{} old backup(s) removed", removed))))
+}
+
+async fn evict_caches(app_handle: AppHandle) -> StepResult {
+    let evicted = crate::smapi_compat::evict_cache(&app_handle)?;
+    Ok(StepOutcome::Done(Some(format!("{} compatibility entries evicted", evicted))))
+}
+
+/// Rescan the Mods folder so the registry notices mods changed outside the app
+async fn reconcile_registry(app_handle: AppHandle) -> StepResult {
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Ok(StepOutcome::Skipped("Game path not configured".to_string()));
+    }
+
+    let handle = app_handle.clone();
+    let count = tokio::task::spawn_blocking(move || {
+        let mut mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+        crate::mod_registry::apply(&handle, &mut mods);
+        mods.len()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(StepOutcome::Done(Some(format!("{} mods reconciled", count))))
+}
+
+/// Remove extraction folders left behind by interrupted installs
+async fn clean_temp(app_handle: AppHandle) -> StepResult {
+    if crate::mod_installer::installs_in_progress() {
+        return Ok(StepOutcome::Skipped("A mod is being installed".to_string()));
+    }
+
+    let temp_dir = app_data_dir(&app_handle)?.join("temp");
+    let removed = tokio::task::spawn_blocking(move || {
+        let mut removed = 0;
+        for path in stale_entries(&temp_dir, SystemTime::now()) {
+            let result = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            match result {
+                Ok(()) => removed += 1,
+                Err(e) => warn!("Failed to remove {}: {}", path.display(), e),
+            }
+        }
+        removed
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(StepOutcome::Done(Some(format!("{} leftover temp entries removed", removed))))
+}
+
+/// Entries of `dir` last modified at least `TEMP_MIN_AGE` before `now`
+fn stale_entries(dir: &Path, now: SystemTime) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|age| age >= TEMP_MIN_AGE))
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_entries() {
+        let dir = std::env::temp_dir().join(format!("tc_maintenance_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("SomeMod")).unwrap();
+        fs::write(dir.join("leftover.txt"), "x").unwrap();
+
+        assert!(stale_entries(&dir, SystemTime::now()).is_empty());
+
+        let later = SystemTime::now() + TEMP_MIN_AGE;
+        let mut stale = stale_entries(&dir, later);
+        stale.sort();
+        assert_eq!(stale, vec![dir.join("SomeMod"), dir.join("leftover.txt")]);
+
+        assert!(stale_entries(&dir.join("missing"), later).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
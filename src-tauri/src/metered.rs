@@ -0,0 +1,90 @@
+//! Whether the current internet connection is metered (mobile data, a phone
+//! hotspot, a connection the user marked as metered), so large downloads can
+//! wait for the user's go-ahead. Only Windows and macOS report this; other
+//! platforms are treated as unmetered.
+
+/// Blocks for up to a second on macOS; call it from `spawn_blocking`
+pub fn is_metered() -> bool {
+    platform::is_metered()
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    pub fn is_metered() -> bool {
+        // No profile means no internet connection at all
+        let Ok(cost) = NetworkInformation::GetInternetConnectionProfile().and_then(|p| p.GetConnectionCost()) else {
+            return false;
+        };
+
+        let limited = matches!(
+            cost.NetworkCostType(),
+            Ok(NetworkCostType::Fixed) | Ok(NetworkCostType::Variable)
+        );
+        limited || cost.Roaming().unwrap_or(false) || cost.OverDataLimit().unwrap_or(false)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use std::ffi::c_void;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    type Object = *mut c_void;
+
+    #[link(name = "Network", kind = "framework")]
+    extern "C" {
+        fn nw_path_monitor_create() -> Object;
+        fn nw_path_monitor_set_queue(monitor: Object, queue: Object);
+        fn nw_path_monitor_set_update_handler(monitor: Object, handler: &block2::Block<dyn Fn(Object)>);
+        fn nw_path_monitor_start(monitor: Object);
+        fn nw_path_monitor_cancel(monitor: Object);
+        fn nw_path_is_expensive(path: Object) -> bool;
+        fn nw_path_is_constrained(path: Object) -> bool;
+        fn nw_release(object: Object);
+    }
+
+    extern "C" {
+        fn dispatch_queue_create(label: *const std::ffi::c_char, attr: Object) -> Object;
+        fn dispatch_release(object: Object);
+    }
+
+    /// Network.framework reports the current path to a monitor's update
+    /// handler right after it starts; "expensive" covers cellular and
+    /// hotspots, "constrained" is Low Data Mode.
+    pub fn is_metered() -> bool {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let handler = RcBlock::new(move |path: Object| {
+            // SAFETY: the path is valid for the duration of the handler
+            let metered = unsafe { nw_path_is_expensive(path) || nw_path_is_constrained(path) };
+            let _ = sender.try_send(metered);
+        });
+
+        // SAFETY: the monitor and queue are released after the monitor is
+        // cancelled, and the handler block is retained by the monitor
+        unsafe {
+            let queue = dispatch_queue_create(c"treasure-chest.metered".as_ptr(), std::ptr::null_mut());
+            let monitor = nw_path_monitor_create();
+            nw_path_monitor_set_queue(monitor, queue);
+            nw_path_monitor_set_update_handler(monitor, &handler);
+            nw_path_monitor_start(monitor);
+
+            let metered = receiver.recv_timeout(Duration::from_secs(1)).unwrap_or(false);
+
+            nw_path_monitor_cancel(monitor);
+            nw_release(monitor);
+            dispatch_release(queue);
+            metered
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    pub fn is_metered() -> bool {
+        false
+    }
+}
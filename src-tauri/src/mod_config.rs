@@ -0,0 +1,131 @@
+//! A mod's config.json, the settings file SMAPI mods create on first run.
+//! It's read as leniently as manifests are; writing it back drops any
+//! comments, like SMAPI does when a mod saves its config, but keeps the
+//! order of the settings.
+
+use crate::models::{ConfigKey, ConfigValueKind, ModManifest};
+use crate::mod_installer::{lenient_json, CONFIG_FILE_NAME};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+pub const GMCM_UNIQUE_ID: &str = "spacechase0.GenericModConfigMenu";
+
+/// Whether the mod lists Generic Mod Config Menu as a dependency, which mods
+/// do (as optional) when they register a config page with it
+pub fn declares_gmcm(manifest: &ModManifest) -> bool {
+    manifest
+        .dependencies
+        .iter()
+        .flatten()
+        .any(|d| d.unique_id.eq_ignore_ascii_case(GMCM_UNIQUE_ID))
+}
+
+/// The top-level settings of a config, in file order, and what type each
+/// holds
+pub fn config_keys(config: &Value) -> Vec<ConfigKey> {
+    let Some(object) = config.as_object() else {
+        return Vec::new();
+    };
+    object
+        .iter()
+        .map(|(key, value)| ConfigKey {
+            key: key.clone(),
+            kind: match value {
+                Value::Bool(_) => ConfigValueKind::Boolean,
+                Value::Number(_) => ConfigValueKind::Number,
+                Value::String(_) => ConfigValueKind::String,
+                Value::Object(_) => ConfigValueKind::Object,
+                Value::Array(_) => ConfigValueKind::Array,
+                Value::Null => ConfigValueKind::Null,
+            },
+        })
+        .collect()
+}
+
+/// The config of the mod in `mod_dir`, or None when the mod hasn't written
+/// one yet (it runs once in game first)
+pub fn read_config(mod_dir: &Path) -> Result<Option<Value>, String> {
+    let path = mod_dir.join(CONFIG_FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&lenient_json(&content))
+        .map(Some)
+        .map_err(|e| format!("Invalid {}: {}", CONFIG_FILE_NAME, e))
+}
+
+/// Replace the config of the mod in `mod_dir`. Written to a temp file and
+/// renamed over the old one, so a failed write leaves the old config intact.
+pub fn write_config(mod_dir: &Path, config: &Value) -> Result<(), String> {
+    if !config.is_object() {
+        return Err(format!("{} must be a JSON object", CONFIG_FILE_NAME));
+    }
+    if !mod_dir.join("manifest.json").is_file() {
+        return Err(format!("Not a mod folder: {}", mod_dir.display()));
+    }
+
+    let path = mod_dir.join(CONFIG_FILE_NAME);
+    let tmp_path = mod_dir.join(format!("{}.tmp", CONFIG_FILE_NAME));
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write {}: {}", CONFIG_FILE_NAME, e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace {}: {}", CONFIG_FILE_NAME, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_lenient_and_write_back() {
+        let dir = std::env::temp_dir().join(format!("tc_mod_config_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("manifest.json"), "{}").unwrap();
+        assert_eq!(read_config(&dir), Ok(None));
+
+        fs::write(
+            dir.join(CONFIG_FILE_NAME),
+            "\u{feff}{\n  // Key to open the menu\n  \"ToggleKey\": \"F1\",\n  \"ShowIcons\": true,\n}",
+        )
+        .unwrap();
+        let mut config = read_config(&dir).unwrap().unwrap();
+        assert_eq!(config["ToggleKey"], "F1");
+        assert_eq!(
+            config_keys(&config),
+            vec![
+                ConfigKey { key: "ToggleKey".to_string(), kind: ConfigValueKind::String },
+                ConfigKey { key: "ShowIcons".to_string(), kind: ConfigValueKind::Boolean },
+            ]
+        );
+
+        config["ShowIcons"] = Value::Bool(false);
+        write_config(&dir, &config).unwrap();
+        assert_eq!(read_config(&dir).unwrap(), Some(config));
+        let written = fs::read_to_string(dir.join(CONFIG_FILE_NAME)).unwrap();
+        assert!(written.find("ToggleKey") < written.find("ShowIcons"));
+        assert!(!dir.join("config.json.tmp").exists());
+
+        assert!(write_config(&dir, &Value::Array(Vec::new())).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_declares_gmcm() {
+        let manifest = |dependencies: &str| -> ModManifest {
+            serde_json::from_str(&format!(
+                r#"{{"Name": "A", "Version": "1.0.0", "UniqueID": "a.A", "Dependencies": {}}}"#,
+                dependencies
+            ))
+            .unwrap()
+        };
+        assert!(declares_gmcm(&manifest(
+            r#"[{"UniqueID": "spacechase0.genericmodconfigmenu", "IsRequired": false}]"#
+        )));
+        assert!(!declares_gmcm(&manifest(r#"[{"UniqueID": "Pathoschild.ContentPatcher"}]"#)));
+        assert!(!declares_gmcm(&manifest("null")));
+    }
+}
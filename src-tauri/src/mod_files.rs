@@ -0,0 +1,138 @@
+use crate::events::{self, AppEvent};
+use crate::nexus_api::{ModFile, NexusClient};
+use crate::nxm_protocol::NxmUrl;
+use crate::settings::Settings;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Categories that are never offered alongside a current file
+const STALE_CATEGORIES: &[&str] = &["ARCHIVED", "OLD_VERSION"];
+
+fn is_stale(file: &ModFile) -> bool {
+    file.category_name
+        .as_deref()
+        .is_some_and(|category| STALE_CATEGORIES.contains(&category))
+}
+
+/// Authors mark companion files in the name or description, e.g.
+/// "Assets (REQUIRED)"
+fn marked_required(file: &ModFile) -> bool {
+    let mentions = |text: &str| text.to_lowercase().contains("required");
+    mentions(&file.name) || file.description.as_deref().is_some_and(mentions)
+}
+
+/// Other files of a mod that should be installed with `file_id`: main files
+/// uploaded for the same version, and current files marked as required
+pub fn required_companions(files: &[ModFile], file_id: u32) -> Vec<ModFile> {
+    let Some(selected) = files.iter().find(|f| f.file_id == file_id) else {
+        return Vec::new();
+    };
+    let selected_is_main = selected.category_name.as_deref() == Some("MAIN");
+
+    files
+        .iter()
+        .filter(|f| f.file_id != file_id && !is_stale(f))
+        .filter(|f| {
+            let same_release_main = selected_is_main
+                && f.category_name.as_deref() == Some("MAIN")
+                && f.version.is_some()
+                && f.version == selected.version;
+            same_release_main || marked_required(f)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Fetch the companions of a file from the mod's file list
+pub async fn get_required_files(app_handle: &AppHandle, mod_id: u32, file_id: u32) -> Result<Vec<ModFile>, String> {
+    let settings = Settings::load(app_handle)?;
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err("Nexus API key not configured".to_string());
+    }
+
+    let files = nexus.mod_files("stardewvalley", mod_id).await?;
+    Ok(required_companions(&files, file_id))
+}
+
+/// After an nxm link is queued, tell the frontend about files it likely needs
+/// too, so the user can queue the whole set in one click
+pub async fn offer_required_files(app_handle: &AppHandle, nxm_url: &NxmUrl, download_id: String) {
+    match get_required_files(app_handle, nxm_url.mod_id, nxm_url.file_id).await {
+        Ok(files) if !files.is_empty() => {
+            info!("📦 Mod {} has {} companion file(s) to offer", nxm_url.mod_id, files.len());
+            events::emit(
+                app_handle,
+                AppEvent::RequiredFilesAvailable {
+                    download_id,
+                    mod_id: nxm_url.mod_id,
+                    files,
+                },
+            );
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to check for required files of mod {}: {}", nxm_url.mod_id, e),
+    }
+}
+
+/// Queue several files of a mod picked in the app. Without a key from the
+/// website, Nexus only hands out download links to premium accounts.
+pub async fn queue_mod_files(app_handle: &AppHandle, mod_id: u32, file_ids: Vec<u32>) -> Result<Vec<String>, String> {
+    let settings = Settings::load(app_handle)?;
+    let user = NexusClient::new(app_handle, &settings).validate_user().await?;
+    if !user.is_premium {
+        return Err(
+            "Queueing files from the app needs a Nexus Mods Premium account. Use \"Mod Manager Download\" on each file instead."
+                .to_string(),
+        );
+    }
+
+    let manager = app_handle.state::<crate::download_manager::DownloadManager>();
+    let mut download_ids = Vec::new();
+    for file_id in file_ids {
+        let download_id = manager
+            .add_to_queue(NxmUrl::direct("stardewvalley", mod_id, file_id))
+            .await?;
+        download_ids.push(download_id);
+    }
+
+    Ok(download_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(file_id: u32, name: &str, category: &str, version: &str, description: Option<&str>) -> ModFile {
+        serde_json::from_value(serde_json::json!({
+            "file_id": file_id,
+            "name": name,
+            "category_name": category,
+            "version": version,
+            "description": description,
+            "uploaded_timestamp": 1700000000,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_required_companions() {
+        let files = vec![
+            file(1, "Seasonal Outfits", "MAIN", "2.0", None),
+            file(2, "Seasonal Outfits - Textures", "MAIN", "2.0", None),
+            file(3, "Shared Assets", "OPTIONAL", "1.0", Some("Required by every version")),
+            file(4, "Old Textures", "OLD_VERSION", "2.0", Some("Required for 1.x")),
+            file(5, "Recolor addon", "OPTIONAL", "2.0", Some("Requires the main file")),
+            file(6, "Seasonal Outfits", "MAIN", "1.0", None),
+        ];
+
+        let ids: Vec<u32> = required_companions(&files, 1).iter().map(|f| f.file_id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        // Picking an optional file doesn't pull in every main file
+        let ids: Vec<u32> = required_companions(&files, 5).iter().map(|f| f.file_id).collect();
+        assert_eq!(ids, vec![3]);
+
+        assert!(required_companions(&files, 99).is_empty());
+    }
+}
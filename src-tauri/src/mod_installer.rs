@@ -1,12 +1,49 @@
-use crate::models::ModManifest;
-use crate::settings::Settings;
+use crate::events::{self, AppEvent, EventSink};
+use crate::models::{ConfigKey, ModManifest};
+use crate::nexus_meta::NexusMeta;
+use crate::settings::{DisabledFolderStyle, InstallMode, Settings};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::fs::{self, File};
 use std::io::{BufReader, Read};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Emitter, Manager};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
 use walkdir::WalkDir;
-use zip::ZipArchive;
+
+/// Per-mod settings SMAPI generates on first run; kept across updates
+pub const CONFIG_FILE_NAME: &str = "config.json";
+
+/// OS and archiver leftovers that are never copied into a mod folder
+const JUNK_NAMES: &[&str] = &["__MACOSX", ".DS_Store", "Thumbs.db", "desktop.ini"];
+
+/// How the files of an archive were mapped onto folders under Mods
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallStrategy {
+    /// The archive holds one folder, installed as is
+    SingleFolder,
+    /// Several manifests; each mod got a folder of its own
+    MultiManifest,
+    /// Files at the archive root, wrapped in a folder named after the mod
+    LooseFiles,
+    /// Folders the user picked in the option chooser
+    SelectedOptions,
+}
+
+/// A manifest.json found in the archive
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedManifest {
+    /// Relative to the archive root, '/'-separated
+    pub path: String,
+    pub unique_id: Option<String>,
+    pub name: Option<String>,
+}
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -15,6 +52,59 @@ pub struct InstallResult {
     pub version: String,
     pub unique_id: String,
     pub install_path: PathBuf,
+    pub strategy: InstallStrategy,
+    /// Every manifest in the archive, including those of other mods it bundles
+    pub manifests: Vec<DetectedManifest>,
+    /// Junk left out of the install, relative to the archive root
+    pub skipped_files: Vec<String>,
+    /// UniqueID of the mod from the same archive this one is a content pack for
+    pub parent_unique_id: Option<String>,
+}
+
+/// Which files of an extracted archive go where, and why
+struct InstallDecision {
+    strategy: InstallStrategy,
+    source_path: PathBuf,
+    target_name: String,
+    manifests: Vec<DetectedManifest>,
+    /// Folders inside `source_path` installed as mods of their own
+    excluded: Vec<PathBuf>,
+    parent_unique_id: Option<String>,
+}
+
+/// A folder of an archive offered in the option chooser. Only folders with a
+/// manifest can be picked; the others are shown for context.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFolder {
+    /// Relative to the archive root, '/'-separated; what the selection names
+    pub path: String,
+    pub name: String,
+    pub mod_name: Option<String>,
+    pub unique_id: Option<String>,
+    pub version: Option<String>,
+    pub children: Vec<ArchiveFolder>,
+}
+
+/// An archive whose mods are alternatives the user has to choose between
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallOptions {
+    pub archive_path: PathBuf,
+    pub mod_name: Option<String>,
+    pub nexus_meta: Option<NexusMeta>,
+    pub folders: Vec<ArchiveFolder>,
+}
+
+/// A snapshot taken before a mod was replaced
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModBackup {
+    /// Snapshot name, what `restore_backup` takes
+    pub timestamp: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub compressed: bool,
+    pub path: PathBuf,
 }
 
 #[derive(Debug)]
@@ -23,6 +113,8 @@ pub enum InstallError {
     ManifestNotFound,
     InvalidManifest(String),
     InstallationFailed(String),
+    /// The archive holds variants; `install-options-needed` was sent
+    OptionsNeeded,
     IoError(std::io::Error),
 }
 
@@ -33,6 +125,7 @@ impl std::fmt::Display for InstallError {
             InstallError::ManifestNotFound => write!(f, "No manifest.json found in mod archive"),
             InstallError::InvalidManifest(e) => write!(f, "Invalid manifest.json: {}", e),
             InstallError::InstallationFailed(e) => write!(f, "Installation failed: {}", e),
+            InstallError::OptionsNeeded => write!(f, "Choose which folders of the archive to install"),
             InstallError::IoError(e) => write!(f, "IO error: {}", e),
         }
     }
@@ -51,28 +144,59 @@ impl From<walkdir::Error> for InstallError {
 }
 
 pub struct ModInstaller {
-    app_handle: AppHandle,
+    events: Arc<dyn EventSink>,
     temp_dir: PathBuf,
+    /// Replaced mods are snapshotted to `<backups_dir>/<name>`
+    backups_dir: PathBuf,
+    /// Archives with variants ask which folders to install
+    ask_for_options: bool,
 }
 
 impl ModInstaller {
     pub fn new(app_handle: AppHandle, temp_dir: PathBuf) -> Self {
+        let backups_dir = app_handle
+            .path()
+            .app_data_dir()
+            .unwrap_or_else(|_| temp_dir.clone())
+            .join("backups");
+
+        Self::with_sink(Arc::new(app_handle), temp_dir, backups_dir)
+    }
+
+    /// Installer that doesn't need a running app
+    pub fn with_sink(events: Arc<dyn EventSink>, temp_dir: PathBuf, backups_dir: PathBuf) -> Self {
         Self {
-            app_handle,
+            events,
             temp_dir,
+            backups_dir,
+            ask_for_options: true,
         }
     }
 
-    /// Install a mod from an archive file
+    /// For installs nobody is there to choose options for (updates, sync,
+    /// requirements, the browser API): archives with variants install every
+    /// mod folder instead, and any duplicates that leaves are flagged
+    pub fn unattended(mut self) -> Self {
+        self.ask_for_options = false;
+        self
+    }
+
+    /// Install the mods in an archive file. Archives bundling several mods
+    /// (manifests under different folders) install each into its own folder.
+    /// Archives with variants ("[Option A]", "Optional", or one mod in several
+    /// folders) send `install-options-needed` and fail with `OptionsNeeded`;
+    /// `install_selected_folders` finishes the install. Unattended installers
+    /// install all of them.
     pub async fn install_from_archive(
         &self,
         archive_path: &Path,
         game_path: &Path,
         settings: &Settings,
-        nexus_info: Option<(u32, u32)>,
+        nexus_meta: Option<NexusMeta>,
         mod_name: Option<String>,
-    ) -> Result<InstallResult, InstallError> {
-        println!("Installing mod from: {}", archive_path.display());
+    ) -> Result<Vec<InstallResult>, InstallError> {
+        info!("Installing mod from: {}", archive_path.display());
+        let _active = ActiveInstall::start();
 
         // Create temp directory if it doesn't exist
         fs::create_dir_all(&self.temp_dir)?;
@@ -80,8 +204,229 @@ impl ModInstaller {
         // Extract archive to temp directory
         let extract_dir = self.extract_archive(archive_path).await?;
 
-        // Determine installation strategy
-        let (source_path, target_name) = self.determine_install_strategy(&extract_dir, archive_path, mod_name.clone())?;
+        let mod_roots = self.mod_roots(&extract_dir)?;
+        if self.ask_for_options && self.has_variants(&extract_dir, &mod_roots) {
+            info!("   Strategy: Archive has optional folders, asking which to install");
+            let options = InstallOptions {
+                archive_path: archive_path.to_path_buf(),
+                mod_name,
+                nexus_meta,
+                folders: self.archive_folders(&extract_dir, &extract_dir, &mod_roots),
+            };
+            events::emit(self.events.as_ref(), AppEvent::InstallOptionsNeeded(options));
+            return Err(InstallError::OptionsNeeded);
+        }
+
+        let results = if mod_roots.len() > 1 {
+            info!("   Strategy: {} mods in one archive, installing each separately", mod_roots.len());
+            let strategy = InstallStrategy::MultiManifest;
+            self.install_each(&extract_dir, &mod_roots, strategy, game_path, settings, nexus_meta.as_ref())?
+        } else {
+            // Determine installation strategy
+            let decision = self.determine_install_strategy(&extract_dir, archive_path, mod_name.clone())?;
+            let (result, _) = self.install_mod_root(&extract_dir, decision, game_path, settings, nexus_meta.as_ref(), mod_name)?;
+            vec![result]
+        };
+
+        self.finish_archive(archive_path, settings);
+        Ok(results)
+    }
+
+    /// Install the folders of an archive the user picked after
+    /// `install-options-needed`, each into its own folder
+    pub async fn install_selected_folders(
+        &self,
+        archive_path: &Path,
+        game_path: &Path,
+        settings: &Settings,
+        nexus_meta: Option<NexusMeta>,
+        folders: &[String],
+    ) -> Result<Vec<InstallResult>, InstallError> {
+        info!("Installing {} selected folders from: {}", folders.len(), archive_path.display());
+        let _active = ActiveInstall::start();
+        fs::create_dir_all(&self.temp_dir)?;
+        let extract_dir = self.extract_archive(archive_path).await?;
+
+        // Only offered folders can be picked, which also keeps "../" out.
+        // Content packs nested in a picked mod come along with it.
+        let roots = self.mod_roots(&extract_dir)?;
+        let picked: Vec<&PathBuf> = roots
+            .iter()
+            .filter(|root| folders.contains(&relative_folder(&extract_dir, root)))
+            .collect();
+        let selected: Vec<PathBuf> = roots
+            .iter()
+            .filter(|root| picked.iter().any(|p| root.starts_with(p)))
+            .cloned()
+            .collect();
+        if selected.is_empty() {
+            return Err(InstallError::InstallationFailed(
+                "None of the selected folders contain a mod".to_string(),
+            ));
+        }
+
+        let strategy = InstallStrategy::SelectedOptions;
+        let results = self.install_each(&extract_dir, &selected, strategy, game_path, settings, nexus_meta.as_ref())?;
+        self.finish_archive(archive_path, settings);
+        Ok(results)
+    }
+
+    /// Install each mod root into a folder of its own. Option folders
+    /// ("[Option A] Blue") are named after the mod instead. Content packs for
+    /// another mod of the archive go in after it and record it as their parent.
+    /// All of them are installed or none: if one fails, those installed
+    /// before it are taken out again and the versions they replaced restored.
+    fn install_each(
+        &self,
+        extract_dir: &Path,
+        mod_roots: &[PathBuf],
+        strategy: InstallStrategy,
+        game_path: &Path,
+        settings: &Settings,
+        nexus_meta: Option<&NexusMeta>,
+    ) -> Result<Vec<InstallResult>, InstallError> {
+        let manifests = self.detected_manifests(extract_dir);
+        let root_manifests: Vec<Option<ModManifest>> = mod_roots.iter().map(|root| self.single_manifest(root)).collect();
+        let parents: Vec<Option<String>> = root_manifests
+            .iter()
+            .map(|manifest| archive_parent(manifest.as_ref()?, &root_manifests))
+            .collect();
+
+        // Frameworks first, so a content pack never lands without its parent
+        let mut order: Vec<usize> = (0..mod_roots.len()).collect();
+        order.sort_by_key(|&i| parents[i].is_some());
+
+        let mut results = Vec::new();
+        let mut undos = Vec::new();
+        for i in order {
+            let mod_root = &mod_roots[i];
+            let folder_name = mod_root.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let manifest = root_manifests[i].clone();
+            let manifest_name = manifest.as_ref().map(|m| m.name.clone()).unwrap_or_default();
+            let unique_id = manifest.map(|m| m.unique_id).unwrap_or_default();
+            let target_name = if is_option_folder(&folder_name) {
+                safe_folder_name(&[&manifest_name, &unique_id, &folder_name])
+            } else {
+                safe_folder_name(&[&folder_name, &manifest_name, &unique_id])
+            };
+            let mod_name = Some(manifest_name).filter(|name| !name.is_empty());
+            if let Some(parent) = &parents[i] {
+                info!("   {} is a content pack for {}", folder_name, parent);
+            }
+            let decision = InstallDecision {
+                strategy,
+                source_path: mod_root.clone(),
+                target_name,
+                manifests: manifests.clone(),
+                excluded: mod_roots
+                    .iter()
+                    .filter(|other| *other != mod_root && other.starts_with(mod_root))
+                    .cloned()
+                    .collect(),
+                parent_unique_id: parents[i].clone(),
+            };
+            match self.install_mod_root(extract_dir, decision, game_path, settings, nexus_meta, mod_name) {
+                Ok((result, undo)) => {
+                    results.push(result);
+                    undos.push(undo);
+                }
+                Err(e) => {
+                    warn!("   Rolling back the {} mods installed from this archive", undos.len());
+                    for undo in undos.into_iter().rev() {
+                        undo.run(game_path);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Delete the archive if the user asked for it; the extracted files go
+    /// when their `ExtractDir` is dropped
+    fn finish_archive(&self, archive_path: &Path, settings: &Settings) {
+        if settings.delete_after_install {
+            if let Err(e) = fs::remove_file(archive_path) {
+                warn!("Failed to delete archive: {}", e);
+            } else {
+                info!("Deleted archive: {}", archive_path.display());
+            }
+        }
+    }
+
+    /// Mods that are alternatives rather than a bundle: two folders with the
+    /// same UniqueID, or folders named like options
+    fn has_variants(&self, extract_dir: &Path, mod_roots: &[PathBuf]) -> bool {
+        if mod_roots.len() < 2 {
+            return false;
+        }
+
+        let mut unique_ids: Vec<String> = mod_roots
+            .iter()
+            .filter_map(|root| self.single_manifest(root))
+            .map(|m| m.unique_id.to_lowercase())
+            .collect();
+        let manifests = unique_ids.len();
+        unique_ids.sort();
+        unique_ids.dedup();
+
+        unique_ids.len() < manifests
+            || mod_roots
+                .iter()
+                .any(|root| relative_folder(extract_dir, root).split('/').any(is_option_folder))
+    }
+
+    /// The folders leading to each mod root, as a tree under `dir`
+    fn archive_folders(&self, extract_dir: &Path, dir: &Path, mod_roots: &[PathBuf]) -> Vec<ArchiveFolder> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut subdirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        subdirs.sort();
+
+        subdirs
+            .into_iter()
+            .filter(|path| mod_roots.iter().any(|root| root.starts_with(path)))
+            .map(|path| {
+                let is_mod = mod_roots.contains(&path);
+                let manifest = if is_mod { self.single_manifest(&path) } else { None };
+                ArchiveFolder {
+                    path: relative_folder(extract_dir, &path),
+                    name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    mod_name: manifest.as_ref().map(|m| m.name.clone()),
+                    unique_id: manifest.as_ref().map(|m| m.unique_id.clone()),
+                    version: manifest.map(|m| m.version),
+                    children: if is_mod {
+                        Vec::new()
+                    } else {
+                        self.archive_folders(extract_dir, &path, mod_roots)
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Install one mod's files as the decision's `target_name` under Mods,
+    /// backing up and replacing an older version of it
+    fn install_mod_root(
+        &self,
+        extract_dir: &Path,
+        decision: InstallDecision,
+        game_path: &Path,
+        settings: &Settings,
+        nexus_meta: Option<&NexusMeta>,
+        mod_name: Option<String>,
+    ) -> Result<(InstallResult, UndoInstall), InstallError> {
+        let InstallDecision {
+            strategy,
+            source_path,
+            target_name,
+            manifests,
+            excluded,
+            parent_unique_id,
+        } = decision;
+        let source_path = source_path.as_path();
 
         // Check for Frameworks
         let is_framework = if let Some(name) = &mod_name {
@@ -91,54 +436,87 @@ impl ModInstaller {
             settings.core_frameworks.contains(&target_name)
         };
 
+        // Rename the folder after the manifest if the user set a template
+        let target_name = self.apply_name_template(source_path, target_name, settings);
+
         let install_base = if is_framework {
             game_path.join("Mods").join("_Frameworks")
         } else {
             game_path.join("Mods")
         };
 
-        let install_path = install_base.join(&target_name);
-        println!("   Target install path: {}", install_path.display());
+        let incoming_id = self.single_manifest(source_path).map(|m| m.unique_id);
+        let (install_path, target_name) = match incoming_id.as_deref().and_then(|id| disabled_copy(game_path, id)) {
+            // Update a disabled mod where it is, so it stays disabled instead
+            // of getting a second, enabled copy
+            Some(path) => {
+                info!("   Mod is disabled, updating it in place");
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let name = enabled_name(&name).to_string();
+                (path, name)
+            }
+            None => {
+                // Don't replace a different mod that happens to use the same folder name
+                let target_name = self.resolve_name_collision(&install_base, target_name, incoming_id.as_deref());
+                (install_base.join(&target_name), target_name)
+            }
+        };
+        info!("   Target install path: {}", install_path.display());
+
+        if !settings.auto_install {
+            return Err(InstallError::InstallationFailed(
+                "Auto-install is disabled".to_string(),
+            ));
+        }
 
         // Handle existing mod
+        let mut kept_config = None;
+        let mut undo = UndoInstall::Remove(install_path.clone());
         if install_path.exists() {
-            println!("   Mod folder already exists, backing up and replacing");
-
-            if let Err(e) = self.backup_mod(&install_path, &target_name) {
-                eprintln!("   Failed to backup mod: {}", e);
+            // Replacing the folder would drop the other tool's marker and
+            // leave two managers fighting over it
+            if let Some(tool) = crate::foreign_managers::folder_manager(&install_path) {
+                return Err(InstallError::InstallationFailed(format!(
+                    "{} is managed by {}, install it there",
+                    target_name, tool
+                )));
             }
+            if settings.is_unmanaged(&install_path) {
+                return Err(InstallError::InstallationFailed(format!(
+                    "{} is in an unmanaged folder, update it by hand",
+                    target_name
+                )));
+            }
+            info!("   Mod folder already exists, backing up and replacing");
 
-            self.force_remove_dir_all(&install_path)?;
-        }
-
-        // Install mod
-        if settings.auto_install {
-            match self.install_mod_files_with_rollback(&source_path, &install_path) {
-                Ok(_) => {
-                    println!("   ✓ Installed to: {}", install_path.display());
-                }
+            undo = match self.backup_mod(&install_path, &target_name, settings) {
+                Ok(backup) => UndoInstall::Restore {
+                    path: install_path.clone(),
+                    backup,
+                },
                 Err(e) => {
-                    eprintln!("   ✗ Failed to install: {}", e);
-                    return Err(e);
+                    warn!("   Failed to backup mod: {}", e);
+                    UndoInstall::Keep(install_path.clone())
                 }
-            }
-        } else {
-            return Err(InstallError::InstallationFailed(
-                "Auto-install is disabled".to_string(),
-            ));
-        }
+            };
 
-        // Cleanup temp directory
-        if let Err(e) = self.force_remove_dir_all(&extract_dir) {
-            eprintln!("Failed to cleanup temp directory: {}", e);
+            kept_config = fs::read(install_path.join(CONFIG_FILE_NAME)).ok();
         }
 
-        // Delete archive if requested
-        if settings.delete_after_install {
-            if let Err(e) = fs::remove_file(archive_path) {
-                eprintln!("Failed to delete archive: {}", e);
-            } else {
-                println!("Deleted archive: {}", archive_path.display());
+        // Install mod; the old version stays in place until the new one is complete
+        let installed = self.place_files(
+            game_path,
+            source_path,
+            &install_path,
+            &excluded,
+            kept_config,
+            settings.install_mode,
+        );
+        match installed {
+            Ok(_) => info!("   ✓ Installed to: {}", install_path.display()),
+            Err(e) => {
+                warn!("   ✗ Failed to install: {}", e);
+                return Err(e);
             }
         }
 
@@ -154,9 +532,9 @@ impl ModInstaller {
         };
 
         // Write Nexus metadata if available
-        if let Some((mod_id, file_id)) = nexus_info {
-            if let Err(e) = self.write_nexus_meta(&install_path, mod_id, file_id) {
-                eprintln!("Failed to write Nexus metadata: {}", e);
+        if let Some(meta) = nexus_meta {
+            if let Err(e) = meta.write(&install_path) {
+                warn!("Failed to write Nexus metadata: {}", e);
             }
         }
 
@@ -165,47 +543,100 @@ impl ModInstaller {
             version,
             unique_id,
             install_path: install_path.clone(),
+            strategy,
+            manifests,
+            skipped_files: junk_files(extract_dir, source_path),
+            parent_unique_id,
         };
 
-        let _ = self.app_handle.emit("mod-installed", &result);
+        events::emit(self.events.as_ref(), AppEvent::ModInstalled(result.clone()));
 
-        Ok(result)
+        Ok((result, undo))
     }
 
-    /// Determine installation strategy based on extracted contents
-    /// Returns (source_path_to_copy_from, target_folder_name)
+    /// Determine installation strategy based on extracted contents: which
+    /// folder to copy from and what to name it under Mods
     fn determine_install_strategy(
         &self,
         extract_dir: &Path,
         archive_path: &Path,
         mod_name: Option<String>,
-    ) -> Result<(PathBuf, String), InstallError> {
+    ) -> Result<InstallDecision, InstallError> {
+        // A "__MACOSX" next to the mod's folder shouldn't make it loose files
         let entries: Vec<_> = fs::read_dir(extract_dir)?
             .filter_map(|e| e.ok())
+            .filter(|e| !is_junk(&e.file_name().to_string_lossy()))
             .collect();
+        let manifests = self.detected_manifests(extract_dir);
+
+        let archive_stem = archive_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
 
         // Case A: Single folder
         if entries.len() == 1 && entries[0].path().is_dir() {
             let folder_name = entries[0].file_name().to_string_lossy().to_string();
-            println!("   Strategy: Single folder detected ({})", folder_name);
-            Ok((entries[0].path(), folder_name))
+            let folder_name = safe_folder_name(&[&folder_name, &archive_stem]);
+            info!("   Strategy: Single folder detected ({})", folder_name);
+            Ok(InstallDecision {
+                strategy: InstallStrategy::SingleFolder,
+                source_path: entries[0].path(),
+                target_name: folder_name,
+                manifests,
+                excluded: Vec::new(),
+                parent_unique_id: None,
+            })
         } else {
             // Case B: Multi-folder / Loose files
             // Use mod_name if available, otherwise archive filename
-            let target_name = mod_name.unwrap_or_else(|| {
-                archive_path
-                    .file_stem()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string()
-            });
-            println!("   Strategy: Multi-item/Loose files detected. Using container: {}", target_name);
-            Ok((extract_dir.to_path_buf(), target_name))
+            let target_name = match &mod_name {
+                Some(name) => safe_folder_name(&[name, &archive_stem]),
+                None => safe_folder_name(&[&archive_stem]),
+            };
+            info!("   Strategy: Multi-item/Loose files detected. Using container: {}", target_name);
+            Ok(InstallDecision {
+                strategy: InstallStrategy::LooseFiles,
+                source_path: extract_dir.to_path_buf(),
+                target_name,
+                manifests,
+                excluded: Vec::new(),
+                parent_unique_id: None,
+            })
+        }
+    }
+
+    /// Keep `name` if the folder is free or holds the same mod (an update);
+    /// otherwise pick "name (2)", "name (3)", ...
+    fn resolve_name_collision(&self, install_base: &Path, name: String, unique_id: Option<&str>) -> String {
+        let Some(unique_id) = unique_id else {
+            return name;
+        };
+
+        let holds_other_mod = |candidate: &str| {
+            self.single_manifest(&install_base.join(candidate))
+                .is_some_and(|existing| !existing.unique_id.eq_ignore_ascii_case(unique_id))
+        };
+
+        if !holds_other_mod(&name) {
+            return name;
+        }
+
+        let mut suffix = 2;
+        loop {
+            let candidate = format!("{} ({})", name, suffix);
+            if !holds_other_mod(&candidate) {
+                info!("   Folder '{}' belongs to another mod, using '{}'", name, candidate);
+                return candidate;
+            }
+            suffix += 1;
         }
     }
 
-    /// Extract a ZIP archive to the temp directory
-    async fn extract_archive(&self, archive_path: &Path) -> Result<PathBuf, InstallError> {
+    /// Extract an archive (format detected from its contents) to the temp
+    /// directory, which is removed again when the result is dropped
+    async fn extract_archive(&self, archive_path: &Path) -> Result<ExtractDir, InstallError> {
         // Generate unique extract directory
         let extract_dir = self.temp_dir.join(
             archive_path
@@ -217,69 +648,69 @@ impl ModInstaller {
 
         // Remove old extraction if exists
         if extract_dir.exists() {
-            self.force_remove_dir_all(&extract_dir)?;
+            force_remove_dir_all(&extract_dir)?;
         }
 
         fs::create_dir_all(&extract_dir)?;
+        let extract_dir = ExtractDir(extract_dir);
 
-        // Open ZIP file
-        let file = File::open(archive_path)?;
-        let mut archive = ZipArchive::new(file)
-            .map_err(|e| InstallError::ExtractionFailed(format!("Invalid ZIP: {}", e)))?;
+        crate::archive::extract_file(archive_path, &extract_dir)
+            .map_err(InstallError::ExtractionFailed)?;
 
-        // Extract all files
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|e| InstallError::ExtractionFailed(e.to_string()))?;
+        info!("Extracted archive to: {}", extract_dir.display());
+        Ok(extract_dir)
+    }
 
-            let outpath = match file.enclosed_name() {
-                Some(path) => extract_dir.join(path),
-                None => continue,
-            };
+    /// Manifests of every mod in an archive, read without installing it
+    pub async fn archive_manifests(&self, archive_path: &Path) -> Result<Vec<ModManifest>, InstallError> {
+        let _active = ActiveInstall::start();
+        fs::create_dir_all(&self.temp_dir)?;
+        let extract_dir = self.extract_archive(archive_path).await?;
 
-            if file.name().ends_with('/') {
-                // Directory
-                fs::create_dir_all(&outpath)?;
-            } else {
-                // File
-                if let Some(parent) = outpath.parent() {
-                    fs::create_dir_all(parent)?;
-                }
+        self.find_all_manifests(&extract_dir).map(|found| {
+            found
+                .iter()
+                .filter_map(|(manifest_path, _)| self.parse_manifest(manifest_path).ok())
+                .collect()
+        })
+    }
 
-                let mut outfile = File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
-            }
 
-            // Set permissions on Unix
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = file.unix_mode() {
-                    // Ensure we always have write permissions for the user
-                    // This prevents "Permission denied" errors when extracting files into read-only directories
-                    // or when trying to overwrite read-only files (though we clean up first)
-                    let safe_mode = if file.name().ends_with('/') {
-                        // For directories, ensure rwx for user (0o700)
-                        mode | 0o700
-                    } else {
-                        // For files, ensure rw for user (0o600)
-                        mode | 0o600
-                    };
 
-                    fs::set_permissions(&outpath, fs::Permissions::from_mode(safe_mode))?;
-                }
-            }
+    /// Name the install folder with `settings.install_name_template`, keeping
+    /// `default_name` when no template is set or the archive isn't a single mod
+    fn apply_name_template(&self, source_path: &Path, default_name: String, settings: &Settings) -> String {
+        if settings.install_name_template.trim().is_empty() {
+            return default_name;
         }
 
-        println!("Extracted archive to: {}", extract_dir.display());
-        Ok(extract_dir)
+        match self.single_manifest(source_path) {
+            Some(manifest) => render_install_name(&settings.install_name_template, &manifest)
+                .inspect(|name| info!("   Folder name from template: {}", name))
+                .unwrap_or(default_name),
+            None => default_name,
+        }
     }
 
+    /// The manifest of a mod folder, or of a folder holding exactly one mod
+    fn single_manifest(&self, dir: &Path) -> Option<ModManifest> {
+        if !dir.is_dir() {
+            return None;
+        }
+        // A mod's own manifest wins over those of packs bundled inside it
+        let own = dir.join("manifest.json");
+        if own.is_file() {
+            return self.parse_manifest(&own).ok();
+        }
 
+        let manifests = self.find_all_manifests(dir).ok()?;
+        let [(manifest_path, _)] = manifests.as_slice() else {
+            return None;
+        };
+        self.parse_manifest(manifest_path).ok()
+    }
 
-    /// Find all manifest.json files in the extracted directory (legacy)
-    #[allow(dead_code)]
+    /// Find all manifest.json files in the extracted directory
     fn find_all_manifests(&self, extract_dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>, InstallError> {
         let mut manifests = Vec::new();
 
@@ -300,6 +731,60 @@ impl ModInstaller {
         Ok(manifests)
     }
 
+    /// Every manifest in the archive, for reporting how it was installed
+    fn detected_manifests(&self, extract_dir: &Path) -> Vec<DetectedManifest> {
+        let mut manifests: Vec<DetectedManifest> = self
+            .find_all_manifests(extract_dir)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(manifest_path, _)| {
+                let manifest = self.parse_manifest(&manifest_path).ok();
+                DetectedManifest {
+                    path: relative_folder(extract_dir, &manifest_path),
+                    unique_id: manifest.as_ref().map(|m| m.unique_id.clone()),
+                    name: manifest.map(|m| m.name),
+                }
+            })
+            .collect();
+        manifests.sort_by(|a, b| a.path.cmp(&b.path));
+        manifests
+    }
+
+    /// Folders holding a mod's manifest, leaving out manifests nested inside
+    /// another mod (e.g. its unit tests or an example pack). A content pack
+    /// for the mod it's nested in is kept: SMAPI doesn't look inside a mod's
+    /// folder, so the pack only loads when installed next to it.
+    fn mod_roots(&self, extract_dir: &Path) -> Result<Vec<PathBuf>, InstallError> {
+        let roots: Vec<PathBuf> = self
+            .find_all_manifests(extract_dir)?
+            .into_iter()
+            .map(|(_, mod_root)| mod_root)
+            .collect();
+
+        Ok(roots
+            .iter()
+            .filter(|root| {
+                let ancestors: Vec<&PathBuf> =
+                    roots.iter().filter(|other| other != root && root.starts_with(other)).collect();
+                if ancestors.is_empty() {
+                    return true;
+                }
+                let Some(parent_id) = self
+                    .single_manifest(root)
+                    .and_then(|m| m.content_pack_for)
+                    .map(|c| c.unique_id)
+                else {
+                    return false;
+                };
+                ancestors.iter().any(|ancestor| {
+                    self.single_manifest(ancestor)
+                        .is_some_and(|m| m.unique_id.eq_ignore_ascii_case(&parent_id))
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
     /// Find manifest.json in the extracted directory (legacy - finds first one)
     #[allow(dead_code)]
     fn find_manifest(&self, extract_dir: &Path) -> Result<(PathBuf, PathBuf), InstallError> {
@@ -385,21 +870,9 @@ impl ModInstaller {
         let file = File::open(manifest_path)?;
         let mut reader = BufReader::new(file);
 
-        // Read and handle BOM
         let mut content = String::new();
         reader.read_to_string(&mut content)?;
-        let mut content = content.trim_start_matches('\u{feff}').to_string();
-
-        // Strip JSON comments (/* */ and //)
-        content = Self::strip_json_comments(&content);
-
-        // Fix common JSON issues: remove trailing commas before closing braces/brackets
-        content = content
-            .replace(",\n}", "\n}")
-            .replace(",\r\n}", "\r\n}")
-            .replace(", }", " }")
-            .replace(",]", "]")
-            .replace(", ]", " ]");
+        let content = lenient_json(&content);
 
         // Try to parse as generic JSON first to check structure
         match serde_json::from_str::<serde_json::Value>(&content) {
@@ -447,27 +920,98 @@ impl ModInstaller {
         }
     }
 
+    /// Put the files of `source` at `install_path` the way `mode` lays mods
+    /// out, replacing whatever is there once the copy is complete
+    pub fn place_files(
+        &self,
+        game_path: &Path,
+        source: &Path,
+        install_path: &Path,
+        excluded: &[PathBuf],
+        kept_config: Option<Vec<u8>>,
+        mode: InstallMode,
+    ) -> Result<(), InstallError> {
+        match mode {
+            InstallMode::Direct => {
+                self.install_mod_files_atomically(source, install_path, excluded, kept_config)?;
+                // A staged copy left from staged mode is out of date now
+                crate::staging::remove_staged(game_path, install_path)?;
+                Ok(())
+            }
+            InstallMode::Staged => self.install_staged(game_path, source, install_path, excluded, kept_config),
+        }
+    }
+
     /// Install mod files with rollback support
-    fn install_mod_files_with_rollback(&self, source: &Path, destination: &Path) -> Result<(), InstallError> {
-        println!(
+    /// Copy the mod into a staging folder next to `destination`, then swap
+    /// it in. A failed copy leaves the installed version untouched.
+    /// `kept_config` replaces any config.json shipped with the mod.
+    fn install_mod_files_atomically(
+        &self,
+        source: &Path,
+        destination: &Path,
+        excluded: &[PathBuf],
+        kept_config: Option<Vec<u8>>,
+    ) -> Result<(), InstallError> {
+        info!(
             "Installing mod files from {} to {}",
             source.display(),
             destination.display()
         );
 
-        // Create destination directory
-        if let Err(e) = fs::create_dir_all(destination) {
-            return Err(InstallError::IoError(e));
-        }
+        let staging = sibling_path(destination, "staging");
+        fs::create_dir_all(&staging)?;
 
-        // Copy all files recursively
-        if let Err(e) = self.copy_dir_recursive(source, destination) {
-            eprintln!("Installation failed, rolling back...");
-            // Rollback: Delete the destination directory
-            let _ = self.force_remove_dir_all(destination);
+        let staged = self.copy_dir_recursive(source, &staging, excluded).and_then(|_| {
+            if let Some(config) = kept_config {
+                // The user's settings win over any config.json shipped in the archive
+                fs::write(staging.join(CONFIG_FILE_NAME), config)?;
+                info!("   ✓ Kept existing {}", CONFIG_FILE_NAME);
+            }
+            Ok(())
+        });
+        if let Err(e) = staged {
+            warn!("Installation failed, rolling back...");
+            let _ = force_remove_dir_all(&staging);
             return Err(e);
         }
 
+        if let Err(e) = swap_in_folder(&staging, destination) {
+            let _ = force_remove_dir_all(&staging);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Staged install: the files go to the staging folder the way a direct
+    /// install puts them in Mods, then a linked copy replaces the folder in Mods
+    fn install_staged(
+        &self,
+        game_path: &Path,
+        source: &Path,
+        install_path: &Path,
+        excluded: &[PathBuf],
+        kept_config: Option<Vec<u8>>,
+    ) -> Result<(), InstallError> {
+        let staged = crate::staging::staged_path(game_path, install_path)
+            .ok_or_else(|| InstallError::InstallationFailed("Install path is outside the Mods folder".to_string()))?;
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.install_mod_files_atomically(source, &staged, excluded, kept_config)?;
+
+        let deployment = sibling_path(install_path, "staging");
+        let deployed = crate::staging::link_tree(&staged, &deployment).and_then(|copied| {
+            if copied > 0 {
+                info!("   ⚠ Copied {} files that couldn't be hardlinked", copied);
+            }
+            swap_in_folder(&deployment, install_path)
+        });
+        if let Err(e) = deployed {
+            let _ = force_remove_dir_all(&deployment);
+            return Err(e.into());
+        }
+        info!("   ✓ Staged in: {}", staged.display());
         Ok(())
     }
 
@@ -482,16 +1026,20 @@ impl ModInstaller {
         path
     }
 
-    /// Recursively copy directory contents
-    fn copy_dir_recursive(&self, source: &Path, destination: &Path) -> Result<(), InstallError> {
+    /// Recursively copy directory contents, leaving out junk files and the
+    /// `excluded` folders
+    fn copy_dir_recursive(&self, source: &Path, destination: &Path, excluded: &[PathBuf]) -> Result<(), InstallError> {
         for entry in fs::read_dir(source)? {
             let entry = entry?;
+            if is_junk(&entry.file_name().to_string_lossy()) || excluded.contains(&entry.path()) {
+                continue;
+            }
             let source_path = entry.path();
             let dest_path = destination.join(entry.file_name());
 
             if source_path.is_dir() {
                 fs::create_dir_all(&dest_path)?;
-                self.copy_dir_recursive(&source_path, &dest_path)?;
+                self.copy_dir_recursive(&source_path, &dest_path, excluded)?;
             } else {
                 fs::copy(&source_path, &dest_path)?;
             }
@@ -500,173 +1048,651 @@ impl ModInstaller {
         Ok(())
     }
 
-    /// Backup a mod to the backups directory
-    fn backup_mod(&self, mod_path: &Path, unique_id: &str) -> Result<PathBuf, std::io::Error> {
-        let app_data_dir = self.app_handle.path().app_data_dir().unwrap();
-        let backups_dir = app_data_dir.join("backups").join(unique_id);
-        
-        // Create timestamped backup folder
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let backup_path = backups_dir.join(timestamp.to_string());
+    /// Backup a mod to the backups directory as an incremental snapshot
+    pub(crate) fn backup_mod(&self, mod_path: &Path, unique_id: &str, settings: &Settings) -> Result<PathBuf, std::io::Error> {
+        let backups_dir = self.backups_dir.join(unique_id);
 
-        fs::create_dir_all(&backup_path)?;
+        let backup_path = crate::backup::create_snapshot(mod_path, &backups_dir, &settings.backup_format)?;
 
-        // Copy mod to backup
-        // We can't use copy_dir_recursive here because it returns InstallError
-        // So we'll implement a simple recursive copy for backup
-        self.copy_dir_all(mod_path, &backup_path)?;
-
-        println!("Backed up mod to: {}", backup_path.display());
+        info!("Backed up mod to: {}", backup_path.display());
         Ok(backup_path)
     }
 
-    fn copy_dir_all(&self, src: &Path, dst: &Path) -> std::io::Result<()> {
-        fs::create_dir_all(dst)?;
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let ty = entry.file_type()?;
-            if ty.is_dir() {
-                self.copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
-            } else {
-                fs::copy(entry.path(), dst.join(entry.file_name()))?;
+    /// Snapshots of the mod `unique_id`, oldest first
+    pub fn list_backups(&self, game_path: &Path, unique_id: &str) -> Result<Vec<ModBackup>, InstallError> {
+        let backup_id = sanitize_folder_name(unique_id)
+            .filter(|id| id == unique_id)
+            .ok_or_else(|| InstallError::InstallationFailed(format!("Invalid mod id: {}", unique_id)))?;
+
+        let mut dirs = vec![self.backups_dir.join(&backup_id)];
+        // Installs file backups under the mod's folder name, updates under its UniqueID
+        if let Some(installed) = installed_mod(game_path, unique_id) {
+            let folder = Path::new(&installed.path)
+                .file_name()
+                .map(|name| enabled_name(&name.to_string_lossy()).to_string());
+            if let Some(folder) = folder.filter(|folder| *folder != backup_id) {
+                dirs.push(self.backups_dir.join(folder));
             }
         }
-        Ok(())
+
+        let mut backups: Vec<ModBackup> = dirs
+            .iter()
+            .flat_map(|dir| crate::backup::list_snapshots(dir))
+            .map(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let timestamp = name.trim_end_matches(".zip").to_string();
+                let created_at = timestamp
+                    .split('-')
+                    .next()
+                    .and_then(|secs| secs.parse::<i64>().ok())
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0));
+                ModBackup {
+                    compressed: path.is_file(),
+                    timestamp,
+                    created_at,
+                    path,
+                }
+            })
+            .collect();
+        backups.sort_by(|a, b| (a.created_at, &a.timestamp).cmp(&(b.created_at, &b.timestamp)));
+        Ok(backups)
     }
 
-    /// Write Nexus metadata to a hidden file in the mod directory
-    fn write_nexus_meta(&self, mod_path: &Path, mod_id: u32, file_id: u32) -> std::io::Result<()> {
-        let meta_path = mod_path.join(".nexus_meta");
-        let meta_content = serde_json::json!({
-            "mod_id": mod_id,
-            "file_id": file_id
-        });
-        
-        let file = File::create(meta_path)?;
-        serde_json::to_writer_pretty(file, &meta_content)?;
-        Ok(())
+    /// Put the snapshot `timestamp` of `unique_id` back into the Mods folder,
+    /// taking a snapshot of the installed version first. Returns the
+    /// restored folder.
+    pub fn restore_backup(
+        &self,
+        game_path: &Path,
+        unique_id: &str,
+        timestamp: &str,
+        settings: &Settings,
+    ) -> Result<PathBuf, InstallError> {
+        let backup = self
+            .list_backups(game_path, unique_id)?
+            .into_iter()
+            .find(|b| b.timestamp == timestamp)
+            .ok_or_else(|| InstallError::InstallationFailed(format!("No backup {} of {}", timestamp, unique_id)))?;
+
+        let target = match installed_mod(game_path, unique_id) {
+            Some(installed) => {
+                let path = PathBuf::from(&installed.path);
+                if let Some(tool) = crate::foreign_managers::folder_manager(&path) {
+                    return Err(InstallError::InstallationFailed(format!(
+                        "{} is managed by {}, restore it there",
+                        installed.name, tool
+                    )));
+                }
+                self.backup_mod(&path, unique_id, settings)?;
+                path
+            }
+            None => game_path.join("Mods").join(safe_folder_name(&[unique_id])),
+        };
+
+        // Unpack beside the installed folder, so it's only replaced once the
+        // snapshot is out in full
+        let unpacked = sibling_path(&target, "restoring");
+        if let Err(e) = crate::backup::restore_snapshot(&backup.path, &unpacked) {
+            let _ = force_remove_dir_all(&unpacked);
+            return Err(e.into());
+        }
+        swap_in_folder(&unpacked, &target)?;
+        match settings.install_mode {
+            InstallMode::Direct => crate::staging::remove_staged(game_path, &target)?,
+            // Stage the restored files so a redeploy doesn't bring the replaced version back
+            InstallMode::Staged => self.install_staged(game_path, &target, &target, &[], None)?,
+        }
+        info!("   ✓ Restored {} from backup {}", unique_id, timestamp);
+        Ok(target)
+    }
+}
+
+/// Force remove a directory by ensuring write permissions first
+fn force_remove_dir_all(path: &Path) -> std::io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    // Try normal remove first
+    if fs::remove_dir_all(path).is_ok() {
+        return Ok(());
+    }
+
+    info!("   ⚠ Normal remove failed, attempting to force permissions on: {}", path.display());
+
+    // Make everything writable
+    for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+         #[cfg(unix)]
+         {
+             use std::os::unix::fs::PermissionsExt;
+             let p = entry.path();
+             if let Ok(metadata) = p.metadata() {
+                 let mut perms = metadata.permissions();
+                 let mode = perms.mode() | 0o700; // u+rwx
+                 perms.set_mode(mode);
+                 let _ = fs::set_permissions(p, perms);
+             }
+         }
     }
 
-    /// Force remove a directory by ensuring write permissions first
-    fn force_remove_dir_all(&self, path: &Path) -> std::io::Result<()> {
-        if !path.exists() {
-            return Ok(());
+    fs::remove_dir_all(path)
+}
+
+/// Enable or disable a mod folder by renaming it the way SMAPI skips: a
+/// ".disabled" suffix or a "." prefix, as `style` says. Enabling undoes
+/// either. Folders another tool deployed are left to it. Returns the
+/// folder's new path.
+pub fn set_mod_enabled(path: &Path, enabled: bool, style: DisabledFolderStyle) -> Result<PathBuf, String> {
+    if !path.exists() {
+        return Err("Mod path does not exist".to_string());
+    }
+    if let Some(tool) = crate::foreign_managers::folder_manager(path) {
+        return Err(format!("Managed by {}, change it there", tool));
+    }
+
+    let parent = path.parent().ok_or("Invalid mod path")?;
+    let file_name = path.file_name().ok_or("Invalid mod path")?.to_string_lossy().to_string();
+    if enabled {
+        // Renaming the mod can't help while a folder around it is hidden
+        if let Some(hidden) = hidden_group(path) {
+            return Err(format!("{} is hidden, which disables every mod in it", hidden.display()));
         }
+    }
+    if is_disabled_name(&file_name) != enabled {
+        return Ok(path.to_path_buf()); // Already in the requested state
+    }
+
+    let new_name = if enabled {
+        enabled_name(&file_name).to_string()
+    } else {
+        disabled_name(&file_name, style)
+    };
+    let new_path = parent.join(&new_name);
+    fs::rename(path, &new_path).map_err(|e| e.to_string())?;
+
+    Ok(new_path)
+}
+
+/// A folder name as it is when the mod is enabled, without a ".disabled"
+/// suffix or "." prefix
+pub fn enabled_name(name: &str) -> &str {
+    name.trim_end_matches(".disabled").trim_start_matches('.')
+}
+
+/// Whether SMAPI skips a folder by its name
+pub fn is_disabled_name(name: &str) -> bool {
+    name.ends_with(".disabled") || name.starts_with('.')
+}
+
+/// The name of a folder disabled in `style`, whatever way it's disabled now
+pub fn disabled_name(name: &str, style: DisabledFolderStyle) -> String {
+    match style {
+        DisabledFolderStyle::Suffix => format!("{}.disabled", enabled_name(name)),
+        DisabledFolderStyle::DotPrefix => format!(".{}", enabled_name(name)),
+    }
+}
+
+/// A hidden group folder between `path` and the Mods folder
+pub(crate) fn hidden_group(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| dir.file_name().is_some_and(|name| name != "Mods"))
+        .find(|dir| dir.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+}
+
+/// Whether a hidden folder without a manifest is a group of mods, i.e.
+/// every folder in it is a mod. Other hidden folders, like `.git` or copies
+/// kept by hand in a `.backup`, are neither scanned nor renamed.
+pub(crate) fn is_mod_group(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    let folders: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && !p.file_name().is_some_and(|name| is_install_leftover(&name.to_string_lossy())))
+        .collect();
+    !folders.is_empty() && folders.iter().all(|folder| folder.join("manifest.json").is_file())
+}
+
+/// Folders this app hides while installing, e.g. `.Mod.staging-<uuid>`
+pub(crate) fn is_install_leftover(name: &str) -> bool {
+    name.starts_with('.')
+        && name
+            .rsplit_once('-')
+            .is_some_and(|(_, id)| id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Fill a folder name template ({ModName}, {UniqueID}, {Author}, {Version})
+/// from a manifest. Returns None if nothing usable is left after sanitizing.
+pub fn render_install_name(template: &str, manifest: &ModManifest) -> Option<String> {
+    let name = template
+        .replace("{ModName}", &manifest.name)
+        .replace("{UniqueID}", &manifest.unique_id)
+        .replace("{Author}", &manifest.author)
+        .replace("{Version}", &manifest.version);
+
+    sanitize_folder_name(&name)
+}
+
+/// Make a string safe to use as a folder name on every OS
+pub fn sanitize_folder_name(name: &str) -> Option<String> {
+    const RESERVED: &[&str] = &[
+        "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+        "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+    ];
+
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+
+    // Collapse whitespace; Windows also rejects trailing dots and spaces
+    let mut cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    cleaned = cleaned.trim_end_matches(['.', ' ']).to_string();
+    cleaned = cleaned.chars().take(100).collect::<String>().trim_end().to_string();
+
+    if cleaned.is_empty() || cleaned.starts_with('.') {
+        return None;
+    }
+
+    let stem = cleaned.split('.').next().unwrap_or_default().to_uppercase();
+    if RESERVED.contains(&stem.as_str()) {
+        cleaned.push('_');
+    }
+
+    Some(cleaned)
+}
+
+/// Hidden folder next to `path` for work in progress; SMAPI skips folders
+/// starting with a dot
+fn sibling_path(path: &Path, purpose: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}-{}", name, purpose, uuid::Uuid::new_v4().simple()))
+}
 
-        // Try normal remove first
-        if fs::remove_dir_all(path).is_ok() {
-            return Ok(());
+/// Move `staging` to `destination`. An existing `destination` is moved
+/// aside first and only deleted once the new folder is in place; if the
+/// swap fails it's put back.
+fn swap_in_folder(staging: &Path, destination: &Path) -> std::io::Result<()> {
+    if !destination.exists() {
+        return fs::rename(staging, destination);
+    }
+
+    let old = sibling_path(destination, "old");
+    fs::rename(destination, &old)?;
+    if let Err(e) = fs::rename(staging, destination) {
+        if let Err(restore) = fs::rename(&old, destination) {
+            warn!("Failed to put {} back: {}", destination.display(), restore);
         }
+        return Err(e);
+    }
 
-        println!("   ⚠ Normal remove failed, attempting to force permissions on: {}", path.display());
+    if let Err(e) = fs::remove_dir_all(&old) {
+        warn!("Failed to remove the previous version at {}: {}", old.display(), e);
+    }
+    Ok(())
+}
 
-        // Make everything writable
-        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
-             #[cfg(unix)]
-             {
-                 use std::os::unix::fs::PermissionsExt;
-                 let p = entry.path();
-                 if let Ok(metadata) = p.metadata() {
-                     let mut perms = metadata.permissions();
-                     let mode = perms.mode() | 0o700; // u+rwx
-                     perms.set_mode(mode);
-                     let _ = fs::set_permissions(p, perms);
-                 }
-             }
+/// The folder of `unique_id` if it's installed but only as a disabled copy
+fn disabled_copy(game_path: &Path, unique_id: &str) -> Option<PathBuf> {
+    let copies: Vec<crate::models::Mod> = scan_mods(game_path)
+        .into_iter()
+        .filter(|m| m.unique_id.eq_ignore_ascii_case(unique_id))
+        .collect();
+    if copies.iter().any(|m| m.is_enabled) {
+        return None;
+    }
+    copies.into_iter().next().map(|m| PathBuf::from(m.path))
+}
+
+fn installed_mod(game_path: &Path, unique_id: &str) -> Option<crate::models::Mod> {
+    scan_mods(game_path)
+        .into_iter()
+        .find(|m| m.unique_id.eq_ignore_ascii_case(unique_id))
+}
+
+/// `path` relative to `base` with '/' separators, as the option chooser names folders
+fn relative_folder(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// UniqueID of the mod among `manifests` that `manifest` is a content pack for
+fn archive_parent(manifest: &ModManifest, manifests: &[Option<ModManifest>]) -> Option<String> {
+    let parent_id = &manifest.content_pack_for.as_ref()?.unique_id;
+    manifests
+        .iter()
+        .flatten()
+        .find(|m| m.unique_id.eq_ignore_ascii_case(parent_id))
+        .map(|m| m.unique_id.clone())
+}
+
+fn is_junk(name: &str) -> bool {
+    JUNK_NAMES.iter().any(|junk| junk.eq_ignore_ascii_case(name)) || name.starts_with("._")
+}
+
+/// Junk that won't be copied from `source`, relative to `extract_dir`. Junk
+/// at the archive root counts too when only a folder inside it is installed.
+fn junk_files(extract_dir: &Path, source: &Path) -> Vec<String> {
+    let mut skipped = Vec::new();
+    let mut walker = WalkDir::new(extract_dir).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !is_junk(&entry.file_name().to_string_lossy()) {
+            continue;
         }
+        // A junk folder is left out whole; list it once
+        if entry.file_type().is_dir() {
+            walker.skip_current_dir();
+        }
+        if entry.depth() == 1 || entry.path().starts_with(source) {
+            skipped.push(relative_folder(extract_dir, entry.path()));
+        }
+    }
+    skipped.sort();
+    skipped
+}
+
+/// Words that mark a folder as one of several to choose from
+const OPTION_WORDS: &[&str] = &[
+    "option",
+    "options",
+    "optional",
+    "variant",
+    "variants",
+    "alt",
+    "alternate",
+    "alternative",
+    "alternatives",
+];
+
+/// "[Option A] Blue", "Optional", "Variant 2", "Alt3", "Alternate textures"...
+/// Only whole words count, so "Adoption Center" isn't an option.
+fn is_option_folder(name: &str) -> bool {
+    name.split(|c: char| !c.is_alphanumeric()).any(|word| {
+        let word = word.to_lowercase();
+        OPTION_WORDS.contains(&word.trim_end_matches(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// First candidate that survives sanitizing, or a generic name
+fn safe_folder_name(candidates: &[&str]) -> String {
+    candidates
+        .iter()
+        .find_map(|name| sanitize_folder_name(name))
+        .unwrap_or_else(|| "Unnamed Mod".to_string())
+}
+
+/// Size and modification time of a file, to tell whether it changed since it
+/// was last parsed
+type FileStamp = (SystemTime, u64);
 
-        fs::remove_dir_all(path)
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Parsed manifest, Nexus metadata and config keys of a mod folder
+#[derive(Clone)]
+struct ScannedFolder {
+    manifest_stamp: FileStamp,
+    meta_stamp: Option<FileStamp>,
+    config_stamp: Option<FileStamp>,
+    manifest: ModManifest,
+    nexus_meta: Option<NexusMeta>,
+    config_keys: Vec<ConfigKey>,
+}
+
+/// Archives being extracted or installed right now
+static ACTIVE_INSTALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts as an active install until dropped
+struct ActiveInstall;
+
+impl ActiveInstall {
+    fn start() -> Self {
+        ACTIVE_INSTALLS.fetch_add(1, Ordering::SeqCst);
+        ActiveInstall
     }
 }
 
-/// Scan a directory for mods
-pub fn scan_mods(game_path: &Path) -> Vec<crate::models::Mod> {
-    let mods_dir = game_path.join("Mods");
-    let mut mods = Vec::new();
-
-    if !mods_dir.exists() {
-        return mods;
-    }
-
-    // Helper function to scan a directory recursively
-    fn scan_dir(dir: &Path, mods: &mut Vec<crate::models::Mod>) {
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.filter_map(|e| e.ok()) {
-                let path = entry.path();
-                if path.is_dir() {
-                    // Check if this folder is a mod (has manifest.json)
-                    let manifest_path = path.join("manifest.json");
-                    if manifest_path.exists() {
-                        if let Ok(manifest_content) = fs::read_to_string(&manifest_path) {
-                            // Strip BOM and JSON comments
-                            let content = manifest_content.trim_start_matches('\u{feff}');
-                            let content = ModInstaller::strip_json_comments(content);
-                            
-                            if let Ok(manifest) = serde_json::from_str::<ModManifest>(&content) {
-                                // Check if enabled based on folder name
-                                // Convention: folder name ending in ".disabled" means disabled
-                                let folder_name = path.file_name().unwrap().to_string_lossy();
-                                let is_enabled = !folder_name.ends_with(".disabled");
-
-                                // Generate a new ID for the mod, as it's not stored in the manifest
-                                let id = uuid::Uuid::new_v4().to_string();
-
-                                mods.push(crate::models::Mod {
-                                    id,
-                                    name: manifest.name,
-                                    author: manifest.author,
-                                    version: manifest.version,
-                                    unique_id: manifest.unique_id,
-                                    description: manifest.description,
-                                    dependencies: manifest.dependencies,
-                                    content_pack_for: manifest.content_pack_for,
-                                    path: path.to_string_lossy().to_string(),
-                                    is_enabled,
-                                    nexus_mod_id: {
-                                        // Read from .nexus_meta if available
-                                        let meta_path = path.join(".nexus_meta");
-                                        if meta_path.exists() {
-                                            fs::read_to_string(&meta_path)
-                                                .ok()
-                                                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
-                                                .and_then(|json| json.get("mod_id").and_then(|v| v.as_u64()).map(|v| v as u32))
-                                        } else {
-                                            None
-                                        }
-                                    },
-                                    nexus_file_id: {
-                                        // Read from .nexus_meta if available
-                                        let meta_path = path.join(".nexus_meta");
-                                        if meta_path.exists() {
-                                            fs::read_to_string(&meta_path)
-                                                .ok()
-                                                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
-                                                .and_then(|json| json.get("file_id").and_then(|v| v.as_u64()).map(|v| v as u32))
-                                        } else {
-                                            None
-                                        }
-                                    },
-                                });
-                            }
-                        }
-                    } else {
-                        // Recurse into subdirectories (e.g. for _Frameworks or organized folders)
-                        // But don't recurse if it's a disabled mod folder (which might contain the manifest inside)
-                        // Actually, we should recurse to find nested mods, but standard structure is Mod/manifest.json
-                        scan_dir(&path, mods);
-                    }
-                }
+impl Drop for ActiveInstall {
+    fn drop(&mut self) {
+        ACTIVE_INSTALLS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// An extracted archive in the temp directory, removed when dropped so no
+/// early return leaves one behind
+struct ExtractDir(PathBuf);
+
+impl std::ops::Deref for ExtractDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ExtractDir {
+    fn drop(&mut self) {
+        if let Err(e) = force_remove_dir_all(&self.0) {
+            warn!("Failed to cleanup temp directory: {}", e);
+        }
+    }
+}
+
+/// How to take back one mod of an archive whose install failed part way
+enum UndoInstall {
+    /// Nothing was there before
+    Remove(PathBuf),
+    /// Put the snapshot of the replaced version back
+    Restore { path: PathBuf, backup: PathBuf },
+    /// The replaced version couldn't be snapshotted, so the new one stays
+    Keep(PathBuf),
+}
+
+impl UndoInstall {
+    fn run(self, game_path: &Path) {
+        let (path, backup) = match self {
+            UndoInstall::Remove(path) => (path, None),
+            UndoInstall::Restore { path, backup } => (path, Some(backup)),
+            UndoInstall::Keep(path) => {
+                warn!("   No snapshot of the previous {}, keeping the new version", path.display());
+                return;
+            }
+        };
+        if let Err(e) = crate::staging::remove_staged(game_path, &path) {
+            warn!("   Failed to remove the staged copy of {}: {}", path.display(), e);
+        }
+        if let Err(e) = force_remove_dir_all(&path) {
+            warn!("   Failed to remove {}: {}", path.display(), e);
+            return;
+        }
+        if let Some(backup) = backup {
+            if let Err(e) = crate::backup::restore_snapshot(&backup, &path) {
+                warn!("   Failed to restore {} from {}: {}", path.display(), backup.display(), e);
             }
         }
     }
+}
 
-    scan_dir(&mods_dir, &mut mods);
-    
-    // Also scan _Frameworks if it exists (it's already covered by recursion above, but just to be sure/explicit if logic changes)
-    // The recursion above handles it.
+/// Whether an archive is being installed, so maintenance can wait
+pub fn installs_in_progress() -> bool {
+    ACTIVE_INSTALLS.load(Ordering::SeqCst) > 0
+}
+
+/// Folders parsed by earlier scans, reused while neither file changed
+static SCAN_CACHE: LazyLock<Mutex<HashMap<PathBuf, ScannedFolder>>> = LazyLock::new(Default::default);
+
+/// Mod folders (with a manifest.json) under `dir`. Folders without a
+/// manifest are searched for nested mods, e.g. `_Frameworks` or groups.
+fn find_mod_folders(dir: &Path, folders: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()) {
+        // Hidden folders are disabled mods or groups, except our staging folders
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if is_install_leftover(&name) {
+            continue;
+        }
+        if path.join("manifest.json").is_file() {
+            folders.push(path);
+        } else if !name.starts_with('.') || is_mod_group(&path) {
+            find_mod_folders(&path, folders);
+        }
+    }
+}
+
+/// JSON as SMAPI accepts it, made parseable by serde: drops the BOM,
+/// comments and trailing commas before closing braces/brackets, whatever
+/// whitespace is between them. Strings are left as they are.
+pub(crate) fn lenient_json(content: &str) -> String {
+    let chars: Vec<char> = ModInstaller::strip_json_comments(content.trim_start_matches('\u{feff}'))
+        .chars()
+        .collect();
+    let mut result = String::with_capacity(chars.len());
+    let mut in_string = false;
+    let mut escape_next = false;
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if in_string {
+            match ch {
+                _ if escape_next => escape_next = false,
+                '\\' => escape_next = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if ch == '"' {
+            in_string = true;
+        } else if ch == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+/// The manifest.json of a mod folder, read the way SMAPI does (BOM and
+/// comments allowed)
+pub fn read_manifest(mod_dir: &Path) -> Option<ModManifest> {
+    let manifest_content = fs::read_to_string(mod_dir.join("manifest.json")).ok()?;
+    serde_json::from_str::<ModManifest>(&lenient_json(&manifest_content)).ok()
+}
+
+fn scan_folder(path: &Path) -> Option<ScannedFolder> {
+    let manifest_path = path.join("manifest.json");
+    let manifest_stamp = file_stamp(&manifest_path)?;
+    let meta_stamp = file_stamp(&NexusMeta::path_for(path));
+    let config_stamp = file_stamp(&path.join(CONFIG_FILE_NAME));
+
+    let cached = SCAN_CACHE.lock().ok().and_then(|cache| cache.get(path).cloned());
+    if let Some(cached) = cached.filter(|c| {
+        c.manifest_stamp == manifest_stamp && c.meta_stamp == meta_stamp && c.config_stamp == config_stamp
+    }) {
+        return Some(cached);
+    }
+
+    let manifest = read_manifest(path)?;
+    let config = config_stamp.and_then(|_| {
+        crate::mod_config::read_config(path)
+            .inspect_err(|e| warn!("{}: {}", path.display(), e))
+            .ok()
+            .flatten()
+    });
+
+    let scanned = ScannedFolder {
+        manifest_stamp,
+        meta_stamp,
+        config_stamp,
+        manifest,
+        nexus_meta: meta_stamp.and_then(|_| NexusMeta::read(path)),
+        config_keys: config.as_ref().map(crate::mod_config::config_keys).unwrap_or_default(),
+    };
+    if let Ok(mut cache) = SCAN_CACHE.lock() {
+        cache.insert(path.to_path_buf(), scanned.clone());
+    }
+    Some(scanned)
+}
+
+fn to_mod(path: &Path, scanned: ScannedFolder) -> crate::models::Mod {
+    // SMAPI skips folders ending in ".disabled" or starting with ".", and
+    // everything in a hidden group
+    let is_enabled = !path.file_name().is_some_and(|name| is_disabled_name(&name.to_string_lossy()))
+        && hidden_group(path).is_none();
+    let ScannedFolder { manifest, nexus_meta, config_keys, .. } = scanned;
+    let has_gmcm = crate::mod_config::declares_gmcm(&manifest);
+
+    crate::models::Mod {
+        // Placeholder; the mod registry hands out stable ids
+        id: uuid::Uuid::new_v4().to_string(),
+        name: manifest.name,
+        author: manifest.author,
+        version: manifest.version,
+        unique_id: manifest.unique_id,
+        description: manifest.description,
+        dependencies: manifest.dependencies,
+        content_pack_for: manifest.content_pack_for,
+        path: path.to_string_lossy().to_string(),
+        is_enabled,
+        nexus_mod_id: nexus_meta.as_ref().map(|m| m.mod_id),
+        nexus_file_id: nexus_meta.as_ref().map(|m| m.file_id),
+        has_gmcm,
+        config_keys,
+        nexus_meta,
+        // Filled in by the registry, compat list and duplicate check
+        ..Default::default()
+    }
+}
+
+/// Every mod in the game's Mods folder, copies with the same UniqueID
+/// flagged. Manifests are parsed on several threads and cached by file size
+/// and modification time, so scanning a large library again is mostly
+/// `stat` calls.
+pub fn scan_mods(game_path: &Path) -> Vec<crate::models::Mod> {
+    let mods_dir = game_path.join("Mods");
+    let mut folders = Vec::new();
+    find_mod_folders(&mods_dir, &mut folders);
+
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let chunk_size = folders.len().div_ceil(threads).max(1);
+    let mut mods: Vec<crate::models::Mod> = std::thread::scope(|scope| {
+        let workers: Vec<_> = folders
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|path| Some(to_mod(path, scan_folder(path)?)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap_or_default()).collect()
+    });
+
+    crate::duplicates::flag(&mut mods);
+
+    // Forget folders that are gone
+    if let Ok(mut cache) = SCAN_CACHE.lock() {
+        cache.retain(|path, _| !path.starts_with(&mods_dir) || folders.contains(path));
+    }
 
     mods
 }
@@ -676,6 +1702,28 @@ mod tests {
     use super::*;
     use semver::Version;
 
+    #[test]
+    fn test_swap_in_folder_keeps_old_version_on_failure() {
+        let temp_dir = std::env::temp_dir().join(format!("tc_swap_{}", uuid::Uuid::new_v4()));
+        let destination = temp_dir.join("Mod");
+        fs::create_dir_all(&destination).unwrap();
+        fs::write(destination.join("manifest.json"), "old").unwrap();
+
+        // Nothing staged: the swap fails and the installed version survives
+        assert!(swap_in_folder(&temp_dir.join(".Mod.staging-missing"), &destination).is_err());
+        assert_eq!(fs::read_to_string(destination.join("manifest.json")).unwrap(), "old");
+
+        let staging = sibling_path(&destination, "staging");
+        fs::create_dir_all(&staging).unwrap();
+        fs::write(staging.join("manifest.json"), "new").unwrap();
+        swap_in_folder(&staging, &destination).unwrap();
+        assert_eq!(fs::read_to_string(destination.join("manifest.json")).unwrap(), "new");
+        // Only the installed folder is left
+        assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_manifest_parsing() {
         let manifest_json = r#"{
@@ -695,6 +1743,26 @@ mod tests {
         assert_eq!(manifest.unique_id, "TestAuthor.TestMod");
     }
 
+    #[test]
+    fn test_is_option_folder() {
+        for name in ["[Option A] Blue", "Optional", "Variant 2", "Alt3", "Alternate textures", "options-b"] {
+            assert!(is_option_folder(name), "{}", name);
+        }
+        for name in ["Adoption Center", "Salt Water", "Invariants", "Optionalish"] {
+            assert!(!is_option_folder(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_lenient_json() {
+        let content = "\u{feff}{\n    \"Name\": \"Odd, } name\", // a comment\n    \"Nested\": {\n        \"List\": [1, 2,\n        ],\n        \"Key\": \"value\",\n    },\n}";
+        let value: serde_json::Value = serde_json::from_str(&lenient_json(content)).unwrap();
+
+        assert_eq!(value["Name"], "Odd, } name");
+        assert_eq!(value["Nested"]["List"], serde_json::json!([1, 2]));
+        assert_eq!(value["Nested"]["Key"], "value");
+    }
+
     #[test]
     fn test_version_comparison() {
         let v1 = Version::parse("1.0.0").unwrap();
@@ -706,6 +1774,25 @@ mod tests {
         assert!(v1 < v2);
     }
 
+    #[test]
+    fn test_render_install_name() {
+        let manifest: ModManifest = serde_json::from_str(
+            r#"{"Name": "Lookup Anything: Plus?", "Author": "Pathoschild", "Version": "1.37.0", "UniqueID": "Pathoschild.LookupAnything"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            render_install_name("{ModName} [{UniqueID}]", &manifest).as_deref(),
+            Some("Lookup Anything Plus [Pathoschild.LookupAnything]")
+        );
+        assert_eq!(sanitize_folder_name("  ...  "), None);
+        assert_eq!(sanitize_folder_name("CON").as_deref(), Some("CON_"));
+        assert_eq!(sanitize_folder_name("My Mod. ").as_deref(), Some("My Mod"));
+        assert_eq!(sanitize_folder_name(&"a".repeat(300)).map(|n| n.len()), Some(100));
+        assert_eq!(safe_folder_name(&["???", "Archive: v1.0"]), "Archive v1.0");
+        assert_eq!(safe_folder_name(&["..."]), "Unnamed Mod");
+    }
+
     #[test]
     fn test_nexus_metadata() {
         // Setup temp directory
@@ -727,13 +1814,8 @@ mod tests {
         }"#;
         fs::write(mod_dir.join("manifest.json"), manifest_json).unwrap();
 
-        // Write metadata manually (simulating write_nexus_meta)
-        let meta_path = mod_dir.join(".nexus_meta");
-        let meta_content = serde_json::json!({
-            "mod_id": 12345,
-            "file_id": 67890
-        });
-        fs::write(meta_path, serde_json::to_string(&meta_content).unwrap()).unwrap();
+        // Write metadata the way installs do
+        NexusMeta::new(12345, 67890).write(&mod_dir).unwrap();
 
         // Scan mods
         let mods = scan_mods(&temp_dir);
@@ -747,4 +1829,63 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    #[test]
+    fn test_scan_cache_sees_changes() {
+        let temp_dir = std::env::temp_dir().join("sdv_mgr_test_scan_cache");
+        let mod_dir = temp_dir.join("Mods").join("_Frameworks").join("CacheMod");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        fs::create_dir_all(&mod_dir).unwrap();
+
+        let manifest = |version: &str| {
+            format!(r#"{{"Name": "Cache Mod", "Author": "Tester", "Version": "{}", "UniqueID": "Tester.CacheMod"}}"#, version)
+        };
+        fs::write(mod_dir.join("manifest.json"), manifest("1.0.0")).unwrap();
+        assert_eq!(scan_mods(&temp_dir)[0].version, "1.0.0");
+        assert!(SCAN_CACHE.lock().unwrap().contains_key(&mod_dir));
+
+        // A different size is caught even within the timestamp resolution
+        fs::write(mod_dir.join("manifest.json"), manifest("1.10.0")).unwrap();
+        NexusMeta::new(1, 2).write(&mod_dir).unwrap();
+        let mods = scan_mods(&temp_dir);
+        assert_eq!(mods[0].version, "1.10.0");
+        assert_eq!(mods[0].nexus_mod_id, Some(1));
+
+        fs::remove_dir_all(&mod_dir).unwrap();
+        assert!(scan_mods(&temp_dir).is_empty());
+        assert!(!SCAN_CACHE.lock().unwrap().contains_key(&mod_dir));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_both_disable_conventions() {
+        let temp_dir = std::env::temp_dir().join(format!("tc_disable_{}", uuid::Uuid::new_v4()));
+        let mods_dir = temp_dir.join("Mods");
+        for (dir, id) in [("A", "Tester.A"), (".B", "Tester.B"), (".Group/C", "Tester.C")] {
+            fs::create_dir_all(mods_dir.join(dir)).unwrap();
+            let manifest = format!(r#"{{"Name": "{0}", "Author": "Tester", "Version": "1.0.0", "UniqueID": "{0}"}}"#, id);
+            fs::write(mods_dir.join(dir).join("manifest.json"), manifest).unwrap();
+        }
+        let enabled = |id: &str| scan_mods(&temp_dir).into_iter().find(|m| m.unique_id == id).unwrap().is_enabled;
+        assert!(enabled("Tester.A"));
+        assert!(!enabled("Tester.B"));
+        assert!(!enabled("Tester.C"));
+
+        let b = set_mod_enabled(&mods_dir.join(".B"), true, DisabledFolderStyle::Suffix).unwrap();
+        assert_eq!(b, mods_dir.join("B"));
+        let a = set_mod_enabled(&mods_dir.join("A"), false, DisabledFolderStyle::DotPrefix).unwrap();
+        assert_eq!(a, mods_dir.join(".A"));
+        assert!(!enabled("Tester.A"));
+        assert!(enabled("Tester.B"));
+        // Only making the group visible enables C
+        assert!(set_mod_enabled(&mods_dir.join(".Group").join("C"), true, DisabledFolderStyle::Suffix).is_err());
+        // Vortex's folders are renamed in Vortex
+        fs::write(mods_dir.join("B").join("__folder_managed_by_vortex"), "").unwrap();
+        assert!(set_mod_enabled(&mods_dir.join("B"), false, DisabledFolderStyle::Suffix).is_err());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
@@ -0,0 +1,162 @@
+use crate::download_manager::DownloadManager;
+use crate::nexus_api::NexusClient;
+use crate::nxm_protocol::NxmUrl;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+/// A mod from the list that was queued for download
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedImport {
+    pub mod_id: u32,
+    pub download_id: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedImport {
+    pub mod_id: u32,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub queued: Vec<QueuedImport>,
+    pub already_installed: Vec<u32>,
+    pub failed: Vec<FailedImport>,
+}
+
+/// Nexus mod id of one line of a plain list: "1915" or a mod page URL
+fn parse_line(line: &str) -> Option<u32> {
+    let line = line.trim();
+    if let Ok(mod_id) = line.parse() {
+        return Some(mod_id);
+    }
+
+    let (_, rest) = line.split_once("/mods/")?;
+    rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+fn json_mod_id(entry: &serde_json::Value) -> Option<u32> {
+    match entry {
+        serde_json::Value::Number(n) => n.as_u64()?.try_into().ok(),
+        serde_json::Value::Object(fields) => ["nexusModId", "nexusId", "modId"]
+            .iter()
+            .find_map(|key| fields.get(*key)?.as_u64()?.try_into().ok()),
+        _ => None,
+    }
+}
+
+/// Nexus mod ids in a mod list, in order and without duplicates. Accepts a
+/// multiplayer modlist, a JSON array (like the diagnostics `mods.json`) or
+/// plain text with one mod id or mod page URL per line (`#` starts a comment).
+fn parse_mod_list(content: &str) -> Result<Vec<u32>, String> {
+    let content = content.trim_start_matches('\u{feff}');
+
+    let ids: Vec<u32> = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(json) => {
+            let entries = json
+                .pointer("/modlist/mods")
+                .or_else(|| json.get("mods"))
+                .unwrap_or(&json);
+            entries
+                .as_array()
+                .ok_or("Expected a list of mods")?
+                .iter()
+                .filter_map(json_mod_id)
+                .collect()
+        }
+        Err(_) => content
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_line(line).ok_or_else(|| format!("Not a Nexus mod id or URL: {}", line.trim())))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut seen = HashSet::new();
+    Ok(ids.into_iter().filter(|id| seen.insert(*id)).collect())
+}
+
+/// Queue the latest main file of every mod in the list that isn't installed.
+/// Completed downloads go through the usual auto-install.
+///
+/// Files are requested without a key from the website, which Nexus only
+/// allows for premium accounts.
+pub async fn import_mod_list(app_handle: &AppHandle, path: &Path) -> Result<ImportReport, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read mod list: {}", e))?;
+    let mod_ids = parse_mod_list(&content)?;
+
+    let settings = Settings::load(app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+    let installed: HashSet<u32> = crate::mod_installer::scan_mods(Path::new(&settings.game_path))
+        .iter()
+        .filter_map(|m| m.nexus_mod_id)
+        .collect();
+
+    let (already_installed, missing): (Vec<u32>, Vec<u32>) = mod_ids.into_iter().partition(|id| installed.contains(id));
+    let mut report = ImportReport {
+        already_installed,
+        ..Default::default()
+    };
+    if missing.is_empty() {
+        return Ok(report);
+    }
+
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.validate_user().await?.is_premium {
+        return Err("Downloading a whole mod list needs a Nexus Mods Premium account".to_string());
+    }
+
+    let manager = app_handle.state::<DownloadManager>();
+    for mod_id in missing {
+        let queued = async {
+            let files = nexus.mod_files("stardewvalley", mod_id).await?;
+            let file = crate::update_all::latest_main_file(&files).ok_or("No main file on the mod page")?;
+            manager
+                .add_to_queue(NxmUrl::direct("stardewvalley", mod_id, file.file_id))
+                .await
+        }
+        .await;
+
+        match queued {
+            Ok(download_id) => report.queued.push(QueuedImport { mod_id, download_id }),
+            Err(error) => report.failed.push(FailedImport { mod_id, error }),
+        }
+    }
+
+    info!(
+        "📋 Imported mod list: {} queued, {} already installed, {} failed",
+        report.queued.len(),
+        report.already_installed.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mod_list() {
+        let plain = "# my farm\n1915\nhttps://www.nexusmods.com/stardewvalley/mods/541?tab=files\n\n1915 # again\n";
+        assert_eq!(parse_mod_list(plain).unwrap(), [1915, 541]);
+
+        let mods_json = r#"[{"name": "Lookup Anything", "nexusId": 541}, {"name": "Local mod", "nexusId": null}]"#;
+        assert_eq!(parse_mod_list(mods_json).unwrap(), [541]);
+
+        let modlist = r#"{"modlist": {"mods": [{"uniqueId": "A", "nexusModId": 1915}]}, "publicKey": "", "signature": ""}"#;
+        assert_eq!(parse_mod_list(modlist).unwrap(), [1915]);
+
+        assert!(parse_mod_list("Content Patcher").is_err());
+    }
+}
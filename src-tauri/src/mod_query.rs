@@ -0,0 +1,64 @@
+use crate::models::Mod;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModSortKey {
+    Name,
+    Author,
+    LastUpdated,
+    Size,
+    InstallDate,
+    UpdateAvailable,
+}
+
+/// Sort mods in place, after `mod_registry::apply` has filled in install dates and
+/// updates. Name is always used as the tie-breaker so the order is stable
+/// between refreshes.
+pub fn sort_mods(mods: &mut [Mod], key: ModSortKey, descending: bool) {
+    match key {
+        ModSortKey::Name => mods.sort_by_cached_key(|m| m.name.to_lowercase()),
+        ModSortKey::Author => {
+            mods.sort_by_cached_key(|m| (m.author.to_lowercase(), m.name.to_lowercase()))
+        }
+        ModSortKey::LastUpdated => {
+            mods.sort_by_cached_key(|m| (last_updated(&m.path), m.name.to_lowercase()))
+        }
+        ModSortKey::Size => mods.sort_by_cached_key(|m| (dir_size(&m.path), m.name.to_lowercase())),
+        ModSortKey::InstallDate => mods.sort_by_cached_key(|m| (m.installed_at, m.name.to_lowercase())),
+        // Mods with an update sort first, unless descending
+        ModSortKey::UpdateAvailable => {
+            mods.sort_by_cached_key(|m| (m.available_update.is_none(), m.name.to_lowercase()))
+        }
+    }
+
+    if descending {
+        mods.reverse();
+    }
+}
+
+/// Whether the mod's Nexus category is `category`, ignoring case
+pub fn in_category(m: &Mod, category: &str) -> bool {
+    m.category.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(category))
+}
+
+/// When the mod's files were last replaced (manifest.json mtime)
+fn last_updated(mod_path: &str) -> Option<SystemTime> {
+    fs::metadata(Path::new(mod_path).join("manifest.json"))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+pub fn dir_size(mod_path: &str) -> u64 {
+    WalkDir::new(mod_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
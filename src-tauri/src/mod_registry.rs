@@ -0,0 +1,552 @@
+use crate::mod_installer::InstallResult;
+use crate::models::{FrameworkFloor, Mod};
+use crate::nexus_meta::NexusMeta;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// How a mod entered the library
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModSource {
+    /// Downloaded from Nexus through an nxm link, an update or a dependency
+    Nexus,
+    /// Installed from an archive the user picked
+    Archive,
+    /// Installed by multiplayer sync from a host's modlist
+    Modpack,
+    /// Deployed by Vortex
+    Vortex,
+    /// Installed from ModDrop, by its client or by hand
+    ModDrop,
+    /// Copied into Mods by hand
+    Manual,
+}
+
+impl ModSource {
+    /// Whether updating the mod can't clash with the user or another tool.
+    /// Vortex redeploys its own files, and folders copied by hand may hold
+    /// edits we know nothing about.
+    pub fn auto_updatable(self) -> bool {
+        !matches!(self, ModSource::Vortex | ModSource::Manual)
+    }
+}
+
+fn detected_source(m: &Mod) -> ModSource {
+    if crate::foreign_managers::folder_manager(Path::new(&m.path)) == Some(crate::foreign_managers::VORTEX) {
+        ModSource::Vortex
+    } else if m.nexus_mod_id.is_some() {
+        ModSource::Nexus
+    } else {
+        ModSource::Manual
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnableChange {
+    pub enabled: bool,
+    pub at: DateTime<Utc>,
+}
+
+/// Result of the last update check that found a newer version
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModUpdate {
+    /// Version installed when the check ran; the record is dropped once
+    /// the mod changes version
+    pub installed_version: String,
+    pub latest_version: Option<String>,
+    pub latest_file_id: Option<u32>,
+    /// First lines of the newest changelog entry
+    #[serde(default)]
+    pub changelog: Vec<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// What we remember about an installed mod across scans
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    /// Stable id handed to the frontend as `Mod::id`
+    pub id: String,
+    pub unique_id: String,
+    /// Folder path without the ".disabled" suffix or "." prefix, so
+    /// toggling a mod doesn't make it a different mod
+    pub path: String,
+    pub installed_at: DateTime<Utc>,
+    pub source: ModSource,
+    /// Archive the mod was installed from, if we installed it
+    #[serde(default)]
+    pub source_file: Option<String>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub enable_history: Vec<EnableChange>,
+    /// Set while the mod isn't in the Mods folder; cleared if it comes back
+    #[serde(default)]
+    pub removed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub update: Option<ModUpdate>,
+    #[serde(default)]
+    pub framework_floors: Vec<FrameworkFloor>,
+    /// Nexus category, e.g. "Gameplay Mechanics" or "Portraits"
+    #[serde(default)]
+    pub category: Option<String>,
+    /// The mod this content pack was installed with from the same archive
+    #[serde(default)]
+    pub parent_unique_id: Option<String>,
+    /// Nexus mod the library migration matched a mod without `.nexus_meta` to
+    #[serde(default)]
+    pub nexus_mod_id: Option<u32>,
+    /// Hash of the mod's files when it was migrated
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// ModDrop mod the migration found in the mod's update keys
+    #[serde(default)]
+    pub moddrop_id: Option<u32>,
+    /// The mod folder's `.nexus_meta` as of the last scan
+    #[serde(default)]
+    pub nexus_meta: Option<NexusMeta>,
+}
+
+impl RegistryEntry {
+    fn is_enabled(&self) -> Option<bool> {
+        self.enable_history.last().map(|change| change.enabled)
+    }
+}
+
+fn registry_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("mod_registry.json"))
+}
+
+fn load_entries(path: &Path) -> Vec<RegistryEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_entries(path: &Path, entries: &[RegistryEntry]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(entries).map_err(|e| format!("Failed to serialize mod registry: {}", e))?;
+    // A crash mid-write must not leave a truncated registry behind
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("Failed to write mod registry: {}", e)
+        })
+}
+
+/// Held while the registry file is read, changed and written back, so
+/// concurrent changes don't overwrite each other. Managed by the app.
+#[derive(Default)]
+pub struct RegistryLock(Mutex<()>);
+
+/// Load the registry, let `change` modify it and save it if it changed,
+/// all under the `RegistryLock`
+fn update_entries<R>(
+    app_handle: &AppHandle,
+    change: impl FnOnce(&mut Vec<RegistryEntry>) -> Result<R, String>,
+) -> Result<R, String> {
+    let path = registry_path(app_handle).ok_or("Failed to get app data directory")?;
+    let lock = app_handle.try_state::<RegistryLock>().ok_or("Mod registry not initialized")?;
+    let _guard = lock.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut entries = load_entries(&path);
+    let before = entries.clone();
+    let result = change(&mut entries)?;
+    if entries != before {
+        save_entries(&path, &entries)?;
+    }
+    Ok(result)
+}
+
+fn registry_key(path: &str) -> String {
+    let path = Path::new(path);
+    match path.file_name() {
+        Some(name) => path
+            .with_file_name(crate::mod_installer::enabled_name(&name.to_string_lossy()))
+            .to_string_lossy()
+            .to_string(),
+        None => path.to_string_lossy().to_string(),
+    }
+}
+
+/// Match scanned mods to registry entries, giving each its stable id and
+/// recorded details. Entries are found by UniqueID and folder; a mod whose
+/// folder was renamed or moved keeps its entry as long as the old folder is
+/// gone. New mods get a new entry, missing ones are marked removed.
+fn reconcile(entries: &mut Vec<RegistryEntry>, mods: &mut [Mod], now: DateTime<Utc>) {
+    let mut seen = vec![false; entries.len()];
+
+    // Exact matches first, so renamed folders can't take another mod's entry
+    let mut unmatched = Vec::new();
+    for (index, m) in mods.iter().enumerate() {
+        let key = registry_key(&m.path);
+        match entries
+            .iter()
+            .position(|e| e.unique_id.eq_ignore_ascii_case(&m.unique_id) && e.path == key)
+        {
+            Some(position) if !seen[position] => seen[position] = true,
+            _ => unmatched.push(index),
+        }
+    }
+
+    for index in unmatched {
+        let m = &mods[index];
+        let moved = entries.iter().enumerate().position(|(position, e)| {
+            !seen[position] && e.unique_id.eq_ignore_ascii_case(&m.unique_id) && !Path::new(&e.path).exists()
+        });
+        match moved {
+            Some(position) => {
+                seen[position] = true;
+                entries[position].path = registry_key(&m.path).to_string();
+            }
+            None => {
+                seen.push(true);
+                entries.push(RegistryEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    unique_id: m.unique_id.clone(),
+                    path: registry_key(&m.path).to_string(),
+                    installed_at: now,
+                    source: detected_source(m),
+                    source_file: None,
+                    notes: String::new(),
+                    favorite: false,
+                    enable_history: Vec::new(),
+                    removed_at: None,
+                    update: None,
+                    framework_floors: Vec::new(),
+                    category: None,
+                    parent_unique_id: None,
+                    nexus_mod_id: None,
+                    content_hash: None,
+                    moddrop_id: None,
+                    nexus_meta: m.nexus_meta.clone(),
+                });
+            }
+        }
+    }
+
+    for (entry, seen) in entries.iter_mut().zip(&seen) {
+        if !seen && entry.removed_at.is_none() {
+            entry.removed_at = Some(now);
+        }
+    }
+
+    for m in mods.iter_mut() {
+        let key = registry_key(&m.path);
+        let Some(entry) = entries
+            .iter_mut()
+            .find(|e| e.unique_id.eq_ignore_ascii_case(&m.unique_id) && e.path == key)
+        else {
+            continue;
+        };
+
+        entry.removed_at = None;
+        if entry.is_enabled() != Some(m.is_enabled) {
+            entry.enable_history.push(EnableChange {
+                enabled: m.is_enabled,
+                at: now,
+            });
+        }
+        // Nexus metadata can show up after the first scan (e.g. a later update)
+        if entry.source == ModSource::Manual && m.nexus_meta.is_some() {
+            entry.source = ModSource::Nexus;
+        }
+        entry.nexus_meta = m.nexus_meta.clone();
+        if entry.update.as_ref().is_some_and(|u| u.installed_version != m.version) {
+            entry.update = None;
+        }
+
+        m.id = entry.id.clone();
+        m.installed_at = Some(entry.installed_at);
+        m.source = Some(entry.source);
+        m.source_file = entry.source_file.clone();
+        m.notes = Some(entry.notes.clone()).filter(|notes| !notes.is_empty());
+        m.is_favorite = entry.favorite;
+        m.available_update = entry.update.clone();
+        m.framework_floors = entry.framework_floors.clone();
+        m.category = entry.category.clone();
+        if m.nexus_mod_id.is_none() {
+            m.nexus_mod_id = entry.nexus_mod_id;
+        }
+    }
+}
+
+/// Give scanned mods their stable ids and recorded details, updating the
+/// registry in app data, and flag those in unmanaged folders. Failures only
+/// cost the ids' stability, so they're logged.
+pub fn apply(app_handle: &AppHandle, mods: &mut [Mod]) {
+    let settings = crate::settings::Settings::load(app_handle).unwrap_or_default();
+    for m in mods.iter_mut() {
+        m.is_unmanaged = settings.is_unmanaged(Path::new(&m.path));
+    }
+
+    let result = update_entries(app_handle, |entries| {
+        reconcile(entries, mods, Utc::now());
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!("{}", e);
+    }
+}
+
+/// Record how a mod we just installed got here. Updates keep the entry and
+/// its install date but take the new source.
+pub fn record_install(app_handle: &AppHandle, result: &InstallResult, source: ModSource, archive: &Path) {
+    let install_path = result.install_path.to_string_lossy();
+    let key = registry_key(&install_path);
+    let source_file = archive.file_name().map(|name| name.to_string_lossy().to_string());
+
+    let recorded = update_entries(app_handle, |entries| {
+        match entries
+            .iter_mut()
+            .find(|e| e.unique_id.eq_ignore_ascii_case(&result.unique_id) && e.path == key)
+        {
+            Some(entry) => {
+                entry.source = source;
+                entry.source_file = source_file;
+                entry.removed_at = None;
+                entry.parent_unique_id = result.parent_unique_id.clone();
+            }
+            None => entries.push(RegistryEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                unique_id: result.unique_id.clone(),
+                path: key.to_string(),
+                installed_at: Utc::now(),
+                source,
+                source_file,
+                notes: String::new(),
+                favorite: false,
+                enable_history: Vec::new(),
+                removed_at: None,
+                update: None,
+                framework_floors: Vec::new(),
+                category: None,
+                parent_unique_id: result.parent_unique_id.clone(),
+                nexus_mod_id: None,
+                content_hash: None,
+                moddrop_id: None,
+                nexus_meta: None,
+            }),
+        }
+        Ok(())
+    });
+    if let Err(e) = recorded {
+        warn!("{}", e);
+    }
+}
+
+/// Store Nexus categories, given as (mod path, category) pairs
+pub fn record_categories(app_handle: &AppHandle, categories: &[(String, String)]) -> Result<(), String> {
+    update_entries(app_handle, |entries| {
+        for (mod_path, category) in categories {
+            let key = registry_key(mod_path);
+            if let Some(entry) = entries.iter_mut().find(|e| e.path == key) {
+                entry.category = Some(category.clone());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// What the library migration found for one mod folder
+#[derive(Debug, Clone)]
+pub struct MigratedMod {
+    pub path: String,
+    pub unique_id: String,
+    pub content_hash: Option<String>,
+    pub nexus_mod_id: Option<u32>,
+    pub moddrop_id: Option<u32>,
+}
+
+/// Store what the library migration found. Entries must exist already, so
+/// run `apply` on the scanned mods first. The ids are guesses from update
+/// keys and smapi.io, so they're kept apart and the source stays as it was:
+/// a folder copied by hand isn't updated from a page we only think it's from.
+pub fn record_migration(app_handle: &AppHandle, mods: &[MigratedMod]) -> Result<(), String> {
+    update_entries(app_handle, |entries| {
+        for migrated in mods {
+            let key = registry_key(&migrated.path);
+            let Some(entry) = entries
+                .iter_mut()
+                .find(|e| e.unique_id.eq_ignore_ascii_case(&migrated.unique_id) && e.path == key)
+            else {
+                continue;
+            };
+
+            entry.content_hash = migrated.content_hash.clone();
+            if migrated.nexus_mod_id.is_some() {
+                entry.nexus_mod_id = migrated.nexus_mod_id;
+            }
+            if migrated.moddrop_id.is_some() {
+                entry.moddrop_id = migrated.moddrop_id;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Registry entry behind a `Mod::id`
+pub fn entry(app_handle: &AppHandle, id: &str) -> Result<RegistryEntry, String> {
+    let path = registry_path(app_handle).ok_or("Failed to get app data directory")?;
+    load_entries(&path)
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Mod {} not found", id))
+}
+
+/// Remember the outcome of an update check for the mod in `mod_path`;
+/// `None` clears a previously found update. Mods the registry hasn't seen
+/// yet are skipped, the next scan picks up a fresh check.
+pub fn record_update(app_handle: &AppHandle, mod_path: &str, update: Option<ModUpdate>) -> Result<(), String> {
+    let key = registry_key(mod_path);
+    update_entries(app_handle, |entries| {
+        if let Some(entry) = entries.iter_mut().find(|e| e.path == key) {
+            entry.update = update;
+        }
+        Ok(())
+    })
+}
+
+/// Record the minimum version of `framework_id` the mod needs, or forget it
+/// with `None`
+pub fn set_framework_floor(
+    app_handle: &AppHandle,
+    id: &str,
+    framework_id: &str,
+    minimum_version: Option<String>,
+) -> Result<(), String> {
+    update_entries(app_handle, |entries| {
+        let entry = find_by_id(entries, id)?;
+        entry.framework_floors.retain(|f| !f.unique_id.eq_ignore_ascii_case(framework_id));
+        if let Some(minimum_version) = minimum_version {
+            entry.framework_floors.push(FrameworkFloor {
+                unique_id: framework_id.to_string(),
+                minimum_version,
+            });
+        }
+        Ok(())
+    })
+}
+
+fn find_by_id<'a>(entries: &'a mut [RegistryEntry], id: &str) -> Result<&'a mut RegistryEntry, String> {
+    entries
+        .iter_mut()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Mod {} not found", id))
+}
+
+pub fn set_notes(app_handle: &AppHandle, id: &str, notes: String) -> Result<(), String> {
+    update_entries(app_handle, |entries| {
+        find_by_id(entries, id)?.notes = notes;
+        Ok(())
+    })
+}
+
+pub fn set_favorite(app_handle: &AppHandle, id: &str, favorite: bool) -> Result<(), String> {
+    update_entries(app_handle, |entries| {
+        find_by_id(entries, id)?.favorite = favorite;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_mod;
+    use chrono::TimeZone;
+
+    fn scanned(unique_id: &str, path: &str, is_enabled: bool) -> Mod {
+        Mod {
+            id: uuid::Uuid::new_v4().to_string(),
+            path: path.to_string(),
+            is_enabled,
+            ..test_mod(unique_id)
+        }
+    }
+
+    #[test]
+    fn test_reconcile_keeps_ids_stable() {
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2024, 2, 1, 12, 0, 0).unwrap();
+        let mut entries = Vec::new();
+
+        let mut mods = vec![
+            scanned("Tester.A", "/no/such/Mods/A", true),
+            scanned("Tester.B", "/no/such/Mods/B", true),
+        ];
+        reconcile(&mut entries, &mut mods, first);
+        let (id_a, id_b) = (mods[0].id.clone(), mods[1].id.clone());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].source, ModSource::Manual);
+        assert!(!ModSource::Manual.auto_updatable() && ModSource::Modpack.auto_updatable());
+
+        // A got disabled, B's folder was renamed
+        let mut mods = vec![
+            scanned("Tester.A", "/no/such/Mods/A.disabled", false),
+            scanned("tester.b", "/no/such/Mods/B renamed", true),
+        ];
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(mods[0].id, id_a);
+        assert_eq!(mods[1].id, id_b);
+        assert_eq!(mods[0].installed_at, Some(first));
+        assert_eq!(
+            entries[0].enable_history,
+            vec![
+                EnableChange { enabled: true, at: first },
+                EnableChange { enabled: false, at: later },
+            ]
+        );
+
+        // Removed and reinstalled in the same place: same entry
+        let mut mods = vec![scanned("Tester.B", "/no/such/Mods/B renamed", true)];
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(entries[0].removed_at, Some(later));
+        let mut mods = vec![scanned("Tester.A", "/no/such/Mods/A", true)];
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(mods[0].id, id_a);
+        assert_eq!(entries[0].removed_at, None);
+
+        // A found update lasts until the mod is updated
+        entries[0].update = Some(ModUpdate {
+            installed_version: "1.0.0".to_string(),
+            latest_version: Some("1.1.0".to_string()),
+            latest_file_id: Some(9001),
+            changelog: vec!["Fixed a crash".to_string()],
+            checked_at: later,
+        });
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(mods[0].available_update.as_ref().unwrap().latest_file_id, Some(9001));
+        mods[0].version = "1.1.0".to_string();
+        reconcile(&mut entries, &mut mods, later);
+        assert!(mods[0].available_update.is_none());
+        assert!(entries[0].update.is_none());
+
+        // Notes and favorites live in the registry, not the mod folder
+        entries[0].notes = "Needed for the museum quest".to_string();
+        entries[0].favorite = true;
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(mods[0].notes.as_deref(), Some("Needed for the museum quest"));
+        assert!(mods[0].is_favorite);
+
+        // .nexus_meta is indexed, and makes a hand-copied mod a Nexus one
+        mods[0].nexus_meta = Some(NexusMeta::new(541, 9001));
+        reconcile(&mut entries, &mut mods, later);
+        assert_eq!(entries[0].nexus_meta, Some(NexusMeta::new(541, 9001)));
+        assert_eq!(entries[0].source, ModSource::Nexus);
+    }
+}
@@ -1,3 +1,7 @@
+use crate::mod_registry::{ModSource, ModUpdate};
+use crate::nexus_meta::NexusMeta;
+use crate::smapi_compat::ModCompatibility;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +20,9 @@ pub struct ModManifest {
     pub dependencies: Option<Vec<ModDependency>>,
     #[serde(rename = "ContentPackFor")]
     pub content_pack_for: Option<ContentPackInfo>,
+    /// Where SMAPI checks for updates, e.g. "Nexus:541"
+    #[serde(rename = "UpdateKeys", default)]
+    pub update_keys: Vec<String>,
 }
 
 fn default_author() -> String {
@@ -26,6 +33,8 @@ fn default_author() -> String {
 pub struct ContentPackInfo {
     #[serde(rename = "UniqueID")]
     pub unique_id: String,
+    #[serde(rename = "MinimumVersion", default)]
+    pub minimum_version: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -34,9 +43,38 @@ pub struct ModDependency {
     pub unique_id: String,
     #[serde(rename = "IsRequired")]
     pub is_required: Option<bool>,
+    #[serde(rename = "MinimumVersion", default)]
+    pub minimum_version: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Minimum version of a framework a content pack needs, recorded by the
+/// user when the pack only states it in its description
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkFloor {
+    pub unique_id: String,
+    pub minimum_version: String,
+}
+
+/// JSON type of a setting in a mod's config.json
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueKind {
+    Boolean,
+    Number,
+    String,
+    Object,
+    Array,
+    Null,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigKey {
+    pub key: String,
+    pub kind: ConfigValueKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Mod {
     pub id: String,
     pub name: String,
@@ -53,4 +91,61 @@ pub struct Mod {
     pub nexus_mod_id: Option<u32>,
     #[serde(rename = "nexusFileId")]
     pub nexus_file_id: Option<u32>,
+    /// Last game launch this mod was enabled for
+    #[serde(rename = "lastPlayedAt", default)]
+    pub last_played_at: Option<DateTime<Utc>>,
+    /// First seen by the mod registry
+    #[serde(rename = "installDate", default)]
+    pub installed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub source: Option<ModSource>,
+    #[serde(rename = "sourceFile", default)]
+    pub source_file: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(rename = "isFavorite", default)]
+    pub is_favorite: bool,
+    /// Newer version found by the last update check
+    #[serde(rename = "availableUpdate", default)]
+    pub available_update: Option<ModUpdate>,
+    /// Recorded framework version floors, on top of the manifest's
+    #[serde(rename = "frameworkFloors", default)]
+    pub framework_floors: Vec<FrameworkFloor>,
+    /// Nexus category, once looked up
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Under one of the user's unmanaged folders: listed, but never changed
+    #[serde(rename = "isUnmanaged", default)]
+    pub is_unmanaged: bool,
+    /// Status on the smapi.io compatibility list, from the last check
+    #[serde(default)]
+    pub compatibility: Option<ModCompatibility>,
+    /// Registers with Generic Mod Config Menu, so it can be set up in game
+    #[serde(rename = "hasGmcm", default)]
+    pub has_gmcm: bool,
+    /// Top-level settings of its config.json; empty until the mod has run
+    #[serde(rename = "configKeys", default)]
+    pub config_keys: Vec<ConfigKey>,
+    /// Other folders with the same UniqueID; SMAPI won't start with two of
+    /// them enabled
+    #[serde(rename = "duplicatePaths", default)]
+    pub duplicate_paths: Vec<String>,
+    /// The folder's `.nexus_meta`, indexed by the registry
+    #[serde(skip)]
+    pub nexus_meta: Option<NexusMeta>,
+}
+
+/// An enabled mod with just a name, for tests to fill in what they need
+#[cfg(test)]
+pub(crate) fn test_mod(unique_id: &str) -> Mod {
+    Mod {
+        id: unique_id.to_string(),
+        name: unique_id.to_string(),
+        author: "Tester".to_string(),
+        version: "1.0.0".to_string(),
+        unique_id: unique_id.to_string(),
+        path: format!("/Mods/{}", unique_id),
+        is_enabled: true,
+        ..Default::default()
+    }
 }
@@ -0,0 +1,526 @@
+//! Keep a multiplayer group on the host's exact modlist.
+//!
+//! The host exports a modlist signed with a key kept in the app data
+//! directory. Joiners check the signature and trust the key of the first
+//! host they sync with, refusing lists signed by any other until they
+//! forget it. They see what differs from their own
+//! setup and apply it: missing or different versions are downloaded (the
+//! host's exact Nexus file), extra mods are disabled. Config files are only
+//! compared, not copied.
+
+use crate::download_manager::compute_md5;
+use crate::mod_installer::{set_mod_enabled, ModInstaller, CONFIG_FILE_NAME};
+use crate::models::Mod;
+use crate::nexus_api::NexusClient;
+use crate::settings::Settings;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+const SIGNING_KEY_FILE_NAME: &str = "sync_signing_key";
+/// Public key of the host the joiner synced with first
+const TRUSTED_HOST_FILE_NAME: &str = "sync_trusted_host";
+
+const FORMAT_VERSION: u32 = 1;
+
+/// One mod of the host's setup
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncMod {
+    pub unique_id: String,
+    pub name: String,
+    pub version: String,
+    pub nexus_mod_id: Option<u32>,
+    pub nexus_file_id: Option<u32>,
+    /// MD5 of the mod's config.json, if it has one
+    pub config_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Modlist {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub mods: Vec<SyncMod>,
+}
+
+/// The file the host shares; `signature` covers the JSON of `modlist`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedModlist {
+    modlist: Modlist,
+    /// Hex-encoded ed25519 public key of the host
+    public_key: String,
+    signature: String,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncAction {
+    Install,
+    Update,
+    Enable,
+    Disable,
+    /// Same version, but the host's config.json is different
+    ConfigDiffers,
+    /// Needed, but the host's copy didn't come from Nexus
+    Unavailable,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncChange {
+    pub unique_id: String,
+    pub name: String,
+    pub action: SyncAction,
+    pub local_version: Option<String>,
+    pub host_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPreview {
+    /// Short form of the host's public key, to compare with the host
+    pub host_key: String,
+    /// No host is trusted yet; applying the modlist trusts this one
+    pub new_host: bool,
+    pub created_at: DateTime<Utc>,
+    pub changes: Vec<SyncChange>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub change: SyncChange,
+    pub error: Option<String>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn short_key(public_key: &str) -> String {
+    public_key.chars().take(16).collect()
+}
+
+/// The host's signing key, created on first export
+fn signing_key(app_handle: &AppHandle) -> Result<SigningKey, String> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let path = app_data_dir.join(SIGNING_KEY_FILE_NAME);
+
+    if let Some(seed) = fs::read_to_string(&path).ok().and_then(|hex| from_hex::<32>(hex.trim())) {
+        return Ok(SigningKey::from_bytes(&seed));
+    }
+
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+
+    fs::create_dir_all(&app_data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    crate::settings::write_private(&path, to_hex(&seed).as_bytes())
+        .map_err(|e| format!("Failed to write signing key: {}", e))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Check `public_key` against the trusted host. Returns whether none is
+/// trusted yet; with `trust`, the key becomes the trusted one then.
+fn check_host(app_data_dir: &Path, public_key: &str, trust: bool) -> Result<bool, String> {
+    let path = app_data_dir.join(TRUSTED_HOST_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(trusted) if trusted.trim() == public_key => Ok(false),
+        Ok(trusted) => Err(format!(
+            "This modlist was signed by host {}, not by {} you synced with before. Forget that host to sync with a new one.",
+            short_key(public_key),
+            short_key(trusted.trim())
+        )),
+        Err(_) => {
+            if trust {
+                fs::create_dir_all(app_data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+                fs::write(&path, public_key).map_err(|e| format!("Failed to trust the host: {}", e))?;
+                info!("🤝 Trusted sync host {}", short_key(public_key));
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// Stop trusting the host synced with so far, so the next modlist applied
+/// sets a new one
+pub fn forget_host(app_handle: &AppHandle) -> Result<(), String> {
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    match fs::remove_file(app_data_dir.join(TRUSTED_HOST_FILE_NAME)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to forget the host: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+fn sign(modlist: Modlist, key: &SigningKey) -> Result<SignedModlist, String> {
+    let message = serde_json::to_vec(&modlist).map_err(|e| e.to_string())?;
+    Ok(SignedModlist {
+        public_key: to_hex(key.verifying_key().as_bytes()),
+        signature: to_hex(&key.sign(&message).to_bytes()),
+        modlist,
+    })
+}
+
+/// Check the signature, returning the modlist and the host's public key
+fn verify(signed: SignedModlist) -> Result<(Modlist, String), String> {
+    let invalid = || "The modlist signature is invalid; the file was changed after the host exported it".to_string();
+
+    let public_key = from_hex::<32>(&signed.public_key)
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(invalid)?;
+    let signature = from_hex::<64>(&signed.signature)
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .ok_or_else(invalid)?;
+
+    let message = serde_json::to_vec(&signed.modlist).map_err(|e| e.to_string())?;
+    public_key.verify(&message, &signature).map_err(|_| invalid())?;
+
+    if signed.modlist.format_version > FORMAT_VERSION {
+        return Err("This modlist was exported by a newer version of Treasure Chest".to_string());
+    }
+    Ok((signed.modlist, signed.public_key))
+}
+
+fn config_hash(mod_path: &Path) -> Option<String> {
+    compute_md5(&mod_path.join(CONFIG_FILE_NAME)).ok()
+}
+
+fn installed_mods(settings: &Settings) -> Result<Vec<Mod>, String> {
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+    Ok(crate::mod_installer::scan_mods(Path::new(&settings.game_path)))
+}
+
+/// What a joiner has to change to match the host. `config_hashes` holds the
+/// local config hash of each installed mod by path.
+fn diff(host: &[SyncMod], local: &[Mod], config_hashes: &HashMap<String, String>) -> Vec<SyncChange> {
+    let mut changes = Vec::new();
+
+    for host_mod in host {
+        let local_mod = local
+            .iter()
+            .filter(|m| m.unique_id.eq_ignore_ascii_case(&host_mod.unique_id))
+            // Prefer the enabled copy if there are several
+            .max_by_key(|m| m.is_enabled);
+        let on_nexus = host_mod.nexus_mod_id.is_some() && host_mod.nexus_file_id.is_some();
+
+        let action = match local_mod {
+            None if on_nexus => SyncAction::Install,
+            Some(m) if m.version != host_mod.version && on_nexus => SyncAction::Update,
+            None => SyncAction::Unavailable,
+            Some(m) if m.version != host_mod.version => SyncAction::Unavailable,
+            Some(m) if !m.is_enabled => SyncAction::Enable,
+            Some(m) if host_mod.config_hash.is_some() && config_hashes.get(&m.path) != host_mod.config_hash.as_ref() => {
+                SyncAction::ConfigDiffers
+            }
+            Some(_) => continue,
+        };
+
+        changes.push(SyncChange {
+            unique_id: host_mod.unique_id.clone(),
+            name: host_mod.name.clone(),
+            action,
+            local_version: local_mod.map(|m| m.version.clone()),
+            host_version: Some(host_mod.version.clone()),
+        });
+    }
+
+    for local_mod in local.iter().filter(|m| m.is_enabled) {
+        if !host.iter().any(|h| h.unique_id.eq_ignore_ascii_case(&local_mod.unique_id)) {
+            changes.push(SyncChange {
+                unique_id: local_mod.unique_id.clone(),
+                name: local_mod.name.clone(),
+                action: SyncAction::Disable,
+                local_version: Some(local_mod.version.clone()),
+                host_version: None,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Write the enabled mods to `path` as a signed modlist. Returns the short
+/// host key joiners should see when they load it.
+pub fn export_modlist(app_handle: &AppHandle, path: &Path) -> Result<String, String> {
+    let settings = Settings::load(app_handle)?;
+    let mods = installed_mods(&settings)?
+        .into_iter()
+        .filter(|m| m.is_enabled)
+        .map(|m| SyncMod {
+            config_hash: config_hash(Path::new(&m.path)),
+            unique_id: m.unique_id,
+            name: m.name,
+            version: m.version,
+            nexus_mod_id: m.nexus_mod_id,
+            nexus_file_id: m.nexus_file_id,
+        })
+        .collect();
+
+    let modlist = Modlist {
+        format_version: FORMAT_VERSION,
+        created_at: Utc::now(),
+        mods,
+    };
+    let signed = sign(modlist, &signing_key(app_handle)?)?;
+
+    let json = serde_json::to_string_pretty(&signed).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("Failed to write modlist: {}", e))?;
+    info!("🤝 Exported modlist with {} mods to {}", signed.modlist.mods.len(), path.display());
+
+    Ok(short_key(&signed.public_key))
+}
+
+fn load(path: &Path) -> Result<(Modlist, String), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read modlist: {}", e))?;
+    let signed: SignedModlist =
+        serde_json::from_str(&content).map_err(|e| format!("Not a Treasure Chest modlist: {}", e))?;
+    verify(signed)
+}
+
+/// Changes the user lets us make: mods in unmanaged folders are left alone
+fn managed_changes(changes: Vec<SyncChange>, local: &[Mod], settings: &Settings) -> Vec<SyncChange> {
+    changes
+        .into_iter()
+        .filter(|change| {
+            !local.iter().any(|m| {
+                m.unique_id.eq_ignore_ascii_case(&change.unique_id) && settings.is_unmanaged(Path::new(&m.path))
+            })
+        })
+        .collect()
+}
+
+fn local_state(settings: &Settings) -> Result<(Vec<Mod>, HashMap<String, String>), String> {
+    let local = installed_mods(settings)?;
+    let config_hashes = local
+        .iter()
+        .filter_map(|m| Some((m.path.clone(), config_hash(Path::new(&m.path))?)))
+        .collect();
+    Ok((local, config_hashes))
+}
+
+/// Verify a modlist and list what applying it would change
+pub fn preview_modlist(app_handle: &AppHandle, path: &Path) -> Result<SyncPreview, String> {
+    let (modlist, public_key) = load(path)?;
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let new_host = check_host(&app_data_dir, &public_key, false)?;
+    let settings = Settings::load(app_handle)?;
+    let (local, config_hashes) = local_state(&settings)?;
+
+    Ok(SyncPreview {
+        host_key: short_key(&public_key),
+        new_host,
+        created_at: modlist.created_at,
+        changes: managed_changes(diff(&modlist.mods, &local, &config_hashes), &local, &settings),
+    })
+}
+
+/// Make this setup match the host's modlist.
+///
+/// Files are requested without a key from the website, which Nexus only
+/// allows for premium accounts.
+pub async fn apply_modlist(app_handle: &AppHandle, path: &Path) -> Result<Vec<SyncResult>, String> {
+    let (modlist, public_key) = load(path)?;
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    check_host(&app_data_dir, &public_key, true)?;
+    let settings = Settings::load(app_handle)?;
+    let (local, config_hashes) = local_state(&settings)?;
+    let changes = managed_changes(diff(&modlist.mods, &local, &config_hashes), &local, &settings);
+
+    let needs_downloads = changes
+        .iter()
+        .any(|c| matches!(c.action, SyncAction::Install | SyncAction::Update));
+    if needs_downloads && !NexusClient::new(app_handle, &settings).validate_user().await?.is_premium {
+        return Err("Downloading the host's mods needs a Nexus Mods Premium account".to_string());
+    }
+
+    let game_path = PathBuf::from(&settings.game_path);
+    let installer = ModInstaller::new(
+        app_handle.clone(),
+        app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("temp"),
+    )
+    .unattended();
+    let find_local = |unique_id: &str| {
+        local
+            .iter()
+            .filter(|m| m.unique_id.eq_ignore_ascii_case(unique_id))
+            .max_by_key(|m| m.is_enabled)
+    };
+    let find_host = |unique_id: &str| modlist.mods.iter().find(|m| m.unique_id.eq_ignore_ascii_case(unique_id));
+
+    let mut results = Vec::new();
+    for change in changes {
+        let result = match change.action {
+            SyncAction::Install | SyncAction::Update => {
+                let Some((mod_id, file_id)) =
+                    find_host(&change.unique_id).and_then(|m| Some((m.nexus_mod_id?, m.nexus_file_id?)))
+                else {
+                    continue;
+                };
+
+                crate::update_all::install_nexus_file(
+                    app_handle,
+                    &installer,
+                    &game_path,
+                    &settings,
+                    mod_id,
+                    file_id,
+                    crate::mod_registry::ModSource::Modpack,
+                )
+                .await
+                    .and_then(|installed| crate::update_all::installed_result(installed, &change.unique_id))
+                    .and_then(|installed| match find_local(&change.unique_id) {
+                        Some(old) => crate::update_all::replace_old_folder(
+                            &installer,
+                            &settings,
+                            &change.unique_id,
+                            Path::new(&old.path),
+                            &installed.install_path,
+                        ),
+                        None => Ok(()),
+                    })
+            }
+            SyncAction::Enable | SyncAction::Disable => match find_local(&change.unique_id) {
+                Some(m) => {
+                    let enabled = change.action == SyncAction::Enable;
+                    set_mod_enabled(Path::new(&m.path), enabled, settings.disabled_folder_style).map(|_| ())
+                }
+                None => Err("Mod is no longer installed".to_string()),
+            },
+            SyncAction::ConfigDiffers | SyncAction::Unavailable => Ok(()),
+        };
+
+        results.push(SyncResult {
+            change,
+            error: result.err(),
+        });
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    info!("🤝 Applied modlist: {} changes, {} failed", results.len(), failed);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_mod;
+
+    fn host_mod(unique_id: &str, version: &str, nexus: bool, config_hash: Option<&str>) -> SyncMod {
+        SyncMod {
+            unique_id: unique_id.to_string(),
+            name: unique_id.to_string(),
+            version: version.to_string(),
+            nexus_mod_id: nexus.then_some(1),
+            nexus_file_id: nexus.then_some(2),
+            config_hash: config_hash.map(str::to_string),
+        }
+    }
+
+    fn local_mod(unique_id: &str, version: &str, is_enabled: bool) -> Mod {
+        Mod {
+            version: version.to_string(),
+            is_enabled,
+            ..test_mod(unique_id)
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let host = vec![
+            host_mod("A.Missing", "1.0.0", true, None),
+            host_mod("A.Outdated", "2.0.0", true, None),
+            host_mod("A.Manual", "1.0.0", false, None),
+            host_mod("A.Disabled", "1.0.0", true, None),
+            host_mod("A.Configured", "1.0.0", true, Some("aaa")),
+            host_mod("A.Same", "1.0.0", true, Some("bbb")),
+        ];
+        let local = vec![
+            local_mod("A.Outdated", "1.0.0", true),
+            local_mod("A.Disabled", "1.0.0", false),
+            local_mod("A.Configured", "1.0.0", true),
+            local_mod("a.same", "1.0.0", true),
+            local_mod("A.Extra", "1.0.0", true),
+            local_mod("A.ExtraDisabled", "1.0.0", false),
+        ];
+        let config_hashes = HashMap::from([
+            ("/Mods/A.Configured".to_string(), "zzz".to_string()),
+            ("/Mods/a.same".to_string(), "bbb".to_string()),
+        ]);
+
+        let actions: Vec<(String, SyncAction)> = diff(&host, &local, &config_hashes)
+            .into_iter()
+            .map(|c| (c.unique_id, c.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("A.Missing".to_string(), SyncAction::Install),
+                ("A.Outdated".to_string(), SyncAction::Update),
+                ("A.Manual".to_string(), SyncAction::Unavailable),
+                ("A.Disabled".to_string(), SyncAction::Enable),
+                ("A.Configured".to_string(), SyncAction::ConfigDiffers),
+                ("A.Extra".to_string(), SyncAction::Disable),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let modlist = Modlist {
+            format_version: FORMAT_VERSION,
+            created_at: Utc::now(),
+            mods: vec![host_mod("A.Mod", "1.0.0", true, None)],
+        };
+
+        let signed = sign(modlist.clone(), &key).unwrap();
+        let json = serde_json::to_string(&signed).unwrap();
+        let (verified, _) = verify(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(verified, modlist);
+
+        let tampered = json.replace("1.0.0", "9.9.9");
+        assert!(verify(serde_json::from_str(&tampered).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_first_host_is_trusted() {
+        let app_data_dir = std::env::temp_dir().join(format!("tc_sync_host_{}", uuid::Uuid::new_v4()));
+        let host = to_hex(SigningKey::from_bytes(&[7u8; 32]).verifying_key().as_bytes());
+        let other = to_hex(SigningKey::from_bytes(&[8u8; 32]).verifying_key().as_bytes());
+
+        // A preview doesn't trust anyone yet
+        assert_eq!(check_host(&app_data_dir, &host, false), Ok(true));
+        assert_eq!(check_host(&app_data_dir, &other, false), Ok(true));
+
+        assert_eq!(check_host(&app_data_dir, &host, true), Ok(true));
+        assert_eq!(check_host(&app_data_dir, &host, true), Ok(false));
+        assert!(check_host(&app_data_dir, &other, true).is_err());
+
+        fs::remove_dir_all(&app_data_dir).unwrap();
+    }
+}
@@ -0,0 +1,659 @@
+use crate::api_usage_tracker::ApiUsageTracker;
+use crate::events::{self, AppEvent};
+use crate::nxm_protocol::NxmUrl;
+use crate::settings::Settings;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const API_BASE: &str = "https://api.nexusmods.com";
+
+/// The v2 API; only it knows the requirements listed on mod pages
+const GRAPHQL_PATH: &str = "/v2/graphql";
+
+const MOD_REQUIREMENTS_QUERY: &str = "query ModRequirements($ids: [CompositeDomainWithIdInput!]!) {
+  legacyModsByDomain(ids: $ids) {
+    nodes { modRequirements { nexusRequirements { nodes { modId modName url notes externalRequirement } } } }
+  }
+}";
+
+/// User-Agent sent with every request to Nexus Mods (API and CDN)
+pub const USER_AGENT: &str = "Treasure Chest Mod Manager/0.1.0";
+
+/// Cool-down used when a 429 response carries no usable Retry-After header
+const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A request made while the quota is used up waits for a reset at most this
+/// close, and fails right away otherwise
+const MAX_QUOTA_WAIT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum NexusApiError {
+    /// The request never got a response (DNS, TLS, timeout, ...)
+    Network(String),
+    /// 429 Too Many Requests; wait this long before the next request
+    RateLimited(Duration),
+    /// Not sent: the hourly and daily quotas are used up until this time
+    QuotaExhausted(DateTime<Utc>),
+    /// Any other non-success status
+    Http { status: StatusCode, body: String },
+    /// The response body didn't match the expected shape
+    Parse(String),
+}
+
+impl NexusApiError {
+    /// Whether trying the same request again later may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            NexusApiError::Network(_) | NexusApiError::RateLimited(_) | NexusApiError::QuotaExhausted(_) => true,
+            NexusApiError::Http { status, .. } => status.is_server_error(),
+            NexusApiError::Parse(_) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for NexusApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NexusApiError::Network(e) => write!(f, "API request failed: {}", e),
+            NexusApiError::RateLimited(delay) => {
+                write!(f, "Rate limited by Nexus Mods, retry in {}s", delay.as_secs())
+            }
+            NexusApiError::QuotaExhausted(resets_at) => write!(
+                f,
+                "Nexus Mods API quota exhausted, resets at {}",
+                resets_at.with_timezone(&chrono::Local).format("%H:%M")
+            ),
+            NexusApiError::Http { status, body } => {
+                let body: String = body.chars().take(200).collect();
+                write!(f, "API error {}: {}", status, body)
+            }
+            NexusApiError::Parse(e) => write!(f, "Failed to parse API response: {}", e),
+        }
+    }
+}
+
+impl From<NexusApiError> for String {
+    fn from(err: NexusApiError) -> Self {
+        err.to_string()
+    }
+}
+
+/// Parse the Retry-After header (delta-seconds or HTTP date) of a 429 response
+pub fn retry_after_from_headers(headers: &reqwest::header::HeaderMap) -> Duration {
+    let Some(value) = headers.get(reqwest::header::RETRY_AFTER).and_then(|v| v.to_str().ok()) else {
+        return DEFAULT_RATE_LIMIT_COOLDOWN;
+    };
+
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Duration::from_secs(seconds.max(1));
+    }
+
+    DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .and_then(|date| (date.with_timezone(&Utc) - Utc::now()).to_std().ok())
+        .map(|d| d.max(Duration::from_secs(1)))
+        .unwrap_or(DEFAULT_RATE_LIMIT_COOLDOWN)
+}
+
+/// Response of /v1/users/validate.json
+#[derive(Debug, Deserialize, Clone)]
+pub struct ValidatedUser {
+    pub name: String,
+    #[serde(default)]
+    pub is_premium: bool,
+}
+
+/// Response of /v1/games/{game}/mods/{id}.json (only the fields we use)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModInfo {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub latest_file_id: Option<u32>,
+    #[serde(default)]
+    pub category_id: Option<u32>,
+}
+
+/// Response of /v1/games/{game}.json (only the fields we use)
+#[derive(Debug, Deserialize, Clone)]
+pub struct GameInfo {
+    #[serde(default)]
+    pub categories: Vec<Category>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Category {
+    pub category_id: u32,
+    pub name: String,
+}
+
+/// A mod the author lists as required on the mod page. Unlike manifest
+/// dependencies these include soft requirements and asset packs.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NexusRequirement {
+    /// A string in the v2 API
+    pub mod_id: String,
+    pub mod_name: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Hosted outside Nexus Mods; can only be linked to
+    #[serde(default)]
+    pub external_requirement: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModRequirementsData {
+    legacy_mods_by_domain: Nodes<RequirementsMod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequirementsMod {
+    mod_requirements: Option<ModRequirements>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModRequirements {
+    nexus_requirements: Option<Nodes<NexusRequirement>>,
+}
+
+/// Response of /v1/games/{game}/mods/{id}/files/{file_id}.json
+#[derive(Debug, Deserialize, Clone)]
+pub struct FileInfo {
+    /// Original archive name as uploaded by the author
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub size_in_bytes: Option<u64>,
+    pub size_kb: Option<u64>,
+    /// JSON listing of the archive's contents, hosted on the Nexus CDN
+    #[serde(default)]
+    pub content_preview_link: Option<String>,
+}
+
+impl FileInfo {
+    /// Archive size in bytes; older files only report size_kb
+    pub fn size_bytes(&self) -> Option<u64> {
+        self.size_in_bytes.or(self.size_kb.map(|kb| kb * 1024))
+    }
+}
+
+/// Node of a file's content preview: a directory with children or a file
+/// with a human readable size like "1.2 MB"
+#[derive(Debug, Deserialize)]
+struct ContentPreviewNode {
+    #[serde(default)]
+    size: Option<String>,
+    #[serde(default)]
+    children: Vec<ContentPreviewNode>,
+}
+
+impl ContentPreviewNode {
+    fn total_size(&self) -> u64 {
+        let own = self.size.as_deref().and_then(parse_preview_size).unwrap_or(0);
+        own + self.children.iter().map(Self::total_size).sum::<u64>()
+    }
+}
+
+/// Bytes in a content preview size ("512 bytes", "1.2 kB", "3.4 MB")
+fn parse_preview_size(size: &str) -> Option<u64> {
+    let (number, unit) = size.trim().split_once(' ')?;
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.to_ascii_lowercase().as_str() {
+        "b" | "byte" | "bytes" => 1.0,
+        "kb" => 1024.0,
+        "mb" => 1024.0 * 1024.0,
+        "gb" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// Entry of /v1/games/{game}/mods/{id}/files.json
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct ModFile {
+    pub file_id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// MAIN, UPDATE, OPTIONAL, OLD_VERSION, MISCELLANEOUS or ARCHIVED
+    #[serde(default)]
+    pub category_name: Option<String>,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub size_in_bytes: Option<u64>,
+    #[serde(default)]
+    pub size_kb: Option<u64>,
+    #[serde(
+        rename(deserialize = "uploaded_timestamp"),
+        deserialize_with = "chrono::serde::ts_seconds::deserialize"
+    )]
+    pub uploaded_at: DateTime<Utc>,
+    #[serde(default)]
+    pub is_primary: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ModFile {
+    /// Archive size in bytes; older files only report size_kb
+    pub fn size_bytes(&self) -> Option<u64> {
+        self.size_in_bytes.or(self.size_kb.map(|kb| kb * 1024))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModFilesResponse {
+    files: Vec<ModFile>,
+}
+
+/// Changes listed for one version on a mod's Nexus page
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub changes: Vec<String>,
+}
+
+/// Parse a mod version leniently: "1.2" and "v1.2" are read as 1.2.0
+fn parse_version(version: &str) -> Option<semver::Version> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    if let Ok(parsed) = semver::Version::parse(version) {
+        return Some(parsed);
+    }
+
+    let mut parts: Vec<&str> = version.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    parts.resize(3, "0");
+    semver::Version::parse(&parts.join(".")).ok()
+}
+
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Changelog entries newer than `installed_version`, newest first. Without
+/// an installed version every entry is returned.
+pub fn changes_since(
+    changelogs: HashMap<String, Vec<String>>,
+    installed_version: Option<&str>,
+) -> Vec<ChangelogEntry> {
+    let mut entries: Vec<ChangelogEntry> = changelogs
+        .into_iter()
+        .filter(|(version, _)| {
+            installed_version.is_none_or(|installed| compare_versions(version, installed) == Ordering::Greater)
+        })
+        .map(|(version, changes)| ChangelogEntry { version, changes })
+        .collect();
+    entries.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    entries
+}
+
+/// Entry of /v1/games/{game}/mods/{id}/files/{file_id}/download_link.json
+#[derive(Debug, Deserialize, Clone)]
+pub struct DownloadLink {
+    #[serde(rename = "URI")]
+    pub uri: String,
+}
+
+/// Entry of /v1/user/endorsements.json
+#[derive(Debug, Deserialize, Clone)]
+pub struct Endorsement {
+    pub mod_id: u32,
+    pub domain_name: String,
+    pub status: String,
+}
+
+/// Entry of /v1/user/tracked_mods.json
+#[derive(Debug, Deserialize, Clone)]
+pub struct TrackedMod {
+    pub mod_id: u32,
+    pub domain_name: String,
+}
+
+/// Thin Nexus Mods API client: adds the apikey and User-Agent headers, feeds
+/// the rate limit headers to the `ApiUsageTracker` and logs raw payloads when
+/// payload logging is enabled
+pub struct NexusClient {
+    client: Client,
+    app_handle: AppHandle,
+    settings: Settings,
+}
+
+impl NexusClient {
+    /// Client authenticated with the active account's API key
+    pub fn new(app_handle: &AppHandle, settings: &Settings) -> Self {
+        Self::with_client(Client::new(), app_handle, settings)
+    }
+
+    /// Same, reusing an existing reqwest client (and its timeouts)
+    pub fn with_client(client: Client, app_handle: &AppHandle, settings: &Settings) -> Self {
+        Self {
+            client,
+            app_handle: app_handle.clone(),
+            settings: settings.clone(),
+        }
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        !self.settings.active_api_key().is_empty()
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        context: &str,
+    ) -> Result<T, NexusApiError> {
+        let url = format!("{}{}", API_BASE, path);
+
+        crate::debug_log::log_api_payload(
+            &self.app_handle,
+            &self.settings,
+            &format!("{} request", context),
+            &format!("{} {:?}", url, query),
+        );
+
+        self.send_json(self.client.get(&url).query(query), context).await
+    }
+
+    /// Run a query against the v2 GraphQL API
+    async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        context: &str,
+    ) -> Result<T, NexusApiError> {
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        crate::debug_log::log_api_payload(&self.app_handle, &self.settings, &format!("{} request", context), &body.to_string());
+
+        let request = self.client.post(format!("{}{}", API_BASE, GRAPHQL_PATH)).json(&body);
+        let response: GraphqlResponse<T> = self.send_json(request, context).await?;
+        match response.data {
+            Some(data) => Ok(data),
+            None => Err(NexusApiError::Parse(
+                response.errors.into_iter().map(|e| e.message).collect::<Vec<_>>().join("; "),
+            )),
+        }
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        context: &str,
+    ) -> Result<T, NexusApiError> {
+        let tracker = self.app_handle.try_state::<ApiUsageTracker>();
+        if let Some(tracker) = &tracker {
+            // Nexus would refuse it anyway; wait out a reset that's close,
+            // otherwise say when requests work again
+            if let Some(resets_at) = tracker.exhausted_until(self.settings.active_api_key()).await {
+                let wait = (resets_at - Utc::now()).to_std().unwrap_or_default();
+                if wait > MAX_QUOTA_WAIT {
+                    return Err(NexusApiError::QuotaExhausted(resets_at));
+                }
+                info!("⏳ Nexus API quota used up, waiting {}s for it to reset", wait.as_secs());
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        let response = request
+            .header("User-Agent", USER_AGENT)
+            .header("apikey", self.settings.active_api_key())
+            .send()
+            .await
+            // Strip the URL, query strings can carry nxm keys
+            .map_err(|e| NexusApiError::Network(e.without_url().to_string()))?;
+
+        let status = response.status();
+        if let Some(tracker) = &tracker {
+            if let Some(warning) = tracker.update_from_headers(response.headers(), self.settings.active_api_key()).await {
+                warn!("⚠️  Only {} of {} Nexus API requests left", warning.remaining, warning.limit);
+                events::emit(&self.app_handle, AppEvent::ApiQuotaLow(warning));
+            }
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(NexusApiError::RateLimited(retry_after_from_headers(response.headers())));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| NexusApiError::Network(e.without_url().to_string()))?;
+
+        crate::debug_log::log_api_payload(
+            &self.app_handle,
+            &self.settings,
+            &format!("{} response ({})", context, status),
+            &body,
+        );
+
+        if !status.is_success() {
+            return Err(NexusApiError::Http { status, body });
+        }
+
+        serde_json::from_str(&body).map_err(|e| NexusApiError::Parse(e.to_string()))
+    }
+
+    pub async fn validate_user(&self) -> Result<ValidatedUser, NexusApiError> {
+        self.get_json("/v1/users/validate.json", &[], "validate").await
+    }
+
+    pub async fn mod_info(&self, game: &str, mod_id: u32) -> Result<ModInfo, NexusApiError> {
+        self.get_json(&format!("/v1/games/{}/mods/{}.json", game, mod_id), &[], "mod info")
+            .await
+    }
+
+    pub async fn file_info(&self, game: &str, mod_id: u32, file_id: u32) -> Result<FileInfo, NexusApiError> {
+        self.get_json(
+            &format!("/v1/games/{}/mods/{}/files/{}.json", game, mod_id, file_id),
+            &[],
+            "file info",
+        )
+        .await
+    }
+
+    /// Size of an archive's contents once unpacked, from its content preview.
+    /// The preview lives on the CDN, so it's fetched without the API key.
+    pub async fn unpacked_size(&self, content_preview_link: &str) -> Result<u64, NexusApiError> {
+        let response = self
+            .client
+            .get(content_preview_link)
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await
+            .map_err(|e| NexusApiError::Network(e.without_url().to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| NexusApiError::Network(e.without_url().to_string()))?;
+        if !status.is_success() {
+            return Err(NexusApiError::Http { status, body });
+        }
+
+        let preview: ContentPreviewNode = serde_json::from_str(&body).map_err(|e| NexusApiError::Parse(e.to_string()))?;
+        Ok(preview.total_size())
+    }
+
+    /// Game details, including the mod categories of its site section
+    pub async fn game_info(&self, game: &str) -> Result<GameInfo, NexusApiError> {
+        self.get_json(&format!("/v1/games/{}.json", game), &[], "game info").await
+    }
+
+    /// Every file uploaded to a mod page, oldest first
+    pub async fn mod_files(&self, game: &str, mod_id: u32) -> Result<Vec<ModFile>, NexusApiError> {
+        let response: ModFilesResponse = self
+            .get_json(&format!("/v1/games/{}/mods/{}/files.json", game, mod_id), &[], "mod files")
+            .await?;
+        Ok(response.files)
+    }
+
+    /// Changelogs by version, as entered by the mod author
+    pub async fn mod_changelogs(&self, game: &str, mod_id: u32) -> Result<HashMap<String, Vec<String>>, NexusApiError> {
+        self.get_json(&format!("/v1/games/{}/mods/{}/changelogs.json", game, mod_id), &[], "changelogs")
+            .await
+    }
+
+    /// CDN links for the file an nxm:// link points at
+    pub async fn download_links(&self, nxm_url: &NxmUrl) -> Result<Vec<DownloadLink>, NexusApiError> {
+        // Premium accounts may request links without the website's key
+        let mut query = Vec::new();
+        if !nxm_url.is_direct() {
+            query.push(("key", nxm_url.key.clone()));
+            query.push(("expires", nxm_url.expires.unwrap_or(0).to_string()));
+        }
+        if let Some(user_id) = nxm_url.user_id {
+            query.push(("user_id", user_id.to_string()));
+        }
+
+        self.get_json(
+            &format!(
+                "/v1/games/{}/mods/{}/files/{}/download_link.json",
+                nxm_url.game, nxm_url.mod_id, nxm_url.file_id
+            ),
+            &query,
+            "download_link",
+        )
+        .await
+    }
+
+    /// Requirements listed on a mod's page
+    pub async fn mod_requirements(&self, game: &str, mod_id: u32) -> Result<Vec<NexusRequirement>, NexusApiError> {
+        let data: ModRequirementsData = self
+            .graphql(
+                MOD_REQUIREMENTS_QUERY,
+                serde_json::json!({ "ids": [{ "gameDomain": game, "modId": mod_id.to_string() }] }),
+                "mod requirements",
+            )
+            .await?;
+
+        Ok(data
+            .legacy_mods_by_domain
+            .nodes
+            .into_iter()
+            .filter_map(|m| m.mod_requirements?.nexus_requirements)
+            .flat_map(|requirements| requirements.nodes)
+            .collect())
+    }
+
+    pub async fn tracked_mods(&self) -> Result<Vec<TrackedMod>, NexusApiError> {
+        self.get_json("/v1/user/tracked_mods.json", &[], "tracked mods").await
+    }
+
+    pub async fn endorsements(&self) -> Result<Vec<Endorsement>, NexusApiError> {
+        self.get_json("/v1/user/endorsements.json", &[], "endorsements").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    #[test]
+    fn test_retry_after_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after_from_headers(&headers), DEFAULT_RATE_LIMIT_COOLDOWN);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_after_from_headers(&headers), Duration::from_secs(30));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("not a date"));
+        assert_eq!(retry_after_from_headers(&headers), DEFAULT_RATE_LIMIT_COOLDOWN);
+    }
+
+    #[test]
+    fn test_file_info_size_falls_back_to_kb() {
+        let info: FileInfo = serde_json::from_str(r#"{"size_kb": 2, "size_in_bytes": null}"#).unwrap();
+        assert_eq!(info.size_bytes(), Some(2048));
+
+        let info: FileInfo = serde_json::from_str(r#"{"size_kb": 2, "size_in_bytes": 2000}"#).unwrap();
+        assert_eq!(info.size_bytes(), Some(2000));
+    }
+
+    #[test]
+    fn test_content_preview_size() {
+        let preview: ContentPreviewNode = serde_json::from_str(
+            r#"{"children": [{"path": "Mod", "name": "Mod", "type": "directory", "children": [
+                {"path": "Mod/manifest.json", "name": "manifest.json", "type": "file", "size": "512 bytes"},
+                {"path": "Mod/assets.xnb", "name": "assets.xnb", "type": "file", "size": "1.5 MB"}
+            ]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(preview.total_size(), 512 + 1_572_864);
+        assert_eq!(parse_preview_size("2 kB"), Some(2048));
+        assert_eq!(parse_preview_size("huge"), None);
+    }
+
+    #[test]
+    fn test_mod_files_response() {
+        let response: ModFilesResponse = serde_json::from_str(
+            r#"{"files": [{"file_id": 9001, "name": "Lookup Anything", "version": "1.37.0",
+                "category_name": "MAIN", "size_kb": 512, "size_in_bytes": null,
+                "uploaded_timestamp": 1700000000, "is_primary": true}], "file_updates": []}"#,
+        )
+        .unwrap();
+
+        let file = &response.files[0];
+        assert_eq!(file.uploaded_at.timestamp(), 1_700_000_000);
+
+        let json = serde_json::to_value(file).unwrap();
+        assert_eq!(json["categoryName"], "MAIN");
+        assert_eq!(json["uploadedAt"], "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_changes_since() {
+        let changelogs = HashMap::from([
+            ("1.2".to_string(), vec!["Fixed crash".to_string()]),
+            ("1.10.0".to_string(), vec!["Added config".to_string()]),
+            ("1.3.0".to_string(), vec!["Faster loading".to_string()]),
+            ("1.0.0".to_string(), vec!["Initial release".to_string()]),
+        ]);
+
+        let versions = |entries: Vec<ChangelogEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.version).collect()
+        };
+        assert_eq!(versions(changes_since(changelogs.clone(), Some("1.2.0"))), ["1.10.0", "1.3.0"]);
+        assert_eq!(versions(changes_since(changelogs, None)), ["1.10.0", "1.3.0", "1.2", "1.0.0"]);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::events::{self, AppEvent};
+use crate::nexus_api::NexusClient;
+use crate::settings::{NexusAccount, Settings};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+const SSO_SOCKET_URL: &str = "wss://sso.nexusmods.com";
+
+/// Application slug registered with Nexus Mods for SSO
+const APPLICATION_SLUG: &str = "treasure-chest";
+
+/// How long the user has to approve the request in the browser
+const SSO_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// First message sent on the socket
+#[derive(Debug, Serialize)]
+struct SsoRequest {
+    id: String,
+    token: Option<String>,
+    protocol: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct SsoResponse {
+    success: bool,
+    #[serde(default)]
+    data: Option<SsoData>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SsoData {
+    #[serde(default)]
+    connection_token: Option<String>,
+    #[serde(default)]
+    api_key: Option<String>,
+}
+
+/// Page the user approves the request on
+fn authorize_url(request_id: &str) -> String {
+    format!(
+        "https://www.nexusmods.com/sso?id={}&application={}",
+        request_id, APPLICATION_SLUG
+    )
+}
+
+/// Run the SSO websocket handshake and wait for the API key
+async fn request_api_key(app_handle: &AppHandle) -> Result<String, String> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(SSO_SOCKET_URL)
+        .await
+        .map_err(|e| format!("Failed to connect to Nexus Mods SSO: {}", e))?;
+
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let request = SsoRequest {
+        id: request_id.clone(),
+        token: None,
+        protocol: 2,
+    };
+    let request = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    socket
+        .send(Message::Text(request))
+        .await
+        .map_err(|e| format!("Failed to send SSO request: {}", e))?;
+
+    while let Some(message) = socket.next().await {
+        let message = message.map_err(|e| format!("SSO connection error: {}", e))?;
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let response: SsoResponse =
+            serde_json::from_str(&text).map_err(|e| format!("Unexpected SSO response: {}", e))?;
+        if !response.success {
+            return Err(response.error.unwrap_or_else(|| "Nexus Mods SSO request failed".to_string()));
+        }
+
+        let Some(data) = response.data else {
+            continue;
+        };
+
+        if let Some(api_key) = data.api_key {
+            let _ = socket.close(None).await;
+            return Ok(api_key);
+        }
+
+        if data.connection_token.is_some() {
+            // Connected; send the user to Nexus to approve the request
+            let url = authorize_url(&request_id);
+            info!("🔑 Waiting for Nexus Mods SSO approval in the browser");
+            events::emit(app_handle, AppEvent::NexusSsoWaiting(url.clone()));
+            if let Err(e) = app_handle.opener().open_url(url, None::<&str>) {
+                warn!("Failed to open the SSO page: {}", e);
+            }
+        }
+    }
+
+    Err("Nexus Mods SSO connection closed before the API key was received".to_string())
+}
+
+/// Store a key obtained through SSO. With no named accounts it becomes the
+/// single `nexusApiKey`; otherwise it's saved as the Nexus user's account and
+/// made active.
+fn store_api_key(settings: &mut Settings, user_name: String, api_key: String) {
+    if settings.nexus_accounts.is_empty() {
+        settings.nexus_api_key = api_key;
+        return;
+    }
+
+    match settings.nexus_accounts.iter_mut().find(|a| a.name == user_name) {
+        Some(account) => account.api_key = api_key,
+        None => settings.nexus_accounts.push(NexusAccount {
+            name: user_name.clone(),
+            api_key,
+        }),
+    }
+    settings.active_account = Some(user_name);
+}
+
+/// "Connect to Nexus": approve in the browser, then validate and save the key
+pub async fn connect(app_handle: &AppHandle) -> Result<Settings, String> {
+    let api_key = tokio::time::timeout(SSO_TIMEOUT, request_api_key(app_handle))
+        .await
+        .map_err(|_| "Timed out waiting for Nexus Mods approval".to_string())??;
+
+    let mut settings = Settings::load(app_handle)?;
+
+    // Validate with the new key before saving it
+    let mut candidate = settings.clone();
+    candidate.nexus_api_key = api_key.clone();
+    candidate.active_account = None;
+    let user = NexusClient::new(app_handle, &candidate).validate_user().await?;
+
+    info!("✅ Connected to Nexus Mods as {}", user.name);
+    store_api_key(&mut settings, user.name, api_key);
+    settings.save(app_handle)?;
+
+    events::emit(app_handle, AppEvent::NexusSsoConnected);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_api_key() {
+        let mut settings = Settings::default();
+        store_api_key(&mut settings, "farmer".to_string(), "key-1".to_string());
+        assert_eq!(settings.nexus_api_key, "key-1");
+        assert!(settings.nexus_accounts.is_empty());
+
+        settings.nexus_accounts.push(NexusAccount {
+            name: "sibling".to_string(),
+            api_key: "key-2".to_string(),
+        });
+        store_api_key(&mut settings, "farmer".to_string(), "key-3".to_string());
+        assert_eq!(settings.active_account.as_deref(), Some("farmer"));
+        assert_eq!(settings.active_api_key(), "key-3");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::endorsements::mod_page_url;
+use crate::nexus_api::NexusClient;
+use crate::settings::Settings;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::info;
+
+/// Why a mod is believed to be part of the user's setup
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum HistorySource {
+    Tracked,
+    Endorsed,
+}
+
+/// A mod from the user's Nexus history that isn't installed here
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingModSuggestion {
+    pub mod_id: u32,
+    pub page_url: String,
+    pub sources: Vec<HistorySource>,
+}
+
+/// Mods from the account's history that aren't in `installed`, ordered by mod id
+fn missing_mods(history: &[(u32, HistorySource)], installed: &HashSet<u32>) -> Vec<MissingModSuggestion> {
+    let mut by_mod: BTreeMap<u32, Vec<HistorySource>> = BTreeMap::new();
+    for (mod_id, source) in history {
+        if installed.contains(mod_id) {
+            continue;
+        }
+        let sources = by_mod.entry(*mod_id).or_default();
+        if !sources.contains(source) {
+            sources.push(*source);
+            sources.sort();
+        }
+    }
+
+    by_mod
+        .into_iter()
+        .map(|(mod_id, sources)| MissingModSuggestion {
+            mod_id,
+            page_url: mod_page_url(mod_id),
+            sources,
+        })
+        .collect()
+}
+
+/// Suggest previously used Stardew mods that aren't installed on this machine.
+///
+/// The public API has no raw download history, so the account's tracked and
+/// endorsed mods stand in for it. Premium accounts only, matching the
+/// "restore my setup" flow this powers.
+pub async fn suggest_missing_mods(app_handle: &AppHandle) -> Result<Vec<MissingModSuggestion>, String> {
+    let settings = Settings::load(app_handle)?;
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.has_api_key() {
+        return Err("Nexus API key not configured".to_string());
+    }
+
+    let user = nexus.validate_user().await?;
+    if !user.is_premium {
+        return Err("Restoring from Nexus history requires a Nexus Mods Premium account".to_string());
+    }
+
+    let mut history = Vec::new();
+    for tracked in nexus.tracked_mods().await? {
+        if tracked.domain_name == "stardewvalley" {
+            history.push((tracked.mod_id, HistorySource::Tracked));
+        }
+    }
+    for endorsement in nexus.endorsements().await? {
+        if endorsement.domain_name == "stardewvalley" && endorsement.status == "Endorsed" {
+            history.push((endorsement.mod_id, HistorySource::Endorsed));
+        }
+    }
+
+    let installed: HashSet<u32> = if settings.game_path.is_empty() {
+        HashSet::new()
+    } else {
+        crate::mod_installer::scan_mods(Path::new(&settings.game_path))
+            .into_iter()
+            .filter_map(|m| m.nexus_mod_id)
+            .collect()
+    };
+
+    let suggestions = missing_mods(&history, &installed);
+    info!("🧭 {} mods from Nexus history are not installed", suggestions.len());
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_mods_merges_sources_and_skips_installed() {
+        let history = vec![
+            (30, HistorySource::Endorsed),
+            (10, HistorySource::Tracked),
+            (30, HistorySource::Tracked),
+            (20, HistorySource::Tracked),
+        ];
+        let installed: HashSet<u32> = [20].into_iter().collect();
+
+        let missing = missing_mods(&history, &installed);
+        assert_eq!(missing.len(), 2);
+        assert_eq!(missing[0].mod_id, 10);
+        assert_eq!(missing[1].mod_id, 30);
+        assert_eq!(missing[1].sources, vec![HistorySource::Tracked, HistorySource::Endorsed]);
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const META_FILE_NAME: &str = ".nexus_meta";
+
+/// Where an installed mod came from on Nexus, stored as `.nexus_meta` in the
+/// mod folder. Older files only have `mod_id` and `file_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NexusMeta {
+    pub mod_id: u32,
+    pub file_id: u32,
+    #[serde(default)]
+    pub file_version: Option<String>,
+    #[serde(default)]
+    pub downloaded_at: Option<DateTime<Utc>>,
+    /// MD5 of the archive the mod was installed from
+    #[serde(default)]
+    pub md5: Option<String>,
+}
+
+impl NexusMeta {
+    pub fn new(mod_id: u32, file_id: u32) -> Self {
+        Self {
+            mod_id,
+            file_id,
+            file_version: None,
+            downloaded_at: None,
+            md5: None,
+        }
+    }
+
+    pub fn path_for(mod_dir: &Path) -> PathBuf {
+        mod_dir.join(META_FILE_NAME)
+    }
+
+    /// Read the metadata of a mod folder, if it has any
+    pub fn read(mod_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(Self::path_for(mod_dir)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write the metadata atomically, so a concurrent scan never sees a
+    /// half-written file
+    pub fn write(&self, mod_dir: &Path) -> std::io::Result<()> {
+        let path = Self::path_for(mod_dir);
+        let tmp_path = mod_dir.join(format!("{}.tmp", META_FILE_NAME));
+
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_legacy_format() {
+        let dir = std::env::temp_dir().join("treasure_chest_nexus_meta_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Files written before the extended fields existed
+        fs::write(NexusMeta::path_for(&dir), r#"{"mod_id": 1, "file_id": 2}"#).unwrap();
+        assert_eq!(NexusMeta::read(&dir), Some(NexusMeta::new(1, 2)));
+
+        let meta = NexusMeta {
+            mod_id: 541,
+            file_id: 9001,
+            file_version: Some("1.37.0".to_string()),
+            downloaded_at: Some(Utc::now()),
+            md5: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+        };
+        meta.write(&dir).unwrap();
+        assert_eq!(NexusMeta::read(&dir), Some(meta));
+        assert!(!dir.join(".nexus_meta.tmp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
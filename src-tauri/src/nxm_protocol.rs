@@ -119,6 +119,23 @@ impl NxmUrl {
         })
     }
 
+    /// Link for a file picked inside the app instead of on the website. It has
+    /// no key, so only premium accounts can download it.
+    pub fn direct(game: &str, mod_id: u32, file_id: u32) -> Self {
+        NxmUrl {
+            game: game.to_string(),
+            mod_id,
+            file_id,
+            key: String::new(),
+            expires: None,
+            user_id: None,
+        }
+    }
+
+    pub fn is_direct(&self) -> bool {
+        self.key.is_empty()
+    }
+
     /// Check if the URL has expired
     pub fn is_expired(&self) -> bool {
         if let Some(expires) = self.expires {
@@ -141,6 +158,12 @@ impl NxmUrl {
     }
 }
 
+/// A link as it may be logged: the query, which holds the download key and
+/// its expiry, is left out
+pub fn loggable(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(path, _)| path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +229,13 @@ mod tests {
         assert!(matches!(result, Err(NxmError::InvalidFileId)));
     }
 
+    #[test]
+    fn test_loggable_drops_the_key() {
+        let url = "nxm://stardewvalley/mods/2400/files/9567?key=abc123&expires=1735344000";
+        assert_eq!(loggable(url), "nxm://stardewvalley/mods/2400/files/9567");
+        assert_eq!(loggable("C:/Downloads/mod.zip"), "C:/Downloads/mod.zip");
+    }
+
     #[test]
     fn test_expiration_validation() {
         // Create URL that expires in year 2000 (already passed)
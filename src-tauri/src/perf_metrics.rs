@@ -0,0 +1,219 @@
+//! How long the commands that grow with the mod library take (scanning,
+//! installing, update checks). Each run is appended to `performance.jsonl`
+//! so "the app is slow" reports come with numbers from the user's machine.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// Once the file grows past this, only the newest `KEEP_TIMINGS` are kept
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const KEEP_TIMINGS: usize = 2000;
+
+/// One run of a command
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// What the command worked through: mods scanned, mods installed...
+    pub items: Option<usize>,
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub average_ms: u64,
+    pub median_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+    pub last_items: Option<usize>,
+    pub last_run_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+    /// Most total time first
+    pub commands: Vec<CommandStats>,
+    /// Oldest timing the report covers
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Started when a command begins; `finish` records the run
+pub struct CommandTimer {
+    command: &'static str,
+    started_at: DateTime<Utc>,
+    start: Instant,
+}
+
+impl CommandTimer {
+    pub fn start(command: &'static str) -> Self {
+        Self {
+            command,
+            started_at: Utc::now(),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn finish(self, app_handle: &AppHandle, ok: bool, items: Option<usize>) {
+        let timing = CommandTiming {
+            command: self.command.to_string(),
+            started_at: self.started_at,
+            duration_ms: self.start.elapsed().as_millis() as u64,
+            items,
+            ok,
+        };
+        info!(
+            "⏱️  {} took {} ms{}",
+            timing.command,
+            timing.duration_ms,
+            items.map(|n| format!(" ({} items)", n)).unwrap_or_default()
+        );
+
+        let Some(path) = timings_path(app_handle) else {
+            return;
+        };
+        if let Err(e) = append_timing(&path, &timing) {
+            warn!("{}", e);
+        }
+    }
+}
+
+fn timings_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("performance.jsonl"))
+}
+
+fn append_timing(path: &Path, timing: &CommandTiming) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_FILE_BYTES) {
+        let timings = load_timings(path);
+        let kept = &timings[timings.len().saturating_sub(KEEP_TIMINGS)..];
+        let contents: String = kept
+            .iter()
+            .filter_map(|t| serde_json::to_string(t).ok())
+            .map(|line| line + "\n")
+            .collect();
+        fs::write(path, contents).map_err(|e| format!("Failed to trim performance log: {}", e))?;
+    }
+
+    let line = serde_json::to_string(timing).map_err(|e| format!("Failed to serialize timing: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open performance log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write performance log: {}", e))
+}
+
+/// Every recorded timing, oldest first, skipping lines that don't parse
+fn load_timings(path: &Path) -> Vec<CommandTiming> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Value at `percent` of sorted durations, nearest rank
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn summarize(timings: &[CommandTiming]) -> PerformanceReport {
+    let mut by_command: HashMap<&str, Vec<&CommandTiming>> = HashMap::new();
+    for timing in timings {
+        by_command.entry(&timing.command).or_default().push(timing);
+    }
+
+    let mut commands: Vec<(u64, CommandStats)> = by_command
+        .into_iter()
+        .map(|(command, runs)| {
+            let mut durations: Vec<u64> = runs.iter().map(|t| t.duration_ms).collect();
+            durations.sort_unstable();
+            let total: u64 = durations.iter().sum();
+            let last = runs.iter().max_by_key(|t| t.started_at).unwrap();
+            let stats = CommandStats {
+                command: command.to_string(),
+                runs: runs.len(),
+                failures: runs.iter().filter(|t| !t.ok).count(),
+                average_ms: total / runs.len() as u64,
+                median_ms: percentile(&durations, 50),
+                p95_ms: percentile(&durations, 95),
+                max_ms: *durations.last().unwrap(),
+                last_ms: last.duration_ms,
+                last_items: last.items,
+                last_run_at: last.started_at,
+            };
+            (total, stats)
+        })
+        .collect();
+    commands.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.command.cmp(&b.1.command)));
+
+    PerformanceReport {
+        commands: commands.into_iter().map(|(_, stats)| stats).collect(),
+        since: timings.iter().map(|t| t.started_at).min(),
+    }
+}
+
+pub fn report(app_handle: &AppHandle) -> Result<PerformanceReport, String> {
+    let path = timings_path(app_handle).ok_or("Failed to get app data directory")?;
+    Ok(summarize(&load_timings(&path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_summarize_timings() {
+        let at = |minute: u32| Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap();
+        let timing = |command: &str, minute: u32, duration_ms: u64, ok: bool| CommandTiming {
+            command: command.to_string(),
+            started_at: at(minute),
+            duration_ms,
+            items: Some(minute as usize),
+            ok,
+        };
+        let mut timings: Vec<CommandTiming> = (1..=20).map(|i| timing("scan_mods", i, i as u64 * 10, true)).collect();
+        timings.push(timing("install_mod_from_file", 30, 5000, true));
+        timings.push(timing("install_mod_from_file", 31, 100, false));
+
+        let report = summarize(&timings);
+        assert_eq!(report.since, Some(at(1)));
+        assert_eq!(report.commands[0].command, "install_mod_from_file");
+        assert_eq!(report.commands[0].failures, 1);
+        assert_eq!(report.commands[0].last_ms, 100);
+
+        let scan = &report.commands[1];
+        assert_eq!(scan.runs, 20);
+        assert_eq!(scan.average_ms, 105);
+        assert_eq!(scan.median_ms, 100);
+        assert_eq!(scan.p95_ms, 190);
+        assert_eq!(scan.max_ms, 200);
+        assert_eq!(scan.last_items, Some(20));
+
+        assert!(summarize(&[]).commands.is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Mod profiles: named sets of enabled mods, and the profile each save is
+//! played with. Launching a save with another profile than its own can
+//! break the farm, so the launch is checked first and, if the user wants,
+//! the save's profile is switched to.
+
+use crate::models::Mod;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModProfile {
+    pub name: String,
+    /// Lowercase UniqueIDs of the mods enabled in this profile
+    pub enabled_mods: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfileStore {
+    pub profiles: Vec<ModProfile>,
+    /// Profile last applied or saved
+    pub active: Option<String>,
+    /// Save folder name -> the profile it's linked to. A save is linked to
+    /// the profile it's first played with, unless the user picked one.
+    pub save_profiles: HashMap<String, String>,
+    /// Save folder name -> the profile it was last played with
+    pub last_played: HashMap<String, String>,
+}
+
+/// What launching `save_id` with the active profile would mean
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchCheck {
+    pub save_id: Option<String>,
+    pub save_profile: Option<String>,
+    pub active_profile: Option<String>,
+    /// The save belongs to another profile than the active one
+    pub mismatch: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyReport {
+    pub profile: String,
+    pub enabled: Vec<String>,
+    pub disabled: Vec<String>,
+    /// Mods of the profile that aren't installed
+    pub missing: Vec<String>,
+    pub failed: Vec<ApplyFailure>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyFailure {
+    pub path: String,
+    pub error: String,
+}
+
+fn store_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("profiles.json")
+}
+
+pub fn load(app_data_dir: &Path) -> ProfileStore {
+    fs::read_to_string(store_path(app_data_dir))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(app_data_dir: &Path, store: &ProfileStore) -> Result<(), String> {
+    fs::create_dir_all(app_data_dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    let json = serde_json::to_string_pretty(store).map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    let path = store_path(app_data_dir);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json)
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+fn find<'a>(store: &'a ProfileStore, name: &str) -> Result<&'a ModProfile, String> {
+    store
+        .profiles
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Profile not found: {}", name))
+}
+
+/// Save the mods enabled now as profile `name`, replacing one with that
+/// name, and make it the active profile
+pub fn save_profile(app_data_dir: &Path, name: &str, mods: &[Mod]) -> Result<ModProfile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name is empty".to_string());
+    }
+
+    let enabled: BTreeSet<String> = mods.iter().filter(|m| m.is_enabled).map(|m| m.unique_id.to_lowercase()).collect();
+    let profile = ModProfile {
+        name: name.to_string(),
+        enabled_mods: enabled.into_iter().collect(),
+    };
+
+    let mut store = load(app_data_dir);
+    store.profiles.retain(|p| p.name != name);
+    store.profiles.push(profile.clone());
+    store.profiles.sort_by_key(|p| p.name.to_lowercase());
+    store.active = Some(profile.name.clone());
+    save(app_data_dir, &store)?;
+    info!("📋 Saved profile {} with {} mods", profile.name, profile.enabled_mods.len());
+    Ok(profile)
+}
+
+/// Delete a profile and unlink the saves that used it
+pub fn delete_profile(app_data_dir: &Path, name: &str) -> Result<(), String> {
+    let mut store = load(app_data_dir);
+    find(&store, name)?;
+    store.profiles.retain(|p| p.name != name);
+    store.save_profiles.retain(|_, profile| profile != name);
+    store.last_played.retain(|_, profile| profile != name);
+    if store.active.as_deref() == Some(name) {
+        store.active = None;
+    }
+    save(app_data_dir, &store)
+}
+
+/// Link a save to a profile, or unlink it with None
+pub fn link_save(app_data_dir: &Path, save_id: &str, profile: Option<&str>) -> Result<(), String> {
+    let mut store = load(app_data_dir);
+    match profile {
+        Some(name) => {
+            find(&store, name)?;
+            store.save_profiles.insert(save_id.to_string(), name.to_string());
+        }
+        None => {
+            store.save_profiles.remove(save_id);
+        }
+    }
+    save(app_data_dir, &store)
+}
+
+/// Compare the profile of `save_id` with the active one. A save without a
+/// profile, or no active profile, is never a mismatch.
+pub fn check_launch(store: &ProfileStore, save_id: Option<&str>) -> LaunchCheck {
+    let save_profile = save_id.and_then(|id| store.save_profiles.get(id)).cloned();
+    let mismatch = match (&save_profile, &store.active) {
+        (Some(save_profile), Some(active)) => save_profile != active,
+        (Some(_), None) => true,
+        _ => false,
+    };
+    LaunchCheck {
+        save_id: save_id.map(str::to_string),
+        save_profile,
+        active_profile: store.active.clone(),
+        mismatch,
+    }
+}
+
+/// Remember that `save_id` was played with the active profile, and link it
+/// to that profile if it isn't linked yet. An existing link is kept, so
+/// launching with another profile keeps being warned about.
+pub fn record_launch(app_data_dir: &Path, save_id: &str) -> Result<(), String> {
+    let mut store = load(app_data_dir);
+    let Some(active) = store.active.clone() else {
+        return Ok(());
+    };
+    if store.last_played.get(save_id) == Some(&active) && store.save_profiles.contains_key(save_id) {
+        return Ok(());
+    }
+    store.last_played.insert(save_id.to_string(), active.clone());
+    store.save_profiles.entry(save_id.to_string()).or_insert(active);
+    save(app_data_dir, &store)
+}
+
+/// Enable the mods of profile `name` and disable the others. Mods in
+/// unmanaged folders are left as they are.
+pub fn apply_profile(settings: &Settings, app_data_dir: &Path, name: &str) -> Result<ApplyReport, String> {
+    let mut store = load(app_data_dir);
+    let profile = find(&store, name)?.clone();
+    let wanted: BTreeSet<&str> = profile.enabled_mods.iter().map(String::as_str).collect();
+
+    let mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    let installed: BTreeSet<String> = mods.iter().map(|m| m.unique_id.to_lowercase()).collect();
+    let mut report = ApplyReport {
+        profile: profile.name.clone(),
+        missing: wanted.iter().filter(|id| !installed.contains(**id)).map(|id| id.to_string()).collect(),
+        ..ApplyReport::default()
+    };
+
+    // Disable first, so a mod and a duplicate of it are never both enabled
+    let (to_enable, to_disable): (Vec<&Mod>, Vec<&Mod>) = mods
+        .iter()
+        .filter(|m| !m.is_unmanaged && m.is_enabled != wanted.contains(m.unique_id.to_lowercase().as_str()))
+        .partition(|m| !m.is_enabled);
+    for (m, enable) in to_disable.into_iter().map(|m| (m, false)).chain(to_enable.into_iter().map(|m| (m, true))) {
+        match crate::mod_installer::set_mod_enabled(Path::new(&m.path), enable, settings.disabled_folder_style) {
+            Ok(_) if enable => report.enabled.push(m.name.clone()),
+            Ok(_) => report.disabled.push(m.name.clone()),
+            Err(error) => report.failed.push(ApplyFailure {
+                path: m.path.clone(),
+                error,
+            }),
+        }
+    }
+
+    store.active = Some(profile.name.clone());
+    save(app_data_dir, &store)?;
+    info!(
+        "📋 Applied profile {} ({} enabled, {} disabled, {} missing, {} failed)",
+        profile.name,
+        report.enabled.len(),
+        report.disabled.len(),
+        report.missing.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_follow_saves() {
+        let game_dir = std::env::temp_dir().join(format!("tc_profiles_{}", uuid::Uuid::new_v4()));
+        let app_data_dir = game_dir.join("app_data");
+        let mods_dir = game_dir.join("Mods");
+        for (dir, id) in [("A", "Tester.A"), ("B", "Tester.B")] {
+            fs::create_dir_all(mods_dir.join(dir)).unwrap();
+            fs::write(
+                mods_dir.join(dir).join("manifest.json"),
+                format!(r#"{{"Name": "{}", "Author": "Tester", "Version": "1.0.0", "UniqueID": "{}"}}"#, dir, id),
+            )
+            .unwrap();
+        }
+        let settings = Settings {
+            game_path: game_dir.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+
+        let mods = crate::mod_installer::scan_mods(&game_dir);
+        save_profile(&app_data_dir, "Everything", &mods).unwrap();
+        record_launch(&app_data_dir, "Farm_1").unwrap();
+        crate::mod_installer::set_mod_enabled(&mods_dir.join("B"), false, settings.disabled_folder_style).unwrap();
+        save_profile(&app_data_dir, "Only A", &crate::mod_installer::scan_mods(&game_dir)).unwrap();
+
+        let check = check_launch(&load(&app_data_dir), Some("Farm_1"));
+        assert!(check.mismatch);
+        assert_eq!(check.save_profile.as_deref(), Some("Everything"));
+
+        // Playing it with the other profile anyway doesn't move the link
+        record_launch(&app_data_dir, "Farm_1").unwrap();
+        assert!(check_launch(&load(&app_data_dir), Some("Farm_1")).mismatch);
+        assert_eq!(load(&app_data_dir).last_played["Farm_1"], "Only A");
+        assert!(!check_launch(&load(&app_data_dir), Some("Farm_2")).mismatch);
+
+        let report = apply_profile(&settings, &app_data_dir, "Everything").unwrap();
+        assert_eq!(report.enabled, vec!["B".to_string()]);
+        assert!(mods_dir.join("B").is_dir());
+        assert!(!check_launch(&load(&app_data_dir), Some("Farm_1")).mismatch);
+
+        delete_profile(&app_data_dir, "Everything").unwrap();
+        let store = load(&app_data_dir);
+        assert!(store.active.is_none());
+        assert!(store.save_profiles.is_empty());
+        assert!(!store.last_played.values().any(|p| p == "Everything"));
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
@@ -0,0 +1,515 @@
+use crate::backup;
+use crate::models::Mod;
+use crate::settings::{stardew_data_dir, BackupFormat, SaveBackupsOnLaunch};
+use chrono::{DateTime, TimeZone, Utc};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// SaveGameInfo stores when the farm was saved as minutes since this date
+const SAVE_TIME_EPOCH: (i32, u32, u32) = (2012, 6, 22);
+const SEASONS: [&str; 4] = ["spring", "summer", "fall", "winter"];
+/// Backups made before launching are kept apart, so pruning them never
+/// touches one made by hand; their names carry this prefix
+const LAUNCH_BACKUP_PREFIX: &str = "launch-";
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RequirementStatus {
+    /// Installed but currently disabled
+    Disabled,
+    /// Not installed at all
+    Missing,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveModRequirement {
+    pub unique_id: String,
+    pub mod_name: Option<String>,
+    pub status: RequirementStatus,
+}
+
+/// A farm as shown in the load menu, from its SaveGameInfo
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSummary {
+    /// Save folder name, e.g. "Farm_123456789"
+    pub id: String,
+    pub farmer_name: String,
+    pub farm_name: String,
+    pub play_time_ms: u64,
+    pub year: Option<u32>,
+    pub season: Option<String>,
+    pub day: Option<u32>,
+    pub last_played: Option<DateTime<Utc>>,
+    /// Backups made by the app
+    pub backups: usize,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveBackup {
+    pub save_id: String,
+    /// File name, which identifies the backup when restoring
+    pub name: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub size: u64,
+    /// Made before a launch, and pruned with the others like it
+    pub on_launch: bool,
+}
+
+/// Get the Stardew Valley Saves folder
+pub fn saves_dir() -> Option<PathBuf> {
+    stardew_data_dir().map(|dir| dir.join("Saves"))
+}
+
+/// List save folder names (e.g. "Farm_123456789")
+pub fn list_saves() -> Vec<String> {
+    let Some(dir) = saves_dir() else {
+        return Vec::new();
+    };
+
+    let mut saves: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().join(e.file_name()).is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    saves.sort();
+    saves
+}
+
+/// Save ids are folder names; anything that could leave the Saves folder
+/// is refused
+fn check_save_id(save_id: &str) -> Result<(), String> {
+    if save_id.is_empty() || save_id.contains(['/', '\\']) || save_id.contains("..") {
+        return Err(format!("Invalid save id: {}", save_id));
+    }
+    Ok(())
+}
+
+/// Get the main save file of a save folder (same name as the folder)
+pub fn save_file_path(save_id: &str) -> Result<PathBuf, String> {
+    check_save_id(save_id)?;
+
+    let path = saves_dir()
+        .ok_or("Could not locate the Stardew Valley Saves folder")?
+        .join(save_id)
+        .join(save_id);
+
+    if !path.is_file() {
+        return Err(format!("Save not found: {}", save_id));
+    }
+
+    Ok(path)
+}
+
+/// First `<tag>` value of an XML document, unescaped
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find('<')?;
+    Some(
+        xml[start..end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Read a SaveGameInfo file: the farmer as it was when the game was saved.
+/// The farmer's own `<name>` comes before any item's.
+pub fn parse_save_game_info(save_id: &str, xml: &str) -> SaveSummary {
+    let number = |tag: &str| xml_value(xml, tag).and_then(|v| v.trim().parse::<u64>().ok());
+    let (year, month, day) = SAVE_TIME_EPOCH;
+    let last_played = number("saveTime").and_then(|minutes| {
+        let epoch = Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).single()?;
+        Some(epoch + chrono::Duration::minutes(minutes as i64))
+    });
+
+    SaveSummary {
+        id: save_id.to_string(),
+        farmer_name: xml_value(xml, "name").unwrap_or_default(),
+        farm_name: xml_value(xml, "farmName").unwrap_or_default(),
+        play_time_ms: number("millisecondsPlayed").unwrap_or(0),
+        year: number("yearForSaveGame").map(|v| v as u32),
+        season: number("seasonForSaveGame").and_then(|v| SEASONS.get(v as usize)).map(|s| s.to_string()),
+        day: number("dayOfMonthForSaveGame").map(|v| v as u32),
+        last_played,
+        backups: 0,
+    }
+}
+
+/// Summaries of every farm, most recently played first
+pub fn list_farms(app_data_dir: &Path) -> Vec<SaveSummary> {
+    let Some(dir) = saves_dir() else {
+        return Vec::new();
+    };
+
+    let mut farms: Vec<SaveSummary> = list_saves()
+        .into_iter()
+        .filter_map(|save_id| {
+            let info_path = dir.join(&save_id).join("SaveGameInfo");
+            let xml = fs::read_to_string(&info_path).ok()?;
+            let mut summary = parse_save_game_info(&save_id, &xml);
+            // Saves from before saveTime existed
+            if summary.last_played.is_none() {
+                summary.last_played = fs::metadata(&info_path).and_then(|m| m.modified()).ok().map(DateTime::from);
+            }
+            summary.backups = backup::list_snapshots(&save_backups_dir(app_data_dir, &save_id)).len()
+                + backup::list_snapshots(&launch_backups_dir(app_data_dir, &save_id)).len();
+            Some(summary)
+        })
+        .collect();
+
+    farms.sort_by_key(|farm| std::cmp::Reverse(farm.last_played));
+    farms
+}
+
+/// Where backups of one save are kept
+pub fn save_backups_dir(app_data_dir: &Path, save_id: &str) -> PathBuf {
+    app_data_dir.join("save_backups").join(save_id)
+}
+
+/// Where backups made before launching a save are kept
+fn launch_backups_dir(app_data_dir: &Path, save_id: &str) -> PathBuf {
+    save_backups_dir(app_data_dir, save_id).join("launch")
+}
+
+fn to_save_backup(save_id: &str, path: &Path, on_launch: bool) -> SaveBackup {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let created_at = file_name
+        .trim_end_matches(".zip")
+        .split('-')
+        .next()
+        .and_then(|secs| secs.parse::<i64>().ok())
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single());
+    SaveBackup {
+        save_id: save_id.to_string(),
+        name: if on_launch {
+            format!("{}{}", LAUNCH_BACKUP_PREFIX, file_name)
+        } else {
+            file_name
+        },
+        created_at,
+        size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        on_launch,
+    }
+}
+
+/// Backups of a save, newest first
+pub fn list_save_backups(app_data_dir: &Path, save_id: &str) -> Result<Vec<SaveBackup>, String> {
+    check_save_id(save_id)?;
+    let mut backups: Vec<SaveBackup> = [
+        (save_backups_dir(app_data_dir, save_id), false),
+        (launch_backups_dir(app_data_dir, save_id), true),
+    ]
+    .iter()
+    .flat_map(|(dir, on_launch)| {
+        backup::list_snapshots(dir)
+            .into_iter()
+            .filter(|path| path.is_file())
+            .map(|path| to_save_backup(save_id, &path, *on_launch))
+    })
+    .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+fn snapshot_save(saves_dir: &Path, save_id: &str, backups_dir: &Path, on_launch: bool) -> Result<SaveBackup, String> {
+    check_save_id(save_id)?;
+    let save_dir = saves_dir.join(save_id);
+    if !save_dir.join(save_id).is_file() {
+        return Err(format!("Save not found: {}", save_id));
+    }
+
+    let path = backup::create_snapshot(&save_dir, backups_dir, &BackupFormat::Zip)
+        .map_err(|e| format!("Failed to back up {}: {}", save_id, e))?;
+    info!("💾 Backed up save {} to {}", save_id, path.display());
+    Ok(to_save_backup(save_id, &path, on_launch))
+}
+
+/// Zip the save folder `saves_dir/<save_id>` into a timestamped backup
+pub fn backup_save(saves_dir: &Path, app_data_dir: &Path, save_id: &str) -> Result<SaveBackup, String> {
+    snapshot_save(saves_dir, save_id, &save_backups_dir(app_data_dir, save_id), false)
+}
+
+#[derive(Debug, Clone)]
+pub struct FailedSaveBackup {
+    pub save_id: String,
+    pub error: String,
+}
+
+/// Back up the saves `mode` asks for before a modded session, then drop
+/// the oldest launch backups of each beyond `keep` (0 keeps all). Backups
+/// made by hand are never pruned. Returns how many saves were backed up and
+/// the ones that failed.
+pub fn backup_before_launch(
+    app_data_dir: &Path,
+    mode: SaveBackupsOnLaunch,
+    keep: usize,
+) -> Result<(usize, Vec<FailedSaveBackup>), String> {
+    let save_ids: Vec<String> = match mode {
+        SaveBackupsOnLaunch::Off => return Ok((0, Vec::new())),
+        SaveBackupsOnLaunch::Latest => list_farms(app_data_dir).into_iter().take(1).map(|farm| farm.id).collect(),
+        SaveBackupsOnLaunch::All => list_saves(),
+    };
+    let dir = saves_dir().ok_or("Could not locate the Stardew Valley Saves folder")?;
+    Ok(backup_saves_for_launch(&dir, app_data_dir, &save_ids, keep))
+}
+
+fn backup_saves_for_launch(
+    saves_dir: &Path,
+    app_data_dir: &Path,
+    save_ids: &[String],
+    keep: usize,
+) -> (usize, Vec<FailedSaveBackup>) {
+    let mut failed = Vec::new();
+    for save_id in save_ids {
+        let backups_dir = launch_backups_dir(app_data_dir, save_id);
+        let result = snapshot_save(saves_dir, save_id, &backups_dir, true).and_then(|_| {
+            if keep > 0 {
+                backup::prune_snapshots(&backups_dir, keep)
+                    .map_err(|e| format!("Failed to prune the backups of {}: {}", save_id, e))?;
+            }
+            Ok(())
+        });
+        if let Err(error) = result {
+            failed.push(FailedSaveBackup {
+                save_id: save_id.clone(),
+                error,
+            });
+        }
+    }
+    (save_ids.len() - failed.len(), failed)
+}
+
+/// Put a backup back in place of the save. The current save is backed up
+/// first (returned, if there was one), and the backup is unpacked next to it
+/// before the folders are swapped, so a failed restore leaves the save as it
+/// was. Close the game first: it would overwrite the restored save.
+pub fn restore_save_backup(
+    saves_dir: &Path,
+    app_data_dir: &Path,
+    save_id: &str,
+    backup_name: &str,
+) -> Result<Option<SaveBackup>, String> {
+    check_save_id(save_id)?;
+    check_save_id(backup_name)?;
+    let backup_path = match backup_name.strip_prefix(LAUNCH_BACKUP_PREFIX) {
+        Some(file_name) => launch_backups_dir(app_data_dir, save_id).join(file_name),
+        None => save_backups_dir(app_data_dir, save_id).join(backup_name),
+    };
+    if !backup_path.is_file() {
+        return Err(format!("Backup not found: {}", backup_name));
+    }
+
+    let save_dir = saves_dir.join(save_id);
+    let previous = if save_dir.join(save_id).is_file() {
+        Some(backup_save(saves_dir, app_data_dir, save_id)?)
+    } else {
+        None
+    };
+
+    // Hidden names, so the game and list_saves skip them if one is left over
+    let unpacked = saves_dir.join(format!(".{}.restoring", save_id));
+    let replaced = saves_dir.join(format!(".{}.replaced", save_id));
+    for leftover in [&unpacked, &replaced] {
+        if leftover.exists() {
+            fs::remove_dir_all(leftover).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let unpack = backup::restore_snapshot(&backup_path, &unpacked)
+        .map_err(|e| format!("Failed to unpack {}: {}", backup_name, e))
+        .and_then(|_| {
+            if unpacked.join(save_id).is_file() {
+                Ok(())
+            } else {
+                Err(format!("{} doesn't contain the save {}", backup_name, save_id))
+            }
+        });
+    if let Err(e) = unpack {
+        let _ = fs::remove_dir_all(&unpacked);
+        return Err(e);
+    }
+
+    if save_dir.exists() {
+        fs::rename(&save_dir, &replaced).map_err(|e| format!("Failed to move the current save aside: {}", e))?;
+    }
+    if let Err(e) = fs::rename(&unpacked, &save_dir) {
+        let _ = fs::rename(&replaced, &save_dir);
+        return Err(format!("Failed to restore {}: {}", save_id, e));
+    }
+    let _ = fs::remove_dir_all(&replaced);
+
+    info!("♻️ Restored save {} from {}", save_id, backup_name);
+    Ok(previous)
+}
+
+/// Work out which mods a save expects that aren't currently active.
+///
+/// SMAPI mods tag the data they write into saves with their UniqueID, most
+/// visibly as `modData` keys like `Author.Mod/Key` and as 1.6 item ids
+/// prefixed with the mod's UniqueID. Disabled mods whose UniqueID appears
+/// anywhere in the save are reported, as are `modData` owners that aren't
+/// installed at all.
+pub fn find_save_requirements(save_content: &str, installed: &[Mod]) -> Vec<SaveModRequirement> {
+    let lowered = save_content.to_lowercase();
+    let mut requirements = Vec::new();
+
+    // Installed but disabled mods that left their mark in the save
+    let enabled: BTreeSet<String> = installed
+        .iter()
+        .filter(|m| m.is_enabled)
+        .map(|m| m.unique_id.to_lowercase())
+        .collect();
+
+    let mut reported = BTreeSet::new();
+    for m in installed.iter().filter(|m| !m.is_enabled) {
+        let id = m.unique_id.to_lowercase();
+        if enabled.contains(&id) || reported.contains(&id) {
+            continue;
+        }
+        if lowered.contains(&id) {
+            reported.insert(id);
+            requirements.push(SaveModRequirement {
+                unique_id: m.unique_id.clone(),
+                mod_name: Some(m.name.clone()),
+                status: RequirementStatus::Disabled,
+            });
+        }
+    }
+
+    // modData owners that aren't installed at all
+    let known: HashMap<String, &Mod> = installed
+        .iter()
+        .map(|m| (m.unique_id.to_lowercase(), m))
+        .collect();
+
+    let mod_data_key = Regex::new(r"<key><string>([A-Za-z0-9_\-]+(?:\.[A-Za-z0-9_\-]+)+)/").unwrap();
+    let mut missing = BTreeSet::new();
+    for capture in mod_data_key.captures_iter(save_content) {
+        let id = capture[1].to_string();
+        if !known.contains_key(&id.to_lowercase()) {
+            missing.insert(id);
+        }
+    }
+
+    requirements.extend(missing.into_iter().map(|unique_id| SaveModRequirement {
+        unique_id,
+        mod_name: None,
+        status: RequirementStatus::Missing,
+    }));
+
+    requirements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_mod(unique_id: &str, is_enabled: bool) -> Mod {
+        Mod {
+            is_enabled,
+            ..crate::models::test_mod(unique_id)
+        }
+    }
+
+    #[test]
+    fn test_parse_save_game_info() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?><Farmer><name>Abigail &amp; Co</name>
+            <items><Item><name>Parsnip</name></Item></items><farmName>Sunny</farmName>
+            <millisecondsPlayed>7200000</millisecondsPlayed><dayOfMonthForSaveGame>12</dayOfMonthForSaveGame>
+            <seasonForSaveGame>2</seasonForSaveGame><yearForSaveGame>3</yearForSaveGame>
+            <saveTime>1440</saveTime></Farmer>"#;
+
+        let summary = parse_save_game_info("Sunny_123", xml);
+
+        assert_eq!(summary.farmer_name, "Abigail & Co");
+        assert_eq!(summary.farm_name, "Sunny");
+        assert_eq!(summary.play_time_ms, 7_200_000);
+        assert_eq!((summary.year, summary.season.as_deref(), summary.day), (Some(3), Some("fall"), Some(12)));
+        assert_eq!(summary.last_played, Utc.with_ymd_and_hms(2012, 6, 23, 0, 0, 0).single());
+    }
+
+    #[test]
+    fn test_backup_and_restore_save() {
+        let root = std::env::temp_dir().join(format!("tc_saves_{}", uuid::Uuid::new_v4()));
+        let (saves, app_data) = (root.join("Saves"), root.join("data"));
+        let save_dir = saves.join("Farm_1");
+        fs::create_dir_all(&save_dir).unwrap();
+        fs::write(save_dir.join("Farm_1"), "day 1").unwrap();
+        fs::write(save_dir.join("SaveGameInfo"), "<Farmer />").unwrap();
+
+        let backup = backup_save(&saves, &app_data, "Farm_1").unwrap();
+        fs::write(save_dir.join("Farm_1"), "day 2, broken").unwrap();
+
+        let previous = restore_save_backup(&saves, &app_data, "Farm_1", &backup.name).unwrap();
+        assert!(previous.is_some());
+        assert_eq!(fs::read_to_string(save_dir.join("Farm_1")).unwrap(), "day 1");
+        assert!(save_dir.join("SaveGameInfo").is_file());
+        assert_eq!(list_save_backups(&app_data, "Farm_1").unwrap().len(), 2);
+        assert!(restore_save_backup(&saves, &app_data, "Farm_1", "../escape.zip").is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_launch_backups_pruned_apart() {
+        let root = std::env::temp_dir().join(format!("tc_launch_backups_{}", uuid::Uuid::new_v4()));
+        let (saves, app_data) = (root.join("Saves"), root.join("data"));
+        fs::create_dir_all(saves.join("Farm_1")).unwrap();
+        fs::write(saves.join("Farm_1").join("Farm_1"), "day 1").unwrap();
+
+        backup_save(&saves, &app_data, "Farm_1").unwrap();
+        let save_ids = vec!["Farm_1".to_string(), "Missing_2".to_string()];
+        for _ in 0..3 {
+            let (count, failed) = backup_saves_for_launch(&saves, &app_data, &save_ids, 1);
+            assert_eq!(count, 1);
+            assert_eq!(failed.len(), 1);
+            assert_eq!(failed[0].save_id, "Missing_2");
+        }
+
+        let backups = list_save_backups(&app_data, "Farm_1").unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups.iter().filter(|b| b.on_launch).count(), 1);
+        let launch = backups.iter().find(|b| b.on_launch).unwrap();
+        assert!(launch.name.starts_with(LAUNCH_BACKUP_PREFIX));
+        restore_save_backup(&saves, &app_data, "Farm_1", &launch.name).unwrap();
+        assert_eq!(fs::read_to_string(saves.join("Farm_1").join("Farm_1")).unwrap(), "day 1");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_find_save_requirements() {
+        let save = r#"<SaveGame><modData><item><key><string>Pathoschild.ChestsAnywhere/Name</string></key>
+            <value><string>Fridge</string></value></item>
+            <item><key><string>spacechase0.SpaceCore/Skills</string></key></item></modData>
+            <itemId>FlashShifter.SVE_Cherry</itemId></SaveGame>"#;
+
+        let installed = vec![
+            test_mod("Pathoschild.ChestsAnywhere", true),
+            test_mod("FlashShifter.SVE", false),
+            test_mod("Unused.Mod", false),
+        ];
+
+        let requirements = find_save_requirements(save, &installed);
+
+        assert_eq!(requirements.len(), 2);
+        assert_eq!(requirements[0].unique_id, "FlashShifter.SVE");
+        assert_eq!(requirements[0].status, RequirementStatus::Disabled);
+        assert_eq!(requirements[1].unique_id, "spacechase0.SpaceCore");
+        assert_eq!(requirements[1].status, RequirementStatus::Missing);
+    }
+}
@@ -0,0 +1,138 @@
+//! User scripts run after installs and updates, e.g. to sync the Mods folder
+//! to a server. Off unless `scriptHooksEnabled` is set. Each script gets the
+//! mod's details as environment variables:
+//!
+//! - `TREASURE_CHEST_EVENT`: `AfterInstall` or `AfterUpdate`
+//! - `TREASURE_CHEST_MOD_NAME`, `TREASURE_CHEST_MOD_UNIQUE_ID`
+//! - `TREASURE_CHEST_MOD_VERSION`, `TREASURE_CHEST_MOD_PATH`
+//!
+//! After-install hooks run for every install, updates included; after-update
+//! hooks run once "Update all" finished a mod.
+
+use crate::events::AppEvent;
+use crate::settings::{HookEvent, Settings};
+use crate::update_all::UpdateStage;
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+/// What a hook is told about the mod
+#[derive(Debug, Default, PartialEq)]
+struct HookMod {
+    name: String,
+    unique_id: String,
+    version: String,
+    path: String,
+}
+
+impl HookMod {
+    fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        vec![
+            ("TREASURE_CHEST_EVENT", format!("{:?}", event)),
+            ("TREASURE_CHEST_MOD_NAME", self.name.clone()),
+            ("TREASURE_CHEST_MOD_UNIQUE_ID", self.unique_id.clone()),
+            ("TREASURE_CHEST_MOD_VERSION", self.version.clone()),
+            ("TREASURE_CHEST_MOD_PATH", self.path.clone()),
+        ]
+    }
+}
+
+/// The hook event an app event triggers, and the mod it's about. Updates
+/// only name the mod, the rest is looked up in `game_path`.
+fn hook_for(event: &AppEvent, game_path: &Path) -> Option<(HookEvent, HookMod)> {
+    match event {
+        AppEvent::ModInstalled(result) => Some((
+            HookEvent::AfterInstall,
+            HookMod {
+                name: result.mod_name.clone(),
+                unique_id: result.unique_id.clone(),
+                version: result.version.clone(),
+                path: result.install_path.to_string_lossy().to_string(),
+            },
+        )),
+        AppEvent::ModUpdateProgress(progress) if progress.stage == UpdateStage::Updated => {
+            let installed = crate::mod_installer::scan_mods(game_path)
+                .into_iter()
+                .find(|m| m.unique_id.eq_ignore_ascii_case(&progress.unique_id));
+            Some((
+                HookEvent::AfterUpdate,
+                HookMod {
+                    name: progress.name.clone(),
+                    unique_id: progress.unique_id.clone(),
+                    version: installed.as_ref().map(|m| m.version.clone()).unwrap_or_default(),
+                    path: installed.map(|m| m.path).unwrap_or_default(),
+                },
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Run the scripts configured for the event in the background
+pub fn run(app_handle: &AppHandle, event: &AppEvent) {
+    if !matches!(event, AppEvent::ModInstalled(_) | AppEvent::ModUpdateProgress(_)) {
+        return;
+    }
+    let Ok(settings) = Settings::load(app_handle) else {
+        return;
+    };
+    if !settings.script_hooks_enabled || settings.script_hooks.is_empty() {
+        return;
+    }
+
+    let event = event.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some((hook_event, hook_mod)) = hook_for(&event, Path::new(&settings.game_path)) else {
+            return;
+        };
+        for hook in settings.script_hooks.iter().filter(|h| h.event == hook_event) {
+            info!("🪝 Running {:?} hook {} for {}", hook_event, hook.command, hook_mod.unique_id);
+            match Command::new(&hook.command).envs(hook_mod.env(hook_event)).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => warn!("Hook {} exited with {}", hook.command, status),
+                Err(e) => warn!("Failed to run hook {}: {}", hook.command, e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_installer::{InstallResult, InstallStrategy};
+    use crate::update_all::ModUpdateProgress;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_hook_env() {
+        let installed = AppEvent::ModInstalled(InstallResult {
+            mod_name: "Lookup Anything".to_string(),
+            version: "1.37.0".to_string(),
+            unique_id: "Pathoschild.LookupAnything".to_string(),
+            install_path: PathBuf::from("/game/Mods/LookupAnything"),
+            strategy: InstallStrategy::SingleFolder,
+            manifests: Vec::new(),
+            skipped_files: Vec::new(),
+            parent_unique_id: None,
+        });
+        let (event, hook_mod) = hook_for(&installed, Path::new("/no/such/game")).unwrap();
+        assert_eq!(event, HookEvent::AfterInstall);
+        let env = hook_mod.env(event);
+        assert!(env.contains(&("TREASURE_CHEST_EVENT", "AfterInstall".to_string())));
+        assert!(env.contains(&("TREASURE_CHEST_MOD_PATH", "/game/Mods/LookupAnything".to_string())));
+
+        let progress = |stage| {
+            AppEvent::ModUpdateProgress(ModUpdateProgress {
+                unique_id: "Pathoschild.LookupAnything".to_string(),
+                name: "Lookup Anything".to_string(),
+                stage,
+                message: None,
+            })
+        };
+        assert!(hook_for(&progress(UpdateStage::Downloading), Path::new("/no/such/game")).is_none());
+        let (event, hook_mod) = hook_for(&progress(UpdateStage::Updated), Path::new("/no/such/game")).unwrap();
+        assert_eq!(event, HookEvent::AfterUpdate);
+        assert_eq!(hook_mod.unique_id, "Pathoschild.LookupAnything");
+    }
+}
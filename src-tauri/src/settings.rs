@@ -1,3 +1,4 @@
+use crate::app_error::AppError;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
@@ -25,6 +26,128 @@ pub struct Settings {
     pub delete_after_install: bool,
     #[serde(rename = "coreFrameworks", default)]
     pub core_frameworks: Vec<String>,
+    #[serde(rename = "nxmHandlerEnabled", default)]
+    pub nxm_handler_enabled: bool,
+    #[serde(rename = "debugApiPayloads", default)]
+    pub debug_api_payloads: bool,
+    #[serde(rename = "backupFormat", default)]
+    pub backup_format: BackupFormat,
+    #[serde(rename = "nexusAccounts", default)]
+    pub nexus_accounts: Vec<NexusAccount>,
+    #[serde(rename = "activeAccount", default)]
+    pub active_account: Option<String>,
+    #[serde(rename = "maxConcurrentDownloads", default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    #[serde(rename = "endorsementReminders", default)]
+    pub endorsement_reminders: EndorsementReminders,
+    /// How many times a download is retried after a transient failure
+    #[serde(rename = "downloadRetryAttempts", default = "default_download_retry_attempts")]
+    pub download_retry_attempts: u32,
+    #[serde(rename = "checkUpdatesOnStartup", default)]
+    pub check_updates_on_startup: bool,
+    /// Folder name for installed mods, e.g. "{ModName} [{UniqueID}]".
+    /// Empty keeps the name from the archive.
+    #[serde(rename = "installNameTemplate", default)]
+    pub install_name_template: String,
+    /// Serve the local HTTP API for external tools on 127.0.0.1
+    #[serde(rename = "localApiEnabled", default)]
+    pub local_api_enabled: bool,
+    #[serde(rename = "localApiPort", default = "default_local_api_port")]
+    pub local_api_port: u16,
+    /// Download missing required dependencies before installing a mod
+    #[serde(rename = "installDependencies", default = "default_install_dependencies")]
+    pub install_dependencies: bool,
+    /// Discord-compatible webhook for install, update and failure
+    /// notifications. Empty disables them.
+    #[serde(rename = "webhookUrl", default)]
+    pub webhook_url: String,
+    /// What to do before a large download starts on a metered connection
+    #[serde(rename = "meteredDownloads", default)]
+    pub metered_downloads: MeteredDownloads,
+    /// Advanced: run `script_hooks` at all. Scripts run with the user's
+    /// permissions, so this stays off until turned on explicitly.
+    #[serde(rename = "scriptHooksEnabled", default)]
+    pub script_hooks_enabled: bool,
+    #[serde(rename = "scriptHooks", default)]
+    pub script_hooks: Vec<ScriptHook>,
+    /// Inspect the library without changing it: installs, updates, deletes
+    /// and toggles are refused while scans, diagnostics and exports work
+    #[serde(rename = "readOnlyMode", default)]
+    pub read_only_mode: bool,
+    /// Snapshots kept per mod when maintenance prunes backups; 0, the
+    /// default, keeps all
+    #[serde(rename = "backupsPerMod", default)]
+    pub backups_per_mod: usize,
+    /// Folders under Mods the app lists but never changes, e.g. mods under
+    /// development. Relative to Mods; a folder covers everything inside it.
+    #[serde(rename = "unmanagedFolders", default)]
+    pub unmanaged_folders: Vec<String>,
+    /// The Mods folder has been imported into the mod registry once
+    #[serde(rename = "libraryMigrated", default)]
+    pub library_migrated: bool,
+    /// Where archives are downloaded; empty keeps them in the app data folder
+    #[serde(rename = "downloadsDir", default)]
+    pub downloads_dir: String,
+    #[serde(rename = "installMode", default)]
+    pub install_mode: InstallMode,
+    /// Least severe messages written to the app log
+    #[serde(rename = "logLevel", default)]
+    pub log_level: LogLevel,
+    /// Saves zipped before the game is launched with mods
+    #[serde(rename = "saveBackupsOnLaunch", default)]
+    pub save_backups_on_launch: SaveBackupsOnLaunch,
+    /// Launch backups kept per save; 0 keeps all. Ones made by hand are never pruned.
+    #[serde(rename = "saveBackupsToKeep", default = "default_save_backups_to_keep")]
+    pub save_backups_to_keep: usize,
+    /// How disabling a mod renames its folder
+    #[serde(rename = "disabledFolderStyle", default)]
+    pub disabled_folder_style: DisabledFolderStyle,
+    /// Apply a save's profile when launching it with another one
+    #[serde(rename = "autoSwitchProfile", default)]
+    pub auto_switch_profile: bool,
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    1
+}
+
+fn default_local_api_port() -> u16 {
+    47321
+}
+
+fn default_download_retry_attempts() -> u32 {
+    3
+}
+
+fn default_install_dependencies() -> bool {
+    true
+}
+
+fn default_save_backups_to_keep() -> usize {
+    10
+}
+
+/// A named Nexus Mods credential, for machines shared by several players
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NexusAccount {
+    pub name: String,
+    #[serde(rename = "apiKey")]
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    AfterInstall,
+    AfterUpdate,
+}
+
+/// A local script run after an install or update, with the mod's details
+/// in `TREASURE_CHEST_*` environment variables
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScriptHook {
+    pub event: HookEvent,
+    /// Path of the script or program; it's run directly, not through a shell
+    pub command: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -41,6 +164,81 @@ pub enum Language {
     BahasaIndonesia,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum BackupFormat {
+    /// Plain folders, with unchanged files hardlinked between snapshots
+    #[default]
+    Folder,
+    Zip,
+    Zstd,
+}
+
+/// What to do with a mod's Nexus page after installing it, so users can
+/// endorse the authors
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub enum EndorsementReminders {
+    #[default]
+    Off,
+    /// Remember the page for the weekly "not endorsed yet" report
+    Record,
+    /// Open the page in the browser right away (and record it)
+    Open,
+}
+
+/// Handling of large downloads while the connection is metered (Windows
+/// and macOS only)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum MeteredDownloads {
+    /// Download as usual
+    Allow,
+    /// Hold the download and ask whether to start it
+    #[default]
+    Confirm,
+    /// Pause the download until it's resumed by hand
+    Pause,
+}
+
+/// Where installed mod files live
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum InstallMode {
+    /// Copied straight into Mods
+    #[default]
+    Direct,
+    /// Kept in a staging folder next to Mods and hardlinked into it, so a
+    /// mod can be taken out of Mods and put back without copying
+    Staged,
+}
+
+/// How a disabled mod's folder is named. SMAPI skips both; other managers
+/// (e.g. Stardrop) use the prefix.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum DisabledFolderStyle {
+    /// `LookupAnything.disabled`
+    #[default]
+    Suffix,
+    /// `.LookupAnything`
+    DotPrefix,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum SaveBackupsOnLaunch {
+    #[default]
+    Off,
+    /// Only the most recently played farm
+    Latest,
+    All,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum ModGroups {
     None,
@@ -67,11 +265,86 @@ impl Default for Settings {
                 "Json Assets".to_string(),
                 "SpaceCore".to_string(),
             ],
+            nxm_handler_enabled: false,
+            debug_api_payloads: false,
+            backup_format: BackupFormat::Folder,
+            nexus_accounts: Vec::new(),
+            active_account: None,
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            endorsement_reminders: EndorsementReminders::Off,
+            download_retry_attempts: default_download_retry_attempts(),
+            check_updates_on_startup: false,
+            install_name_template: String::new(),
+            local_api_enabled: false,
+            local_api_port: default_local_api_port(),
+            install_dependencies: default_install_dependencies(),
+            webhook_url: String::new(),
+            metered_downloads: MeteredDownloads::Confirm,
+            script_hooks_enabled: false,
+            script_hooks: Vec::new(),
+            read_only_mode: false,
+            backups_per_mod: 0,
+            unmanaged_folders: Vec::new(),
+            library_migrated: false,
+            downloads_dir: String::new(),
+            install_mode: InstallMode::Direct,
+            log_level: LogLevel::Info,
+            save_backups_on_launch: SaveBackupsOnLaunch::Off,
+            save_backups_to_keep: default_save_backups_to_keep(),
+            disabled_folder_style: DisabledFolderStyle::Suffix,
+            auto_switch_profile: false,
         }
     }
 }
 
 impl Settings {
+    /// The folder downloads go to: `downloads_dir`, or `downloads/nexus`
+    /// under the app data folder when it isn't set
+    pub fn downloads_path(&self, app_data_dir: &Path) -> PathBuf {
+        if self.downloads_dir.trim().is_empty() {
+            app_data_dir.join("downloads").join("nexus")
+        } else {
+            PathBuf::from(self.downloads_dir.trim())
+        }
+    }
+
+    /// Refuse changes to the Mods folder while read-only mode is on
+    pub fn ensure_writable(&self) -> Result<(), AppError> {
+        if self.read_only_mode {
+            return Err(AppError::ReadOnlyMode);
+        }
+        Ok(())
+    }
+
+    /// Whether a mod folder is in one of `unmanaged_folders`. Disabling a mod,
+    /// either way, doesn't change the answer.
+    pub fn is_unmanaged(&self, mod_path: &Path) -> bool {
+        let Ok(relative) = mod_path.strip_prefix(Path::new(&self.game_path).join("Mods")) else {
+            return false;
+        };
+        let relative: Vec<String> = relative
+            .components()
+            .map(|c| crate::mod_installer::enabled_name(&c.as_os_str().to_string_lossy()).to_lowercase())
+            .collect();
+
+        self.unmanaged_folders.iter().any(|folder| {
+            let folder: Vec<String> = folder
+                .split(['/', '\\'])
+                .filter(|part| !part.is_empty())
+                .map(|part| crate::mod_installer::enabled_name(part).to_lowercase())
+                .collect();
+            !folder.is_empty() && relative.starts_with(&folder)
+        })
+    }
+
+    /// Refuse to change a mod in one of `unmanaged_folders`
+    pub fn ensure_managed(&self, mod_path: &Path) -> Result<(), AppError> {
+        if self.is_unmanaged(mod_path) {
+            return Err(AppError::UnmanagedFolder);
+        }
+        Ok(())
+    }
+
     /// Get the settings file path in the Tauri app data directory
     pub fn get_settings_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
         let app_data_dir = app_handle
@@ -101,6 +374,23 @@ impl Settings {
             .map_err(|e| format!("Failed to parse settings: {}", e))
     }
 
+    /// Get the API key of the active account, falling back to the single
+    /// `nexusApiKey` when no named account is selected
+    pub fn active_api_key(&self) -> &str {
+        self.active_account
+            .as_ref()
+            .and_then(|name| self.nexus_accounts.iter().find(|a| &a.name == name))
+            .map(|account| account.api_key.as_str())
+            .unwrap_or(&self.nexus_api_key)
+    }
+
+    /// Get the name of the active account, if it exists
+    pub fn active_account_name(&self) -> Option<String> {
+        self.active_account
+            .clone()
+            .filter(|name| self.nexus_accounts.iter().any(|a| &a.name == name))
+    }
+
     /// Save settings to disk
     pub fn save(&self, app_handle: &tauri::AppHandle) -> Result<(), String> {
         let settings_path = Self::get_settings_path(app_handle)?;
@@ -108,36 +398,152 @@ impl Settings {
         let json = serde_json::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        fs::write(&settings_path, json)
+        // Holds the Nexus API keys
+        write_private(&settings_path, json.as_bytes())
             .map_err(|e| format!("Failed to write settings file: {}", e))?;
 
         Ok(())
     }
 }
 
-/// Auto-detect Stardew Valley game path from Steam installation
+/// Write a file only the current user can read (0600 on Unix, tightening an
+/// existing file too). Windows profiles are private already.
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents)
+    }
+    #[cfg(not(unix))]
+    {
+        fs::write(path, contents)
+    }
+}
+
+/// Auto-detect Stardew Valley game path from a Steam, GOG or Xbox app installation
 /// Returns the first valid path found, or None if not found
 pub fn auto_detect_game_path() -> Option<PathBuf> {
-    let steam_paths = get_steam_paths();
+    get_steam_paths()
+        .into_iter()
+        .chain(get_gog_paths())
+        .chain(get_xbox_paths())
+        .find(|path| validate_game_path(path))
+}
 
-    for path in steam_paths {
-        if validate_game_path(&path) {
-            return Some(path);
+/// Get platform-specific Steam installation paths, in every Steam library
+fn get_steam_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for root in steam_roots() {
+        for library in steam_libraries(&root) {
+            let path = library.join("steamapps").join("common").join("Stardew Valley");
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
         }
     }
-
-    None
+    paths
 }
 
-/// Get platform-specific Steam installation paths
-fn get_steam_paths() -> Vec<PathBuf> {
+/// GOG product id of Stardew Valley, the key GOG Galaxy registers it under
+#[cfg(target_os = "windows")]
+const GOG_GAME_ID: &str = "1453375253";
+
+/// Where the GOG installers put the game by default
+fn get_gog_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
     #[cfg(target_os = "windows")]
     {
-        // Windows Steam paths
-        paths.push(PathBuf::from(r"C:\Program Files (x86)\Steam\steamapps\common\Stardew Valley"));
-        paths.push(PathBuf::from(r"C:\Program Files\Steam\steamapps\common\Stardew Valley"));
+        // Galaxy records the folder the user picked, wherever it is
+        paths.extend(windows_registry::gog_game_path(GOG_GAME_ID));
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Some(program_files) = std::env::var_os(var) {
+                paths.push(PathBuf::from(program_files).join("GOG Galaxy").join("Games").join("Stardew Valley"));
+            }
+        }
+        if let Some(drive) = std::env::var_os("SystemDrive") {
+            let root = PathBuf::from(format!("{}\\", drive.to_string_lossy()));
+            paths.push(root.join("GOG Games").join("Stardew Valley"));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // The GOG Linux installer keeps the game files in a "game" subfolder
+        if let Some(home) = std::env::var_os("HOME") {
+            let home_path = PathBuf::from(home);
+            paths.push(home_path.join("GOG Games/Stardew Valley/game"));
+        }
+        // The Windows installer run through Wine
+        for prefix in wine_prefixes() {
+            let drive_c = prefix.join("drive_c");
+            paths.push(drive_c.join("GOG Games").join("Stardew Valley"));
+            paths.push(drive_c.join("Program Files (x86)").join("GOG Galaxy").join("Games").join("Stardew Valley"));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // The game path is the app bundle; SMAPI and the game live in Contents/MacOS
+        paths.push(PathBuf::from("/Applications/Stardew Valley.app"));
+    }
+
+    paths
+}
+
+/// Where the Xbox app (PC Game Pass, Microsoft Store) installs the game. It
+/// lets players pick the drive and puts the game files in a "Content"
+/// subfolder; older installs use the moddable copy outside WindowsApps.
+fn get_xbox_paths() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut paths = Vec::new();
+        for drive in b'C'..=b'Z' {
+            let xbox_games = PathBuf::from(format!("{}:\\", drive as char)).join("XboxGames");
+            if xbox_games.is_dir() {
+                paths.push(xbox_games.join("Stardew Valley").join("Content"));
+            }
+        }
+        for var in ["ProgramFiles", "ProgramW6432"] {
+            if let Some(program_files) = std::env::var_os(var) {
+                let path = PathBuf::from(program_files).join("ModifiableWindowsApps").join("Stardew Valley");
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Folders Steam may be installed in
+fn steam_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        // Ask Steam where it lives rather than guessing "C:\Program Files (x86)",
+        // which is named differently on localized or relocated installs
+        roots.extend(windows_registry::steam_path());
+        for var in ["ProgramFiles(x86)", "ProgramFiles", "ProgramW6432"] {
+            if let Some(program_files) = std::env::var_os(var) {
+                roots.push(PathBuf::from(program_files).join("Steam"));
+            }
+        }
     }
 
     #[cfg(target_os = "linux")]
@@ -146,10 +552,10 @@ fn get_steam_paths() -> Vec<PathBuf> {
         if let Some(home) = std::env::var_os("HOME") {
             let home_path = PathBuf::from(home);
 
-            paths.push(home_path.join(".local/share/Steam/steamapps/common/Stardew Valley"));
-            paths.push(home_path.join(".steam/steam/steamapps/common/Stardew Valley"));
+            roots.push(home_path.join(".local/share/Steam"));
+            roots.push(home_path.join(".steam/steam"));
             // Flatpak Steam
-            paths.push(home_path.join(".var/app/com.valvesoftware.Steam/.local/share/Steam/steamapps/common/Stardew Valley"));
+            roots.push(home_path.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
         }
     }
 
@@ -158,11 +564,122 @@ fn get_steam_paths() -> Vec<PathBuf> {
         // macOS Steam path
         if let Some(home) = std::env::var_os("HOME") {
             let home_path = PathBuf::from(home);
-            paths.push(home_path.join("Library/Application Support/Steam/steamapps/common/Stardew Valley"));
+            roots.push(home_path.join("Library/Application Support/Steam"));
         }
     }
 
-    paths
+    roots
+}
+
+/// Steam app id of Stardew Valley, which names its Proton prefix
+#[cfg(target_os = "linux")]
+pub(crate) const STEAM_APP_ID: &str = "413150";
+
+/// Wine prefixes the Windows build may run in on Linux: the one Proton
+/// creates for the game in each Steam library, and the default `~/.wine`
+#[cfg(target_os = "linux")]
+fn wine_prefixes() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = steam_roots()
+        .iter()
+        .flat_map(|root| steam_libraries(root))
+        .map(|library| library.join("steamapps").join("compatdata").join(STEAM_APP_ID).join("pfx"))
+        .collect();
+    if let Some(home) = std::env::var_os("HOME") {
+        candidates.push(PathBuf::from(home).join(".wine"));
+    }
+
+    let mut prefixes: Vec<PathBuf> = Vec::new();
+    for prefix in candidates {
+        if prefix.join("drive_c").is_dir() && !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+    prefixes
+}
+
+/// The game's data folder inside a Wine prefix, under whichever user ran it
+/// ("steamuser" for Proton)
+#[cfg(target_os = "linux")]
+fn prefix_data_dir(prefix: &Path) -> Option<PathBuf> {
+    let mut users: Vec<PathBuf> = fs::read_dir(prefix.join("drive_c").join("users"))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    users.sort();
+    users
+        .into_iter()
+        .map(|user| user.join("AppData").join("Roaming").join("StardewValley"))
+        .find(|dir| dir.is_dir())
+}
+
+/// The Steam install itself and the extra libraries it lists in
+/// `steamapps/libraryfolders.vdf` (games on other drives)
+fn steam_libraries(steam_root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_root.to_path_buf()];
+    if let Ok(vdf) = fs::read_to_string(steam_root.join("steamapps").join("libraryfolders.vdf")) {
+        libraries.extend(parse_library_folders(&vdf).into_iter().filter(|l| l != steam_root));
+    }
+    libraries
+}
+
+/// The `"path"` entries of a libraryfolders.vdf
+fn parse_library_folders(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("\"path\"")?.trim();
+            let value = rest.strip_prefix('"')?.strip_suffix('"')?;
+            Some(PathBuf::from(value.replace("\\\\", "\\")))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+mod windows_registry {
+    use std::path::PathBuf;
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    /// Where the Steam client says it's installed
+    pub fn steam_path() -> Option<PathBuf> {
+        read_string(HKEY_CURRENT_USER, r"Software\Valve\Steam", "SteamPath")
+            .or_else(|| read_string(HKEY_LOCAL_MACHINE, r"SOFTWARE\WOW6432Node\Valve\Steam", "InstallPath"))
+            .or_else(|| read_string(HKEY_LOCAL_MACHINE, r"SOFTWARE\Valve\Steam", "InstallPath"))
+            .map(PathBuf::from)
+    }
+
+    /// Install folder GOG Galaxy (or the offline installer) registered for a game
+    pub fn gog_game_path(game_id: &str) -> Option<PathBuf> {
+        let key = format!(r"SOFTWARE\WOW6432Node\GOG.com\Games\{}", game_id);
+        read_string(HKEY_LOCAL_MACHINE, &key, "path")
+            .or_else(|| read_string(HKEY_LOCAL_MACHINE, &format!(r"SOFTWARE\GOG.com\Games\{}", game_id), "path"))
+            .map(PathBuf::from)
+    }
+
+    fn read_string(root: HKEY, key: &str, value: &str) -> Option<String> {
+        let mut buffer = [0u16; 1024];
+        let mut size = std::mem::size_of_val(&buffer) as u32;
+        let (key, value) = (HSTRING::from(key), HSTRING::from(value));
+        let status = unsafe {
+            RegGetValueW(
+                root,
+                PCWSTR(key.as_ptr()),
+                PCWSTR(value.as_ptr()),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return None;
+        }
+
+        // `size` is in bytes and counts the trailing NUL
+        let len = (size as usize / 2).saturating_sub(1);
+        Some(String::from_utf16_lossy(&buffer[..len])).filter(|s| !s.is_empty())
+    }
 }
 
 /// Validate that a path is a valid Stardew Valley installation
@@ -171,15 +688,21 @@ pub fn validate_game_path(path: &Path) -> bool {
         return false;
     }
 
-    // Check for game files
+    // Check for game files. The Xbox app version ships "Stardew Valley.exe"
+    // and "Stardew Valley.dll" without the deps file.
     #[cfg(target_os = "windows")]
     {
-        path.join("StardewValley.exe").exists() || path.join("Stardew Valley.deps.json").exists()
+        ["StardewValley.exe", "Stardew Valley.exe", "Stardew Valley.deps.json", "Stardew Valley.dll"]
+            .iter()
+            .any(|name| path.join(name).exists())
     }
 
+    // On Linux this may be the Windows build, run through Proton or Wine
     #[cfg(not(target_os = "windows"))]
     {
-        path.join("Stardew Valley").exists() || path.join("Stardew Valley.deps.json").exists()
+        ["Stardew Valley", "Stardew Valley.deps.json", "Stardew Valley.exe"]
+            .iter()
+            .any(|name| path.join(name).exists())
     }
 }
 
@@ -190,13 +713,14 @@ pub fn detect_smapi_path(game_path: &Path) -> Option<PathBuf> {
     }
 
     #[cfg(target_os = "windows")]
-    let smapi_name = "StardewModdingAPI.exe";
+    let smapi_names = ["StardewModdingAPI.exe"];
 
     #[cfg(target_os = "macos")]
     let smapi_path = game_path.join("Contents/MacOS/StardewModdingAPI");
 
+    // The Windows SMAPI when the game runs through Proton or Wine
     #[cfg(target_os = "linux")]
-    let smapi_name = "StardewModdingAPI";
+    let smapi_names = ["StardewModdingAPI", "StardewModdingAPI.exe"];
 
     // For macOS, check special path
     #[cfg(target_os = "macos")]
@@ -209,8 +733,7 @@ pub fn detect_smapi_path(game_path: &Path) -> Option<PathBuf> {
     // For Windows and Linux, check game directory
     #[cfg(not(target_os = "macos"))]
     {
-        let smapi_path = game_path.join(smapi_name);
-        if smapi_path.exists() {
+        if let Some(smapi_path) = smapi_names.iter().map(|name| game_path.join(name)).find(|p| p.exists()) {
             return Some(smapi_path);
         }
     }
@@ -218,7 +741,161 @@ pub fn detect_smapi_path(game_path: &Path) -> Option<PathBuf> {
     None
 }
 
+/// The game's own executable, which starts it without SMAPI. SMAPI's
+/// installer replaces the `StardewValley` launcher on Linux and macOS and
+/// keeps the original as `StardewValley-original`.
+pub fn detect_game_executable(game_path: &Path) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let (dir, names) = (game_path.to_path_buf(), ["Stardew Valley.exe", "StardewValley.exe"]);
+
+    #[cfg(target_os = "macos")]
+    let (dir, names) = (
+        game_path.join("Contents/MacOS"),
+        ["Stardew Valley", "StardewValley-original", "StardewValley"],
+    );
+
+    #[cfg(target_os = "linux")]
+    let (dir, names) = (
+        game_path.to_path_buf(),
+        ["Stardew Valley", "StardewValley-original", "StardewValley"],
+    );
+
+    names.iter().map(|name| dir.join(name)).find(|path| path.is_file())
+}
+
+/// Get the Stardew Valley user data folder (saves, SMAPI logs)
+/// Windows: %APPDATA%\StardewValley, Linux/macOS: ~/.config/StardewValley,
+/// or the Wine prefix's AppData when only the Windows build has been run
+pub fn stardew_data_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA").map(|appdata| PathBuf::from(appdata).join("StardewValley"))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let native = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|config| config.join("StardewValley"));
+
+        #[cfg(target_os = "linux")]
+        if !native.as_ref().is_some_and(|dir| dir.is_dir()) {
+            if let Some(dir) = wine_prefixes().iter().find_map(|prefix| prefix_data_dir(prefix)) {
+                return Some(dir);
+            }
+        }
+        native
+    }
+}
+
+/// Get the path of the log SMAPI writes for the most recent session
+pub fn smapi_log_path() -> Option<PathBuf> {
+    stardew_data_dir().map(|dir| dir.join("ErrorLogs").join("SMAPI-latest.txt"))
+}
+
+/// A downloads folder must be an absolute path the app can create and write to
+pub fn validate_downloads_dir(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err("The downloads folder must be an absolute path".to_string());
+    }
+    fs::create_dir_all(path).map_err(|e| format!("Failed to create downloads folder: {}", e))?;
+
+    let probe = path.join(".write_test");
+    fs::write(&probe, b"").map_err(|e| format!("The downloads folder isn't writable: {}", e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
 /// Validate that SMAPI path exists and is executable
 pub fn validate_smapi_path(path: &Path) -> bool {
     path.exists() && path.is_file()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unmanaged() {
+        let settings = Settings {
+            game_path: "/games/Stardew Valley".to_string(),
+            unmanaged_folders: vec!["ConsoleCommands".to_string(), "_Dev\\".to_string(), "".to_string()],
+            ..Settings::default()
+        };
+        let mods = Path::new("/games/Stardew Valley/Mods");
+
+        assert!(settings.is_unmanaged(&mods.join("ConsoleCommands")));
+        assert!(settings.is_unmanaged(&mods.join("consolecommands.disabled")));
+        assert!(settings.is_unmanaged(&mods.join("_Dev").join("MyMod")));
+        assert!(!settings.is_unmanaged(&mods.join("ConsoleCommandsPlus")));
+        assert!(!settings.is_unmanaged(&mods.join("SaveBackup")));
+        assert!(!settings.is_unmanaged(Path::new("/elsewhere/ConsoleCommands")));
+    }
+
+    #[test]
+    fn test_parse_library_folders() {
+        let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Programme (x86)\\Steam"
+		"label"		""
+	}
+	"1"
+	{
+		"path"		"D:\\SteamLibrary"
+		"apps"
+		{
+			"413150"		"1234"
+		}
+	}
+}"#;
+        assert_eq!(
+            parse_library_folders(vdf),
+            vec![PathBuf::from(r"C:\Programme (x86)\Steam"), PathBuf::from(r"D:\SteamLibrary")]
+        );
+    }
+
+    #[test]
+    fn test_downloads_path() {
+        let app_data = Path::new("/data/treasure-chest");
+        let mut settings = Settings::default();
+        assert_eq!(settings.downloads_path(app_data), app_data.join("downloads").join("nexus"));
+
+        settings.downloads_dir = " /mnt/storage/archives ".to_string();
+        assert_eq!(settings.downloads_path(app_data), PathBuf::from("/mnt/storage/archives"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_prefix_data_dir() {
+        let prefix = std::env::temp_dir().join(format!("tc_prefix_{}", uuid::Uuid::new_v4()));
+        let users = prefix.join("drive_c").join("users");
+        fs::create_dir_all(users.join("Public")).unwrap();
+        assert_eq!(prefix_data_dir(&prefix), None);
+
+        let data_dir = users.join("steamuser").join("AppData").join("Roaming").join("StardewValley");
+        fs::create_dir_all(&data_dir).unwrap();
+        assert_eq!(prefix_data_dir(&prefix), Some(data_dir));
+
+        fs::remove_dir_all(&prefix).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("tc_settings_{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, b"{\"nexusApiKey\": \"secret\"}").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(fs::read_to_string(&path).unwrap().contains("secret"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
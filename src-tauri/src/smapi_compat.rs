@@ -0,0 +1,651 @@
+use crate::models::Mod;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const SMAPI_API_URL: &str = "https://smapi.io/api/v3.0/mods";
+/// Cached smapi.io answers older than this are fetched again when online
+const CACHE_MAX_AGE_DAYS: i64 = 7;
+/// Stale entries still serve as a fallback while smapi.io is down, up to this age
+const CACHE_EVICT_DAYS: i64 = 30;
+
+/// Versions read from the header of the last SMAPI log
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GameVersions {
+    pub smapi: String,
+    pub game: String,
+}
+
+/// Parse "SMAPI 4.0.8 with Stardew Valley 1.6.8 build 24119 on ..." from the log
+pub fn parse_log_versions(log: &str) -> Option<GameVersions> {
+    log.lines().take(20).find_map(|line| {
+        let rest = &line[line.find("SMAPI ")? + "SMAPI ".len()..];
+        let (smapi, rest) = rest.split_once(" with Stardew Valley ")?;
+        let game = rest.split_whitespace().next()?;
+        Some(GameVersions {
+            smapi: smapi.trim().to_string(),
+            game: game.to_string(),
+        })
+    })
+}
+
+pub fn detect_versions() -> Option<GameVersions> {
+    let content = fs::read_to_string(crate::settings::smapi_log_path()?).ok()?;
+    parse_log_versions(&content)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModSearchEntry {
+    id: String,
+    installed_version: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModSearchRequest {
+    mods: Vec<ModSearchEntry>,
+    api_version: String,
+    game_version: String,
+    platform: &'static str,
+    include_extended_metadata: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ModEntry {
+    id: String,
+    #[serde(default)]
+    metadata: Option<ModMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ModMetadata {
+    #[serde(default)]
+    compatibility_status: Option<String>,
+    #[serde(default)]
+    compatibility_summary: Option<String>,
+    #[serde(rename = "nexusID", default)]
+    nexus_id: Option<u32>,
+    /// Unofficial update posted on the compatibility list
+    #[serde(default)]
+    unofficial: Option<ModVersionLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ModVersionLink {
+    version: String,
+    url: String,
+}
+
+/// A smapi.io answer for one mod, kept so reports work offline
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CachedEntry {
+    entry: ModEntry,
+    /// Installed version of the mod the answer is for; an update asks again
+    #[serde(default)]
+    mod_version: String,
+    game_version: String,
+    fetched_at: DateTime<Utc>,
+}
+
+/// smapi.io answers by lowercased UniqueID, each for one mod version
+type CompatCache = HashMap<String, CachedEntry>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompatStatus {
+    Ok,
+    Broken,
+    /// Its features are now in the game or SMAPI itself
+    Obsolete,
+    /// Broken, but someone posted a fixed version
+    UnofficialUpdate,
+}
+
+/// What the smapi.io compatibility list says about an installed mod
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModCompatibility {
+    pub status: CompatStatus,
+    pub summary: Option<String>,
+    pub unofficial_version: Option<String>,
+    pub unofficial_url: Option<String>,
+}
+
+/// An enabled mod smapi.io lists as broken for the installed game version
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenMod {
+    pub unique_id: String,
+    pub name: String,
+    pub version: String,
+    pub path: String,
+    pub summary: Option<String>,
+}
+
+/// A mod this module disabled, kept until the mod is enabled again
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatDisabledMod {
+    pub unique_id: String,
+    pub name: String,
+    /// Version that was broken; a different installed version means it was updated
+    pub version: String,
+    pub reason: String,
+    pub game_version: String,
+    pub disabled_at: DateTime<Utc>,
+    /// Set when listing: the installed version differs from the broken one
+    #[serde(default, skip_deserializing)]
+    pub updated: bool,
+}
+
+/// Broken mods found on the installed game version. `fetched_at` is when the
+/// oldest smapi.io answer used was fetched, so stale offline data can be told apart.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    pub versions: GameVersions,
+    pub broken: Vec<BrokenMod>,
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+fn platform() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "Windows"
+    } else if cfg!(target_os = "macos") {
+        "Mac"
+    } else {
+        "Linux"
+    }
+}
+
+fn state_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("compat_disabled.json"))
+}
+
+fn cache_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let app_data_dir = app_handle.path().app_data_dir().ok()?;
+    Some(app_data_dir.join("smapi_compat_cache.json"))
+}
+
+fn load_cache(path: &Path) -> CompatCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &Path, cache: &CompatCache) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string(cache).map_err(|e| format!("Failed to serialize compatibility cache: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write compatibility cache: {}", e))
+}
+
+/// Cached answers for the requested mods, or None when any is missing, older
+/// than a week or for another game version (unless `allow_stale`). Answers
+/// for another version of a mod never count.
+fn cached_entries(
+    cache: &CompatCache,
+    request: &ModSearchRequest,
+    now: DateTime<Utc>,
+    allow_stale: bool,
+) -> Option<Vec<CachedEntry>> {
+    let entries: Vec<CachedEntry> = request
+        .mods
+        .iter()
+        .filter_map(|m| cache.get(&m.id.to_lowercase()).filter(|c| c.mod_version == m.installed_version).cloned())
+        .collect();
+
+    let fresh = entries.len() == request.mods.len()
+        && entries.iter().all(|c| {
+            c.game_version == request.game_version
+                && now.signed_duration_since(c.fetched_at) < chrono::Duration::days(CACHE_MAX_AGE_DAYS)
+        });
+    if fresh || (allow_stale && !entries.is_empty()) {
+        Some(entries)
+    } else {
+        None
+    }
+}
+
+/// Drop cache entries too old to be worth falling back to. Returns how many went.
+fn evict_expired(cache: &mut CompatCache, now: DateTime<Utc>) -> usize {
+    let before = cache.len();
+    cache.retain(|_, c| now.signed_duration_since(c.fetched_at) < chrono::Duration::days(CACHE_EVICT_DAYS));
+    before - cache.len()
+}
+
+/// Evict expired entries from the compatibility cache on disk
+pub fn evict_cache(app_handle: &AppHandle) -> Result<usize, String> {
+    let Some(path) = cache_path(app_handle) else {
+        return Ok(0);
+    };
+
+    let mut cache = load_cache(&path);
+    let evicted = evict_expired(&mut cache, Utc::now());
+    if evicted > 0 {
+        save_cache(&path, &cache)?;
+    }
+    Ok(evicted)
+}
+
+fn load_records(path: &Path) -> Vec<CompatDisabledMod> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_records(path: &Path, records: &[CompatDisabledMod]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Failed to serialize disabled mods: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write disabled mods: {}", e))
+}
+
+/// Map smapi.io's statuses onto ours. "Optional" and "Workaround" mods still
+/// work; "Abandoned" ones are broken with nobody left to fix them.
+fn compatibility(metadata: &ModMetadata) -> Option<ModCompatibility> {
+    let status = match metadata.compatibility_status.as_deref()? {
+        "Ok" | "Optional" | "Workaround" => CompatStatus::Ok,
+        "Broken" | "Abandoned" if metadata.unofficial.is_some() => CompatStatus::UnofficialUpdate,
+        "Broken" | "Abandoned" => CompatStatus::Broken,
+        "Obsolete" => CompatStatus::Obsolete,
+        "Unofficial" => CompatStatus::UnofficialUpdate,
+        _ => return None,
+    };
+
+    Some(ModCompatibility {
+        status,
+        summary: metadata.compatibility_summary.clone(),
+        unofficial_version: metadata.unofficial.as_ref().map(|u| u.version.clone()),
+        unofficial_url: metadata.unofficial.as_ref().map(|u| u.url.clone()),
+    })
+}
+
+/// Fill in `compatibility` on each mod from the cached smapi.io answers.
+/// Doesn't go online; `check_compatibility` refreshes the cache.
+pub fn annotate(app_handle: &AppHandle, mods: &mut [Mod]) {
+    let Some(path) = cache_path(app_handle) else {
+        return;
+    };
+
+    let cache = load_cache(&path);
+    let game_version = detect_versions().map(|v| v.game);
+    for m in mods.iter_mut() {
+        m.compatibility = cache
+            .get(&m.unique_id.to_lowercase())
+            .filter(|c| c.mod_version == m.version)
+            .filter(|c| game_version.as_ref().is_none_or(|v| *v == c.game_version))
+            .and_then(|c| compatibility(c.entry.metadata.as_ref()?));
+    }
+}
+
+/// Enabled mods whose smapi.io entry says "Broken"
+fn broken_mods(installed: &[Mod], entries: &[ModEntry]) -> Vec<BrokenMod> {
+    let by_id: HashMap<String, &ModEntry> = entries.iter().map(|e| (e.id.to_lowercase(), e)).collect();
+
+    installed
+        .iter()
+        .filter(|m| m.is_enabled)
+        .filter_map(|m| {
+            let metadata = by_id.get(&m.unique_id.to_lowercase())?.metadata.as_ref()?;
+            if metadata.compatibility_status.as_deref() != Some("Broken") {
+                return None;
+            }
+            Some(BrokenMod {
+                unique_id: m.unique_id.clone(),
+                name: m.name.clone(),
+                version: m.version.clone(),
+                path: m.path.clone(),
+                summary: metadata.compatibility_summary.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Ask smapi.io which enabled mods are broken on the installed game version.
+/// The answers are cached, and shown on each mod by `annotate`.
+pub async fn check_compatibility(app_handle: &AppHandle) -> Result<CompatibilityReport, String> {
+    let settings = crate::settings::Settings::load(app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let versions = detect_versions()
+        .ok_or("Game version unknown. Launch the game through SMAPI once so it writes its log.")?;
+
+    // Disabled mods are looked up too so the mod list can show their status
+    let installed = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    let request = ModSearchRequest {
+        mods: installed
+            .iter()
+            .map(|m| ModSearchEntry {
+                id: m.unique_id.clone(),
+                installed_version: m.version.clone(),
+            })
+            .collect(),
+        api_version: versions.smapi.clone(),
+        game_version: versions.game.clone(),
+        platform: platform(),
+        include_extended_metadata: true,
+    };
+
+    let (entries, fetched_at) = search_cached(app_handle, &request).await?;
+    Ok(CompatibilityReport {
+        broken: broken_mods(&installed, &entries),
+        versions,
+        fetched_at,
+    })
+}
+
+/// Nexus mod ids smapi.io knows for these UniqueIDs (read from the mods'
+/// UpdateKeys), for mods that aren't installed yet
+pub async fn nexus_ids(app_handle: &AppHandle, unique_ids: &[String]) -> Result<HashMap<String, u32>, String> {
+    // The search needs some SMAPI and game version; ids don't depend on them
+    let versions = detect_versions().unwrap_or(GameVersions {
+        smapi: "4.0.0".to_string(),
+        game: "1.6.0".to_string(),
+    });
+
+    let request = ModSearchRequest {
+        mods: unique_ids
+            .iter()
+            .map(|id| ModSearchEntry {
+                id: id.clone(),
+                installed_version: "0.0.0".to_string(),
+            })
+            .collect(),
+        api_version: versions.smapi,
+        game_version: versions.game,
+        platform: platform(),
+        include_extended_metadata: true,
+    };
+
+    Ok(search_cached(app_handle, &request)
+        .await?
+        .0
+        .into_iter()
+        .filter_map(|entry| Some((entry.id.to_lowercase(), entry.metadata?.nexus_id?)))
+        .collect())
+}
+
+/// Search through the on-disk cache: answers younger than a week are reused,
+/// and when smapi.io can't be reached (offline, rate limited) whatever was
+/// cached is served instead. Returns the entries and when the oldest was fetched.
+async fn search_cached(
+    app_handle: &AppHandle,
+    request: &ModSearchRequest,
+) -> Result<(Vec<ModEntry>, Option<DateTime<Utc>>), String> {
+    let path = cache_path(app_handle).ok_or("Failed to get app data directory")?;
+    let mut cache = load_cache(&path);
+    let split = |cached: Vec<CachedEntry>| {
+        let oldest = cached.iter().map(|c| c.fetched_at).min();
+        (cached.into_iter().map(|c| c.entry).collect(), oldest)
+    };
+
+    if let Some(cached) = cached_entries(&cache, request, Utc::now(), false) {
+        return Ok(split(cached));
+    }
+
+    match search(request).await {
+        Ok(entries) => {
+            let fetched_at = Utc::now();
+            let versions: HashMap<String, &str> = request
+                .mods
+                .iter()
+                .map(|m| (m.id.to_lowercase(), m.installed_version.as_str()))
+                .collect();
+            for entry in &entries {
+                let id = entry.id.to_lowercase();
+                let mod_version = versions.get(&id).copied().unwrap_or_default().to_string();
+                cache.insert(
+                    id,
+                    CachedEntry {
+                        entry: entry.clone(),
+                        mod_version,
+                        game_version: request.game_version.clone(),
+                        fetched_at,
+                    },
+                );
+            }
+            if let Err(e) = save_cache(&path, &cache) {
+                warn!("{}", e);
+            }
+            Ok((entries, Some(fetched_at)))
+        }
+        Err(e) => match cached_entries(&cache, request, Utc::now(), true) {
+            Some(cached) => {
+                info!("📦 {}; using cached compatibility data", e);
+                Ok(split(cached))
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn search(request: &ModSearchRequest) -> Result<Vec<ModEntry>, String> {
+    let response = reqwest::Client::new()
+        .post(SMAPI_API_URL)
+        .header("User-Agent", crate::nexus_api::USER_AGENT)
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach smapi.io: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("smapi.io returned {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse smapi.io response: {}", e))
+}
+
+/// Disable every enabled mod that's broken on this game version, recording why
+pub async fn disable_broken_mods(app_handle: &AppHandle) -> Result<Vec<CompatDisabledMod>, String> {
+    let CompatibilityReport { versions, broken, .. } = check_compatibility(app_handle).await?;
+    let settings = crate::settings::Settings::load(app_handle)?;
+    let path = state_path(app_handle).ok_or("Failed to get app data directory")?;
+    let mut records = load_records(&path);
+
+    let mut disabled = Vec::new();
+    for m in broken.into_iter().filter(|m| !settings.is_unmanaged(Path::new(&m.path))) {
+        if let Err(e) = crate::mod_installer::set_mod_enabled(Path::new(&m.path), false, settings.disabled_folder_style) {
+            warn!("Failed to disable {}: {}", m.name, e);
+            continue;
+        }
+
+        info!("🚫 Disabled {} {} (broken on Stardew Valley {})", m.name, m.version, versions.game);
+        let record = CompatDisabledMod {
+            unique_id: m.unique_id,
+            name: m.name,
+            version: m.version,
+            reason: m
+                .summary
+                .unwrap_or_else(|| format!("Broken in Stardew Valley {}", versions.game)),
+            game_version: versions.game.clone(),
+            disabled_at: Utc::now(),
+            updated: false,
+        };
+        records.retain(|r| !r.unique_id.eq_ignore_ascii_case(&record.unique_id));
+        records.push(record.clone());
+        disabled.push(record);
+    }
+
+    save_records(&path, &records)?;
+    Ok(disabled)
+}
+
+/// Mods disabled for compatibility that are still disabled, flagged when an
+/// update has been installed since. Records of re-enabled mods are dropped.
+pub fn list_disabled(app_handle: &AppHandle) -> Result<Vec<CompatDisabledMod>, String> {
+    let settings = crate::settings::Settings::load(app_handle)?;
+    let path = state_path(app_handle).ok_or("Failed to get app data directory")?;
+    let records = load_records(&path);
+    if records.is_empty() || settings.game_path.is_empty() {
+        return Ok(records);
+    }
+
+    let installed = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    let still_disabled: Vec<CompatDisabledMod> = records
+        .into_iter()
+        .filter_map(|mut record| {
+            let m = installed
+                .iter()
+                .find(|m| m.unique_id.eq_ignore_ascii_case(&record.unique_id))?;
+            if m.is_enabled {
+                return None;
+            }
+            record.updated = m.version != record.version;
+            Some(record)
+        })
+        .collect();
+
+    save_records(&path, &still_disabled)?;
+    Ok(still_disabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_versions() {
+        let log = "[12:00:00 INFO  SMAPI] SMAPI 4.0.8 with Stardew Valley 1.6.8 build 24119 on Microsoft Windows 11 Home\n\
+                   [12:00:00 INFO  SMAPI] Mods go here: C:\\Games\\Stardew Valley\\Mods";
+        assert_eq!(
+            parse_log_versions(log),
+            Some(GameVersions {
+                smapi: "4.0.8".to_string(),
+                game: "1.6.8".to_string(),
+            })
+        );
+        assert_eq!(parse_log_versions("not a smapi log"), None);
+    }
+
+    #[test]
+    fn test_broken_mods_only_lists_enabled_broken_entries() {
+        let installed: Vec<Mod> = serde_json::from_value(serde_json::json!([
+            {"id": "1", "name": "Old Mod", "author": "A", "version": "1.0.0", "unique_id": "A.Old",
+             "path": "/Mods/Old", "isEnabled": true},
+            {"id": "2", "name": "Fine Mod", "author": "A", "version": "1.0.0", "unique_id": "A.Fine",
+             "path": "/Mods/Fine", "isEnabled": true},
+            {"id": "3", "name": "Off Mod", "author": "A", "version": "1.0.0", "unique_id": "A.Off",
+             "path": "/Mods/Off.disabled", "isEnabled": false}
+        ]))
+        .unwrap();
+        let entries: Vec<ModEntry> = serde_json::from_str(
+            r#"[
+                {"id": "a.old", "metadata": {"compatibilityStatus": "Broken", "compatibilitySummary": "broken in 1.6"}},
+                {"id": "A.Fine", "metadata": {"compatibilityStatus": "Ok"}},
+                {"id": "A.Off", "metadata": {"compatibilityStatus": "Broken"}}
+            ]"#,
+        )
+        .unwrap();
+
+        let broken = broken_mods(&installed, &entries);
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].unique_id, "A.Old");
+        assert_eq!(broken[0].summary.as_deref(), Some("broken in 1.6"));
+    }
+
+    #[test]
+    fn test_compatibility_status() {
+        let entries: Vec<ModEntry> = serde_json::from_str(
+            r#"[
+                {"id": "A.Ok", "metadata": {"compatibilityStatus": "Optional"}},
+                {"id": "A.Broken", "metadata": {"compatibilityStatus": "Broken", "compatibilitySummary": "broken in 1.6"}},
+                {"id": "A.Fixed", "metadata": {"compatibilityStatus": "Broken",
+                    "unofficial": {"version": "1.2.1-unofficial.1", "url": "https://smapi.io/mods#A_Fixed"}}},
+                {"id": "A.Gone", "metadata": {"compatibilityStatus": "Obsolete"}},
+                {"id": "A.New", "metadata": {}}
+            ]"#,
+        )
+        .unwrap();
+        let status = |i: usize| compatibility(entries[i].metadata.as_ref().unwrap()).map(|c| c.status);
+
+        assert_eq!(status(0), Some(CompatStatus::Ok));
+        assert_eq!(status(1), Some(CompatStatus::Broken));
+        assert_eq!(status(2), Some(CompatStatus::UnofficialUpdate));
+        assert_eq!(status(3), Some(CompatStatus::Obsolete));
+        assert_eq!(status(4), None);
+
+        let fixed = compatibility(entries[2].metadata.as_ref().unwrap()).unwrap();
+        assert_eq!(fixed.unofficial_version.as_deref(), Some("1.2.1-unofficial.1"));
+    }
+
+    #[test]
+    fn test_cached_entries_freshness() {
+        let now = Utc::now();
+        let cached = |id: &str, game_version: &str, days_old: i64| CachedEntry {
+            entry: ModEntry {
+                id: id.to_string(),
+                metadata: None,
+            },
+            mod_version: "1.0.0".to_string(),
+            game_version: game_version.to_string(),
+            fetched_at: now - chrono::Duration::days(days_old),
+        };
+        let request = |ids: &[&str]| ModSearchRequest {
+            mods: ids
+                .iter()
+                .map(|id| ModSearchEntry {
+                    id: id.to_string(),
+                    installed_version: "1.0.0".to_string(),
+                })
+                .collect(),
+            api_version: "4.0.8".to_string(),
+            game_version: "1.6.8".to_string(),
+            platform: "Windows",
+            include_extended_metadata: true,
+        };
+        let cache: CompatCache = [
+            ("a.fresh".to_string(), cached("A.Fresh", "1.6.8", 1)),
+            ("a.old".to_string(), cached("A.Old", "1.6.8", 8)),
+            ("a.othergame".to_string(), cached("A.OtherGame", "1.5.6", 1)),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(cached_entries(&cache, &request(&["A.FRESH"]), now, false).unwrap().len(), 1);
+        assert!(cached_entries(&cache, &request(&["A.Fresh", "A.Old"]), now, false).is_none());
+        assert!(cached_entries(&cache, &request(&["A.OtherGame"]), now, false).is_none());
+        assert!(cached_entries(&cache, &request(&["A.Fresh", "A.Missing"]), now, false).is_none());
+        let mut updated = request(&["A.Fresh"]);
+        updated.mods[0].installed_version = "1.1.0".to_string();
+        assert!(cached_entries(&cache, &updated, now, false).is_none());
+        assert!(cached_entries(&cache, &updated, now, true).is_none());
+
+        // Offline: serve whatever is known
+        assert_eq!(
+            cached_entries(&cache, &request(&["A.Fresh", "A.Old", "A.Missing"]), now, true)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(cached_entries(&cache, &request(&["A.Missing"]), now, true).is_none());
+
+        let mut cache = cache;
+        cache.insert("a.ancient".to_string(), cached("A.Ancient", "1.6.8", 31));
+        assert_eq!(evict_expired(&mut cache, now), 1);
+        assert!(cache.contains_key("a.old") && !cache.contains_key("a.ancient"));
+    }
+}
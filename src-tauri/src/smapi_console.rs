@@ -0,0 +1,154 @@
+//! Run SMAPI with its console piped into the app: every line is sent as
+//! `smapi-output`, and `smapi-exited` follows when the game closes. Lines that
+//! mark the game finishing its launch or crashing are flagged so the frontend
+//! can react without parsing the log itself.
+
+use crate::events::{self, AppEvent};
+use regex::Regex;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use tauri::AppHandle;
+use tracing::info;
+
+/// Logged once mods are loaded and the game window is up
+const LAUNCHED_MARKERS: &[&str] = &["Type 'help' for help"];
+const CRASH_MARKERS: &[&str] = &["The game failed to launch", "A fatal error occurred", "The game crashed"];
+
+/// "[12:00:00 INFO  SMAPI] message"
+static LINE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[(\d{2}:\d{2}:\d{2}) (\w+)\s+([^\]]+)\] ?(.*)$").unwrap());
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConsoleMarker {
+    GameLaunched,
+    Crashed,
+}
+
+/// One console line, split into SMAPI's log fields when it has them
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SmapiLine {
+    pub text: String,
+    pub stderr: bool,
+    pub time: Option<String>,
+    /// TRACE, DEBUG, INFO, WARN, ERROR or ALERT
+    pub level: Option<String>,
+    /// "SMAPI" or the mod that logged the line
+    pub source: Option<String>,
+    pub message: String,
+    pub marker: Option<ConsoleMarker>,
+}
+
+pub fn parse_line(text: &str, stderr: bool) -> SmapiLine {
+    let captures = LINE_PATTERN.captures(text);
+    let field = |i| captures.as_ref().and_then(|c| c.get(i)).map(|m| m.as_str().trim().to_string());
+    let message = field(4).unwrap_or_else(|| text.to_string());
+
+    let marker = if CRASH_MARKERS.iter().any(|m| message.contains(m)) {
+        Some(ConsoleMarker::Crashed)
+    } else if LAUNCHED_MARKERS.iter().any(|m| message.contains(m)) {
+        Some(ConsoleMarker::GameLaunched)
+    } else {
+        None
+    };
+
+    SmapiLine {
+        text: text.to_string(),
+        stderr,
+        time: field(1),
+        level: field(2),
+        source: field(3),
+        message,
+        marker,
+    }
+}
+
+/// Start SMAPI with its output captured. Returns once the process started;
+/// output is streamed from a background thread until it exits.
+pub fn launch(app_handle: &AppHandle, smapi_path: &Path, working_dir: &Path) -> Result<(), String> {
+    let mut command = Command::new(smapi_path);
+    command
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Without this Windows opens an empty console window next to the game
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let started_at = chrono::Utc::now();
+    let mut child = command.spawn().map_err(|e| format!("Failed to launch game: {}", e))?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let crashed = Arc::new(AtomicBool::new(false));
+
+    let handle = app_handle.clone();
+    let stderr_crashed = crashed.clone();
+    let stderr_reader = stderr.map(|stderr| std::thread::spawn(move || stream(&handle, stderr, true, &stderr_crashed)));
+
+    let handle = app_handle.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(stdout) = stdout {
+            stream(&handle, stdout, false, &crashed);
+        }
+        if let Some(reader) = stderr_reader {
+            let _ = reader.join();
+        }
+
+        let exit_code = child.wait().ok().and_then(|status| status.code());
+        let crashed = crashed.load(Ordering::Relaxed) || exit_code.is_some_and(|code| code != 0);
+        info!("🎮 SMAPI exited with {:?}{}", exit_code, if crashed { " (crashed)" } else { "" });
+        events::emit(&handle, AppEvent::SmapiExited { exit_code, crashed });
+        crate::game_session::session_ended(&handle, started_at, exit_code, crashed);
+    });
+
+    Ok(())
+}
+
+fn stream(app_handle: &AppHandle, output: impl Read, stderr: bool, crashed: &AtomicBool) {
+    // SMAPI writes UTF-8, but a mod may log anything; don't stop on bad bytes
+    for line in BufReader::new(output).split(b'\n').map_while(Result::ok) {
+        let text = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+        let line = parse_line(&text, stderr);
+        if line.marker == Some(ConsoleMarker::Crashed) {
+            crashed.store(true, Ordering::Relaxed);
+        }
+        events::emit(app_handle, AppEvent::SmapiOutput(line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line("[12:00:03 INFO  SMAPI] Type 'help' for help, or 'help <cmd>' for a command's usage", false);
+        assert_eq!(line.time.as_deref(), Some("12:00:03"));
+        assert_eq!(line.level.as_deref(), Some("INFO"));
+        assert_eq!(line.source.as_deref(), Some("SMAPI"));
+        assert_eq!(line.marker, Some(ConsoleMarker::GameLaunched));
+
+        let line = parse_line("[12:01:00 ERROR Lookup Anything] Something broke", false);
+        assert_eq!(line.source.as_deref(), Some("Lookup Anything"));
+        assert_eq!(line.message, "Something broke");
+        assert_eq!(line.marker, None);
+
+        let line = parse_line("[12:01:05 ERROR SMAPI] The game failed to launch: System.Exception", true);
+        assert_eq!(line.marker, Some(ConsoleMarker::Crashed));
+
+        let line = parse_line("   at StardewValley.Game1.Update()", true);
+        assert_eq!(line.level, None);
+        assert_eq!(line.message, "   at StardewValley.Game1.Update()");
+    }
+}
@@ -0,0 +1,319 @@
+//! Install or update SMAPI without its separate installer. The latest release
+//! is downloaded from GitHub and installed the way SMAPI's installer does a
+//! plain install: the platform's `install.dat` is unpacked over the game
+//! folder, replacing the previous `smapi-internal` and bundled mods.
+
+use crate::settings::{detect_smapi_path, validate_game_path, Settings};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Pathoschild/SMAPI/releases/latest";
+
+/// Folders under `internal/` holding this platform's files, by installer version
+#[cfg(target_os = "windows")]
+const PLATFORM_FOLDERS: &[&str] = &["windows"];
+#[cfg(target_os = "macos")]
+const PLATFORM_FOLDERS: &[&str] = &["macOS", "unix"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PLATFORM_FOLDERS: &[&str] = &["linux", "unix"];
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// "sha256:<hex>", on assets uploaded since GitHub started hashing them
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SmapiInstallResult {
+    pub version: String,
+    /// Also saved as `smapi_path` in settings
+    pub smapi_path: PathBuf,
+}
+
+/// The player installer, not the one for mod developers
+fn installer_asset(assets: &[ReleaseAsset]) -> Option<&ReleaseAsset> {
+    assets
+        .iter()
+        .find(|a| a.name.ends_with("-installer.zip") && !a.name.contains("developer"))
+}
+
+/// Check a downloaded asset against the size and hash GitHub lists for it
+fn verify_asset(asset: &ReleaseAsset, bytes: &[u8]) -> Result<(), String> {
+    if bytes.len() as u64 != asset.size {
+        return Err(format!(
+            "SMAPI download is {} bytes, expected {}",
+            bytes.len(),
+            asset.size
+        ));
+    }
+    if let Some(expected) = asset.digest.as_deref().and_then(|d| d.strip_prefix("sha256:")) {
+        let actual: String = Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect();
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err("SMAPI download doesn't match its published checksum".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// `internal/<platform>/install.dat` inside an unpacked installer
+fn install_data(installer_dir: &Path) -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = WalkDir::new(installer_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "install.dat")
+        .map(|e| e.into_path())
+        .collect();
+
+    PLATFORM_FOLDERS.iter().find_map(|platform| {
+        candidates
+            .iter()
+            .find(|path| {
+                let parent = path.parent();
+                parent.and_then(|p| p.file_name()).is_some_and(|name| name == *platform)
+                    && parent.and_then(|p| p.parent()).and_then(|p| p.file_name()).is_some_and(|name| name == "internal")
+            })
+            .cloned()
+    })
+}
+
+/// Where SMAPI's files go: next to the game's executable
+fn install_dir(game_path: &Path) -> PathBuf {
+    let mac_dir = game_path.join("Contents").join("MacOS");
+    if mac_dir.is_dir() {
+        mac_dir
+    } else {
+        game_path.to_path_buf()
+    }
+}
+
+/// Copy `source` into `target`, replacing files that exist
+fn copy_over(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let destination = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_over(&entry.path(), &destination)?;
+        } else {
+            fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Put unpacked SMAPI files into the game folder. Like SMAPI's installer,
+/// the previous `smapi-internal` and bundled mods are replaced rather than
+/// merged, so files dropped by the new version don't linger, and the game's
+/// `.deps.json` is copied for SMAPI, which needs the same runtime libraries.
+fn install_files(files: &Path, target: &Path) -> std::io::Result<()> {
+    let internal = target.join("smapi-internal");
+    if internal.exists() {
+        fs::remove_dir_all(&internal)?;
+    }
+    if let Ok(bundled) = fs::read_dir(files.join("Mods")) {
+        for entry in bundled.flatten() {
+            let installed = target.join("Mods").join(entry.file_name());
+            if installed.is_dir() {
+                fs::remove_dir_all(&installed)?;
+            }
+        }
+    }
+
+    copy_over(files, target)?;
+
+    let game_deps = target.join("Stardew Valley.deps.json");
+    if game_deps.is_file() {
+        fs::copy(&game_deps, target.join("StardewModdingAPI.deps.json"))?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let executable = target.join("StardewModdingAPI");
+        if executable.is_file() {
+            fs::set_permissions(&executable, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn latest_release() -> Result<Release, String> {
+    let response = reqwest::Client::new()
+        .get(LATEST_RELEASE_URL)
+        .header("User-Agent", crate::nexus_api::USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("GitHub returned {}", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse SMAPI release: {}", e))
+}
+
+/// Download the latest SMAPI and install it for the configured game,
+/// pointing `smapi_path` at it. Also updates an existing install.
+pub async fn install_latest(app_handle: &AppHandle) -> Result<SmapiInstallResult, String> {
+    let mut settings = Settings::load(app_handle)?;
+    settings.ensure_writable()?;
+    let game_path = PathBuf::from(&settings.game_path);
+    if settings.game_path.is_empty() || !validate_game_path(&game_path) {
+        return Err("Set a valid game path before installing SMAPI".to_string());
+    }
+
+    let release = latest_release().await?;
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    let asset = installer_asset(&release.assets).ok_or(format!("SMAPI {} has no installer download", version))?;
+    info!("⬇️  Downloading SMAPI {} from {}", version, asset.browser_download_url);
+
+    let response = reqwest::Client::new()
+        .get(&asset.browser_download_url)
+        .header("User-Agent", crate::nexus_api::USER_AGENT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download SMAPI: {}", e))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download SMAPI: {}", e))?;
+    verify_asset(asset, &bytes)?;
+
+    let work_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("temp")
+        .join(format!("smapi-{}", version));
+
+    let target = install_dir(&game_path);
+    let unpack = {
+        let work_dir = work_dir.clone();
+        move || -> Result<(), String> {
+            if work_dir.exists() {
+                fs::remove_dir_all(&work_dir).map_err(|e| format!("Failed to clear temp folder: {}", e))?;
+            }
+            let installer_dir = work_dir.join("installer");
+            crate::archive::extract(Cursor::new(bytes), &installer_dir)?;
+
+            let data = install_data(&installer_dir).ok_or("SMAPI installer has no files for this platform")?;
+            let files = work_dir.join("files");
+            crate::archive::extract_file(&data, &files)?;
+
+            install_files(&files, &target).map_err(|e| format!("Failed to install SMAPI files: {}", e))
+        }
+    };
+    let result = tokio::task::spawn_blocking(unpack).await.map_err(|e| e.to_string())?;
+    if let Err(e) = fs::remove_dir_all(&work_dir) {
+        warn!("Failed to clean up SMAPI installer: {}", e);
+    }
+    result?;
+
+    let smapi_path = detect_smapi_path(&game_path).ok_or("SMAPI was installed but its executable wasn't found")?;
+    settings.smapi_path = smapi_path.to_string_lossy().to_string();
+    settings.save(app_handle)?;
+
+    info!("✅ Installed SMAPI {} to {}", version, smapi_path.display());
+    Ok(SmapiInstallResult { version, smapi_path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_player_installer_and_platform_files() {
+        let asset = |name: &str| ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://github.com/Pathoschild/SMAPI/releases/download/4.1.10/{}", name),
+            size: 0,
+            digest: None,
+        };
+        let assets = vec![
+            asset("SMAPI-4.1.10-installer-for-developers.zip"),
+            asset("SMAPI-4.1.10-installer.zip"),
+        ];
+        assert_eq!(installer_asset(&assets).unwrap().name, "SMAPI-4.1.10-installer.zip");
+
+        let dir = std::env::temp_dir().join(format!("tc_smapi_installer_{}", uuid::Uuid::new_v4()));
+        let internal = dir.join("SMAPI 4.1.10 installer").join("internal");
+        for platform in ["windows", "linux", "macOS", "unix"] {
+            fs::create_dir_all(internal.join(platform)).unwrap();
+            fs::write(internal.join(platform).join("install.dat"), platform).unwrap();
+        }
+        let found = install_data(&dir).unwrap();
+        assert_eq!(found.parent().unwrap().file_name().unwrap(), PLATFORM_FOLDERS[0]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_asset() {
+        let asset = |size: u64, digest: Option<&str>| ReleaseAsset {
+            name: "SMAPI-4.1.10-installer.zip".to_string(),
+            browser_download_url: String::new(),
+            size,
+            digest: digest.map(str::to_string),
+        };
+        // sha256("abc")
+        let abc = "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert!(verify_asset(&asset(3, None), b"abc").is_ok());
+        assert!(verify_asset(&asset(3, Some(abc)), b"abc").is_ok());
+        assert!(verify_asset(&asset(4, None), b"abc").is_err());
+        assert!(verify_asset(&asset(3, Some(abc)), b"abd").is_err());
+    }
+
+    #[test]
+    fn test_install_files_replaces_internal_folder() {
+        let dir = std::env::temp_dir().join(format!("tc_smapi_install_{}", uuid::Uuid::new_v4()));
+        let files = dir.join("files");
+        let game = dir.join("game");
+        fs::create_dir_all(files.join("smapi-internal")).unwrap();
+        fs::create_dir_all(files.join("Mods").join("ConsoleCommands")).unwrap();
+        fs::write(files.join("smapi-internal").join("SMAPI.config.json"), "{}").unwrap();
+        fs::write(files.join("Mods").join("ConsoleCommands").join("manifest.json"), "new").unwrap();
+        fs::write(files.join("StardewModdingAPI.dll"), "4.1").unwrap();
+
+        fs::create_dir_all(game.join("smapi-internal")).unwrap();
+        fs::create_dir_all(game.join("Mods").join("ConsoleCommands")).unwrap();
+        fs::create_dir_all(game.join("Mods").join("Other")).unwrap();
+        fs::write(game.join("smapi-internal").join("Removed.dll"), "").unwrap();
+        fs::write(game.join("Mods").join("ConsoleCommands").join("Old.dll"), "").unwrap();
+        fs::write(game.join("StardewModdingAPI.dll"), "4.0").unwrap();
+        fs::write(game.join("Stardew Valley.deps.json"), "{}").unwrap();
+
+        install_files(&files, &game).unwrap();
+        assert!(!game.join("smapi-internal").join("Removed.dll").exists());
+        assert!(game.join("smapi-internal").join("SMAPI.config.json").exists());
+        assert!(!game.join("Mods").join("ConsoleCommands").join("Old.dll").exists());
+        assert!(game.join("Mods").join("Other").exists());
+        assert_eq!(fs::read_to_string(game.join("StardewModdingAPI.dll")).unwrap(), "4.1");
+        assert_eq!(fs::read_to_string(game.join("StardewModdingAPI.deps.json")).unwrap(), "{}");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+//! Staged installs: mod files are kept in `ModsStaging` next to `Mods` and
+//! hardlinked into it. Bringing a mod back into Mods then only creates links,
+//! whatever the mod's size, and the staged copy survives anything that wipes
+//! Mods. A folder keeps its relative path in both, e.g. `Mods/_Frameworks/X`
+//! is staged in `ModsStaging/_Frameworks/X`.
+
+use crate::mod_installer::CONFIG_FILE_NAME;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use walkdir::WalkDir;
+
+pub const STAGING_DIR_NAME: &str = "ModsStaging";
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployReport {
+    /// Staged mods linked into Mods
+    pub deployed: usize,
+    /// Files copied because linking failed (staging on another drive)
+    pub copied_files: usize,
+}
+
+/// The staging folder of a game, on the same drive as Mods so files can be
+/// hardlinked
+pub fn staging_root(game_path: &Path) -> PathBuf {
+    game_path.join(STAGING_DIR_NAME)
+}
+
+/// Where the mod deployed at `mod_path` is staged. A disabled mod keeps
+/// its staged copy under the enabled name, whichever way it's disabled.
+pub fn staged_path(game_path: &Path, mod_path: &Path) -> Option<PathBuf> {
+    let relative = mod_path.strip_prefix(game_path.join("Mods")).ok()?;
+    let name = relative.file_name()?.to_string_lossy();
+    let name = crate::mod_installer::enabled_name(&name).to_string();
+    Some(staging_root(game_path).join(relative).with_file_name(name))
+}
+
+/// Recreate the files of `staged` in `target` as hardlinks, copying instead
+/// when linking fails. Files only in `target` are left alone, and so is a
+/// config.json SMAPI already wrote there. Returns how many files were copied.
+pub fn link_tree(staged: &Path, target: &Path) -> io::Result<usize> {
+    fs::create_dir_all(target)?;
+
+    let mut copied = 0;
+    for entry in WalkDir::new(staged).min_depth(1) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(staged).unwrap_or(entry.path());
+        let destination = target.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)?;
+            continue;
+        }
+
+        if destination.exists() {
+            if relative == Path::new(CONFIG_FILE_NAME) {
+                continue;
+            }
+            fs::remove_file(&destination)?;
+        }
+        if fs::hard_link(entry.path(), &destination).is_err() {
+            fs::copy(entry.path(), &destination)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Staged mod folders: those with a manifest.json, not looking inside them
+fn staged_mods(root: &Path) -> Vec<PathBuf> {
+    let mut folders = Vec::new();
+    let mut entries = WalkDir::new(root).min_depth(1).into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_dir() || entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if entry.path().join("manifest.json").is_file() {
+            folders.push(entry.into_path());
+            entries.skip_current_dir();
+        }
+    }
+    folders
+}
+
+/// Link every staged mod into Mods again, e.g. after a game reinstall or a
+/// file check emptied it. Disabled mods are refreshed where they are.
+pub fn deploy_staged(game_path: &Path) -> Result<DeployReport, String> {
+    let root = staging_root(game_path);
+    let mods_dir = game_path.join("Mods");
+
+    let mut report = DeployReport::default();
+    for staged in staged_mods(&root) {
+        let relative = staged.strip_prefix(&root).unwrap_or(&staged);
+        let mut target = mods_dir.join(relative);
+        if !target.exists() {
+            let name = target.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let disabled = [format!("{}.disabled", name), format!(".{}", name)]
+                .into_iter()
+                .map(|disabled| target.with_file_name(disabled))
+                .find(|disabled| disabled.exists());
+            if let Some(disabled) = disabled {
+                target = disabled;
+            }
+        }
+
+        report.copied_files += link_tree(&staged, &target)
+            .map_err(|e| format!("Failed to deploy {}: {}", relative.display(), e))?;
+        report.deployed += 1;
+    }
+
+    info!("🔗 Deployed {} staged mods ({} files copied)", report.deployed, report.copied_files);
+    Ok(report)
+}
+
+/// Drop the staged copy of a mod that was deleted from Mods
+pub fn remove_staged(game_path: &Path, mod_path: &Path) -> io::Result<()> {
+    match staged_path(game_path, mod_path) {
+        Some(staged) if staged.exists() => fs::remove_dir_all(staged),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staged_path() {
+        let game = Path::new("/games/Stardew Valley");
+        let mods = game.join("Mods");
+        let staging = game.join(STAGING_DIR_NAME);
+
+        assert_eq!(staged_path(game, &mods.join("LookupAnything")), Some(staging.join("LookupAnything")));
+        assert_eq!(
+            staged_path(game, &mods.join("_Frameworks").join("SpaceCore.disabled")),
+            Some(staging.join("_Frameworks").join("SpaceCore"))
+        );
+        assert_eq!(staged_path(game, &mods.join(".ContentPatcher")), Some(staging.join("ContentPatcher")));
+        assert_eq!(staged_path(game, Path::new("/elsewhere/Mod")), None);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::download_manager::DownloadManager;
+use crate::events::{self, AppEvent};
+use crate::settings::Settings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+use tracing::{error, info};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Ok,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StepReport {
+    pub name: String,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    pub steps: Vec<StepReport>,
+    pub total_ms: u64,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Result of the last startup run, kept for `get_startup_report`
+#[derive(Default)]
+pub struct StartupState {
+    report: Mutex<Option<StartupReport>>,
+}
+
+impl StartupState {
+    pub async fn report(&self) -> Option<StartupReport> {
+        self.report.lock().await.clone()
+    }
+}
+
+/// What a step reports when it didn't fail
+pub(crate) enum StepOutcome {
+    Done(Option<String>),
+    Skipped(String),
+}
+
+pub(crate) type StepResult = Result<StepOutcome, String>;
+
+/// Run one step on its own task, so a panic only fails that step
+pub(crate) async fn run_step<F, Fut>(app_handle: &AppHandle, name: &str, step: F) -> StepReport
+where
+    F: FnOnce(AppHandle) -> Fut,
+    Fut: Future<Output = StepResult> + Send + 'static,
+{
+    let started = Instant::now();
+    let result = tokio::spawn(step(app_handle.clone()))
+        .await
+        .unwrap_or_else(|e| Err(format!("Step panicked: {}", e)));
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (status, message) = match result {
+        Ok(StepOutcome::Done(message)) => (StepStatus::Ok, message),
+        Ok(StepOutcome::Skipped(reason)) => (StepStatus::Skipped, Some(reason)),
+        Err(e) => (StepStatus::Failed, Some(e)),
+    };
+
+    match status {
+        StepStatus::Failed => error!("   ❌ {} failed after {}ms: {:?}", name, duration_ms, message),
+        _ => info!("   ⏱️  {} {:?} in {}ms", name, status, duration_ms),
+    }
+
+    StepReport {
+        name: name.to_string(),
+        status,
+        duration_ms,
+        message,
+    }
+}
+
+/// Run the startup steps in order and publish the timing report
+pub async fn run(app_handle: AppHandle) {
+    info!("=== Running startup tasks ===");
+    let started = Instant::now();
+
+    let steps = vec![
+        run_step(&app_handle, "settings", load_settings).await,
+        run_step(&app_handle, "mods", scan_installed_mods).await,
+        run_step(&app_handle, "download_queue", restore_download_queue).await,
+        run_step(&app_handle, "launch_args", scan_launch_args).await,
+        run_step(&app_handle, "endorsement_report", endorsement_report).await,
+        run_step(&app_handle, "update_check", check_updates).await,
+    ];
+
+    let report = StartupReport {
+        steps,
+        total_ms: started.elapsed().as_millis() as u64,
+        finished_at: Utc::now(),
+    };
+    info!("=== Startup tasks finished in {}ms ===", report.total_ms);
+
+    if let Some(state) = app_handle.try_state::<StartupState>() {
+        *state.report.lock().await = Some(report.clone());
+    }
+    events::emit(&app_handle, AppEvent::StartupComplete(report));
+}
+
+async fn load_settings(app_handle: AppHandle) -> StepResult {
+    Settings::load(&app_handle)?;
+    Ok(StepOutcome::Done(None))
+}
+
+async fn scan_installed_mods(app_handle: AppHandle) -> StepResult {
+    let settings = Settings::load(&app_handle)?;
+    if settings.game_path.is_empty() {
+        return Ok(StepOutcome::Skipped("Game path not configured".to_string()));
+    }
+
+    let game_path = settings.game_path.clone();
+    let mods = tokio::task::spawn_blocking(move || crate::mod_installer::scan_mods(Path::new(&game_path)))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(StepOutcome::Done(Some(format!("{} mods installed", mods.len()))))
+}
+
+/// Put back the downloads that were queued when the app last closed
+async fn restore_download_queue(app_handle: AppHandle) -> StepResult {
+    let Some(manager) = app_handle.try_state::<DownloadManager>() else {
+        return Ok(StepOutcome::Skipped("Download manager not initialized".to_string()));
+    };
+
+    let restored = manager.restore_queue().await?;
+    Ok(StepOutcome::Done(Some(format!("{} download(s) restored", restored))))
+}
+
+/// Surface nxm:// links the app was launched with
+async fn scan_launch_args(app_handle: AppHandle) -> StepResult {
+    let args: Vec<String> = std::env::args().collect();
+    info!("🚀 App launched with {} arguments:", args.len());
+
+    let mut nxm_links = 0;
+    for (i, arg) in args.iter().enumerate() {
+        info!("   [{}]: {}", i, crate::nxm_protocol::loggable(arg));
+        if arg.starts_with("nxm://") {
+            info!("   ⚠️  NXM URL found in launch arguments!");
+            events::emit(&app_handle, AppEvent::DebugDeepLink(arg.clone()));
+            nxm_links += 1;
+        }
+    }
+
+    Ok(StepOutcome::Done(Some(format!("{} nxm link(s) in arguments", nxm_links))))
+}
+
+async fn endorsement_report(app_handle: AppHandle) -> StepResult {
+    crate::endorsements::run_weekly_report(&app_handle).await;
+    Ok(StepOutcome::Done(None))
+}
+
+/// Check installed Nexus mods for updates, if enabled in settings
+async fn check_updates(app_handle: AppHandle) -> StepResult {
+    let settings = Settings::load(&app_handle)?;
+    if !settings.check_updates_on_startup {
+        return Ok(StepOutcome::Skipped("Disabled in settings".to_string()));
+    }
+    if settings.game_path.is_empty() || settings.active_api_key().is_empty() {
+        return Ok(StepOutcome::Skipped("Game path or API key not configured".to_string()));
+    }
+
+    let (updates, errors) = crate::update_all::find_updates(&app_handle, &settings).await;
+
+    let summary = format!("{} update(s) available, {} check(s) failed", updates.len(), errors);
+    if !updates.is_empty() {
+        events::emit(&app_handle, AppEvent::ModUpdatesAvailable(updates));
+    }
+
+    Ok(StepOutcome::Done(Some(summary)))
+}
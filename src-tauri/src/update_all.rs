@@ -0,0 +1,497 @@
+use crate::download_manager::{DownloadManager, DownloadStatus};
+use crate::events::{self, AppEvent};
+use crate::mod_installer::{sanitize_folder_name, InstallResult, ModInstaller, CONFIG_FILE_NAME};
+use crate::mod_registry::ModSource;
+use crate::models::Mod;
+use crate::nexus_api::{ModFile, NexusClient};
+use crate::nxm_protocol::NxmUrl;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// How often the queue is checked for finished downloads
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Nexus API requests `update_all` makes per mod: the file list, the mod
+/// and file info shown in the queue, and the download link
+const API_CALLS_PER_UPDATE: u32 = 4;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateStage {
+    Queued,
+    Downloading,
+    Installing,
+    Updated,
+    Failed,
+}
+
+/// Payload of `mod-update-progress`, one per stage change of each mod
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModUpdateProgress {
+    pub unique_id: String,
+    pub name: String,
+    pub stage: UpdateStage,
+    pub message: Option<String>,
+}
+
+/// Payload of `mod-updates-available`, also the input of `update_all_mods`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableUpdate {
+    pub unique_id: String,
+    pub name: String,
+    pub nexus_mod_id: u32,
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    pub latest_file_id: Option<u32>,
+}
+
+/// Check every installed Nexus mod for a newer version. Returns the updates
+/// and the number of mods whose check failed.
+pub async fn find_updates(app_handle: &AppHandle, settings: &Settings) -> (Vec<AvailableUpdate>, usize) {
+    let timer = crate::perf_metrics::CommandTimer::start("find_updates");
+    let mut mods = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    crate::mod_registry::apply(app_handle, &mut mods);
+
+    let mut updates = Vec::new();
+    let mut errors = 0;
+    let mut checked = 0;
+    for m in mods.into_iter().filter(|m| !m.is_unmanaged) {
+        let Some(nexus_mod_id) = m.nexus_mod_id else {
+            continue;
+        };
+
+        checked += 1;
+        match crate::fetch_update_info(app_handle, &m.path, m.version.clone(), nexus_mod_id).await {
+            Ok(info) if info.has_update => updates.push(AvailableUpdate {
+                unique_id: m.unique_id,
+                name: m.name,
+                nexus_mod_id,
+                current_version: info.current_version,
+                latest_version: info.latest_version,
+                latest_file_id: info.latest_file_id,
+            }),
+            Ok(_) => {}
+            Err(e) => {
+                warn!("Update check failed for {}: {}", m.name, e);
+                errors += 1;
+            }
+        }
+    }
+
+    timer.finish(app_handle, errors == 0, Some(checked));
+    (updates, errors)
+}
+
+/// What `update_all` would do for one mod
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedUpdate {
+    pub unique_id: String,
+    pub name: String,
+    pub file_id: Option<u32>,
+    pub file_name: Option<String>,
+    pub download_bytes: Option<u64>,
+    /// Installed folder, snapshotted before the new version replaces it
+    pub backup_path: Option<String>,
+    pub backup_bytes: u64,
+    /// Why the mod would be left alone, if it would
+    pub skip_reason: Option<String>,
+}
+
+/// Everything `update_all` would do for a batch, without doing it
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlan {
+    pub updates: Vec<PlannedUpdate>,
+    pub download_bytes: u64,
+    /// Nexus API requests the batch will make, including the premium check
+    pub api_calls: u32,
+    pub backups: usize,
+    /// Based on the speed of the last large download, when there was one
+    pub estimated_seconds: Option<u64>,
+}
+
+/// Work out what updating `updates` involves: the files to download and
+/// their sizes, the API requests, the backups and roughly how long it takes.
+/// Looking up the files costs one API request per mod, nothing is queued.
+pub async fn plan_updates(app_handle: &AppHandle, updates: Vec<AvailableUpdate>) -> Result<UpdatePlan, String> {
+    let settings = Settings::load(app_handle)?;
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let nexus = NexusClient::new(app_handle, &settings);
+    let mut installed = crate::mod_installer::scan_mods(Path::new(&settings.game_path));
+    crate::mod_registry::apply(app_handle, &mut installed);
+
+    let mut planned = Vec::new();
+    for update in updates {
+        let mut plan = PlannedUpdate {
+            unique_id: update.unique_id.clone(),
+            name: update.name.clone(),
+            file_id: None,
+            file_name: None,
+            download_bytes: None,
+            backup_path: None,
+            backup_bytes: 0,
+            skip_reason: None,
+        };
+
+        let installed_mod = match updatable_mod(&installed, &update) {
+            Ok(installed_mod) => installed_mod,
+            Err(e) => {
+                plan.skip_reason = Some(e);
+                planned.push(plan);
+                continue;
+            }
+        };
+        plan.backup_path = Some(installed_mod.path.clone());
+        plan.backup_bytes = crate::mod_query::dir_size(&installed_mod.path);
+
+        match nexus.mod_files("stardewvalley", update.nexus_mod_id).await {
+            Ok(files) => match latest_main_file(&files) {
+                Some(file) => {
+                    plan.file_id = Some(file.file_id);
+                    plan.file_name = file.file_name.clone().or_else(|| Some(file.name.clone()));
+                    plan.download_bytes = file.size_bytes();
+                }
+                None => plan.skip_reason = Some("No main file on the mod page".to_string()),
+            },
+            Err(e) => plan.skip_reason = Some(e.to_string()),
+        }
+        planned.push(plan);
+    }
+
+    let to_update: Vec<&PlannedUpdate> = planned.iter().filter(|p| p.skip_reason.is_none()).collect();
+    let download_bytes = to_update.iter().filter_map(|p| p.download_bytes).sum();
+    let speed = app_handle.state::<DownloadManager>().recent_speed_bps().await;
+
+    Ok(UpdatePlan {
+        download_bytes,
+        api_calls: 1 + API_CALLS_PER_UPDATE * to_update.len() as u32,
+        backups: to_update.len(),
+        estimated_seconds: speed.map(|bps| download_bytes / bps),
+        updates: planned,
+    })
+}
+
+/// The installed copy of the mod `update` is for, if we may update it
+fn updatable_mod<'a>(installed: &'a [Mod], update: &AvailableUpdate) -> Result<&'a Mod, String> {
+    let installed_mod = installed
+        .iter()
+        .find(|m| m.unique_id.eq_ignore_ascii_case(&update.unique_id))
+        .ok_or("Mod is no longer installed")?;
+    if installed_mod.is_unmanaged {
+        return Err("In an unmanaged folder, update it by hand".to_string());
+    }
+    match installed_mod.source {
+        Some(ModSource::Vortex) => Err("Deployed by Vortex, update it there".to_string()),
+        Some(source) if !source.auto_updatable() => Err("Copied into Mods by hand, update it manually".to_string()),
+        _ => Ok(installed_mod),
+    }
+}
+
+/// A mod being updated, between queueing and installing
+struct PendingUpdate {
+    update: AvailableUpdate,
+    download_id: String,
+    /// Folder of the installed version
+    old_path: PathBuf,
+    downloading: bool,
+}
+
+fn report(app_handle: &AppHandle, update: &AvailableUpdate, stage: UpdateStage, message: Option<String>) -> ModUpdateProgress {
+    let progress = ModUpdateProgress {
+        unique_id: update.unique_id.clone(),
+        name: update.name.clone(),
+        stage,
+        message,
+    };
+    events::emit(app_handle, AppEvent::ModUpdateProgress(progress.clone()));
+    progress
+}
+
+/// The file an update should install: the primary main file if there is
+/// one, otherwise the newest main file
+pub fn latest_main_file(files: &[ModFile]) -> Option<&ModFile> {
+    let main_files = files.iter().filter(|f| f.category_name.as_deref() == Some("MAIN"));
+    main_files
+        .clone()
+        .filter(|f| f.is_primary)
+        .max_by_key(|f| f.uploaded_at)
+        .or_else(|| main_files.max_by_key(|f| f.uploaded_at))
+}
+
+/// Download and install the latest main file of every mod in `updates`,
+/// emitting `mod-update-progress` along the way. Returns the final state of
+/// each mod. Refused in read-only mode, whoever asks.
+///
+/// Files are requested without a key from the website, which Nexus only
+/// allows for premium accounts.
+pub async fn update_all(app_handle: &AppHandle, updates: Vec<AvailableUpdate>) -> Result<Vec<ModUpdateProgress>, String> {
+    let settings = Settings::load(app_handle)?;
+    settings.ensure_writable().map_err(|e| e.to_string())?;
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+
+    let nexus = NexusClient::new(app_handle, &settings);
+    if !nexus.validate_user().await?.is_premium {
+        return Err("Updating all mods at once needs a Nexus Mods Premium account".to_string());
+    }
+
+    let game_path = PathBuf::from(&settings.game_path);
+    let mut installed = crate::mod_installer::scan_mods(&game_path);
+    crate::mod_registry::apply(app_handle, &mut installed);
+    let manager = app_handle.state::<DownloadManager>();
+
+    let mut results = Vec::new();
+    let mut pending = Vec::new();
+    for update in updates {
+        match queue_update(&nexus, &manager, &installed, &update).await {
+            Ok((download_id, old_path)) => {
+                report(app_handle, &update, UpdateStage::Queued, None);
+                pending.push(PendingUpdate {
+                    update,
+                    download_id,
+                    old_path,
+                    downloading: false,
+                });
+            }
+            Err(e) => results.push(report(app_handle, &update, UpdateStage::Failed, Some(e))),
+        }
+    }
+
+    let installer = ModInstaller::new(
+        app_handle.clone(),
+        app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("temp"),
+    )
+    .unattended();
+
+    // Install each mod as soon as its download finishes
+    while !pending.is_empty() {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let queue = manager.get_queue_state().await;
+
+        let mut still_pending = Vec::new();
+        for mut p in pending {
+            let Some(task) = queue.iter().find(|t| t.id == p.download_id) else {
+                results.push(report(app_handle, &p.update, UpdateStage::Failed, Some("Download cancelled".to_string())));
+                continue;
+            };
+
+            match &task.status {
+                DownloadStatus::Completed => {
+                    report(app_handle, &p.update, UpdateStage::Installing, None);
+                    let archive = task.file_path.clone().unwrap_or_default();
+                    let mut meta =
+                        crate::download_manager::archive_nexus_meta(&archive, task.nxm_url.mod_id, task.nxm_url.file_id);
+                    meta.file_version = task.file_version.clone();
+
+                    let result = installer
+                        .install_from_archive(&archive, &game_path, &settings, Some(meta), task.mod_name.clone())
+                        .await
+                        .map_err(|e| e.to_string())
+                        .and_then(|results| {
+                            for result in &results {
+                                crate::mod_registry::record_install(app_handle, result, ModSource::Nexus, &archive);
+                            }
+                            let result = installed_result(results, &p.update.unique_id)?;
+                            replace_old_folder(&installer, &settings, &p.update.unique_id, &p.old_path, &result.install_path)?;
+                            Ok(result)
+                        });
+
+                    results.push(match result {
+                        Ok(result) => report(
+                            app_handle,
+                            &p.update,
+                            UpdateStage::Updated,
+                            Some(format!("Updated to {}", result.version)),
+                        ),
+                        Err(e) => report(app_handle, &p.update, UpdateStage::Failed, Some(e)),
+                    });
+                }
+                DownloadStatus::Failed { error, .. } => {
+                    results.push(report(app_handle, &p.update, UpdateStage::Failed, Some(error.clone())));
+                }
+                DownloadStatus::Paused => {
+                    let error = give_up_paused(&manager, &p.download_id).await;
+                    results.push(report(app_handle, &p.update, UpdateStage::Failed, Some(error)));
+                }
+                DownloadStatus::Downloading if !p.downloading => {
+                    p.downloading = true;
+                    report(app_handle, &p.update, UpdateStage::Downloading, None);
+                    still_pending.push(p);
+                }
+                _ => still_pending.push(p),
+            }
+        }
+        pending = still_pending;
+    }
+
+    let updated = results.iter().filter(|r| r.stage == UpdateStage::Updated).count();
+    info!("🔄 Update all finished: {}/{} mods updated", updated, results.len());
+    Ok(results)
+}
+
+/// Queue the latest main file of one mod, returning the download id and the
+/// folder of the installed version
+async fn queue_update(
+    nexus: &NexusClient,
+    manager: &DownloadManager,
+    installed: &[Mod],
+    update: &AvailableUpdate,
+) -> Result<(String, PathBuf), String> {
+    let installed_mod = updatable_mod(installed, update)?;
+
+    let files = nexus.mod_files("stardewvalley", update.nexus_mod_id).await?;
+    let file = latest_main_file(&files).ok_or("No main file on the mod page")?;
+
+    let download_id = manager
+        .add_managed_to_queue(NxmUrl::direct("stardewvalley", update.nexus_mod_id, file.file_id))
+        .await?;
+
+    Ok((download_id, PathBuf::from(&installed_mod.path)))
+}
+
+/// Download one file of a mod without the website's key, wait for it and
+/// install it
+pub async fn install_nexus_file(
+    app_handle: &AppHandle,
+    installer: &ModInstaller,
+    game_path: &Path,
+    settings: &Settings,
+    mod_id: u32,
+    file_id: u32,
+    source: ModSource,
+) -> Result<Vec<InstallResult>, String> {
+    let manager = app_handle.state::<DownloadManager>();
+    let download_id = manager
+        .add_managed_to_queue(NxmUrl::direct("stardewvalley", mod_id, file_id))
+        .await?;
+
+    let task = loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let queue = manager.get_queue_state().await;
+        let Some(task) = queue.into_iter().find(|t| t.id == download_id) else {
+            return Err("Download cancelled".to_string());
+        };
+        match &task.status {
+            DownloadStatus::Completed => break task,
+            DownloadStatus::Failed { error, .. } => return Err(error.clone()),
+            DownloadStatus::Paused => return Err(give_up_paused(&manager, &download_id).await),
+            _ => {}
+        }
+    };
+
+    let archive = task.file_path.clone().unwrap_or_default();
+    let mut meta = crate::download_manager::archive_nexus_meta(&archive, mod_id, file_id);
+    meta.file_version = task.file_version.clone();
+
+    let results = installer
+        .install_from_archive(&archive, game_path, settings, Some(meta), task.mod_name.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    for result in &results {
+        crate::mod_registry::record_install(app_handle, result, source, &archive);
+    }
+    Ok(results)
+}
+
+/// Stop waiting for a managed download that was paused, by hand or held on
+/// a metered connection. Nothing would install it once it's resumed, so it's
+/// cancelled; returns the error to report.
+async fn give_up_paused(manager: &DownloadManager, download_id: &str) -> String {
+    if let Err(e) = manager.cancel_download(download_id).await {
+        warn!("Failed to cancel paused download {}: {}", download_id, e);
+    }
+    "Download paused, run the update again to finish it".to_string()
+}
+
+/// The install of `unique_id` among the mods an archive installed. An archive
+/// holding a single mod counts even if its id changed.
+pub fn installed_result(mut results: Vec<InstallResult>, unique_id: &str) -> Result<InstallResult, String> {
+    match results.iter().position(|r| r.unique_id.eq_ignore_ascii_case(unique_id)) {
+        Some(index) => Ok(results.swap_remove(index)),
+        None if results.len() == 1 => Ok(results.remove(0)),
+        None => Err(format!("The downloaded file doesn't contain {}", unique_id)),
+    }
+}
+
+/// When the new version landed in a different folder, carry the user's
+/// config over and remove the old folder, and its staged copy, so SMAPI
+/// doesn't see the mod twice. The old folder is snapshotted first, like a
+/// replace in place.
+pub fn replace_old_folder(
+    installer: &ModInstaller,
+    settings: &Settings,
+    unique_id: &str,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<(), String> {
+    if old_path == new_path || !old_path.exists() {
+        return Ok(());
+    }
+    if let Some(tool) = crate::foreign_managers::folder_manager(old_path) {
+        return Err(format!("The old version is managed by {}, remove it there", tool));
+    }
+
+    let backup_id = sanitize_folder_name(unique_id).ok_or_else(|| format!("Invalid mod id: {}", unique_id))?;
+    installer
+        .backup_mod(old_path, &backup_id, settings)
+        .map_err(|e| format!("Failed to back up the old version: {}", e))?;
+
+    let old_config = old_path.join(CONFIG_FILE_NAME);
+    if old_config.exists() {
+        fs::copy(&old_config, new_path.join(CONFIG_FILE_NAME))
+            .map_err(|e| format!("Failed to keep {}: {}", CONFIG_FILE_NAME, e))?;
+    }
+
+    fs::remove_dir_all(old_path).map_err(|e| format!("Failed to remove the old version: {}", e))?;
+    if let Err(e) = crate::staging::remove_staged(Path::new(&settings.game_path), old_path) {
+        warn!("Failed to remove the staged copy of {}: {}", old_path.display(), e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(file_id: u32, category: &str, uploaded: i64, is_primary: bool) -> ModFile {
+        serde_json::from_value(serde_json::json!({
+            "file_id": file_id,
+            "name": "File",
+            "category_name": category,
+            "uploaded_timestamp": uploaded,
+            "is_primary": is_primary,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_latest_main_file() {
+        let files = vec![
+            file(1, "MAIN", 100, false),
+            file(2, "MAIN", 300, false),
+            file(3, "OPTIONAL", 400, false),
+        ];
+        assert_eq!(latest_main_file(&files).map(|f| f.file_id), Some(2));
+
+        let files = vec![file(1, "MAIN", 100, true), file(2, "MAIN", 300, false)];
+        assert_eq!(latest_main_file(&files).map(|f| f.file_id), Some(1));
+
+        assert!(latest_main_file(&[file(3, "OPTIONAL", 400, false)]).is_none());
+    }
+}
@@ -0,0 +1,433 @@
+//! Taking over the mods Vortex deployed into Stardew Valley's Mods folder.
+//! Vortex keeps each mod in its own staging folder and hardlinks the files
+//! into Mods, listing every link in `vortex.deployment.json`. The import
+//! copies each deployed mod from that staging folder into the layout this
+//! app uses, carries over the Nexus ids Vortex put in the staging folder
+//! name and then disables the deployment so Vortex stops managing Mods.
+//! Vortex keeps its own record of the deployment, so it has to be closed
+//! during the import and purged or told to stop managing the game after.
+//! Mods disabled in Vortex aren't deployed and are left behind.
+
+use crate::mod_installer::{ModInstaller, CONFIG_FILE_NAME};
+use crate::mod_registry::MigratedMod;
+use crate::nexus_api::{compare_versions, ModFile, NexusClient};
+use crate::nexus_meta::NexusMeta;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const DEPLOYMENT_MANIFEST: &str = "vortex.deployment.json";
+/// Left by Vortex in the folders it deploys to
+const FOLDER_MARKER: &str = "__folder_managed_by_vortex";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeploymentManifest {
+    #[serde(default)]
+    staging_path: String,
+    #[serde(default)]
+    files: Vec<DeployedFile>,
+}
+
+/// One hardlink Vortex made: `relPath` under Mods, from the staging folder
+/// of the Vortex mod named `source`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeployedFile {
+    rel_path: String,
+    source: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VortexMod {
+    pub name: String,
+    pub unique_id: Option<String>,
+    /// Deployed folder in Mods, which the import replaces
+    pub path: String,
+    /// Folder the files are copied from; the deployed folder itself when
+    /// the Vortex staging folder is gone
+    pub source_path: String,
+    /// Vortex's name for the mod, the archive it was installed from
+    pub vortex_name: String,
+    pub version: Option<String>,
+    pub nexus_mod_id: Option<u32>,
+    pub nexus_file_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedVortexMod {
+    pub name: String,
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VortexImportReport {
+    pub dry_run: bool,
+    pub staging_path: String,
+    pub mods: Vec<VortexMod>,
+    pub failed: Vec<FailedVortexMod>,
+    /// The deployment manifest was set aside. Only done once every mod was
+    /// imported, so a failed import can be run again. Vortex still has to
+    /// be purged, or stop managing the game, before it's opened again.
+    pub deployment_disabled: bool,
+}
+
+/// Path parts of a deployment entry, which uses the separators of the
+/// system Vortex ran on
+fn path_parts(rel_path: &str) -> Vec<&str> {
+    rel_path.split(['/', '\\']).filter(|part| !part.is_empty()).collect()
+}
+
+/// Vortex names a mod after its archive, and Nexus names archives
+/// `<name>-<mod id>-<version with dashes>-<upload time>`. A second install
+/// of the same archive gets a "+1" suffix. Returns the mod id and version.
+fn parse_vortex_name(name: &str) -> Option<(u32, String)> {
+    let archive = archive_name(name);
+    let parts: Vec<&str> = archive.split('-').collect();
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if parts.len() < 4 || !is_number(parts[parts.len() - 1]) {
+        return None;
+    }
+
+    // The first number after the name; names with a number in them are rare
+    let id_index = (1..parts.len() - 2).find(|&i| is_number(parts[i]))?;
+    let mod_id = parts[id_index].parse().ok()?;
+    Some((mod_id, parts[id_index + 1..parts.len() - 1].join(".")))
+}
+
+/// A Vortex mod name without the suffix of a repeated install
+fn archive_name(name: &str) -> &str {
+    match name.rsplit_once('+') {
+        Some((archive, n)) if n.chars().all(|c| c.is_ascii_digit()) => archive,
+        _ => name,
+    }
+}
+
+/// The Nexus file a Vortex mod was installed from: the file with the same
+/// archive name, or the one main file of the same version
+fn matching_file(files: &[ModFile], vortex_name: &str, version: Option<&str>) -> Option<u32> {
+    let archive = archive_name(vortex_name);
+    let by_name = files.iter().find(|f| {
+        f.file_name
+            .as_deref()
+            .and_then(|name| Path::new(name).file_stem())
+            .is_some_and(|stem| stem.to_string_lossy() == archive)
+    });
+    if let Some(file) = by_name {
+        return Some(file.file_id);
+    }
+
+    let version = version?;
+    let same_version: Vec<&ModFile> = files
+        .iter()
+        .filter(|f| f.version.as_deref().is_some_and(|v| compare_versions(v, version) == Ordering::Equal))
+        .collect();
+    match same_version.as_slice() {
+        [file] => Some(file.file_id),
+        _ => {
+            let mut main = same_version.iter().filter(|f| f.category_name.as_deref() == Some("MAIN"));
+            match (main.next(), main.next()) {
+                (Some(file), None) => Some(file.file_id),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn read_deployment(mods_dir: &Path) -> Result<DeploymentManifest, String> {
+    let path = mods_dir.join(DEPLOYMENT_MANIFEST);
+    let content = fs::read_to_string(&path).map_err(|_| "No Vortex deployment found in the Mods folder".to_string())?;
+    serde_json::from_str(&crate::mod_installer::lenient_json(&content))
+        .map_err(|e| format!("Invalid {}: {}", DEPLOYMENT_MANIFEST, e))
+}
+
+/// The mod folders of a deployment: every deployed manifest.json marks one,
+/// and a Vortex mod may deploy several
+fn deployed_mods(manifest: &DeploymentManifest, mods_dir: &Path, staging_path: &Path) -> Vec<VortexMod> {
+    let mut folders: BTreeMap<Vec<&str>, &str> = BTreeMap::new();
+    for file in &manifest.files {
+        let parts = path_parts(&file.rel_path);
+        if let Some((last, folder)) = parts.split_last() {
+            if last.eq_ignore_ascii_case("manifest.json") && !folder.is_empty() {
+                folders.insert(folder.to_vec(), &file.source);
+            }
+        }
+    }
+
+    folders
+        .into_iter()
+        .map(|(folder, vortex_name)| {
+            let path: PathBuf = folder.iter().fold(mods_dir.to_path_buf(), |path, part| path.join(part));
+            let staged: PathBuf = folder
+                .iter()
+                .fold(staging_path.join(vortex_name), |path, part| path.join(part));
+            let source = if staged.join("manifest.json").is_file() { staged } else { path.clone() };
+
+            let manifest = crate::mod_installer::read_manifest(&source);
+            let parsed = parse_vortex_name(vortex_name);
+            VortexMod {
+                name: manifest
+                    .as_ref()
+                    .map(|m| m.name.clone())
+                    .unwrap_or_else(|| folder.join("/")),
+                unique_id: manifest.as_ref().map(|m| m.unique_id.clone()),
+                path: path.to_string_lossy().to_string(),
+                source_path: source.to_string_lossy().to_string(),
+                vortex_name: vortex_name.to_string(),
+                version: parsed
+                    .as_ref()
+                    .map(|(_, version)| version.clone())
+                    .or_else(|| manifest.map(|m| m.version)),
+                nexus_mod_id: parsed.map(|(mod_id, _)| mod_id),
+                nexus_file_id: None,
+            }
+        })
+        .collect()
+}
+
+/// Replace the deployed links of one mod with our own copy, keeping the
+/// config.json SMAPI wrote into the deployed folder
+fn import_mod(installer: &ModInstaller, game_path: &Path, settings: &Settings, vortex_mod: &VortexMod) -> Result<(), String> {
+    let install_path = Path::new(&vortex_mod.path);
+    let source = Path::new(&vortex_mod.source_path);
+    settings.ensure_managed(install_path)?;
+
+    let kept_config = fs::read(install_path.join(CONFIG_FILE_NAME)).ok();
+    installer
+        .place_files(
+            game_path,
+            source,
+            install_path,
+            &[source.join(FOLDER_MARKER)],
+            kept_config,
+            settings.install_mode,
+        )
+        .map_err(|e| e.to_string())?;
+
+    if let (Some(mod_id), Some(file_id)) = (vortex_mod.nexus_mod_id, vortex_mod.nexus_file_id) {
+        let mut meta = NexusMeta::new(mod_id, file_id);
+        meta.file_version = vortex_mod.version.clone();
+        if let Err(e) = meta.write(install_path) {
+            warn!("Failed to write Nexus metadata for {}: {}", vortex_mod.name, e);
+        }
+    }
+    Ok(())
+}
+
+/// Set Vortex's deployment manifest and folder marker aside so it treats
+/// Mods as no longer deployed. The `.bak` copies keep what Vortex knew.
+fn disable_deployment(mods_dir: &Path) -> std::io::Result<()> {
+    for name in [DEPLOYMENT_MANIFEST, FOLDER_MARKER] {
+        let path = mods_dir.join(name);
+        if path.exists() {
+            fs::rename(&path, mods_dir.join(format!("{}.bak", name)))?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether Vortex is open. It deploys again on its own, over the copies
+/// the import just made.
+fn vortex_running() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq Vortex.exe", "/NH"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).to_lowercase().contains("vortex.exe"))
+    }
+
+    // Under Wine or Proton
+    #[cfg(not(target_os = "windows"))]
+    {
+        Command::new("pgrep")
+            .args(["-f", "-i", "vortex\\.exe"])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+}
+
+/// Import every mod Vortex deployed. `staging_dir` overrides the staging
+/// folder in the deployment manifest, for when Vortex's folder moved. With
+/// `dry_run` nothing is written and the report shows what would be imported.
+pub async fn import(app_handle: &AppHandle, dry_run: bool, staging_dir: Option<String>) -> Result<VortexImportReport, String> {
+    let settings = Settings::load(app_handle)?;
+    if !dry_run {
+        settings.ensure_writable()?;
+    }
+    if settings.game_path.is_empty() {
+        return Err("Game path not configured".to_string());
+    }
+    if !dry_run && vortex_running() {
+        return Err("Close Vortex first, it would deploy its mods again over the imported ones".to_string());
+    }
+
+    let game_path = PathBuf::from(&settings.game_path);
+    let mods_dir = game_path.join("Mods");
+    let deployment = read_deployment(&mods_dir)?;
+    let staging_path = staging_dir
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| deployment.staging_path.clone());
+    let mut mods = deployed_mods(&deployment, &mods_dir, Path::new(&staging_path));
+
+    // The folder name only has the mod id; the file comes from its file list
+    let nexus = NexusClient::new(app_handle, &settings);
+    if nexus.has_api_key() {
+        let mut files: HashMap<u32, Vec<ModFile>> = HashMap::new();
+        for vortex_mod in mods.iter_mut() {
+            let Some(mod_id) = vortex_mod.nexus_mod_id else { continue };
+            let listed = match files.entry(mod_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(nexus.mod_files("stardewvalley", mod_id).await.unwrap_or_else(|e| {
+                    warn!("Couldn't list the files of Nexus mod {}: {}", mod_id, e);
+                    Vec::new()
+                })),
+            };
+            vortex_mod.nexus_file_id = matching_file(listed, &vortex_mod.vortex_name, vortex_mod.version.as_deref());
+        }
+    }
+
+    let mut report = VortexImportReport {
+        dry_run,
+        staging_path,
+        mods,
+        failed: Vec::new(),
+        deployment_disabled: false,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    let temp_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?.join("temp");
+    let installer = ModInstaller::new(app_handle.clone(), temp_dir);
+    let mods = report.mods.clone();
+    let scan_path = game_path.clone();
+    let (failed, deployment_disabled) = tokio::task::spawn_blocking(move || {
+        let failed: Vec<FailedVortexMod> = mods
+            .iter()
+            .filter_map(|vortex_mod| {
+                info!("📦 Importing {} from Vortex", vortex_mod.name);
+                import_mod(&installer, &game_path, &settings, vortex_mod)
+                    .err()
+                    .map(|error| FailedVortexMod {
+                        name: vortex_mod.name.clone(),
+                        path: vortex_mod.path.clone(),
+                        error,
+                    })
+            })
+            .collect();
+
+        let disabled = failed.is_empty()
+            && disable_deployment(&mods_dir)
+                .inspect_err(|e| warn!("Failed to disable the Vortex deployment: {}", e))
+                .is_ok();
+        (failed, disabled)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    report.failed = failed;
+    report.deployment_disabled = deployment_disabled;
+
+    // Registry entries for the copies, with the mod id even where no file matched
+    let mut scanned = crate::mod_installer::scan_mods(&scan_path);
+    crate::mod_registry::apply(app_handle, &mut scanned);
+    let imported: Vec<MigratedMod> = report
+        .mods
+        .iter()
+        .filter(|m| !report.failed.iter().any(|f| f.path == m.path))
+        .filter_map(|m| {
+            Some(MigratedMod {
+                path: m.path.clone(),
+                unique_id: m.unique_id.clone()?,
+                content_hash: crate::library_migration::folder_hash(Path::new(&m.path)).ok(),
+                nexus_mod_id: m.nexus_mod_id,
+                moddrop_id: None,
+            })
+        })
+        .collect();
+    crate::mod_registry::record_migration(app_handle, &imported)?;
+
+    info!(
+        "📦 Imported {} mods from Vortex ({} failed)",
+        report.mods.len() - report.failed.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vortex_name() {
+        assert_eq!(
+            parse_vortex_name("Lookup Anything-541-1-40-0-1700000000"),
+            Some((541, "1.40.0".to_string()))
+        );
+        assert_eq!(
+            parse_vortex_name("Stardew Valley Expanded-3753-1-15-10-1700000000+1"),
+            Some((3753, "1.15.10".to_string()))
+        );
+        assert_eq!(parse_vortex_name("My Local Mod"), None);
+        assert_eq!(parse_vortex_name("CJB-Cheats-Menu-1-0"), None);
+    }
+
+    #[test]
+    fn test_deployed_mods() {
+        let game_dir = std::env::temp_dir().join(format!("tc_vortex_{}", uuid::Uuid::new_v4()));
+        let mods_dir = game_dir.join("Mods");
+        let staging = game_dir.join("vortex_staging");
+        let staged = staging.join("Lookup Anything-541-1-40-0-1700000000").join("LookupAnything");
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(
+            staged.join("manifest.json"),
+            r#"{"Name": "Lookup Anything", "Version": "1.40.0", "UniqueID": "Pathoschild.LookupAnything"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(mods_dir.join("Manual")).unwrap();
+        fs::write(
+            mods_dir.join(DEPLOYMENT_MANIFEST),
+            format!(
+                r#"{{"stagingPath": {:?}, "files": [
+                    {{"relPath": "LookupAnything\\manifest.json", "source": "Lookup Anything-541-1-40-0-1700000000"}},
+                    {{"relPath": "LookupAnything\\LookupAnything.dll", "source": "Lookup Anything-541-1-40-0-1700000000"}},
+                    {{"relPath": "Manual/manifest.json", "source": "Manual"}}
+                ]}}"#,
+                staging.to_string_lossy()
+            ),
+        )
+        .unwrap();
+
+        let deployment = read_deployment(&mods_dir).unwrap();
+        let mods = deployed_mods(&deployment, &mods_dir, Path::new(&deployment.staging_path));
+        assert_eq!(mods.len(), 2);
+        assert_eq!(mods[0].unique_id.as_deref(), Some("Pathoschild.LookupAnything"));
+        assert_eq!(mods[0].path, mods_dir.join("LookupAnything").to_string_lossy());
+        assert_eq!(mods[0].source_path, staged.to_string_lossy());
+        assert_eq!(mods[0].nexus_mod_id, Some(541));
+        // Not in Vortex's staging folder, so copied from where it's deployed
+        assert_eq!(mods[1].source_path, mods_dir.join("Manual").to_string_lossy());
+        assert_eq!(mods[1].nexus_mod_id, None);
+
+        disable_deployment(&mods_dir).unwrap();
+        assert!(read_deployment(&mods_dir).is_err());
+        assert!(mods_dir.join("vortex.deployment.json.bak").is_file());
+
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
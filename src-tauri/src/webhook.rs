@@ -0,0 +1,151 @@
+//! Optional outbound notifications for install, update and failure events,
+//! posted to a user-configured webhook. The payload uses Discord's webhook
+//! format; other services that accept `content`/`embeds` work too.
+
+use crate::events::AppEvent;
+use crate::settings::Settings;
+use crate::update_all::UpdateStage;
+use chrono::Utc;
+use serde::Serialize;
+use tauri::AppHandle;
+use tracing::warn;
+
+const USERNAME: &str = "Treasure Chest";
+
+const COLOR_SUCCESS: u32 = 0x2ecc71;
+const COLOR_FAILURE: u32 = 0xe74c3c;
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Embed {
+    title: String,
+    description: String,
+    color: u32,
+    timestamp: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct WebhookPayload {
+    username: &'static str,
+    embeds: Vec<Embed>,
+}
+
+fn payload(title: String, description: String, color: u32) -> WebhookPayload {
+    WebhookPayload {
+        username: USERNAME,
+        embeds: vec![Embed {
+            title,
+            description,
+            color,
+            timestamp: Utc::now().to_rfc3339(),
+        }],
+    }
+}
+
+/// The message for an event, if it's one worth notifying about
+fn message_for(event: &AppEvent) -> Option<WebhookPayload> {
+    match event {
+        AppEvent::ModInstalled(result) => Some(payload(
+            format!("Installed {}", result.mod_name),
+            format!("Version {} ({})", result.version, result.unique_id),
+            COLOR_SUCCESS,
+        )),
+        AppEvent::ModInstallFailed(error) => Some(payload("Install failed".to_string(), error.clone(), COLOR_FAILURE)),
+        AppEvent::ModUpdateProgress(progress) => {
+            let (title, color) = match progress.stage {
+                UpdateStage::Updated => (format!("Updated {}", progress.name), COLOR_SUCCESS),
+                UpdateStage::Failed => (format!("Update of {} failed", progress.name), COLOR_FAILURE),
+                _ => return None,
+            };
+            Some(payload(title, progress.message.clone().unwrap_or_default(), color))
+        }
+        // Retryable failures are retried automatically; only report the final one
+        AppEvent::DownloadFailed {
+            error,
+            retryable: false,
+            ..
+        } => Some(payload("Download failed".to_string(), error.clone(), COLOR_FAILURE)),
+        _ => None,
+    }
+}
+
+async fn post(url: &str, payload: &WebhookPayload) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("User-Agent", crate::nexus_api::USER_AGENT)
+        .json(payload)
+        .send()
+        .await
+        // Strip the URL, its path holds the webhook token
+        .map_err(|e| format!("Failed to reach webhook: {}", e.without_url()))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Post the event to the configured webhook in the background
+pub fn notify(app_handle: &AppHandle, event: &AppEvent) {
+    let Some(payload) = message_for(event) else {
+        return;
+    };
+    let Ok(settings) = Settings::load(app_handle) else {
+        return;
+    };
+    if settings.webhook_url.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = post(&settings.webhook_url, &payload).await {
+            warn!("{}", e);
+        }
+    });
+}
+
+/// Send a test message so users can check the URL from the settings screen
+pub async fn send_test(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("Webhook URL not configured".to_string());
+    }
+    post(
+        url,
+        &payload(
+            "Webhook connected".to_string(),
+            "Install, update and failure notifications will be posted here.".to_string(),
+            COLOR_SUCCESS,
+        ),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_all::ModUpdateProgress;
+
+    #[test]
+    fn test_message_for() {
+        let updated = message_for(&AppEvent::ModUpdateProgress(ModUpdateProgress {
+            unique_id: "Pathoschild.LookupAnything".to_string(),
+            name: "Lookup Anything".to_string(),
+            stage: UpdateStage::Updated,
+            message: Some("Updated to 1.37.0".to_string()),
+        }))
+        .unwrap();
+        assert_eq!(updated.embeds[0].title, "Updated Lookup Anything");
+        assert_eq!(updated.embeds[0].color, COLOR_SUCCESS);
+
+        let json = serde_json::to_value(&updated).unwrap();
+        assert_eq!(json["username"], USERNAME);
+        assert_eq!(json["embeds"][0]["description"], "Updated to 1.37.0");
+
+        let retrying = AppEvent::DownloadFailed {
+            download_id: "abc".to_string(),
+            error: "timeout".to_string(),
+            retryable: true,
+        };
+        assert!(message_for(&retrying).is_none());
+        assert!(message_for(&AppEvent::DownloadCompleted("abc".to_string())).is_none());
+    }
+}
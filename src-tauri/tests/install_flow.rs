@@ -0,0 +1,430 @@
+//! End-to-end install tests against temp directories, without a Tauri runtime
+
+use sdv_mods_manager_lib::app_core::{
+    deploy_staged, scan_mods, staging_root, EventSink, InstallError, InstallMode, InstallStrategy, ModInstaller, NexusMeta,
+    NxmUrl, Settings,
+};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use zip::write::FileOptions;
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<(String, serde_json::Value)>>,
+}
+
+impl RecordingSink {
+    fn names(&self) -> Vec<String> {
+        self.events.lock().unwrap().iter().map(|(name, _)| name.clone()).collect()
+    }
+}
+
+impl EventSink for RecordingSink {
+    fn emit_json(&self, event: &str, payload: serde_json::Value) {
+        self.events.lock().unwrap().push((event.to_string(), payload));
+    }
+}
+
+struct Fixture {
+    root: PathBuf,
+    game_path: PathBuf,
+    sink: Arc<RecordingSink>,
+    installer: ModInstaller,
+    settings: Settings,
+}
+
+impl Fixture {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("treasure_chest_it_{}", name));
+        let _ = fs::remove_dir_all(&root);
+        let game_path = root.join("game");
+        fs::create_dir_all(game_path.join("Mods")).unwrap();
+
+        let sink = Arc::new(RecordingSink::default());
+        let installer = ModInstaller::with_sink(sink.clone(), root.join("temp"), root.join("backups"));
+        let settings = Settings {
+            game_path: game_path.to_string_lossy().to_string(),
+            ..Settings::default()
+        };
+
+        Self {
+            root,
+            game_path,
+            sink,
+            installer,
+            settings,
+        }
+    }
+
+    /// Write a zip archive with the given (path, contents) entries
+    fn archive(&self, file_name: &str, entries: &[(&str, &str)]) -> PathBuf {
+        let path = self.root.join(file_name);
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        for (name, contents) in entries {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn manifest(name: &str, unique_id: &str, version: &str) -> String {
+    format!(
+        r#"{{"Name": "{}", "Author": "Tester", "Version": "{}", "UniqueID": "{}"}}"#,
+        name, version, unique_id
+    )
+}
+
+fn installed_folders(game_path: &Path) -> Vec<String> {
+    let mut folders: Vec<String> = fs::read_dir(game_path.join("Mods"))
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    folders.sort();
+    folders
+}
+
+#[tokio::test]
+async fn installs_single_folder_archive_with_nexus_meta() {
+    let fx = Fixture::new("single_folder");
+    let archive = fx.archive(
+        "LookupAnything-541-1-37.zip",
+        &[
+            ("LookupAnything/manifest.json", &manifest("Lookup Anything", "Pathoschild.LookupAnything", "1.37.0")),
+            ("LookupAnything/LookupAnything.dll", "binary"),
+            ("LookupAnything/.DS_Store", ""),
+            ("__MACOSX/LookupAnything/._manifest.json", ""),
+        ],
+    );
+
+    let results = fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, Some(NexusMeta::new(541, 9001)), None)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].unique_id, "Pathoschild.LookupAnything");
+    assert_eq!(installed_folders(&fx.game_path), vec!["LookupAnything"]);
+    assert_eq!(results[0].strategy, InstallStrategy::SingleFolder);
+    assert_eq!(results[0].manifests.len(), 1);
+    assert_eq!(results[0].manifests[0].path, "LookupAnything/manifest.json");
+    assert_eq!(results[0].skipped_files, vec!["LookupAnything/.DS_Store", "__MACOSX"]);
+    assert!(!fx.game_path.join("Mods").join("LookupAnything").join(".DS_Store").exists());
+    assert_eq!(fx.sink.names(), vec!["mod-installed"]);
+
+    let mods = scan_mods(&fx.game_path);
+    assert_eq!(mods.len(), 1);
+    assert_eq!(mods[0].nexus_mod_id, Some(541));
+    assert_eq!(mods[0].nexus_file_id, Some(9001));
+}
+
+#[tokio::test]
+async fn wraps_loose_files_in_a_sanitized_folder() {
+    let fx = Fixture::new("loose_files");
+    let archive = fx.archive(
+        "loose.zip",
+        &[
+            ("manifest.json", &manifest("Loose Mod", "Tester.Loose", "1.0.0")),
+            ("content.json", "{}"),
+        ],
+    );
+
+    let results = fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, Some("Loose: Mod?".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(results[0].strategy, InstallStrategy::LooseFiles);
+    assert_eq!(installed_folders(&fx.game_path), vec!["Loose Mod"]);
+}
+
+#[tokio::test]
+async fn installs_each_mod_of_a_bundle_separately() {
+    let fx = Fixture::new("bundle");
+    let archive = fx.archive(
+        "Bundle.zip",
+        &[
+            ("Bundle/[SMAPI] Core/manifest.json", &manifest("Core", "Tester.Core", "1.0.0")),
+            ("Bundle/[SMAPI] Core/Core.dll", "binary"),
+            ("Bundle/[CP] Pack/manifest.json", &manifest("Pack", "Tester.Pack", "1.0.0")),
+            ("Bundle/[CP] Pack/content.json", "{}"),
+        ],
+    );
+
+    let mut results = fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, Some(NexusMeta::new(7, 70)), None)
+        .await
+        .unwrap();
+    results.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+
+    let ids: Vec<&str> = results.iter().map(|r| r.unique_id.as_str()).collect();
+    assert_eq!(ids, vec!["Tester.Core", "Tester.Pack"]);
+    assert_eq!(results[1].mod_name, "Pack");
+    assert!(results.iter().all(|r| r.strategy == InstallStrategy::MultiManifest && r.manifests.len() == 2));
+    assert_eq!(installed_folders(&fx.game_path), vec!["[CP] Pack", "[SMAPI] Core"]);
+    assert_eq!(fx.sink.names(), vec!["mod-installed", "mod-installed"]);
+    assert!(scan_mods(&fx.game_path).iter().all(|m| m.nexus_mod_id == Some(7)));
+}
+
+#[tokio::test]
+async fn a_failed_bundle_install_rolls_back_the_mods_before_it() {
+    let fx = Fixture::new("bundle_rollback");
+    let mods = fx.game_path.join("Mods");
+    for (dir, id) in [("Beta", "Tester.Beta"), ("Zeta", "Tester.Zeta")] {
+        fs::create_dir_all(mods.join(dir)).unwrap();
+        fs::write(mods.join(dir).join("manifest.json"), manifest(dir, id, "1.0.0")).unwrap();
+    }
+    fs::write(mods.join("Zeta").join("__folder_managed_by_vortex"), "").unwrap();
+    let archive = fx.archive(
+        "Bundle.zip",
+        &[
+            ("Alpha/manifest.json", &manifest("Alpha", "Tester.Alpha", "2.0.0")),
+            ("Beta/manifest.json", &manifest("Beta", "Tester.Beta", "2.0.0")),
+            ("Zeta/manifest.json", &manifest("Zeta", "Tester.Zeta", "2.0.0")),
+        ],
+    );
+
+    assert!(fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+        .await
+        .is_err());
+    assert_eq!(installed_folders(&fx.game_path), vec!["Beta", "Zeta"]);
+    let versions: Vec<String> = scan_mods(&fx.game_path).into_iter().map(|m| m.version).collect();
+    assert_eq!(versions, vec!["1.0.0", "1.0.0"]);
+    assert_eq!(fs::read_dir(fx.root.join("temp")).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn installs_a_nested_content_pack_next_to_its_framework() {
+    let fx = Fixture::new("companion");
+    let pack_manifest = r#"{"Name": "Tool [CP]", "Author": "Tester", "Version": "1.0.0", "UniqueID": "Tester.Tool.CP",
+        "ContentPackFor": {"UniqueID": "tester.tool"}}"#;
+    let archive = fx.archive(
+        "Tool.zip",
+        &[
+            ("Tool/manifest.json", &manifest("Tool", "Tester.Tool", "1.0.0")),
+            ("Tool/Tool.dll", "binary"),
+            ("Tool/[CP] Tool/manifest.json", pack_manifest),
+            ("Tool/[CP] Tool/content.json", "{}"),
+        ],
+    );
+
+    let results = fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+        .await
+        .unwrap();
+
+    let ids: Vec<&str> = results.iter().map(|r| r.unique_id.as_str()).collect();
+    assert_eq!(ids, vec!["Tester.Tool", "Tester.Tool.CP"]);
+    assert_eq!(results[0].parent_unique_id, None);
+    assert_eq!(results[1].parent_unique_id.as_deref(), Some("Tester.Tool"));
+    assert_eq!(installed_folders(&fx.game_path), vec!["Tool", "[CP] Tool"]);
+    assert!(!fx.game_path.join("Mods").join("Tool").join("[CP] Tool").exists());
+}
+
+#[tokio::test]
+async fn asks_which_variant_to_install() {
+    let fx = Fixture::new("variants");
+    let archive = fx.archive(
+        "Seasonal Outfits.zip",
+        &[
+            ("Outfits/[Option A] Blue/manifest.json", &manifest("Outfits", "Tester.Outfits", "1.0.0")),
+            ("Outfits/[Option B] Red/manifest.json", &manifest("Outfits", "Tester.Outfits", "1.0.0")),
+        ],
+    );
+
+    let err = fx
+        .installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, InstallError::OptionsNeeded));
+    assert!(installed_folders(&fx.game_path).is_empty());
+
+    let events = fx.sink.events.lock().unwrap().clone();
+    assert_eq!(events[0].0, "install-options-needed");
+    let outfits = &events[0].1["folders"][0];
+    assert_eq!(outfits["path"], "Outfits");
+    assert_eq!(outfits["children"][1]["path"], "Outfits/[Option B] Red");
+    assert_eq!(outfits["children"][1]["uniqueId"], "Tester.Outfits");
+
+    let results = fx
+        .installer
+        .install_selected_folders(&archive, &fx.game_path, &fx.settings, None, &["Outfits/[Option B] Red".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(installed_folders(&fx.game_path), vec!["Outfits"]);
+
+    assert!(fx
+        .installer
+        .install_selected_folders(&archive, &fx.game_path, &fx.settings, None, &["../Outfits".to_string()])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn unattended_install_takes_every_option() {
+    let fx = Fixture::new("unattended_options");
+    let archive = fx.archive(
+        "Farm Animals.zip",
+        &[
+            ("Farm Animals/manifest.json", &manifest("Farm Animals", "Tester.FarmAnimals", "1.0.0")),
+            ("Optional Goats/manifest.json", &manifest("Goats", "Tester.FarmAnimals.Goats", "1.0.0")),
+        ],
+    );
+    let installer = ModInstaller::with_sink(fx.sink.clone(), fx.root.join("temp"), fx.root.join("backups")).unattended();
+
+    let results = installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(installed_folders(&fx.game_path), vec!["Farm Animals", "Goats"]);
+    assert!(!fx.sink.names().contains(&"install-options-needed".to_string()));
+}
+
+#[tokio::test]
+async fn update_keeps_config_and_never_replaces_a_different_mod() {
+    let fx = Fixture::new("collisions");
+    let install = |archive: PathBuf| {
+        let fx = &fx;
+        async move {
+            fx.installer
+                .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+                .await
+                .unwrap()
+                .remove(0)
+        }
+    };
+
+    install(fx.archive("v1.zip", &[("Shared/manifest.json", &manifest("A", "Tester.A", "1.0.0"))])).await;
+    let config_path = fx.game_path.join("Mods").join("Shared").join("config.json");
+    fs::write(&config_path, r#"{"Enabled": false}"#).unwrap();
+
+    let updated = install(fx.archive("v2.zip", &[("Shared/manifest.json", &manifest("A", "Tester.A", "2.0.0"))])).await;
+    assert_eq!(updated.version, "2.0.0");
+    assert_eq!(installed_folders(&fx.game_path), vec!["Shared"]);
+    assert!(fx.root.join("backups").join("Shared").exists());
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), r#"{"Enabled": false}"#);
+
+    let other = install(fx.archive("other.zip", &[("Shared/manifest.json", &manifest("B", "Tester.B", "1.0.0"))])).await;
+    assert!(other.install_path.ends_with("Shared (2)"));
+    assert_eq!(installed_folders(&fx.game_path), vec!["Shared", "Shared (2)"]);
+}
+
+#[tokio::test]
+async fn updates_a_disabled_mod_in_place() {
+    let fx = Fixture::new("disabled_update");
+    let install = |file: &str, version: &str| {
+        let archive = fx.archive(file, &[("Quiet/manifest.json", &manifest("Quiet", "Tester.Quiet", version))]);
+        let fx = &fx;
+        async move {
+            fx.installer
+                .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+                .await
+                .unwrap()
+                .remove(0)
+        }
+    };
+
+    install("v1.zip", "1.0.0").await;
+    let mods_dir = fx.game_path.join("Mods");
+    fs::rename(mods_dir.join("Quiet"), mods_dir.join("Quiet.disabled")).unwrap();
+
+    let updated = install("v2.zip", "2.0.0").await;
+    assert_eq!(updated.install_path, mods_dir.join("Quiet.disabled"));
+    assert_eq!(installed_folders(&fx.game_path), vec!["Quiet.disabled"]);
+
+    let mods = scan_mods(&fx.game_path);
+    assert_eq!(mods.len(), 1);
+    assert_eq!(mods[0].version, "2.0.0");
+    assert!(!mods[0].is_enabled);
+}
+
+#[tokio::test]
+async fn staged_install_links_files_into_mods() {
+    let mut fx = Fixture::new("staged");
+    fx.settings.install_mode = InstallMode::Staged;
+    let archive = fx.archive(
+        "LookupAnything.zip",
+        &[
+            ("LookupAnything/manifest.json", &manifest("Lookup Anything", "Pathoschild.LookupAnything", "1.0.0")),
+            ("LookupAnything/assets/data.json", "{}"),
+        ],
+    );
+    fx.installer
+        .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+        .await
+        .unwrap();
+
+    let deployed = fx.game_path.join("Mods").join("LookupAnything");
+    let staged = staging_root(&fx.game_path).join("LookupAnything");
+    assert_eq!(installed_folders(&fx.game_path), vec!["LookupAnything"]);
+    assert!(staged.join("manifest.json").is_file());
+
+    // Hardlinked: a change to the staged file shows up in Mods
+    fs::OpenOptions::new()
+        .write(true)
+        .open(staged.join("assets").join("data.json"))
+        .unwrap()
+        .write_all(b"[]")
+        .unwrap();
+    assert_eq!(fs::read_to_string(deployed.join("assets").join("data.json")).unwrap(), "[]");
+
+    // A redeploy brings the mod back after Mods was emptied
+    fs::remove_dir_all(fx.game_path.join("Mods")).unwrap();
+    let report = deploy_staged(&fx.game_path).unwrap();
+    assert_eq!(report.deployed, 1);
+    assert_eq!(installed_folders(&fx.game_path), vec!["LookupAnything"]);
+    assert!(deployed.join("assets").join("data.json").is_file());
+}
+
+#[tokio::test]
+async fn restores_a_backup_after_snapshotting_the_current_version() {
+    let fx = Fixture::new("restore_backup");
+    for (file, version) in [("v1.zip", "1.0.0"), ("v2.zip", "2.0.0")] {
+        let archive = fx.archive(file, &[("Restored/manifest.json", &manifest("R", "Tester.R", version))]);
+        fx.installer
+            .install_from_archive(&archive, &fx.game_path, &fx.settings, None, None)
+            .await
+            .unwrap();
+    }
+
+    let backups = fx.installer.list_backups(&fx.game_path, "Tester.R").unwrap();
+    assert_eq!(backups.len(), 1);
+
+    let restored = fx
+        .installer
+        .restore_backup(&fx.game_path, "Tester.R", &backups[0].timestamp, &fx.settings)
+        .unwrap();
+    assert_eq!(restored, fx.game_path.join("Mods").join("Restored"));
+    assert_eq!(scan_mods(&fx.game_path)[0].version, "1.0.0");
+    assert_eq!(fx.installer.list_backups(&fx.game_path, "Tester.R").unwrap().len(), 2);
+
+    assert!(fx.installer.list_backups(&fx.game_path, "../Tester.R").is_err());
+}
+
+#[test]
+fn parses_nxm_links() {
+    let url = NxmUrl::parse("nxm://stardewvalley/mods/541/files/9001?key=abc&expires=1700000000&user_id=7").unwrap();
+    assert_eq!((url.mod_id, url.file_id), (541, 9001));
+    assert_eq!(url.user_id, Some(7));
+
+    assert!(NxmUrl::parse("https://www.nexusmods.com/stardewvalley/mods/541").is_err());
+}
@@ -1,19 +1,21 @@
 import { useState, useEffect, useMemo } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
+import { ask } from '@tauri-apps/plugin-dialog';
 import { Header } from './components/layout/Header';
 import { Toolbar } from './components/layout/Toolbar';
 import { Footer } from './components/layout/Footer';
 import { ModList } from './components/features/mods/ModList';
 import { AddModModal } from './components/features/mods/AddModModal';
 import { SettingsModal } from './components/features/settings/SettingsModal';
-import { Mod } from './types/mod';
+import { DuplicateResolution, EnablePlan, Mod } from './types/mod';
 import { Settings, defaultSettings } from './types/settings';
+import { listenEvent } from './types/events';
 import { MOCK_MODS } from './data/mock';
 import { DownloadManager } from './components/features/downloads/DownloadManager';
 import { ToastContainer } from './components/ui/Toast';
 import { ConfirmDialog } from './components/ui/ConfirmDialog';
 import { useToast } from './hooks/useToast';
+import { errorMessage, setErrorLanguage } from './i18n/errors';
 
 function App() {
   const [mods, setMods] = useState<Mod[]>([]);
@@ -119,12 +121,26 @@ function App() {
     initializeSettings();
   }, []);
 
+  useEffect(() => {
+    setErrorLanguage(settings.language);
+  }, [settings.language]);
+
   useEffect(() => {
     if (settings.gamePath) {
-      loadMods();
+      loadMods().then(refreshCompatibility);
     }
   }, [settings.gamePath]);
 
+  // Refresh the smapi.io compatibility list in the background, then show it
+  async function refreshCompatibility() {
+    try {
+      await invoke('check_mod_compatibility');
+      await loadMods();
+    } catch (error) {
+      console.warn('Compatibility check skipped:', error);
+    }
+  }
+
   // Auto-check for updates after mods load (with rate limiting)
   useEffect(() => {
     const autoCheckUpdates = async () => {
@@ -195,7 +211,7 @@ function App() {
 
   // Listen for mod installation events from backend
   useEffect(() => {
-    const unlistenPromise = listen('mod-installed', (event: any) => {
+    const unlistenPromise = listenEvent('mod-installed', (event) => {
       console.log('Mod installed event received:', event.payload);
       // Refresh mod list when a mod is installed
       if (settings.gamePath) {
@@ -204,7 +220,7 @@ function App() {
     });
 
     return () => {
-      unlistenPromise.then((unlisten: any) => unlisten());
+      unlistenPromise.then((unlisten) => unlisten());
     };
   }, [settings.gamePath]);
 
@@ -269,8 +285,7 @@ function App() {
       // Set initial status based on enabled state
       const modsWithStatus = loadedMods.map(mod => ({
         ...mod,
-        status: mod.isEnabled ? 'working' : 'disabled' as Mod['status'],
-        installDate: new Date().toISOString(),
+        status: (mod.availableUpdate ? 'update-available' : mod.isEnabled ? 'working' : 'disabled') as Mod['status'],
       }));
 
       console.log('📦 Mods with status:', modsWithStatus);
@@ -283,10 +298,38 @@ function App() {
     }
   }
 
+  // Enable mods along with the disabled mods they need, if the user agrees
+  const enableMods = async (modIds: string[]): Promise<EnablePlan> => {
+    const args = { gamePath: settings.gamePath, modIds, enabled: true };
+    const plan = await invoke<EnablePlan>('set_mods_enabled', args);
+    if (plan.applied) return plan;
+
+    const needed = plan.required.map(r => `• ${r.name} (needed by ${r.requiredBy})`).join('\n');
+    const includeRequired = await ask(`These disabled mods are required:\n\n${needed}\n\nEnable them too?`, {
+      title: 'Enable required mods',
+      kind: 'info',
+    });
+    return invoke<EnablePlan>('set_mods_enabled', { ...args, includeRequired });
+  };
+
   const handleToggleMod = async (id: string, enabled: boolean) => {
     const mod = mods.find(m => m.id === id);
     if (!mod) return;
 
+    if (enabled) {
+      try {
+        const plan = await enableMods([id]);
+        if (plan.errors.length > 0) {
+          showToast('error', 'Failed to Toggle Mod', { message: plan.errors.join('\n') });
+        }
+        await loadMods();
+      } catch (error) {
+        console.error('Failed to toggle mod:', error);
+        showToast('error', 'Failed to Toggle Mod', { message: errorMessage(error) });
+      }
+      return;
+    }
+
     // Optimistic update
     setMods(mods.map(m => m.id === id ? {
       ...m,
@@ -309,7 +352,7 @@ function App() {
       } : m));
     } catch (error) {
       console.error('Failed to toggle mod:', error);
-      showToast('error', 'Failed to Toggle Mod', { message: String(error) });
+      showToast('error', 'Failed to Toggle Mod', { message: errorMessage(error) });
       // Revert optimistic update
       setMods(mods.map(m => m.id === id ? mod : m));
     }
@@ -372,7 +415,7 @@ function App() {
       }
     } catch (error) {
       console.error('❌ Failed to check updates:', error);
-      showToast('error', 'Update Check Failed', { message: String(error) });
+      showToast('error', 'Update Check Failed', { message: errorMessage(error) });
     }
   };
 
@@ -401,7 +444,34 @@ function App() {
       showToast('success', 'Mod Deleted', { message: `${mod.name} has been deleted.` });
     } catch (error) {
       console.error('Failed to delete mod:', error);
-      showToast('error', 'Failed to Delete Mod', { message: String(error) });
+      showToast('error', 'Failed to Delete Mod', { message: errorMessage(error) });
+    }
+  };
+
+  // Keep the chosen copy; the others are disabled, not deleted
+  const handleResolveDuplicate = async (id: string) => {
+    const mod = mods.find(m => m.id === id);
+    if (!mod?.duplicatePaths?.length) return;
+
+    const confirmed = await ask(
+      `Keep ${mod.name} ${mod.version} at\n${mod.path}\n\nand disable the other copies?\n\n${mod.duplicatePaths.join('\n')}`,
+      { title: 'Duplicate mod', kind: 'warning' }
+    );
+    if (!confirmed) return;
+
+    try {
+      const result = await invoke<DuplicateResolution>('resolve_duplicate', { keepPath: mod.path });
+      await loadMods();
+      if (result.failed.length > 0) {
+        showToast('error', 'Some Copies Were Not Disabled', {
+          message: result.failed.map(f => `${f.path}: ${f.error}`).join('\n'),
+        });
+      } else {
+        showToast('success', 'Duplicate Resolved', { message: `Disabled ${result.disabled.length} other copies of ${mod.name}.` });
+      }
+    } catch (error) {
+      console.error('Failed to resolve duplicate:', error);
+      showToast('error', 'Failed to Resolve Duplicate', { message: errorMessage(error) });
     }
   };
 
@@ -436,7 +506,7 @@ function App() {
       await invoke('open_game_mods_folder', { gamePath: settings.gamePath });
     } catch (error) {
       console.error('Failed to open mods folder:', error);
-      showToast('error', 'Failed to Open Folder', { message: String(error) });
+      showToast('error', 'Failed to Open Folder', { message: errorMessage(error) });
     }
   };
 
@@ -454,17 +524,14 @@ function App() {
       status: 'working'
     } : m));
 
-    for (const id of selected) {
-      const mod = mods.find(m => m.id === id);
-      if (!mod) continue;
-
-      try {
-        await invoke('toggle_mod_enabled', { modPath: mod.path, enabled: true });
-        successCount++;
-      } catch (error) {
-        console.error(`Failed to enable mod ${mod.name}:`, error);
-        failCount++;
-      }
+    try {
+      const plan = await enableMods(selected);
+      failCount = plan.errors.length;
+      successCount = selected.length - failCount;
+      plan.errors.forEach(error => console.error('Failed to enable mod:', error));
+    } catch (error) {
+      console.error('Failed to enable mods:', error);
+      failCount = selected.length;
     }
 
     // Refresh mods to ensure correct state/paths
@@ -570,7 +637,7 @@ function App() {
       }
     } catch (error) {
       console.error('Auto-detection error:', error);
-      showToast('error', 'Auto-detection Error', { message: String(error) });
+      showToast('error', 'Auto-detection Error', { message: errorMessage(error) });
     }
   };
 
@@ -626,6 +693,7 @@ function App() {
           onToggleMod={handleToggleMod}
           onUpdateMod={handleUpdateMod}
           onDeleteMod={handleDeleteMod}
+          onResolveDuplicate={handleResolveDuplicate}
           highlightedModId={newlyInstalledModId}
           selectedModIds={selectedModIds}
           onSelectMod={handleSelectMod}
@@ -1,5 +1,7 @@
 import { useState } from 'react';
+import { createPortal } from 'react-dom';
 import { DownloadItem } from './DownloadItem';
+import { InstallOptionsDialog } from './InstallOptionsDialog';
 import { useDownloads } from '../../../hooks/useDownloads';
 import { X, FolderOpen, Trash2, DownloadCloud } from 'lucide-react';
 
@@ -14,14 +16,29 @@ export function DownloadManager({ isOpen, onClose, onModInstalled, onToast }: Do
     const {
         downloads,
         currentProgress,
+        queueProgress,
+        installOptions,
         cancelDownload,
         clearCompleted,
-        openDownloadFolder
+        openDownloadFolder,
+        installSelectedOptions,
+        dismissInstallOptions
     } = useDownloads({ onModInstalled, onToast });
 
     const [filter, setFilter] = useState<'all' | 'active' | 'completed' | 'failed'>('all');
 
-    if (!isOpen) return null;
+    // Shown even with the panel closed, installs finish in the background.
+    // A portal, so the panel's transform doesn't confine the overlay.
+    const optionsDialog = createPortal(
+        <InstallOptionsDialog
+            options={installOptions}
+            onInstall={installSelectedOptions}
+            onCancel={dismissInstallOptions}
+        />,
+        document.body
+    );
+
+    if (!isOpen) return optionsDialog;
 
     const filteredDownloads = downloads.filter(d => {
         if (filter === 'all') return true;
@@ -36,6 +53,7 @@ export function DownloadManager({ isOpen, onClose, onModInstalled, onToast }: Do
 
     return (
         <div className="fixed inset-y-0 right-0 w-96 bg-stone-950 border-l border-stone-800 shadow-2xl transform transition-transform duration-300 z-40 flex flex-col">
+            {optionsDialog}
             {/* Header */}
             <div className="p-4 border-b border-stone-800 flex justify-between items-center bg-stone-900/50">
                 <div className="flex items-center space-x-2">
@@ -50,6 +68,15 @@ export function DownloadManager({ isOpen, onClose, onModInstalled, onToast }: Do
                 </button>
             </div>
 
+            {/* Whole queue, announced as one figure */}
+            {queueProgress && queueProgress.filesRemaining > 0 && (
+                <div role="status" aria-live="polite" className="px-4 py-2 border-b border-stone-800 text-xs text-stone-400">
+                    {Math.floor(queueProgress.progressPercent)}% overall, {queueProgress.filesRemaining} of {queueProgress.filesTotal} files left
+                    {queueProgress.etaSeconds !== undefined && queueProgress.etaSeconds !== null &&
+                        `, about ${Math.ceil(queueProgress.etaSeconds / 60)} min remaining`}
+                </div>
+            )}
+
             {/* Toolbar */}
             <div className="p-2 border-b border-stone-800 flex space-x-2 overflow-x-auto">
                 {(['all', 'active', 'completed', 'failed'] as const).map((f) => (
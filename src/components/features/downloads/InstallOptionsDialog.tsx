@@ -0,0 +1,101 @@
+import React, { useEffect, useState } from 'react';
+import { FolderTree } from 'lucide-react';
+import type { ArchiveFolder, InstallOptions } from '../../../types/events';
+
+interface InstallOptionsDialogProps {
+    options: InstallOptions | null;
+    onInstall: (folders: string[]) => void;
+    onCancel: () => void;
+}
+
+const FolderRow: React.FC<{
+    folder: ArchiveFolder;
+    depth: number;
+    selected: Set<string>;
+    onToggle: (path: string) => void;
+}> = ({ folder, depth, selected, onToggle }) => (
+    <>
+        <div className="flex items-center gap-2 py-1 text-sm" style={{ paddingLeft: depth * 16 }}>
+            {folder.uniqueId ? (
+                <label className="flex items-center gap-2 cursor-pointer text-stone-200">
+                    <input
+                        type="checkbox"
+                        checked={selected.has(folder.path)}
+                        onChange={() => onToggle(folder.path)}
+                        className="accent-orange-500"
+                    />
+                    <span>{folder.name}</span>
+                    <span className="text-xs text-stone-500">
+                        {folder.modName} {folder.version && `v${folder.version}`}
+                    </span>
+                </label>
+            ) : (
+                <span className="text-stone-500">{folder.name}/</span>
+            )}
+        </div>
+        {folder.children.map(child => (
+            <FolderRow key={child.path} folder={child} depth={depth + 1} selected={selected} onToggle={onToggle} />
+        ))}
+    </>
+);
+
+/** Pick which folders of an archive with variants to install */
+export const InstallOptionsDialog: React.FC<InstallOptionsDialogProps> = ({ options, onInstall, onCancel }) => {
+    const [selected, setSelected] = useState<Set<string>>(new Set());
+
+    useEffect(() => {
+        setSelected(new Set());
+    }, [options]);
+
+    if (!options) return null;
+
+    const toggle = (path: string) => {
+        setSelected(prev => {
+            const next = new Set(prev);
+            if (next.has(path)) {
+                next.delete(path);
+            } else {
+                next.add(path);
+            }
+            return next;
+        });
+    };
+
+    return (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/70 backdrop-blur-sm">
+            <div className="max-w-lg w-full mx-4 border-2 border-orange-500 bg-stone-950 shadow-2xl">
+                <div className="px-6 py-4 border-b border-stone-800 flex items-center gap-3">
+                    <FolderTree className="w-6 h-6 text-orange-400" strokeWidth={2.5} />
+                    <h2 className="text-lg font-bold text-stone-200 font-mono uppercase tracking-wider">
+                        Choose options
+                    </h2>
+                </div>
+
+                <div className="px-6 py-4 max-h-96 overflow-y-auto">
+                    <p className="text-sm text-stone-400 mb-3">
+                        {options.modName ?? 'This archive'} comes with alternative folders. Pick the ones to install.
+                    </p>
+                    {options.folders.map(folder => (
+                        <FolderRow key={folder.path} folder={folder} depth={0} selected={selected} onToggle={toggle} />
+                    ))}
+                </div>
+
+                <div className="px-6 py-4 border-t border-stone-800 flex gap-3 justify-end">
+                    <button
+                        onClick={onCancel}
+                        className="px-4 py-2 text-sm text-stone-400 hover:text-stone-200 border border-stone-700 hover:bg-stone-800"
+                    >
+                        Cancel
+                    </button>
+                    <button
+                        onClick={() => onInstall([...selected])}
+                        disabled={selected.size === 0}
+                        className="px-4 py-2 text-sm font-bold text-white bg-orange-600 hover:bg-orange-700 border border-orange-500 disabled:opacity-50"
+                    >
+                        Install selected
+                    </button>
+                </div>
+            </div>
+        </div>
+    );
+};
@@ -1,15 +1,23 @@
 import React from 'react';
 import { ThumbsUp, RefreshCw, Trash2, DownloadCloud, ChevronUp, ChevronDown, ChevronLeft, ChevronRight } from 'lucide-react';
-import { Mod } from '../../../types/mod';
+import { CompatStatus, Mod } from '../../../types/mod';
 import clsx from 'clsx';
 import { Checkbox } from '../../ui/Checkbox';
 import { Select } from '../../ui/Select';
 
+const COMPAT_LABELS: Record<CompatStatus, string> = {
+    ok: 'OK',
+    broken: 'BROKEN',
+    obsolete: 'OBSOLETE',
+    unofficialUpdate: 'UNOFFICIAL FIX',
+};
+
 interface ModListProps {
     mods: Mod[];
     onToggleMod: (id: string, enabled: boolean) => void;
     onUpdateMod: (id: string) => void;
     onDeleteMod: (id: string) => void;
+    onResolveDuplicate: (id: string) => void;
     highlightedModId?: string | null;
     selectedModIds: Set<string>;
     onSelectMod: (id: string, selected: boolean) => void;
@@ -31,6 +39,7 @@ export const ModList: React.FC<ModListProps> = ({
     onToggleMod,
     onUpdateMod,
     onDeleteMod,
+    onResolveDuplicate,
     highlightedModId,
     selectedModIds,
     onSelectMod,
@@ -124,6 +133,44 @@ export const ModList: React.FC<ModListProps> = ({
                                                     NEXUS
                                                 </span>
                                             )}
+                                            {mod.isUnmanaged && (
+                                                <span
+                                                    className="px-1.5 py-0.5 rounded text-[10px] font-medium bg-stone-800 text-stone-400 border border-stone-700"
+                                                    title="In an unmanaged folder, change it by hand"
+                                                >
+                                                    UNMANAGED
+                                                </span>
+                                            )}
+                                            {(mod.hasGmcm || (mod.configKeys && mod.configKeys.length > 0)) && (
+                                                <span
+                                                    className="px-1.5 py-0.5 rounded text-[10px] font-medium bg-stone-800 text-stone-400 border border-stone-700"
+                                                    title={mod.hasGmcm ? 'Configurable in game with Generic Mod Config Menu' : 'Configurable through config.json'}
+                                                >
+                                                    {mod.hasGmcm ? 'GMCM' : 'CONFIG'}
+                                                </span>
+                                            )}
+                                            {mod.duplicatePaths && mod.duplicatePaths.length > 0 && (
+                                                <button
+                                                    onClick={() => onResolveDuplicate(mod.id)}
+                                                    className="px-1.5 py-0.5 rounded text-[10px] font-medium bg-red-500/10 text-red-400 border border-red-500/20 hover:bg-red-500/20"
+                                                    title={`Also installed in:\n${mod.duplicatePaths.join('\n')}\n\nClick to keep this copy`}
+                                                >
+                                                    DUPLICATE
+                                                </button>
+                                            )}
+                                            {mod.compatibility && mod.compatibility.status !== 'ok' && (
+                                                <span
+                                                    className={clsx(
+                                                        "px-1.5 py-0.5 rounded text-[10px] font-medium border",
+                                                        mod.compatibility.status === 'broken' && "bg-red-500/10 text-red-400 border-red-500/20",
+                                                        mod.compatibility.status === 'obsolete' && "bg-stone-800 text-stone-400 border-stone-700",
+                                                        mod.compatibility.status === 'unofficialUpdate' && "bg-yellow-500/10 text-yellow-400 border-yellow-500/20"
+                                                    )}
+                                                    title={mod.compatibility.summary ?? undefined}
+                                                >
+                                                    {COMPAT_LABELS[mod.compatibility.status]}
+                                                </span>
+                                            )}
                                         </div>
                                     </td>
                                     <td className="px-4 py-4 text-center">
@@ -148,8 +195,9 @@ export const ModList: React.FC<ModListProps> = ({
                                                 id={`toggle-${mod.id}`}
                                                 type="checkbox"
                                                 checked={mod.isEnabled}
+                                                disabled={mod.isUnmanaged}
                                                 onChange={(e) => onToggleMod(mod.id, e.target.checked)}
-                                                className="toggle-checkbox absolute block w-4 h-4 rounded-full border-4 appearance-none cursor-pointer transition-all duration-300 bg-white border-stone-700" />
+                                                className="toggle-checkbox disabled:opacity-50 disabled:cursor-not-allowed absolute block w-4 h-4 rounded-full border-4 appearance-none cursor-pointer transition-all duration-300 bg-white border-stone-700" />
                                             <label
                                                 htmlFor={`toggle-${mod.id}`}
                                                 className="toggle-label block overflow-hidden h-4 rounded-full cursor-pointer bg-stone-700" />
@@ -1,8 +1,20 @@
 import React, { useState, useEffect } from 'react';
 import { X, FolderOpen } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
-import type { Settings, Theme, Language, ModGroups } from '../../../types/settings';
+import { open, ask } from '@tauri-apps/plugin-dialog';
+import type { Settings, Theme, Language, ModGroups, InstallMode, LogLevel, SaveBackupsOnLaunch, DisabledFolderStyle } from '../../../types/settings';
+import type { AppInfo } from '../../../types/appInfo';
+import type { EnvironmentInfo } from '../../../types/environment';
+import type { MaintenanceStatus } from '../../../types/events';
+import type { PerformanceReport } from '../../../types/performance';
+import type { MigrationReport } from '../../../types/migration';
+import type { VortexImportReport } from '../../../types/vortex';
+import type { ForeignImportReport, ForeignTool } from '../../../types/foreignImport';
+import type { LogLine } from '../../../types/logs';
+import type { NormalizeReport } from '../../../types/disabledFolders';
+import type { SaveSummary, SaveBackup } from '../../../types/saves';
+import type { ProfileStore, ApplyReport } from '../../../types/profiles';
+import { errorMessage } from '../../../i18n/errors';
 import { Checkbox } from '../../ui/Checkbox';
 import { Select } from '../../ui/Select';
 
@@ -22,11 +34,118 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
 }) => {
   const [settings, setSettings] = useState<Settings>(initialSettings);
   const [errors, setErrors] = useState<{ [key: string]: string }>({});
+  const [appInfo, setAppInfo] = useState<AppInfo | null>(null);
+  const [environment, setEnvironment] = useState<EnvironmentInfo | null>(null);
+  const [maintenance, setMaintenance] = useState<MaintenanceStatus | null>(null);
+  const [maintenanceError, setMaintenanceError] = useState<string | null>(null);
+  const [performance, setPerformance] = useState<PerformanceReport | null>(null);
+  const [migration, setMigration] = useState<{ running: boolean; report?: MigrationReport; error?: string }>({ running: false });
+  const [vortexImport, setVortexImport] = useState<{ running: boolean; report?: VortexImportReport; error?: string }>({ running: false });
+  const [foreignImport, setForeignImport] = useState<{ running: boolean; report?: ForeignImportReport; error?: string }>({ running: false });
+  const [downloadsDirError, setDownloadsDirError] = useState<string | null>(null);
+  const [deployStatus, setDeployStatus] = useState<{ running: boolean; message?: string; error?: boolean }>({ running: false });
+  const [renameStatus, setRenameStatus] = useState<{ running: boolean; message?: string; error?: boolean }>({ running: false });
+  const [logs, setLogs] = useState<LogLine[]>([]);
+  const [farms, setFarms] = useState<SaveSummary[]>([]);
+  const [saveBackups, setSaveBackups] = useState<{ saveId: string; backups: SaveBackup[] } | null>(null);
+  const [saveStatus, setSaveStatus] = useState<{ message: string; error?: boolean } | null>(null);
+  const [profiles, setProfiles] = useState<ProfileStore | null>(null);
+  const [newProfileName, setNewProfileName] = useState('');
+  const [smapiInstall, setSmapiInstall] = useState<{ installing: boolean; message?: string; error?: boolean }>({ installing: false });
 
   useEffect(() => {
     setSettings(initialSettings);
   }, [initialSettings, isOpen]);
 
+  useEffect(() => {
+    if (!isOpen) return;
+    invoke<AppInfo>('get_app_info')
+      .then(setAppInfo)
+      .catch((error) => console.error('Failed to load app info:', error));
+    invoke<EnvironmentInfo>('get_environment_info')
+      .then(setEnvironment)
+      .catch((error) => console.error('Failed to read SMAPI and game versions:', error));
+    invoke<MaintenanceStatus>('get_maintenance_status')
+      .then(setMaintenance)
+      .catch((error) => console.error('Failed to load maintenance status:', error));
+    invoke<PerformanceReport>('get_performance_report')
+      .then(setPerformance)
+      .catch((error) => console.error('Failed to load performance report:', error));
+    invoke<SaveSummary[]>('list_farms')
+      .then(setFarms)
+      .catch((error) => console.error('Failed to list saves:', error));
+    invoke<ProfileStore>('list_profiles')
+      .then(setProfiles)
+      .catch((error) => console.error('Failed to load profiles:', error));
+  }, [isOpen]);
+
+  const handleInstallSmapi = async () => {
+    setSmapiInstall({ installing: true });
+    try {
+      const result = await invoke<{ version: string; smapiPath: string }>('install_smapi');
+      setSettings(prev => ({ ...prev, smapiPath: result.smapiPath }));
+      setSmapiInstall({ installing: false, message: `SMAPI ${result.version} installed` });
+      setEnvironment(await invoke<EnvironmentInfo>('get_environment_info'));
+    } catch (error) {
+      setSmapiInstall({ installing: false, message: errorMessage(error), error: true });
+    }
+  };
+
+  const loadLogs = () => {
+    invoke<LogLine[]>('get_recent_logs', { limit: 200 })
+      .then(setLogs)
+      .catch(console.error);
+  };
+
+  const handleRunMaintenance = async () => {
+    setMaintenanceError(null);
+    setMaintenance(prev => prev && { ...prev, running: true });
+    try {
+      setMaintenance(await invoke<MaintenanceStatus>('run_maintenance'));
+    } catch (error) {
+      setMaintenanceError(errorMessage(error));
+      setMaintenance(await invoke<MaintenanceStatus>('get_maintenance_status'));
+    }
+  };
+
+  // First call previews, second call (with the preview on screen) writes
+  const handleMigrateLibrary = async (dryRun: boolean) => {
+    setMigration(prev => ({ ...prev, running: true, error: undefined }));
+    try {
+      const report = await invoke<MigrationReport>('migrate_library', { dryRun });
+      setMigration({ running: false, report });
+      if (!dryRun) {
+        setSettings(prev => ({ ...prev, libraryMigrated: true }));
+      }
+    } catch (error) {
+      setMigration(prev => ({ ...prev, running: false, error: errorMessage(error) }));
+    }
+  };
+
+  // Same preview-then-import flow as the library migration
+  const handleImportVortex = async (dryRun: boolean) => {
+    setVortexImport(prev => ({ ...prev, running: true, error: undefined }));
+    try {
+      const report = await invoke<VortexImportReport>('import_vortex', { dryRun, stagingDir: null });
+      setVortexImport({ running: false, report });
+    } catch (error) {
+      setVortexImport(prev => ({ ...prev, running: false, error: errorMessage(error) }));
+    }
+  };
+
+  const handleImportForeign = async (tool: ForeignTool, dryRun: boolean) => {
+    setForeignImport(prev => ({ ...prev, running: true, error: undefined }));
+    try {
+      const report = await invoke<ForeignImportReport>('import_foreign_mods', { tool, dryRun });
+      setForeignImport({ running: false, report });
+      if (!dryRun) {
+        setSettings(prev => ({ ...prev, libraryMigrated: true }));
+      }
+    } catch (error) {
+      setForeignImport(prev => ({ ...prev, running: false, error: errorMessage(error) }));
+    }
+  };
+
   const handleGamePathPick = async () => {
     try {
       const selected = await open({
@@ -53,7 +172,7 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
             console.error('Failed to auto-detect SMAPI:', error);
           }
         } else {
-          setErrors({ ...errors, gamePath: 'Invalid game directory. Please select the Stardew Valley installation folder.' });
+          setErrors({ ...errors, gamePath: 'Invalid game directory. Please select the Stardew Valley installation folder (for the Xbox app version, its "Content" subfolder).' });
         }
       }
     } catch (error) {
@@ -88,6 +207,165 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
     }
   };
 
+  // Applied right away: the download manager switches folders and takes the
+  // existing archives along
+  const handleDownloadsDirChange = async (path: string) => {
+    setDownloadsDirError(null);
+    try {
+      const saved = await invoke<Settings>('set_downloads_dir', { path, moveExisting: true });
+      setSettings(prev => ({ ...prev, downloadsDir: saved.downloadsDir }));
+    } catch (error) {
+      setDownloadsDirError(errorMessage(error));
+    }
+  };
+
+  const handleDownloadsDirPick = async () => {
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: false,
+        title: 'Select Downloads Folder',
+      });
+      if (selected) {
+        await handleDownloadsDirChange(Array.isArray(selected) ? selected[0] : selected);
+      }
+    } catch (error) {
+      console.error('Failed to pick downloads folder:', error);
+    }
+  };
+
+  const handleDeployStaged = async () => {
+    setDeployStatus({ running: true });
+    try {
+      const report = await invoke<{ deployed: number; copiedFiles: number }>('deploy_staged_mods');
+      setDeployStatus({ running: false, message: `Deployed ${report.deployed} mods` });
+    } catch (error) {
+      setDeployStatus({ running: false, message: errorMessage(error), error: true });
+    }
+  };
+
+  // Previews the renames first and asks before making them
+  const handleNormalizeDisabled = async () => {
+    const style = settings.disabledFolderStyle;
+    setRenameStatus({ running: true });
+    try {
+      const preview = await invoke<NormalizeReport>('normalize_disabled_folders', { style, dryRun: true });
+      if (preview.renames.length === 0) {
+        setRenameStatus({ running: false, message: 'Every disabled folder already uses this style' });
+        return;
+      }
+      const confirmed = await ask(
+        `Rename ${preview.renames.length} folders in Mods? Close the game first.`,
+        { title: 'Rename Disabled Folders', kind: 'warning' }
+      );
+      if (!confirmed) {
+        setRenameStatus({ running: false });
+        return;
+      }
+      const report = await invoke<NormalizeReport>('normalize_disabled_folders', { style, dryRun: false });
+      setRenameStatus({
+        running: false,
+        message: report.failed.length
+          ? `Renamed ${report.renames.length - report.failed.length} folders, ${report.failed.length} failed: ${report.failed[0].error}`
+          : `Renamed ${report.renames.length} folders`,
+        error: report.failed.length > 0,
+      });
+    } catch (error) {
+      setRenameStatus({ running: false, message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleShowSaveBackups = async (saveId: string) => {
+    if (saveBackups?.saveId === saveId) {
+      setSaveBackups(null);
+      return;
+    }
+    try {
+      setSaveBackups({ saveId, backups: await invoke<SaveBackup[]>('list_save_backups', { saveId }) });
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleBackupSave = async (saveId: string) => {
+    try {
+      await invoke<SaveBackup>('backup_save', { saveId });
+      setSaveStatus({ message: `Backed up ${saveId}` });
+      setFarms(await invoke<SaveSummary[]>('list_farms'));
+      if (saveBackups?.saveId === saveId) {
+        setSaveBackups({ saveId, backups: await invoke<SaveBackup[]>('list_save_backups', { saveId }) });
+      }
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleRestoreSave = async (backup: SaveBackup) => {
+    const confirmed = await ask(
+      `Replace ${backup.saveId} with this backup? The current save is backed up first. Close the game before restoring.`,
+      { title: 'Restore Save', kind: 'warning' }
+    );
+    if (!confirmed) return;
+    try {
+      await invoke('restore_save_backup', { saveId: backup.saveId, backup: backup.name });
+      setSaveStatus({ message: `Restored ${backup.saveId}` });
+      setFarms(await invoke<SaveSummary[]>('list_farms'));
+      setSaveBackups({
+        saveId: backup.saveId,
+        backups: await invoke<SaveBackup[]>('list_save_backups', { saveId: backup.saveId }),
+      });
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleSaveProfile = async () => {
+    try {
+      await invoke('save_mod_profile', { name: newProfileName });
+      setNewProfileName('');
+      setSaveStatus({ message: `Saved profile ${newProfileName.trim()}` });
+      setProfiles(await invoke<ProfileStore>('list_profiles'));
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleApplyProfile = async (name: string) => {
+    try {
+      const report = await invoke<ApplyReport>('apply_mod_profile', { name });
+      const missing = report.missing.length > 0 ? `, ${report.missing.length} not installed` : '';
+      setSaveStatus({
+        message: report.failed.length > 0
+          ? `Applied ${name}, but ${report.failed.length} mods could not be changed: ${report.failed.map(f => f.error).join('; ')}`
+          : `Applied ${name}: ${report.enabled.length} enabled, ${report.disabled.length} disabled${missing}`,
+        error: report.failed.length > 0,
+      });
+      setProfiles(await invoke<ProfileStore>('list_profiles'));
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    const confirmed = await ask(`Delete profile ${name}? Saves linked to it are unlinked.`, { title: 'Delete Profile', kind: 'warning' });
+    if (!confirmed) return;
+    try {
+      await invoke('delete_mod_profile', { name });
+      setProfiles(await invoke<ProfileStore>('list_profiles'));
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
+  const handleLinkSave = async (saveId: string, profile: string) => {
+    try {
+      await invoke('link_save_profile', { saveId, profile: profile || null });
+      setProfiles(await invoke<ProfileStore>('list_profiles'));
+    } catch (error) {
+      setSaveStatus({ message: errorMessage(error), error: true });
+    }
+  };
+
   const handleSave = () => {
     // Validate required fields
     const newErrors: { [key: string]: string } = {};
@@ -196,6 +474,119 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
                 {errors.smapiPath && (
                   <p className="text-xs text-red-400 mt-1">{errors.smapiPath}</p>
                 )}
+                <div className="flex items-center gap-3">
+                  <button
+                    onClick={handleInstallSmapi}
+                    disabled={smapiInstall.installing || !initialSettings.gamePath}
+                    title={initialSettings.gamePath ? undefined : 'Save a game path first'}
+                    className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                  >
+                    {smapiInstall.installing ? 'Installing SMAPI...' : 'Install or update SMAPI'}
+                  </button>
+                  {smapiInstall.message && (
+                    <p className={`text-xs ${smapiInstall.error ? 'text-red-400' : 'text-stone-500'}`}>{smapiInstall.message}</p>
+                  )}
+                </div>
+                {environment?.smapiOutdated && (
+                  <p className="text-xs text-amber-400">
+                    SMAPI {environment.smapi?.version} is too old for Stardew Valley {environment.game?.version}.
+                    Update to SMAPI {environment.minimumSmapi} or newer.
+                  </p>
+                )}
+              </div>
+
+              {/* Downloads Folder */}
+              <div className="space-y-1.5">
+                <label className="text-xs font-medium text-stone-400 font-sans">
+                  Downloads Folder
+                </label>
+                <div className="flex gap-2">
+                  <input
+                    type="text"
+                    readOnly
+                    value={settings.downloadsDir}
+                    className="flex-1 border text-xs px-3 py-2 focus:outline-none transition-colors font-mono bg-stone-900 border-stone-800 text-stone-300"
+                    placeholder="Default (app data folder)"
+                  />
+                  <button
+                    onClick={handleDownloadsDirPick}
+                    className="px-3 py-2 border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400"
+                  >
+                    <FolderOpen className="w-4 h-4" />
+                  </button>
+                  {settings.downloadsDir && (
+                    <button
+                      onClick={() => handleDownloadsDirChange('')}
+                      className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400"
+                    >
+                      Use default
+                    </button>
+                  )}
+                </div>
+                <p className="text-xs text-stone-500 font-sans">
+                  Downloaded archives move to the new folder when you change it
+                </p>
+                {downloadsDirError && (
+                  <p className="text-xs text-red-400 mt-1">{downloadsDirError}</p>
+                )}
+              </div>
+
+              {/* Install Mode */}
+              <div className="space-y-1.5">
+                <Select
+                  label="Install Mode"
+                  value={settings.installMode}
+                  onChange={(value) => setSettings({ ...settings, installMode: value as InstallMode })}
+                  options={[
+                    { label: 'Copy into Mods', value: 'Direct' },
+                    { label: 'Stage and hardlink', value: 'Staged' },
+                  ]}
+                />
+                <p className="text-xs text-stone-500 font-sans">
+                  Staged installs keep the files in a ModsStaging folder next to Mods and link them in, so Mods can be rebuilt without reinstalling
+                </p>
+                {settings.installMode === 'Staged' && (
+                  <div className="flex items-center gap-3">
+                    <button
+                      onClick={handleDeployStaged}
+                      disabled={deployStatus.running || !initialSettings.gamePath}
+                      className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                    >
+                      {deployStatus.running ? 'Deploying...' : 'Redeploy staged mods'}
+                    </button>
+                    {deployStatus.message && (
+                      <p className={`text-xs ${deployStatus.error ? 'text-red-400' : 'text-stone-500'}`}>{deployStatus.message}</p>
+                    )}
+                  </div>
+                )}
+              </div>
+
+              {/* Disabled Folder Style */}
+              <div className="space-y-1.5">
+                <Select
+                  label="Disabled Folders"
+                  value={settings.disabledFolderStyle}
+                  onChange={(value) => setSettings({ ...settings, disabledFolderStyle: value as DisabledFolderStyle })}
+                  options={[
+                    { label: 'Add .disabled (Mod.disabled)', value: 'Suffix' },
+                    { label: 'Hide with a dot (.Mod)', value: 'DotPrefix' },
+                  ]}
+                />
+                <p className="text-xs text-stone-500 font-sans">
+                  SMAPI skips both; Stardrop and some other managers hide folders with a dot
+                </p>
+                <div className="flex items-center gap-3">
+                  <button
+                    onClick={handleNormalizeDisabled}
+                    disabled={renameStatus.running || !initialSettings.gamePath}
+                    className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                  >
+                    {renameStatus.running ? 'Renaming...' : 'Rename disabled folders to this style'}
+                  </button>
+                  {renameStatus.message && (
+                    <p className={`text-xs ${renameStatus.error ? 'text-red-400' : 'text-stone-500'}`}>{renameStatus.message}</p>
+                  )}
+                </div>
               </div>
 
               {/* Nexus Auth Cookie */}
@@ -342,6 +733,468 @@ export const SettingsModal: React.FC<SettingsModalProps> = ({
               </div>
             </div>
           </div>
+
+          <div className="h-px bg-stone-800/50" />
+
+          {/* Saves Section */}
+          <div className="space-y-3">
+            <h3 className="text-sm font-medium text-stone-100 font-sans">
+              Saves
+            </h3>
+            <Select
+              label="Back Up Before Launching"
+              value={settings.saveBackupsOnLaunch}
+              onChange={(value) => setSettings({ ...settings, saveBackupsOnLaunch: value as SaveBackupsOnLaunch })}
+              options={[
+                { label: 'Off', value: 'Off' },
+                { label: 'Most recent save', value: 'Latest' },
+                { label: 'All saves', value: 'All' },
+              ]}
+            />
+            {settings.saveBackupsOnLaunch !== 'Off' && (
+              <div className="space-y-1.5">
+                <label className="text-xs font-medium text-stone-400 font-sans">
+                  Launch backups kept per save (0 keeps all)
+                </label>
+                <input
+                  type="number"
+                  min={0}
+                  value={settings.saveBackupsToKeep}
+                  onChange={(e) => setSettings({ ...settings, saveBackupsToKeep: Math.max(0, Number(e.target.value) || 0) })}
+                  className="w-24 border text-xs px-3 py-2 focus:outline-none focus:border-orange-500/50 transition-colors font-mono bg-stone-900 border-stone-800 text-stone-300"
+                />
+              </div>
+            )}
+            <div className="space-y-2">
+              <label className="text-xs font-medium text-stone-400 font-sans">
+                Profiles {profiles?.active && <span className="text-stone-500">(active: {profiles.active})</span>}
+              </label>
+              {profiles?.profiles.map(profile => (
+                <div key={profile.name} className="flex items-center justify-between gap-4 text-xs font-sans">
+                  <span className="text-stone-300">{profile.name} <span className="text-stone-500">· {profile.enabledMods.length} mods</span></span>
+                  <div className="flex gap-2">
+                    <button
+                      onClick={() => handleApplyProfile(profile.name)}
+                      className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400"
+                    >
+                      Apply
+                    </button>
+                    <button
+                      onClick={() => handleDeleteProfile(profile.name)}
+                      className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-red-400"
+                    >
+                      Delete
+                    </button>
+                  </div>
+                </div>
+              ))}
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={newProfileName}
+                  onChange={(e) => setNewProfileName(e.target.value)}
+                  placeholder="Profile name"
+                  className="flex-1 border text-xs px-3 py-2 focus:outline-none focus:border-orange-500/50 transition-colors font-sans bg-stone-900 border-stone-800 text-stone-300"
+                />
+                <button
+                  onClick={handleSaveProfile}
+                  disabled={!newProfileName.trim()}
+                  className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                >
+                  Save enabled mods as profile
+                </button>
+              </div>
+              <Checkbox
+                label="Switch to a save's profile when launching it"
+                checked={settings.autoSwitchProfile}
+                onChange={(checked) => setSettings({ ...settings, autoSwitchProfile: checked })}
+              />
+            </div>
+            {farms.length === 0 ? (
+              <p className="text-xs text-stone-500 font-sans">No saves found</p>
+            ) : (
+              <ul className="space-y-2 text-xs font-sans">
+                {farms.map(farm => (
+                  <li key={farm.id} className="space-y-1">
+                    <div className="flex items-center justify-between gap-4">
+                      <div>
+                        <p className="text-stone-300">{farm.farmerName} - {farm.farmName} Farm</p>
+                        <p className="text-stone-500">
+                          {farm.year !== null && `Year ${farm.year}, ${farm.season} ${farm.day} · `}
+                          {Math.floor(farm.playTimeMs / 3_600_000)}h played
+                          {farm.lastPlayed && ` · ${new Date(farm.lastPlayed).toLocaleDateString()}`}
+                        </p>
+                      </div>
+                      <div className="flex gap-2">
+                        {profiles && profiles.profiles.length > 0 && (
+                          <select
+                            value={profiles.saveProfiles[farm.id] ?? ''}
+                            onChange={(e) => handleLinkSave(farm.id, e.target.value)}
+                            title="Profile this save is played with"
+                            className="border text-xs px-2 py-1.5 bg-stone-900 border-stone-800 text-stone-400"
+                          >
+                            <option value="">No profile</option>
+                            {profiles.profiles.map(profile => (
+                              <option key={profile.name} value={profile.name}>{profile.name}</option>
+                            ))}
+                          </select>
+                        )}
+                        <button
+                          onClick={() => handleShowSaveBackups(farm.id)}
+                          disabled={farm.backups === 0}
+                          className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                        >
+                          {farm.backups} backups
+                        </button>
+                        <button
+                          onClick={() => handleBackupSave(farm.id)}
+                          className="px-3 py-1.5 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400"
+                        >
+                          Back up
+                        </button>
+                      </div>
+                    </div>
+                    {saveBackups?.saveId === farm.id && (
+                      <ul className="pl-3 space-y-0.5">
+                        {saveBackups.backups.map(backup => (
+                          <li key={backup.name} className="flex items-center justify-between gap-4">
+                            <span className="text-stone-400 font-mono">
+                              {backup.createdAt ? new Date(backup.createdAt).toLocaleString() : backup.name}
+                              {' '}({(backup.size / 1024 / 1024).toFixed(1)} MB)
+                              {backup.onLaunch && ' · before launch'}
+                            </span>
+                            <button
+                              onClick={() => handleRestoreSave(backup)}
+                              className="text-orange-400 hover:text-orange-300"
+                            >
+                              Restore
+                            </button>
+                          </li>
+                        ))}
+                      </ul>
+                    )}
+                  </li>
+                ))}
+              </ul>
+            )}
+            {saveStatus && (
+              <p className={`text-xs font-sans ${saveStatus.error ? 'text-red-400' : 'text-stone-500'}`}>{saveStatus.message}</p>
+            )}
+          </div>
+
+          <div className="h-px bg-stone-800/50" />
+
+          {/* Maintenance Section */}
+          <div className="space-y-4">
+            <div>
+              <h3 className="text-sm font-medium text-stone-100 font-sans">
+                Maintenance
+              </h3>
+              <p className="text-xs text-stone-500 mt-1 font-sans">
+                Old backups, stale caches and leftover temp files are cleaned up while nothing else is running
+              </p>
+            </div>
+
+            <div className="space-y-1.5">
+              <label className="text-xs font-medium text-stone-400 font-sans">
+                Backups kept per mod (0 keeps all)
+              </label>
+              <input
+                type="number"
+                min={0}
+                value={settings.backupsPerMod}
+                onChange={(e) => setSettings({ ...settings, backupsPerMod: Math.max(0, Number(e.target.value) || 0) })}
+                className="w-24 border text-xs px-3 py-2 focus:outline-none focus:border-orange-500/50 transition-colors font-mono bg-stone-900 border-stone-800 text-stone-300"
+              />
+            </div>
+
+            <div className="space-y-1.5">
+              <label className="text-xs font-medium text-stone-400 font-sans">
+                Unmanaged folders (one per line, relative to Mods)
+              </label>
+              <textarea
+                value={settings.unmanagedFolders.join('\n')}
+                onChange={(e) => setSettings({ ...settings, unmanagedFolders: e.target.value.split('\n') })}
+                onBlur={() => setSettings({ ...settings, unmanagedFolders: settings.unmanagedFolders.map(f => f.trim()).filter(Boolean) })}
+                rows={3}
+                className="w-full border text-xs px-3 py-2 focus:outline-none focus:border-orange-500/50 transition-colors font-mono bg-stone-900 border-stone-800 text-stone-300"
+                placeholder={'ConsoleCommands\n_Dev'}
+              />
+              <p className="text-xs text-stone-500 font-sans">
+                Mods in these folders are listed but never updated, toggled or removed by the app
+              </p>
+            </div>
+
+            <div className="flex items-center justify-between gap-4">
+              <p className="text-xs text-stone-500 font-sans">
+                {maintenance?.lastRunAt
+                  ? `Last run ${new Date(maintenance.lastRunAt).toLocaleString()}`
+                  : 'Not run yet this session'}
+              </p>
+              <button
+                onClick={handleRunMaintenance}
+                disabled={maintenance?.running}
+                className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+              >
+                {maintenance?.running ? 'Running...' : 'Run now'}
+              </button>
+            </div>
+            {maintenanceError && (
+              <p className="text-xs text-red-400 font-sans">{maintenanceError}</p>
+            )}
+
+            <div className="flex items-center justify-between gap-4">
+              <p className="text-xs text-stone-500 font-sans">
+                {migration.report
+                  ? migration.report.dryRun
+                    ? `${migration.report.mods.length} mods found, ${migration.report.nexusMatched} matched to Nexus`
+                    : `Imported ${migration.report.mods.length} mods, ${migration.report.nexusMatched} matched to Nexus`
+                  : settings.libraryMigrated
+                    ? 'Existing mods already imported'
+                    : 'Import mods installed by hand or by another manager'}
+              </p>
+              {migration.report?.dryRun ? (
+                <button
+                  onClick={() => handleMigrateLibrary(false)}
+                  disabled={migration.running}
+                  className="px-3 py-2 text-xs border transition-colors bg-orange-600 border-orange-500 hover:bg-orange-500 text-white disabled:opacity-50"
+                >
+                  {migration.running ? 'Importing...' : 'Import'}
+                </button>
+              ) : (
+                <button
+                  onClick={() => handleMigrateLibrary(true)}
+                  disabled={migration.running}
+                  className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                >
+                  {migration.running ? 'Checking...' : 'Preview import'}
+                </button>
+              )}
+            </div>
+            {migration.report?.dryRun && (
+              <ul className="max-h-40 overflow-y-auto text-xs font-sans space-y-0.5">
+                {migration.report.mods.map(entry => (
+                  <li key={entry.path} className="flex justify-between gap-4">
+                    <span className={entry.isEnabled ? 'text-stone-300' : 'text-stone-500'}>{entry.name}</span>
+                    <span className="text-stone-500 font-mono">
+                      {entry.nexusModId ? `Nexus ${entry.nexusModId}` : 'No Nexus match'}
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            )}
+            {migration.error && (
+              <p className="text-xs text-red-400 font-sans">{migration.error}</p>
+            )}
+
+            <div className="flex items-center justify-between gap-4">
+              <p className="text-xs text-stone-500 font-sans">
+                {vortexImport.report
+                  ? vortexImport.report.dryRun
+                    ? `${vortexImport.report.mods.length} mods deployed by Vortex`
+                    : `Imported ${vortexImport.report.mods.length - vortexImport.report.failed.length} mods from Vortex${
+                        vortexImport.report.deploymentDisabled ? ', Vortex deployment turned off' : ''
+                      }`
+                  : 'Take over the mods Vortex deployed'}
+              </p>
+              {vortexImport.report?.dryRun ? (
+                <button
+                  onClick={() => handleImportVortex(false)}
+                  disabled={vortexImport.running || vortexImport.report.mods.length === 0}
+                  className="px-3 py-2 text-xs border transition-colors bg-orange-600 border-orange-500 hover:bg-orange-500 text-white disabled:opacity-50"
+                >
+                  {vortexImport.running ? 'Importing...' : 'Import from Vortex'}
+                </button>
+              ) : (
+                <button
+                  onClick={() => handleImportVortex(true)}
+                  disabled={vortexImport.running}
+                  className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                >
+                  {vortexImport.running ? 'Checking...' : 'Preview Vortex import'}
+                </button>
+              )}
+            </div>
+            {vortexImport.report?.dryRun && (
+              <ul className="max-h-40 overflow-y-auto text-xs font-sans space-y-0.5">
+                {vortexImport.report.mods.map(entry => (
+                  <li key={entry.path} className="flex justify-between gap-4">
+                    <span className="text-stone-300">{entry.name}</span>
+                    <span className="text-stone-500 font-mono">
+                      {entry.nexusModId
+                        ? `Nexus ${entry.nexusModId}${entry.nexusFileId ? ` / ${entry.nexusFileId}` : ''}`
+                        : 'No Nexus match'}
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            )}
+            {vortexImport.report && !vortexImport.report.dryRun && (
+              <p className="text-xs text-orange-400 font-sans">
+                Before opening Vortex again, purge Stardew Valley's mods in it or stop managing the game there, otherwise its next deploy overwrites the imported mods.
+              </p>
+            )}
+            {vortexImport.report?.failed.map(failed => (
+              <p key={failed.path} className="text-xs text-red-400 font-sans">
+                {failed.name}: {failed.error}
+              </p>
+            ))}
+            {vortexImport.error && (
+              <p className="text-xs text-red-400 font-sans">{vortexImport.error}</p>
+            )}
+
+            <div className="flex items-center justify-between gap-4">
+              <p className="text-xs text-stone-500 font-sans">
+                {foreignImport.report
+                  ? foreignImport.report.dryRun
+                    ? `${foreignImport.report.migration.mods.length} mods found, ${foreignImport.report.renames.length} folders to rename, ${foreignImport.report.moddropMatched} from ModDrop`
+                    : `Imported ${foreignImport.report.migration.mods.length} mods, ${foreignImport.report.moddropMatched} from ModDrop${
+                        foreignImport.report.failed.length > 0 ? `, ${foreignImport.report.failed.length} folders left as they were` : ''
+                      }`
+                  : 'Switch from Stardrop or ModDrop'}
+              </p>
+              {foreignImport.report?.dryRun ? (
+                <button
+                  onClick={() => handleImportForeign(foreignImport.report!.tool, false)}
+                  disabled={foreignImport.running}
+                  className="px-3 py-2 text-xs border transition-colors bg-orange-600 border-orange-500 hover:bg-orange-500 text-white disabled:opacity-50"
+                >
+                  {foreignImport.running ? 'Importing...' : 'Import'}
+                </button>
+              ) : (
+                <div className="flex gap-2">
+                  {(['stardrop', 'modDrop'] as const).map(tool => (
+                    <button
+                      key={tool}
+                      onClick={() => handleImportForeign(tool, true)}
+                      disabled={foreignImport.running}
+                      className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400 disabled:opacity-50"
+                    >
+                      {tool === 'stardrop' ? 'From Stardrop' : 'From ModDrop'}
+                    </button>
+                  ))}
+                </div>
+              )}
+            </div>
+            {foreignImport.report?.dryRun && foreignImport.report.renames.length > 0 && (
+              <ul className="max-h-40 overflow-y-auto text-xs font-sans space-y-0.5">
+                {foreignImport.report.renames.map(rename => (
+                  <li key={rename.from} className="flex justify-between gap-4">
+                    <span className="text-stone-500 font-mono truncate">{rename.from}</span>
+                    <span className="text-stone-300 font-mono truncate">{rename.to}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+            {foreignImport.report?.failed.map(failed => (
+              <p key={failed.path} className="text-xs text-red-400 font-sans">
+                <span className="font-mono">{failed.path}</span>: {failed.error}
+              </p>
+            ))}
+            {foreignImport.error && (
+              <p className="text-xs text-red-400 font-sans">{foreignImport.error}</p>
+            )}
+          </div>
+
+          <div className="h-px bg-stone-800/50" />
+
+          {/* Logs Section */}
+          <div className="space-y-3">
+            <h3 className="text-sm font-medium text-stone-100 font-sans">
+              Logs
+            </h3>
+            <Select
+              label="Log Level"
+              value={settings.logLevel}
+              onChange={(value) => setSettings({ ...settings, logLevel: value as LogLevel })}
+              options={[
+                { label: 'Errors only', value: 'Error' },
+                { label: 'Warnings', value: 'Warn' },
+                { label: 'Info', value: 'Info' },
+                { label: 'Debug', value: 'Debug' },
+                { label: 'Trace', value: 'Trace' },
+              ]}
+            />
+            <div className="flex items-center justify-between gap-4">
+              <p className="text-xs text-stone-500 font-sans">
+                Daily log files are kept in the logs folder of the data folder for a week
+              </p>
+              <button
+                onClick={loadLogs}
+                className="px-3 py-2 text-xs border transition-colors bg-stone-900 border-stone-800 hover:bg-stone-800 text-stone-400"
+              >
+                {logs.length > 0 ? 'Refresh' : 'Show recent logs'}
+              </button>
+            </div>
+            {logs.length > 0 && (
+              <ul className="max-h-60 overflow-y-auto text-xs font-mono space-y-0.5">
+                {logs.map((line, i) => (
+                  <li
+                    key={i}
+                    className={
+                      line.level === 'ERROR' ? 'text-red-400' : line.level === 'WARN' ? 'text-yellow-400' : 'text-stone-400'
+                    }
+                  >
+                    <span className="text-stone-600">{line.timestamp.slice(11, 19)}</span> {line.level}{' '}
+                    <span className="whitespace-pre-wrap break-all">{line.message}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+
+          {appInfo && (
+            <>
+              <div className="h-px bg-stone-800/50" />
+
+              {/* About Section */}
+              <div className="space-y-2">
+                <h3 className="text-sm font-medium text-stone-100 font-sans">
+                  About
+                </h3>
+                <dl className="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-xs font-sans">
+                  <dt className="text-stone-500">Version</dt>
+                  <dd className="text-stone-300 font-mono">{appInfo.version}{appInfo.build.debug && ' (debug)'}</dd>
+                  <dt className="text-stone-500">Tauri</dt>
+                  <dd className="text-stone-300 font-mono">{appInfo.build.tauriVersion} ({appInfo.build.target})</dd>
+                  <dt className="text-stone-500">SMAPI</dt>
+                  <dd className="text-stone-300 font-mono">{environment?.smapi?.version ?? 'Not found'}</dd>
+                  <dt className="text-stone-500">Stardew Valley</dt>
+                  <dd className="text-stone-300 font-mono">{environment?.game?.version ?? 'Unknown'}</dd>
+                  <dt className="text-stone-500">Data folder</dt>
+                  <dd className="text-stone-300 font-mono break-all">{appInfo.dataDirs.appData}</dd>
+                  <dt className="text-stone-500">Features</dt>
+                  <dd className="text-stone-300">{appInfo.features.length > 0 ? appInfo.features.join(', ') : 'None'}</dd>
+                </dl>
+                {performance && performance.commands.length > 0 && (
+                  <table className="w-full text-xs font-sans">
+                    <thead>
+                      <tr className="text-stone-500 text-left">
+                        <th className="font-normal">Command</th>
+                        <th className="font-normal text-right">Runs</th>
+                        <th className="font-normal text-right">Median</th>
+                        <th className="font-normal text-right">95th</th>
+                        <th className="font-normal text-right">Last</th>
+                      </tr>
+                    </thead>
+                    <tbody className="text-stone-300 font-mono">
+                      {performance.commands.map(stats => (
+                        <tr key={stats.command}>
+                          <td>{stats.command}</td>
+                          <td className="text-right">{stats.runs}</td>
+                          <td className="text-right">{stats.medianMs} ms</td>
+                          <td className="text-right">{stats.p95Ms} ms</td>
+                          <td className="text-right">
+                            {stats.lastMs} ms{stats.lastItems !== null && ` / ${stats.lastItems}`}
+                          </td>
+                        </tr>
+                      ))}
+                    </tbody>
+                  </table>
+                )}
+              </div>
+            </>
+          )}
         </div>
 
         {/* Footer */}
@@ -1,16 +1,30 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { ApiUsage } from '../../types/apiUsage';
-import { Activity } from 'lucide-react';
+import type { ApiUsage, QuotaWarning } from '../../types/apiUsage';
+import { listenEvent } from '../../types/events';
+import { Activity, AlertTriangle } from 'lucide-react';
+
+/** Whether the requests left are still below 10% of the limit, counting the
+ * daily allowance first like Nexus does */
+const isQuotaLow = (usage: ApiUsage) => {
+  const [remaining, limit] = usage.dailyRemaining
+    ? [usage.dailyRemaining, usage.dailyLimit]
+    : [usage.hourlyRemaining, usage.hourlyLimit];
+  if (remaining === null || !limit) return false;
+  return remaining < limit / 10;
+};
 
 export const Footer = () => {
   const [apiUsage, setApiUsage] = useState<ApiUsage | null>(null);
   const [isLoading, setIsLoading] = useState(true);
+  const [quotaWarning, setQuotaWarning] = useState<QuotaWarning | null>(null);
 
   const fetchApiUsage = async () => {
     try {
       const usage = await invoke<ApiUsage>('get_api_usage');
       setApiUsage(usage);
+      // The quota was reset, or another account is in use now
+      if (!isQuotaLow(usage)) setQuotaWarning(null);
       setIsLoading(false);
     } catch (error) {
       console.error('Failed to fetch API usage:', error);
@@ -28,6 +42,16 @@ export const Footer = () => {
     return () => clearInterval(interval);
   }, []);
 
+  useEffect(() => {
+    const unlisten = listenEvent('api-quota-low', (event) => {
+      setQuotaWarning(event.payload);
+      fetchApiUsage();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // Show footer even if no data yet
   const hasData = apiUsage && (apiUsage.hourlyLimit || apiUsage.dailyLimit);
 
@@ -106,6 +130,16 @@ export const Footer = () => {
           )}
         </div>
 
+        {quotaWarning && (
+          <div className="flex items-center gap-1 text-red-400">
+            <AlertTriangle className="w-3 h-3" />
+            <span>
+              {quotaWarning.remaining} API requests left
+              {quotaWarning.resetsAt && `, resets at ${new Date(quotaWarning.resetsAt).toLocaleTimeString()}`}
+            </span>
+          </div>
+        )}
+
         {hasData && apiUsage.lastUpdated && (
           <div className="text-stone-600">
             Last updated: {new Date(apiUsage.lastUpdated).toLocaleTimeString()}
@@ -1,9 +1,13 @@
 import React from 'react';
-import { Bell, Settings, X, DownloadCloud, Play, RefreshCw } from 'lucide-react';
+import { Bell, Settings, X, DownloadCloud, Play, RefreshCw, Gamepad2 } from 'lucide-react';
 import { NexusLogo } from '../ui/NexusLogo';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { invoke } from '@tauri-apps/api/core';
+import { ask } from '@tauri-apps/plugin-dialog';
 import { useToast } from '../../hooks/useToast';
+import { errorMessage } from '../../i18n/errors';
+import type { LaunchCheck } from '../../types/profiles';
+import type { Settings as AppSettings } from '../../types/settings';
 
 interface HeaderProps {
     onOpenSettings: () => void;
@@ -20,6 +24,22 @@ export const Header: React.FC<HeaderProps> = ({ onOpenSettings, onOpenDownloads
     };
 
     const handleLaunchGame = async () => {
+        // The last played farm belongs to another profile; with auto-switch
+        // on, launch_game applies the farm's profile itself
+        try {
+            const check = await invoke<LaunchCheck>('check_launch_profile', { saveId: null });
+            const { autoSwitchProfile } = await invoke<AppSettings>('load_settings');
+            if (check.mismatch && !autoSwitchProfile) {
+                const proceed = await ask(
+                    `${check.saveId} was last played with profile ${check.saveProfile}, but ${check.activeProfile ?? 'no profile'} is active. Launch anyway?`,
+                    { title: 'Different Profile', kind: 'warning' }
+                );
+                if (!proceed) return;
+            }
+        } catch (err) {
+            console.error('Failed to check the launch profile:', err);
+        }
+
         setIsLoadingGame(true);
 
         try {
@@ -27,7 +47,7 @@ export const Header: React.FC<HeaderProps> = ({ onOpenSettings, onOpenDownloads
             success('Game launched successfully!');
         } catch (err) {
             console.error('Failed to launch game:', err);
-            error('Failed to launch game', String(err));
+            error('Failed to launch game', errorMessage(err));
         } finally {
             setTimeout(() => {
                 setIsLoadingGame(false);
@@ -35,6 +55,17 @@ export const Header: React.FC<HeaderProps> = ({ onOpenSettings, onOpenDownloads
         }
     };
 
+    // Without SMAPI, to tell whether a problem comes from mods
+    const handleLaunchVanilla = async () => {
+        try {
+            await invoke('launch_game_vanilla');
+            success('Game launched without mods');
+        } catch (err) {
+            console.error('Failed to launch game:', err);
+            error('Failed to launch game', errorMessage(err));
+        }
+    };
+
     return (
         <header className="flex-none h-10 border-b bg-stone-900/80 backdrop-blur-md flex items-center justify-between px-6 z-20 border-stone-800">
             <div className="flex items-center gap-4">
@@ -70,6 +101,13 @@ export const Header: React.FC<HeaderProps> = ({ onOpenSettings, onOpenDownloads
                         </>
                     )}
                 </button>
+                <button
+                    onClick={handleLaunchVanilla}
+                    className="p-2 rounded-md text-stone-500 transition-colors hover:bg-stone-800 hover:text-stone-200"
+                    title="Launch without mods"
+                >
+                    <Gamepad2 className="w-5 h-5" />
+                </button>
                 <div className="h-5 w-px bg-stone-800 mx-2"></div>
                 <button className="p-2 rounded-md text-stone-500 transition-colors relative hover:bg-stone-800 hover:text-stone-200">
                     <Bell className="w-5 h-5" />
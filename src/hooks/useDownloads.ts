@@ -1,17 +1,25 @@
 import { useState, useEffect, useCallback } from 'react';
-import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
-import { DownloadTask, DownloadProgress, DownloadFailure } from '../types/download';
-import { NxmUrl } from '../types/nxm';
+import { ask } from '@tauri-apps/plugin-dialog';
+import { DownloadTask, DownloadProgress, QueueProgress } from '../types/download';
+import { listenEvent, InstallOptions, QueuedRequirements } from '../types/events';
+import { errorMessage } from '../i18n/errors';
 
 interface UseDownloadsReturn {
     downloads: DownloadTask[];
     currentProgress: Map<string, DownloadProgress>;
+    queueProgress: QueueProgress | null;
+    installOptions: InstallOptions | null;
     activeCount: number;
     queuedCount: number;
     cancelDownload: (id: string) => Promise<void>;
+    pauseAll: () => Promise<void>;
+    resumeAll: () => Promise<void>;
+    cancelAll: () => Promise<void>;
     clearCompleted: () => Promise<void>;
     openDownloadFolder: () => Promise<void>;
+    installSelectedOptions: (folders: string[]) => Promise<void>;
+    dismissInstallOptions: () => void;
 }
 
 interface UseDownloadsOptions {
@@ -22,6 +30,8 @@ interface UseDownloadsOptions {
 export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn {
     const [downloads, setDownloads] = useState<DownloadTask[]>([]);
     const [currentProgress, setCurrentProgress] = useState<Map<string, DownloadProgress>>(new Map());
+    const [queueProgress, setQueueProgress] = useState<QueueProgress | null>(null);
+    const [installOptions, setInstallOptions] = useState<InstallOptions | null>(null);
 
     // Load initial state
     useEffect(() => {
@@ -32,7 +42,7 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
 
     useEffect(() => {
         // Listen for NXM protocol events
-        const unlistenNxm = listen<NxmUrl>('nxm-url-received', (event) => {
+        const unlistenNxm = listenEvent('nxm-url-received', (event) => {
             console.log('NXM URL received:', event.payload);
             if (options?.onToast) {
                 options.onToast('info', 'NXM Link Received', `Processing mod ${event.payload.mod_id}`);
@@ -40,12 +50,12 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             // Backend handles queuing, we just wait for download-queued event
         });
 
-        const unlistenDebug = listen<string>('debug-deep-link', (event) => {
+        const unlistenDebug = listenEvent('debug-deep-link', (event) => {
             console.log('Raw deep link received:', event.payload);
             // Only for debugging, skip toast
         });
 
-        const unlistenError = listen<string>('nxm-error', (event) => {
+        const unlistenError = listenEvent('nxm-error', (event) => {
             console.error('NXM error:', event.payload);
             if (options?.onToast) {
                 options.onToast('error', 'NXM Protocol Error', event.payload);
@@ -53,7 +63,7 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
         });
 
         // Listen for download events
-        const unlistenQueued = listen<DownloadTask>('download-queued', (event) => {
+        const unlistenQueued = listenEvent('download-queued', (event) => {
             setDownloads(prev => {
                 // Prevent duplicates if backend sends multiple events
                 if (prev.some(d => d.id === event.payload.id)) return prev;
@@ -65,19 +75,28 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             }
         });
 
-        const unlistenProgress = listen<DownloadProgress>('download-progress', (event) => {
-            const progress = event.payload;
-            setCurrentProgress(prev => new Map(prev).set(progress.downloadId, progress));
+        const unlistenProgress = listenEvent('download-progress-batch', (event) => {
+            const batch = event.payload;
+            setCurrentProgress(prev => {
+                const next = new Map(prev);
+                batch.forEach(progress => next.set(progress.downloadId, progress));
+                return next;
+            });
 
             // Update status in list if needed (e.g. from queued to downloading)
+            const ids = new Set(batch.map(progress => progress.downloadId));
             setDownloads(prev => prev.map(d =>
-                d.id === progress.downloadId && d.status === 'queued'
+                ids.has(d.id) && d.status === 'queued'
                     ? { ...d, status: 'downloading' }
                     : d
             ));
         });
 
-        const unlistenCompleted = listen<string>('download-completed', (event) => {
+        const unlistenQueueProgress = listenEvent('download-queue-progress', (event) => {
+            setQueueProgress(event.payload);
+        });
+
+        const unlistenCompleted = listenEvent('download-completed', (event) => {
             const downloadId = event.payload;
             setDownloads(prev => prev.map(d => {
                 if (d.id === downloadId) {
@@ -96,7 +115,7 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             });
         });
 
-        const unlistenFailed = listen<DownloadFailure>('download-failed', (event) => {
+        const unlistenFailed = listenEvent('download-failed', (event) => {
             const { downloadId, error } = event.payload;
             setDownloads(prev => prev.map(d =>
                 d.id === downloadId ? { ...d, status: { failed: { error } } } : d
@@ -112,7 +131,37 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             }
         });
 
-        const unlistenConfirmation = listen<string>('install-confirmation-needed', (event) => {
+        const unlistenMetered = listenEvent('download-metered-hold', async (event) => {
+            const { downloadId, bytesTotal, needsConfirmation } = event.payload;
+            setDownloads(prev => prev.map(d =>
+                d.id === downloadId ? { ...d, status: 'paused', bytesTotal } : d
+            ));
+
+            const size = `${Math.round(bytesTotal / 1024 / 1024)} MB`;
+            if (!needsConfirmation) {
+                if (options?.onToast) {
+                    options.onToast('info', 'Download Paused', `Metered connection, resume to download ${size}`);
+                }
+                return;
+            }
+
+            const download = await ask(`This download is ${size} and your connection is metered. Download it anyway?`, {
+                title: 'Metered connection',
+                kind: 'warning',
+            });
+            if (download) {
+                try {
+                    await invoke('confirm_metered_download', { downloadId });
+                    setDownloads(prev => prev.map(d =>
+                        d.id === downloadId ? { ...d, status: 'queued', meteredApproved: true } : d
+                    ));
+                } catch (error) {
+                    console.error('Failed to start download:', error);
+                }
+            }
+        });
+
+        const unlistenConfirmation = listenEvent('install-confirmation-needed', (event) => {
             const downloadId = event.payload;
             // Find download and maybe update status or show modal
             // For now, we'll just log it, as the UI for confirmation isn't fully designed yet
@@ -120,23 +169,55 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             // TODO: Trigger confirmation UI
         });
 
+        const unlistenOptions = listenEvent('install-options-needed', (event) => {
+            setInstallOptions(event.payload);
+        });
+
+        const unlistenRequirements = listenEvent('nexus-requirements-missing', async (event) => {
+            const { requirements } = event.payload;
+            const names = requirements.map(r => `• ${r.modName}${r.notes ? ` (${r.notes})` : ''}`).join('\n');
+            const queue = await ask(`The mod page lists requirements you don't have:\n\n${names}\n\nDownload them now?`, {
+                title: 'Missing requirements',
+                kind: 'info',
+            });
+            if (!queue) return;
+
+            try {
+                const queued = await invoke<QueuedRequirements>('queue_nexus_requirements', {
+                    modIds: requirements.map(r => Number(r.modId)),
+                });
+                if (queued.failed.length > 0 && options?.onToast) {
+                    const failed = queued.failed.map(f => {
+                        const name = requirements.find(r => Number(r.modId) === f.modId)?.modName ?? `Mod ${f.modId}`;
+                        return `${name}: ${f.error}`;
+                    });
+                    options.onToast('error', 'Some Requirements Not Queued', failed.join('\n'));
+                }
+            } catch (error) {
+                console.error('Failed to queue requirements:', error);
+                if (options?.onToast) {
+                    options.onToast('error', 'Requirements Not Queued', errorMessage(error));
+                }
+            }
+        });
+
         // Listen for mod installation events
-        const unlistenModInstalled = listen<{ mod_name: string; version: string; unique_id: string }>('mod-installed', (event) => {
-            const { mod_name, version, unique_id } = event.payload;
-            console.log('Mod installed:', mod_name, version, unique_id);
+        const unlistenModInstalled = listenEvent('mod-installed', (event) => {
+            const { modName, version, uniqueId } = event.payload;
+            console.log('Mod installed:', modName, version, uniqueId);
 
             // Show success notification
             if (options?.onToast) {
-                options.onToast('success', 'Mod Installed', `${mod_name} v${version}`);
+                options.onToast('success', 'Mod Installed', `${modName} v${version}`);
             }
 
-            // Trigger callback to refresh mod list with unique_id
+            // Trigger callback to refresh mod list with uniqueId
             if (options?.onModInstalled) {
-                options.onModInstalled(unique_id);
+                options.onModInstalled(uniqueId);
             }
         });
 
-        const unlistenModInstallFailed = listen<string>('mod-install-failed', (event) => {
+        const unlistenModInstallFailed = listenEvent('mod-install-failed', (event) => {
             const error = event.payload;
             console.error('Mod installation failed:', error);
 
@@ -152,14 +233,38 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
             unlistenError.then(fn => fn());
             unlistenQueued.then(fn => fn());
             unlistenProgress.then(fn => fn());
+            unlistenQueueProgress.then(fn => fn());
             unlistenCompleted.then(fn => fn());
             unlistenFailed.then(fn => fn());
+            unlistenMetered.then(fn => fn());
             unlistenConfirmation.then(fn => fn());
+            unlistenOptions.then(fn => fn());
+            unlistenRequirements.then(fn => fn());
             unlistenModInstalled.then(fn => fn());
             unlistenModInstallFailed.then(fn => fn());
         };
     }, [options]);
 
+    const installSelectedOptions = useCallback(async (folders: string[]) => {
+        if (!installOptions) return;
+        setInstallOptions(null);
+        try {
+            // Each installed mod is announced through mod-installed
+            await invoke('install_archive_options', {
+                archivePath: installOptions.archivePath,
+                folders,
+                nexusMeta: installOptions.nexusMeta ?? null,
+            });
+        } catch (error) {
+            console.error('Failed to install selected folders:', error);
+            if (options?.onToast) {
+                options.onToast('error', 'Installation Failed', errorMessage(error));
+            }
+        }
+    }, [installOptions, options]);
+
+    const dismissInstallOptions = useCallback(() => setInstallOptions(null), []);
+
     const cancelDownload = useCallback(async (id: string) => {
         try {
             await invoke('cancel_download', { downloadId: id });
@@ -168,6 +273,30 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
         }
     }, []);
 
+    const pauseAll = useCallback(async () => {
+        try {
+            setDownloads(await invoke<DownloadTask[]>('pause_all_downloads'));
+        } catch (error) {
+            console.error('Failed to pause downloads:', error);
+        }
+    }, []);
+
+    const resumeAll = useCallback(async () => {
+        try {
+            setDownloads(await invoke<DownloadTask[]>('resume_all_downloads'));
+        } catch (error) {
+            console.error('Failed to resume downloads:', error);
+        }
+    }, []);
+
+    const cancelAll = useCallback(async () => {
+        try {
+            setDownloads(await invoke<DownloadTask[]>('cancel_all_downloads'));
+        } catch (error) {
+            console.error('Failed to cancel downloads:', error);
+        }
+    }, []);
+
     const clearCompleted = useCallback(async () => {
         try {
             await invoke('clear_completed_downloads');
@@ -191,10 +320,17 @@ export function useDownloads(options?: UseDownloadsOptions): UseDownloadsReturn
     return {
         downloads,
         currentProgress,
+        queueProgress,
+        installOptions,
         activeCount,
         queuedCount,
         cancelDownload,
+        pauseAll,
+        resumeAll,
+        cancelAll,
         clearCompleted,
-        openDownloadFolder
+        openDownloadFolder,
+        installSelectedOptions,
+        dismissInstallOptions
     };
 }
@@ -0,0 +1,41 @@
+import type { Language } from '../types/settings';
+import type { AppError, AppErrorCode } from '../types/errors';
+
+// English comes from the backend; `other` errors have no translation
+const translations: Record<Exclude<Language, 'English'>, Partial<Record<AppErrorCode, string>>> = {
+  'Bahasa Indonesia': {
+    gamePathNotConfigured: 'Lokasi game belum diatur. Atur di Pengaturan.',
+    smapiPathNotConfigured: 'Lokasi SMAPI belum diatur. Atur di Pengaturan.',
+    nexusApiKeyNotConfigured: 'API key Nexus belum diatur',
+    readOnlyMode: 'Mode hanya-baca aktif, matikan di Pengaturan untuk mengubah mod',
+    unmanagedFolder: 'Folder ini ditandai tidak dikelola, ubah secara manual',
+    modsFolderNotFound: 'Folder Mods tidak ditemukan',
+    modNotFound: 'Mod tidak ditemukan: {path}',
+    downloadNotFound: 'Unduhan tidak ditemukan: {id}',
+    downloadsActive: 'Tunggu unduhan yang berjalan selesai sebelum mengganti folder unduhan',
+    manifestNotFound: 'Tidak ada manifest.json di arsip mod',
+  },
+};
+
+let language: Language = 'English';
+
+/** Called when settings load or change, so errors follow the UI language */
+export function setErrorLanguage(next: Language) {
+  language = next;
+}
+
+function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null && 'code' in error && 'message' in error;
+}
+
+/** Text to show for whatever a command rejected with */
+export function errorMessage(error: unknown): string {
+  if (!isAppError(error)) {
+    return String(error);
+  }
+  const template = language === 'English' ? undefined : translations[language][error.code];
+  if (!template) {
+    return error.message;
+  }
+  return template.replace(/\{(\w+)\}/g, (match, key: string) => error.params[key] ?? match);
+}
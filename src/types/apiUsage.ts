@@ -6,4 +6,12 @@ export interface ApiUsage {
   dailyRemaining: number | null;
   dailyReset: string | null;
   lastUpdated: string | null;
+  keyId: string | null;
+}
+
+/** Sent once when the requests left drop below 10% of the limit */
+export interface QuotaWarning {
+  remaining: number;
+  limit: number;
+  resetsAt: string | null;
 }
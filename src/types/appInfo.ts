@@ -0,0 +1,24 @@
+export interface BuildInfo {
+  tauriVersion: string;
+  crateVersion: string;
+  target: string;
+  debug: boolean;
+}
+
+export interface DataDirs {
+  appData: string;
+  settingsFile: string;
+  downloads: string;
+  backups: string;
+  temp: string;
+  logs: string;
+  smapiLog: string | null;
+}
+
+export interface AppInfo {
+  name: string;
+  version: string;
+  build: BuildInfo;
+  dataDirs: DataDirs;
+  features: string[];
+}
@@ -0,0 +1,20 @@
+// Mirrors NormalizeReport in src-tauri/src/disabled_folders.rs
+import type { DisabledFolderStyle } from './settings';
+
+export interface FolderRename {
+  from: string;
+  to: string;
+}
+
+export interface FailedRename {
+  path: string;
+  error: string;
+}
+
+export interface NormalizeReport {
+  style: DisabledFolderStyle;
+  dryRun: boolean;
+  /** In the order they're made; a group is renamed before the mods in it */
+  renames: FolderRename[];
+  failed: FailedRename[];
+}
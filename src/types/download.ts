@@ -5,17 +5,23 @@ export type DownloadStatus =
   | 'downloading'
   | 'paused'
   | 'completed'
-  | { failed: { error: string } };
+  | { failed: { error: string; retryable?: boolean } }
+  | { rateLimited: { retryAt: string } };
 
 export interface DownloadTask {
   id: string;
   nxmUrl: NxmUrl;
   modName?: string;
   fileName: string;
+  fileVersion?: string;
   status: DownloadStatus;
   filePath?: string;
   bytesDownloaded: number;
   bytesTotal?: number;
+  installSize?: number;
+  account?: string;
+  managedInstall?: boolean;
+  meteredApproved?: boolean;
 }
 
 export interface DownloadProgress {
@@ -23,11 +29,41 @@ export interface DownloadProgress {
   bytesDownloaded: number;
   bytesTotal?: number;
   speedBps: number;
+  averageSpeedBps: number;
   etaSeconds?: number;
   progressPercent: number;
 }
 
+export interface QueueProgress {
+  filesTotal: number;
+  filesRemaining: number;
+  bytesDownloaded: number;
+  bytesTotal: number;
+  speedBps: number;
+  etaSeconds?: number;
+  progressPercent: number;
+}
+
+export interface DownloadRateLimited {
+  downloadId: string;
+  retryAt: string;
+}
+
 export interface DownloadFailure {
   downloadId: string;
   error: string;
+  retryable: boolean;
+}
+
+export interface DownloadMeteredHold {
+  downloadId: string;
+  bytesTotal: number;
+  needsConfirmation: boolean;
+}
+
+export interface DownloadRetrying {
+  downloadId: string;
+  attempt: number;
+  maxAttempts: number;
+  error: string;
 }
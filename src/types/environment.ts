@@ -0,0 +1,14 @@
+export type VersionSource = 'assembly' | 'depsJson' | 'smapiLog';
+
+export interface DetectedVersion {
+  version: string;
+  source: VersionSource;
+}
+
+export interface EnvironmentInfo {
+  smapi: DetectedVersion | null;
+  game: DetectedVersion | null;
+  /** Oldest SMAPI that supports the installed game version */
+  minimumSmapi: string | null;
+  smapiOutdated: boolean;
+}
@@ -0,0 +1,22 @@
+// Mirrors AppError in src-tauri/src/app_error.rs
+export type AppErrorCode =
+  | 'gamePathNotConfigured'
+  | 'smapiPathNotConfigured'
+  | 'nexusApiKeyNotConfigured'
+  | 'readOnlyMode'
+  | 'unmanagedFolder'
+  | 'modsFolderNotFound'
+  | 'modNotFound'
+  | 'downloadNotFound'
+  | 'downloadsActive'
+  | 'manifestNotFound'
+  | 'other';
+
+/** What a failed command rejects with */
+export interface AppError {
+  code: AppErrorCode;
+  /** Filled into the translated message, e.g. `path` for modNotFound */
+  params: Record<string, string>;
+  /** English message, shown when there's no translation */
+  message: string;
+}
@@ -0,0 +1,214 @@
+import { listen, EventCallback, UnlistenFn } from '@tauri-apps/api/event';
+import { DownloadTask, DownloadProgress, QueueProgress, DownloadFailure, DownloadRateLimited, DownloadRetrying, DownloadMeteredHold } from './download';
+import { NxmUrl } from './nxm';
+import { ModFile } from './mod';
+import { QuotaWarning } from './apiUsage';
+
+// Mirrors AppEvent in src-tauri/src/events.rs; keep the two in sync
+export type InstallStrategy = 'singleFolder' | 'multiManifest' | 'looseFiles' | 'selectedOptions';
+
+export interface DetectedManifest {
+  /** Relative to the archive root */
+  path: string;
+  uniqueId?: string | null;
+  name?: string | null;
+}
+
+export interface InstallResult {
+  modName: string;
+  version: string;
+  uniqueId: string;
+  installPath: string;
+  strategy: InstallStrategy;
+  manifests: DetectedManifest[];
+  skippedFiles: string[];
+  /** The mod from the same archive this one is a content pack for */
+  parentUniqueId?: string | null;
+}
+
+/** Where a mod came from on Nexus; passed back unchanged to `install_archive_options` */
+export interface NexusMeta {
+  mod_id: number;
+  file_id: number;
+  file_version?: string | null;
+  downloaded_at?: string | null;
+  md5?: string | null;
+}
+
+/** A folder of an archive; only those with a `uniqueId` hold a mod and can be picked */
+export interface ArchiveFolder {
+  path: string;
+  name: string;
+  modName?: string;
+  uniqueId?: string;
+  version?: string;
+  children: ArchiveFolder[];
+}
+
+export interface InstallOptions {
+  archivePath: string;
+  modName?: string;
+  nexusMeta?: NexusMeta;
+  folders: ArchiveFolder[];
+}
+
+export interface AvailableUpdate {
+  uniqueId: string;
+  name: string;
+  nexusModId: number;
+  currentVersion: string;
+  latestVersion?: string;
+  latestFileId?: number;
+}
+
+export interface RequiredFilesAvailable {
+  downloadId: string;
+  modId: number;
+  files: ModFile[];
+}
+
+export interface MissingDependency {
+  uniqueId: string;
+  requiredBy: string[];
+  nexusModId?: number;
+  pageUrl?: string;
+  reason: string;
+}
+
+export interface NexusRequirement {
+  modId: string;
+  modName: string;
+  url?: string;
+  notes?: string;
+  externalRequirement: boolean;
+}
+
+export interface NexusRequirementsMissing {
+  modId: number;
+  requirements: NexusRequirement[];
+}
+
+/** Result of `queue_nexus_requirements`; a failed mod doesn't stop the rest */
+export interface QueuedRequirements {
+  downloadIds: string[];
+  failed: { modId: number; error: string }[];
+}
+
+export interface MaintenanceStep {
+  name: string;
+  status: 'ok' | 'skipped' | 'failed';
+  durationMs: number;
+  message?: string;
+}
+
+export interface MaintenanceStatus {
+  running: boolean;
+  lastRunAt?: string;
+  nextRunAt?: string;
+  steps: MaintenanceStep[];
+}
+
+export interface ModUpdateProgress {
+  uniqueId: string;
+  name: string;
+  stage: 'queued' | 'downloading' | 'installing' | 'updated' | 'failed';
+  message?: string;
+}
+
+export interface PlannedUpdate {
+  uniqueId: string;
+  name: string;
+  fileId?: number;
+  fileName?: string;
+  downloadBytes?: number;
+  backupPath?: string;
+  backupBytes: number;
+  skipReason?: string;
+}
+
+/** Result of `plan_updates`, a dry run of `update_all_mods` */
+export interface UpdatePlan {
+  updates: PlannedUpdate[];
+  downloadBytes: number;
+  apiCalls: number;
+  backups: number;
+  estimatedSeconds?: number;
+}
+
+/** A console line of a game started with `launch_game({ captureOutput: true })` */
+export interface SmapiLine {
+  text: string;
+  stderr: boolean;
+  time?: string;
+  level?: string;
+  source?: string;
+  message: string;
+  marker?: 'gameLaunched' | 'crashed';
+}
+
+export interface SmapiExited {
+  exitCode?: number;
+  crashed: boolean;
+}
+
+export interface ModLogIssues {
+  source: string;
+  warnings: number;
+  errors: number;
+}
+
+export interface GameSessionSummary {
+  startedAt: string;
+  endedAt: string;
+  durationSeconds: number;
+  exitCode?: number;
+  crashed: boolean;
+  errors: number;
+  warnings: number;
+  mods: ModLogIssues[];
+  logPath?: string;
+}
+
+export interface AppEventPayloads {
+  'nxm-url-received': NxmUrl;
+  'nxm-error': string;
+  'debug-deep-link': string;
+  'download-queued': DownloadTask;
+  'download-queue-reordered': DownloadTask[];
+  'download-progress-batch': DownloadProgress[];
+  'download-queue-progress': QueueProgress;
+  'download-completed': string;
+  'download-cancelled': string;
+  'download-retrying': DownloadRetrying;
+  'download-rate-limited': DownloadRateLimited;
+  'download-failed': DownloadFailure;
+  'download-metered-hold': DownloadMeteredHold;
+  'required-files-available': RequiredFilesAvailable;
+  'install-confirmation-needed': string;
+  'install-options-needed': InstallOptions;
+  'dependencies-missing': MissingDependency[];
+  'nexus-requirements-missing': NexusRequirementsMissing;
+  'mod-installed': InstallResult;
+  'mod-install-failed': string;
+  'mod-updates-available': AvailableUpdate[];
+  'mod-update-progress': ModUpdateProgress;
+  'endorsement-report': unknown;
+  'startup-complete': unknown;
+  'maintenance-finished': MaintenanceStatus;
+  'nexus-sso-waiting': string;
+  'nexus-sso-connected': null;
+  'api-quota-low': QuotaWarning;
+  'smapi-output': SmapiLine;
+  'smapi-exited': SmapiExited;
+  'game-session-ended': GameSessionSummary;
+}
+
+export type AppEventName = keyof AppEventPayloads;
+
+/** `listen` restricted to known channels, with the payload type filled in */
+export function listenEvent<K extends AppEventName>(
+  event: K,
+  handler: EventCallback<AppEventPayloads[K]>,
+): Promise<UnlistenFn> {
+  return listen<AppEventPayloads[K]>(event, handler);
+}
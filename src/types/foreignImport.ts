@@ -0,0 +1,17 @@
+// Mirrors ForeignImportReport in src-tauri/src/foreign_import.rs
+import type { MigrationReport } from './migration';
+import type { FailedRename, FolderRename } from './disabledFolders';
+
+export type ForeignTool = 'stardrop' | 'modDrop';
+
+export interface ForeignImportReport {
+  tool: ForeignTool;
+  dryRun: boolean;
+  /** Disabled folders renamed to the app's style */
+  renames: FolderRename[];
+  /** Renames that failed; those folders are left as Stardrop had them */
+  failed: FailedRename[];
+  /** Mods with a ModDrop update key */
+  moddropMatched: number;
+  migration: MigrationReport;
+}
@@ -0,0 +1,8 @@
+// Mirrors LogLine in src-tauri/src/logging.rs
+export interface LogLine {
+  timestamp: string;
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE';
+  /** Module the message came from */
+  target: string;
+  message: string;
+}
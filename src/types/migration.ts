@@ -0,0 +1,23 @@
+// Mirrors MigrationReport in src-tauri/src/library_migration.rs
+export type NexusIdSource = 'nexusMeta' | 'updateKeys' | 'knownMod' | 'smapiIo';
+
+export interface MigrationEntry {
+  uniqueId: string;
+  name: string;
+  path: string;
+  isEnabled: boolean;
+  /** MD5 over the mod's files; null when they couldn't be read */
+  contentHash: string | null;
+  nexusModId: number | null;
+  nexusIdSource: NexusIdSource | null;
+  /** From a "ModDrop:<id>" update key */
+  moddropId: number | null;
+}
+
+export interface MigrationReport {
+  dryRun: boolean;
+  /** A previous migration was already committed */
+  alreadyMigrated: boolean;
+  mods: MigrationEntry[];
+  nexusMatched: number;
+}
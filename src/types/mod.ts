@@ -8,11 +8,190 @@ export interface Mod {
     dependencies?: string[];
     isEnabled: boolean;
     path: string;
-    installDate: string;
+    /** When the mod registry first saw the mod */
+    installDate?: string;
+    source?: ModSource;
+    sourceFile?: string;
+    notes?: string;
+    isFavorite?: boolean;
+    availableUpdate?: ModUpdate;
+    frameworkFloors?: FrameworkFloor[];
+    /** Nexus category, e.g. "Gameplay Mechanics" */
+    category?: string;
+    /** In one of `settings.unmanagedFolders`: shown but never changed */
+    isUnmanaged?: boolean;
+    /** From the smapi.io compatibility list, once checked */
+    compatibility?: ModCompatibility;
+    /** Registers with Generic Mod Config Menu, so it can be set up in game */
+    hasGmcm?: boolean;
+    /** Top-level settings of its config.json; empty until the mod has run */
+    configKeys?: ConfigKey[];
+    /** Other folders with the same UniqueID; SMAPI won't start with two enabled */
+    duplicatePaths?: string[];
     updateDate?: string;
     status: 'working' | 'update-available' | 'error' | 'disabled';
     endorsements?: number;
     nexusId?: number;
     nexusFileId?: number;
+    lastPlayedAt?: string;
     downloadUrl?: string;
 }
+
+export type ConfigValueKind = 'boolean' | 'number' | 'string' | 'object' | 'array' | 'null';
+
+export interface ConfigKey {
+    key: string;
+    kind: ConfigValueKind;
+}
+
+export type CompatStatus = 'ok' | 'broken' | 'obsolete' | 'unofficialUpdate';
+
+export interface ModCompatibility {
+    status: CompatStatus;
+    summary?: string;
+    unofficialVersion?: string;
+    unofficialUrl?: string;
+}
+
+export type ModSource = 'nexus' | 'archive' | 'modpack' | 'vortex' | 'moddrop' | 'manual';
+
+export interface EnableChange {
+    enabled: boolean;
+    at: string;
+}
+
+export interface ModUpdate {
+    installedVersion: string;
+    latestVersion?: string;
+    latestFileId?: number;
+    changelog: string[];
+    checkedAt: string;
+}
+
+export interface ModRecord {
+    id: string;
+    uniqueId: string;
+    path: string;
+    installedAt: string;
+    source: ModSource;
+    sourceFile?: string;
+    notes: string;
+    favorite: boolean;
+    enableHistory: EnableChange[];
+    removedAt?: string;
+    update?: ModUpdate;
+    frameworkFloors: FrameworkFloor[];
+    category?: string;
+    /** The mod this content pack was installed with from the same archive */
+    parentUniqueId?: string;
+    /** Nexus mod the library migration matched a mod without `.nexus_meta` to */
+    nexusModId?: number;
+    /** Hash of the mod's files when it was migrated */
+    contentHash?: string;
+    /** ModDrop mod the migration found in the mod's update keys */
+    moddropId?: number;
+    /** The mod folder's `.nexus_meta` as of the last scan */
+    nexusMeta?: NexusMeta;
+}
+
+/** `.nexus_meta` as stored in a mod folder (snake_case on disk) */
+export interface NexusMeta {
+    mod_id: number;
+    file_id: number;
+    file_version?: string;
+    downloaded_at?: string;
+    md5?: string;
+}
+
+export interface RequiredEnable {
+    id: string;
+    uniqueId: string;
+    name: string;
+    requiredBy: string;
+}
+
+/** Result of `set_mods_enabled`; unapplied while required mods need a decision */
+export interface EnablePlan {
+    required: RequiredEnable[];
+    applied: boolean;
+    errors: string[];
+}
+
+/** Snapshot taken before an install replaced the mod */
+export interface ModBackup {
+    timestamp: string;
+    createdAt?: string;
+    compressed: boolean;
+    path: string;
+}
+
+export interface ModFile {
+    fileId: number;
+    name: string;
+    version?: string;
+    categoryName?: 'MAIN' | 'UPDATE' | 'OPTIONAL' | 'OLD_VERSION' | 'MISCELLANEOUS' | 'ARCHIVED';
+    fileName?: string;
+    sizeInBytes?: number;
+    sizeKb?: number;
+    uploadedAt: string;
+    isPrimary: boolean;
+    description?: string;
+}
+
+export interface ChangelogEntry {
+    version: string;
+    changes: string[];
+}
+
+export type SyncAction = 'install' | 'update' | 'enable' | 'disable' | 'configDiffers' | 'unavailable';
+
+export interface SyncChange {
+    uniqueId: string;
+    name: string;
+    action: SyncAction;
+    localVersion?: string;
+    hostVersion?: string;
+}
+
+export interface SyncPreview {
+    hostKey: string;
+    /** No host is trusted yet; applying the modlist trusts this one */
+    newHost: boolean;
+    createdAt: string;
+    changes: SyncChange[];
+}
+
+export interface SyncResult {
+    change: SyncChange;
+    error?: string;
+}
+
+export interface DependencyIssue {
+    uniqueId: string;
+    name: string;
+    dependencyId: string;
+    problem: 'missing' | 'disabled' | 'outdated';
+    nexusModId?: number;
+    requiredVersion?: string;
+    installedVersion?: string;
+}
+
+export interface FrameworkFloor {
+    uniqueId: string;
+    minimumVersion: string;
+}
+
+export interface ImportReport {
+    queued: { modId: number; downloadId: string }[];
+    alreadyInstalled: number[];
+    failed: { modId: number; error: string }[];
+}
+
+/** Result of resolve_duplicate */
+export interface DuplicateResolution {
+    /** The copy kept, enabled */
+    kept: string;
+    disabled: string[];
+    deleted: string[];
+    failed: { path: string; error: string }[];
+}
@@ -0,0 +1,20 @@
+// Mirrors PerformanceReport in src-tauri/src/perf_metrics.rs
+export interface CommandStats {
+  command: string;
+  runs: number;
+  failures: number;
+  averageMs: number;
+  medianMs: number;
+  p95Ms: number;
+  maxMs: number;
+  lastMs: number;
+  /** Mods scanned, installed or checked by the last run */
+  lastItems: number | null;
+  lastRunAt: string;
+}
+
+export interface PerformanceReport {
+  /** Most total time first */
+  commands: CommandStats[];
+  since: string | null;
+}
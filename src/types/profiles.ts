@@ -0,0 +1,34 @@
+// Mirrors src-tauri/src/profiles.rs
+export interface ModProfile {
+  name: string;
+  /** Lowercase UniqueIDs of the mods enabled in this profile */
+  enabledMods: string[];
+}
+
+export interface ProfileStore {
+  profiles: ModProfile[];
+  /** Profile last applied or saved */
+  active: string | null;
+  /** Save folder name -> the profile it's linked to. A save is linked to
+   * the profile it's first played with, unless the user picked one. */
+  saveProfiles: Record<string, string>;
+  /** Save folder name -> the profile it was last played with */
+  lastPlayed: Record<string, string>;
+}
+
+export interface LaunchCheck {
+  saveId: string | null;
+  saveProfile: string | null;
+  activeProfile: string | null;
+  /** The save belongs to another profile than the active one */
+  mismatch: boolean;
+}
+
+export interface ApplyReport {
+  profile: string;
+  enabled: string[];
+  disabled: string[];
+  /** Mods of the profile that aren't installed */
+  missing: string[];
+  failed: { path: string; error: string }[];
+}
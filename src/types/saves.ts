@@ -0,0 +1,24 @@
+// Mirrors SaveSummary and SaveBackup in src-tauri/src/saves.rs
+export interface SaveSummary {
+  /** Save folder name, e.g. "Farm_123456789" */
+  id: string;
+  farmerName: string;
+  farmName: string;
+  playTimeMs: number;
+  year: number | null;
+  season: string | null;
+  day: number | null;
+  lastPlayed: string | null;
+  /** Backups made by the app */
+  backups: number;
+}
+
+export interface SaveBackup {
+  saveId: string;
+  /** File name, which identifies the backup when restoring */
+  name: string;
+  createdAt: string | null;
+  size: number;
+  /** Made before a launch, and pruned with the others like it */
+  onLaunch: boolean;
+}
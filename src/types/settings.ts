@@ -1,6 +1,28 @@
 export type Theme = 'System' | 'Dark' | 'Light';
 export type Language = 'English' | 'Bahasa Indonesia';
 export type ModGroups = 'None' | 'Folder' | 'Pack';
+export type BackupFormat = 'Folder' | 'Zip' | 'Zstd';
+/** Staged keeps mod files in ModsStaging and hardlinks them into Mods */
+export type InstallMode = 'Direct' | 'Staged';
+export type EndorsementReminders = 'Off' | 'Record' | 'Open';
+export type MeteredDownloads = 'Allow' | 'Confirm' | 'Pause';
+export type LogLevel = 'Error' | 'Warn' | 'Info' | 'Debug' | 'Trace';
+export type SaveBackupsOnLaunch = 'Off' | 'Latest' | 'All';
+/** How disabling a mod renames its folder: `Mod.disabled` or `.Mod` */
+export type DisabledFolderStyle = 'Suffix' | 'DotPrefix';
+
+export type HookEvent = 'AfterInstall' | 'AfterUpdate';
+
+/** Local script run with the mod's details in TREASURE_CHEST_* variables */
+export interface ScriptHook {
+  event: HookEvent;
+  command: string;
+}
+
+export interface NexusAccount {
+  name: string;
+  apiKey: string;
+}
 
 export interface Settings {
   gamePath: string;
@@ -14,6 +36,43 @@ export interface Settings {
   confirmBeforeInstall: boolean;
   deleteAfterInstall: boolean;
   coreFrameworks: string[];
+  nxmHandlerEnabled: boolean;
+  debugApiPayloads: boolean;
+  backupFormat: BackupFormat;
+  nexusAccounts: NexusAccount[];
+  activeAccount: string | null;
+  maxConcurrentDownloads: number;
+  endorsementReminders: EndorsementReminders;
+  downloadRetryAttempts: number;
+  checkUpdatesOnStartup: boolean;
+  installNameTemplate: string;
+  localApiEnabled: boolean;
+  localApiPort: number;
+  installDependencies: boolean;
+  webhookUrl: string;
+  meteredDownloads: MeteredDownloads;
+  /** Advanced: scripts run with your permissions, only enable hooks you trust */
+  scriptHooksEnabled: boolean;
+  scriptHooks: ScriptHook[];
+  /** Blocks installs, updates, deletes and toggles; scans and exports still work */
+  readOnlyMode: boolean;
+  /** Snapshots kept per mod when maintenance prunes backups; 0 keeps all */
+  backupsPerMod: number;
+  /** Folders under Mods (e.g. "ConsoleCommands", "_Dev") the app never changes */
+  unmanagedFolders: string[];
+  /** Set once an existing Mods folder has been imported into the registry */
+  libraryMigrated: boolean;
+  /** Where archives are downloaded; empty keeps them in the app data folder */
+  downloadsDir: string;
+  installMode: InstallMode;
+  /** Least severe messages written to the app log */
+  logLevel: LogLevel;
+  saveBackupsOnLaunch: SaveBackupsOnLaunch;
+  /** Launch backups kept per save; 0 keeps all. Ones made by hand are never pruned. */
+  saveBackupsToKeep: number;
+  disabledFolderStyle: DisabledFolderStyle;
+  /** Apply a save's profile when launching it with another one */
+  autoSwitchProfile: boolean;
 }
 
 export const defaultSettings: Settings = {
@@ -33,4 +92,32 @@ export const defaultSettings: Settings = {
     'Json Assets',
     'SpaceCore',
   ],
+  nxmHandlerEnabled: false,
+  debugApiPayloads: false,
+  backupFormat: 'Folder',
+  nexusAccounts: [],
+  activeAccount: null,
+  maxConcurrentDownloads: 1,
+  endorsementReminders: 'Off',
+  downloadRetryAttempts: 3,
+  checkUpdatesOnStartup: false,
+  installNameTemplate: '',
+  localApiEnabled: false,
+  localApiPort: 47321,
+  installDependencies: true,
+  webhookUrl: '',
+  meteredDownloads: 'Confirm',
+  scriptHooksEnabled: false,
+  scriptHooks: [],
+  readOnlyMode: false,
+  backupsPerMod: 0,
+  unmanagedFolders: [],
+  libraryMigrated: false,
+  downloadsDir: '',
+  installMode: 'Direct',
+  logLevel: 'Info',
+  saveBackupsOnLaunch: 'Off',
+  saveBackupsToKeep: 10,
+  disabledFolderStyle: 'Suffix',
+  autoSwitchProfile: false,
 };
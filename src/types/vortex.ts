@@ -0,0 +1,30 @@
+// Mirrors VortexImportReport in src-tauri/src/vortex_import.rs
+export interface VortexMod {
+  name: string;
+  uniqueId: string | null;
+  /** Deployed folder in Mods, which the import replaces */
+  path: string;
+  /** Folder the files are copied from */
+  sourcePath: string;
+  /** Vortex's name for the mod, the archive it was installed from */
+  vortexName: string;
+  version: string | null;
+  nexusModId: number | null;
+  nexusFileId: number | null;
+}
+
+export interface FailedVortexMod {
+  name: string;
+  path: string;
+  error: string;
+}
+
+export interface VortexImportReport {
+  dryRun: boolean;
+  stagingPath: string;
+  mods: VortexMod[];
+  failed: FailedVortexMod[];
+  /** Vortex's deployment manifest was set aside; Vortex still has to be
+   * purged before it's opened again */
+  deploymentDisabled: boolean;
+}